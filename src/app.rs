@@ -2,13 +2,26 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::bitcoin_config::ConfigEntry as BitcoinEntry;
+use crate::bitcoin_config::{
+    ConfigEntry as BitcoinEntry, parse_config_with_includes as parse_bitcoin_config,
+};
 use crate::components::bitcoin_config_view::BitcoinConfigView;
 use crate::components::file_explorer::FileExplorer;
 use crate::components::p2pool_config_view::P2PoolConfigView;
 use crate::components::settings_view::SettingsView;
-use crate::settings::Settings;
+use crate::cross_validate::cross_validate;
+use crate::diff::DiffLine;
+use crate::loader::BitcoinLoad;
+use crate::p2poolv2_config::{
+    Origin, P2PoolConfigEntry, apply_edit as apply_p2pool_edit, flatten_config,
+    load_config as load_p2pool_config, network_field_warnings, read_origins, read_unknown_keys,
+    store_path_warnings,
+};
+use crate::settings::{SessionState, Settings, load_session_state, save_session_state};
+use crate::theme::{Theme, ThemeOverrides};
+use crate::warning::Warning;
 use p2poolv2_config::Config as P2PoolConfig;
+use ratatui::layout::Rect;
 use std::path::PathBuf;
 
 /// Sidebar items labels
@@ -43,6 +56,8 @@ pub enum CurrentScreen {
     SharesMarket,
     FileExplorer,
     Settings,
+    /// Quit confirmation drawn over `App::previous_screen`.
+    Exiting,
 }
 
 /// Identifies which screen (and optionally which field) triggered the file explorer.
@@ -54,12 +69,147 @@ pub enum ExplorerTrigger {
     Settings(usize),
 }
 
+/// Config file a save or reload confirmation applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveTarget {
+    BitcoinConfig,
+    P2PoolConfig,
+}
+
+/// A change to one bitcoin entry: its `(value, enabled)` before and after.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub index: usize,
+    pub before: (String, bool),
+    pub after: (String, bool),
+}
+
+/// A change to the p2pool config: the typed config and custom keys before
+/// and after, as one edit can change more than one field. `index` is the
+/// entry to select when it is undone or redone.
+#[derive(Clone)]
+pub struct P2PoolEdit {
+    pub index: usize,
+    pub before: (P2PoolConfig, Vec<P2PoolConfigEntry>),
+    pub after: (P2PoolConfig, Vec<P2PoolConfigEntry>),
+}
+
+/// Undo and redo stacks of config edits, bitcoin entry [`Edit`]s by default.
+#[derive(Debug, Clone)]
+pub struct EditHistory<E = Edit> {
+    undo: Vec<E>,
+    redo: Vec<E>,
+    /// Undo depth at which entries match the file on disk, or `None` once
+    /// no sequence of undos and redos leads back there.
+    saved_depth: Option<usize>,
+}
+
+impl<E> Default for EditHistory<E> {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            saved_depth: Some(0),
+        }
+    }
+}
+
+impl<E: Clone> EditHistory<E> {
+    /// Records a new edit, dropping anything that could have been redone.
+    pub fn record(&mut self, edit: E) {
+        if self
+            .saved_depth
+            .is_some_and(|depth| depth > self.undo.len())
+        {
+            // The saved state was on the redo branch being discarded
+            self.saved_depth = None;
+        }
+        self.redo.clear();
+        self.undo.push(edit);
+    }
+
+    /// Takes the latest edit to revert; apply its `before` state.
+    pub fn undo(&mut self) -> Option<E> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit.clone());
+        Some(edit)
+    }
+
+    /// Takes the latest undone edit to repeat; apply its `after` state.
+    pub fn redo(&mut self) -> Option<E> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit.clone());
+        Some(edit)
+    }
+
+    /// Marks the current state as the one on disk.
+    pub fn mark_saved(&mut self) {
+        self.saved_depth = Some(self.undo.len());
+    }
+
+    /// Notes a change made outside the history, such as an added entry,
+    /// which undoing cannot take back to the saved state.
+    pub fn mark_unsaved(&mut self) {
+        self.saved_depth = None;
+    }
+
+    /// Whether the entries are back in the state last saved or loaded.
+    #[must_use]
+    pub fn is_saved(&self) -> bool {
+        self.saved_depth == Some(self.undo.len())
+    }
+}
+
+impl EditHistory {
+    /// Moves recorded edits of entries at or after `index` down one row, as
+    /// an entry was inserted there.
+    pub fn shift_from(&mut self, index: usize) {
+        for edit in self.undo.iter_mut().chain(&mut self.redo) {
+            if edit.index >= index {
+                edit.index += 1;
+            }
+        }
+    }
+}
+
+/// Warnings the config screens show, worked out once per draw and shared
+/// by the banners, the footer counts and the p2pool rows.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigWarnings {
+    /// Mismatches between bitcoin.conf and the p2pool config.
+    pub mismatches: Vec<Warning>,
+    /// `[network]` limits that combine oddly.
+    pub network: Vec<Warning>,
+    /// A `store.path` whose directory p2pool could not create the store in.
+    pub store: Vec<Warning>,
+}
+
+impl ConfigWarnings {
+    /// Everything shown against p2pool fields, in [`App::p2pool_warnings`]
+    /// order.
+    #[must_use]
+    pub fn p2pool(&self) -> Vec<Warning> {
+        self.mismatches
+            .iter()
+            .chain(&self.network)
+            .chain(&self.store)
+            .cloned()
+            .collect()
+    }
+}
+
 /// Actions that components (Explorer, Editors) can trigger.
 /// This decouples input handling from business logic.
 #[derive(Debug, Clone)]
 pub enum AppAction {
     None,
     Quit,
+    /// Returns to the screen on top of the navigation history
+    GoBack,
+    /// Opens the quit confirmation on top of the current screen
+    RequestQuit,
+    /// Dismisses the quit confirmation and returns to the previous screen
+    CancelQuit,
     ToggleMenu,
     Navigate(CurrentScreen),
     // Triggers the file explorer; the trigger identifies the caller
@@ -68,18 +218,51 @@ pub enum AppAction {
     FileSelected(PathBuf),
     // Closes the explorer without selection
     CloseModal,
+    /// The explorer bookmark list was modified and should be persisted
+    BookmarksChanged,
     // Commits an edited value: (entry index, new value)
     CommitEdit(usize, String),
+    /// Appends a schema-less bitcoin entry: (key, value)
+    AddBitcoinEntry(String, String),
+    /// Appends a custom p2pool entry: (section, key, value)
+    AddP2PoolEntry(String, String, String),
+    /// Copies a bitcoin entry below itself, ready to edit (entry index)
+    DuplicateBitcoinEntry(usize),
+    /// Generates an `rpcauth` entry for the given RPC username
+    GenerateRpcAuth(String),
+    /// Flips `enabled` on a bitcoin config entry (entry index)
+    ToggleBitcoinEntry(usize),
+    /// Asks for confirmation before overwriting the target's file
+    RequestSave(SaveTarget),
+    /// Saves the target right away if its entries pass validation, otherwise
+    /// lists the errors that block the save
+    SaveValidated(SaveTarget),
+    /// Compares the target's entries with a fresh read of its file
+    ShowDiff(SaveTarget),
     // Saves bitcoin config to disk
     SaveBitcoinConfig,
     /// Commits an edited p2pool config value: (entry index, new value)
     CommitP2PoolEdit(usize, String),
     /// Saves p2pool config to disk
     SaveP2PoolConfig,
+    /// The target's file was changed by another program
+    ConfigChanged(SaveTarget),
+    /// Asks to re-read the target's file, confirming first if it has edits
+    RequestReload(SaveTarget),
+    /// Re-reads the target's file, discarding any unsaved edits
+    ReloadConfig(SaveTarget),
+    /// Reverts the latest edit to the target's config
+    Undo(SaveTarget),
+    /// Re-applies the latest undone edit to the target's config
+    Redo(SaveTarget),
+    /// Copies a value from the target's entry list to the system clipboard
+    CopyValue(SaveTarget, String),
     // Open the file explorer to pick a path for a settings field (field index)
     OpenExplorerForSettings(usize),
     // Clear a settings field by index, setting it back to None
     ClearSettingsField(usize),
+    // Flip an on/off settings field by index
+    ToggleSettingsField(usize),
 }
 
 pub struct App {
@@ -93,6 +276,17 @@ pub struct App {
     pub p2pool_config_view: P2PoolConfigView,
     pub settings_view: SettingsView,
     pub p2pool_config: Option<P2PoolConfig>,
+    /// User-added p2pool keys the typed config doesn't model; listed after
+    /// the flattened entries and written on save.
+    pub p2pool_custom: Vec<P2PoolConfigEntry>,
+    /// Origin of each flattened p2pool entry, computed when the file loads.
+    pub p2pool_origins: Vec<Origin>,
+    /// Sections and keys of the p2pool file its schema doesn't know, which
+    /// the loader ignores; computed when the file loads.
+    pub p2pool_unknown_keys: Vec<String>,
+    /// Problems with the directory `store.path` points into, found by
+    /// [`App::check_store_path`] when the p2pool config loads or changes.
+    pub p2pool_store_warnings: Vec<Warning>,
     pub bitcoin_data: Vec<BitcoinEntry>,
     pub bitcoin_status_tab: usize,
     pub settings: Settings,
@@ -102,27 +296,316 @@ pub struct App {
     /// Cached result of `settings::config_dir()`, used to display the default
     /// settings storage path without repeated env-var lookups during rendering.
     pub config_dir: PathBuf,
+    /// Height in rows of the main content area's inner list region, recorded
+    /// on every draw so PageUp/PageDown can jump by one visible page.
+    pub viewport_height: u16,
+    /// Sidebar and config entry list as last drawn, so mouse clicks can be
+    /// mapped back to rows. `list_area` is only set by the config screens.
+    pub sidebar_area: Rect,
+    pub list_area: Rect,
+    /// Active color palette; `t` cycles through the available themes.
+    pub theme: Theme,
+    /// Colors from the user's `theme.toml`, applied to every palette.
+    pub theme_overrides: ThemeOverrides,
+    /// Load or IO failure shown in a modal over the current screen until
+    /// the next key press.
+    pub error: Option<String>,
+    /// One-off information, such as a generated password, shown in a modal
+    /// until the next key press.
+    pub notice: Option<String>,
+    /// Differences between the edited entries and the file on disk, shown
+    /// in a modal until the next key press.
+    pub diff: Option<Vec<DiffLine>>,
+    /// Save awaiting a y/n answer in the overwrite confirmation modal.
+    pub pending_save: Option<SaveTarget>,
+    /// File changed on disk while its view had unsaved edits, awaiting a
+    /// y/n answer on whether to reload it.
+    pub pending_reload: Option<SaveTarget>,
+    /// Screen to return to when the quit confirmation is cancelled.
+    pub previous_screen: CurrentScreen,
+    /// Screens left via `Navigate` or the file explorer, most recent last.
+    pub history: Vec<CurrentScreen>,
+    /// Edits to `bitcoin_data` since it was loaded, for `u` and Ctrl+r.
+    pub bitcoin_edits: EditHistory,
+    /// Edits to `p2pool_config` and `p2pool_custom` since the config was
+    /// loaded, for `u` and Ctrl+r.
+    pub p2pool_edits: EditHistory<P2PoolEdit>,
+    /// bitcoin.conf picked in the explorer and still being parsed; the
+    /// Bitcoin Config screen shows a spinner until it is done.
+    pub bitcoin_loading: Option<BitcoinLoad>,
+    /// System clipboard, opened on the first copy and kept for the rest of
+    /// the session: on X11 and Wayland the copied text is only served while
+    /// the owning handle is alive.
+    pub clipboard: Option<arboard::Clipboard>,
 }
 
 impl App {
     #[must_use]
     pub fn new() -> App {
+        App::with_start_dir(None)
+    }
+
+    /// Builds the app with the file explorer opening in `start_dir`, or in
+    /// the process working directory when `None`.
+    #[must_use]
+    pub fn with_start_dir(start_dir: Option<PathBuf>) -> App {
         App {
             current_screen: CurrentScreen::Home,
             sidebar_index: 0,
             explorer_trigger: None,
             bitcoin_conf_path: None,
             p2pool_conf_path: None,
-            explorer: FileExplorer::new(),
+            explorer: start_dir.map_or_else(FileExplorer::new, FileExplorer::with_dir),
             bitcoin_config_view: BitcoinConfigView::new(),
             p2pool_config_view: P2PoolConfigView::new(),
             settings_view: SettingsView::new(),
             p2pool_config: None,
+            p2pool_custom: Vec::new(),
+            p2pool_origins: Vec::new(),
+            p2pool_unknown_keys: Vec::new(),
+            p2pool_store_warnings: Vec::new(),
             bitcoin_data: Vec::new(),
             bitcoin_status_tab: 0,
             settings: Settings::default(),
             home_dir: std::env::var("HOME").unwrap_or_default(),
             config_dir: crate::settings::config_dir().unwrap_or_default(),
+            viewport_height: 0,
+            sidebar_area: Rect::default(),
+            list_area: Rect::default(),
+            theme: Theme::default(),
+            theme_overrides: ThemeOverrides::default(),
+            error: None,
+            notice: None,
+            diff: None,
+            pending_save: None,
+            pending_reload: None,
+            previous_screen: CurrentScreen::Home,
+            history: Vec::new(),
+            bitcoin_edits: EditHistory::default(),
+            p2pool_edits: EditHistory::default(),
+            bitcoin_loading: None,
+            clipboard: None,
+        }
+    }
+
+    /// Builds the app with the configs named on the command line already
+    /// loaded: `--bitcoin <path>` and `--p2pool <path>`. The matching config
+    /// screen is selected, bitcoin's if both are given.
+    ///
+    /// # Errors
+    /// Returns an error if a named file is missing or does not parse as the
+    /// config it was given for.
+    pub fn with_args(bitcoin: Option<PathBuf>, p2pool: Option<PathBuf>) -> anyhow::Result<App> {
+        let mut app = App::new();
+
+        if let Some(path) = p2pool {
+            app.open_p2pool(path)?;
+            app.current_screen = CurrentScreen::P2PoolConfig;
+        }
+
+        if let Some(path) = bitcoin {
+            app.open_bitcoin(path)?;
+            app.current_screen = CurrentScreen::BitcoinConfig;
+        }
+
+        app.sync_sidebar();
+        Ok(app)
+    }
+
+    /// Loads `path` as the p2pool config.
+    fn open_p2pool(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        if !path.exists() {
+            anyhow::bail!("{} does not exist", path.display());
+        }
+        let cfg = load_p2pool_config(&path.to_string_lossy())
+            .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+        self.p2pool_origins = read_origins(&path, &cfg);
+        self.p2pool_unknown_keys = read_unknown_keys(&path, &cfg);
+        self.p2pool_config = Some(cfg);
+        self.p2pool_conf_path = Some(path);
+        self.check_store_path();
+        Ok(())
+    }
+
+    /// Loads `path` as the bitcoin.conf.
+    fn open_bitcoin(&mut self, path: PathBuf) -> anyhow::Result<()> {
+        if !path.exists() {
+            anyhow::bail!("{} does not exist", path.display());
+        }
+        let entries = parse_bitcoin_config(&path)?;
+        if !entries.iter().any(|e| e.enabled && e.schema.is_some()) {
+            anyhow::bail!("{} does not appear to be a Bitcoin config", path.display());
+        }
+        self.bitcoin_data = entries;
+        self.bitcoin_conf_path = Some(path);
+        Ok(())
+    }
+
+    /// Where this run is leaving off: the open screen, sidebar selection
+    /// and palette.
+    #[must_use]
+    pub fn session_state(&self) -> SessionState {
+        // Quitting is confirmed over the screen the user was on
+        let screen = match self.current_screen {
+            CurrentScreen::Exiting => self.previous_screen,
+            screen => screen,
+        };
+        SessionState {
+            screen: SIDEBAR_ITEMS
+                .iter()
+                .find(|(_, s)| *s == screen)
+                .map(|(label, _)| (*label).to_string()),
+            sidebar_index: self.sidebar_index,
+            theme: Some(self.theme.name.to_string()),
+        }
+    }
+
+    /// Writes [`App::session_state`] to `state.toml` for the next launch.
+    ///
+    /// # Errors
+    /// Returns an error if the state file cannot be written.
+    pub fn save_state(&self) -> anyhow::Result<()> {
+        save_session_state(&self.session_state())
+    }
+
+    /// Restores the session saved by the last run, if any; a missing or
+    /// corrupt state file leaves the defaults.
+    pub fn load_state(&mut self) {
+        if let Some(state) = load_session_state() {
+            self.restore_state(&state);
+        }
+    }
+
+    /// Applies `state` over a freshly built app. A screen selected by a
+    /// config given on the command line wins over the saved one; screens or
+    /// palettes this version doesn't know are skipped.
+    pub fn restore_state(&mut self, state: &SessionState) {
+        if let Some(theme) = state.theme.as_deref().and_then(Theme::named) {
+            self.theme = theme;
+        }
+        if self.current_screen != CurrentScreen::Home {
+            return;
+        }
+        if state.sidebar_index <= MAX_SIDEBAR_INDEX {
+            self.sidebar_index = state.sidebar_index;
+        }
+        if let Some(&(_, screen)) = SIDEBAR_ITEMS
+            .iter()
+            .find(|(label, _)| Some(*label) == state.screen.as_deref())
+        {
+            self.current_screen = screen;
+        }
+    }
+
+    /// Rows of the p2pool list: the flattened config followed by custom keys.
+    #[must_use]
+    pub fn p2pool_entries(&self) -> Vec<P2PoolConfigEntry> {
+        let mut entries = self
+            .p2pool_config
+            .as_ref()
+            .map(flatten_config)
+            .unwrap_or_default();
+        for (entry, origin) in entries.iter_mut().zip(&self.p2pool_origins) {
+            entry.origin = *origin;
+        }
+        if !entries.is_empty() {
+            entries.extend(self.p2pool_custom.iter().cloned());
+        }
+        entries
+    }
+
+    /// Why the p2pool value being edited would be rejected on commit, found
+    /// by applying it to a copy of the config so nothing changes before
+    /// Enter. Custom keys have no typed field and accept anything.
+    #[must_use]
+    pub fn p2pool_edit_error(&self) -> Option<String> {
+        let view = &self.p2pool_config_view;
+        let cfg = self.p2pool_config.as_ref().filter(|_| view.editing)?;
+        if view.selected_index >= flatten_config(cfg).len() {
+            return None;
+        }
+        apply_p2pool_edit(&mut cfg.clone(), view.selected_index, &view.edit_input).err()
+    }
+
+    /// Warnings about p2pool fields: mismatches with bitcoin.conf when both
+    /// files are loaded, then `[network]` limits that combine oddly, then a
+    /// `store.path` whose directory p2pool could not create the store in.
+    #[must_use]
+    pub fn p2pool_warnings(&self) -> Vec<Warning> {
+        self.config_warnings().p2pool()
+    }
+
+    /// Runs the checks behind [`App::p2pool_warnings`] once, flattening the
+    /// p2pool entries a single time, so a frame can share the result.
+    #[must_use]
+    pub fn config_warnings(&self) -> ConfigWarnings {
+        let entries = self.p2pool_entries();
+        let mismatches = if self.bitcoin_conf_path.is_some() && self.p2pool_conf_path.is_some() {
+            cross_validate(&self.bitcoin_data, &entries)
+        } else {
+            Vec::new()
+        };
+        ConfigWarnings {
+            mismatches,
+            network: network_field_warnings(&entries),
+            store: self.p2pool_store_warnings.clone(),
+        }
+    }
+
+    /// Recomputes [`App::p2pool_store_warnings`] against the filesystem,
+    /// resolving a relative `store.path` from pdm's working directory, where
+    /// p2pool is expected to be started.
+    pub fn check_store_path(&mut self) {
+        let working_dir = std::env::current_dir().unwrap_or_default();
+        self.p2pool_store_warnings = store_path_warnings(&self.p2pool_entries(), &working_dir);
+    }
+
+    /// Path a save of `target` would overwrite, if a file is loaded.
+    #[must_use]
+    pub fn save_path(&self, target: SaveTarget) -> Option<&PathBuf> {
+        match target {
+            SaveTarget::BitcoinConfig => self.bitcoin_conf_path.as_ref(),
+            SaveTarget::P2PoolConfig => self.p2pool_conf_path.as_ref(),
+        }
+    }
+
+    /// True when either config view holds edits that have not been saved.
+    #[must_use]
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.bitcoin_config_view.dirty || self.p2pool_config_view.dirty
+    }
+
+    /// Switches to `screen`, remembering the current one for `go_back`.
+    pub fn navigate_to(&mut self, screen: CurrentScreen) {
+        if screen != self.current_screen {
+            self.history.push(self.current_screen);
+            self.current_screen = screen;
+        }
+    }
+
+    /// Restores the most recent screen from `history`, or Home when it is
+    /// empty. The sidebar selection follows the restored screen.
+    pub fn go_back(&mut self) {
+        let screen = self.history.pop().unwrap_or(CurrentScreen::Home);
+        self.current_screen = screen;
+        self.sync_sidebar();
+    }
+
+    /// Points the sidebar highlight at the current screen, if it has an item.
+    pub fn sync_sidebar(&mut self) {
+        if let Some(index) = SIDEBAR_ITEMS
+            .iter()
+            .position(|&(_, s)| s == self.current_screen)
+        {
+            self.sidebar_index = index;
+        }
+    }
+
+    /// Drops the history entry pushed when the file explorer opened, once it
+    /// has closed onto that same screen.
+    pub fn forget_explorer_entry(&mut self) {
+        if self.history.last() == Some(&self.current_screen) {
+            self.history.pop();
         }
     }
 
@@ -145,6 +628,7 @@ impl App {
         }
     }
 }
+
 impl Default for App {
     fn default() -> Self {
         Self::new()
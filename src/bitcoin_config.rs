@@ -2,11 +2,17 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::util::atomic_write;
+use crate::warning::Warning;
 use anyhow::Result;
+use bitcoin::hashes::{Hash, HashEngine, hmac, sha256};
+use bitcoin::hex::DisplayHex;
+use bitcoin::{Amount, Denomination};
 use config::{Config, File, FileFormat};
 use std::{
     collections::{HashMap, HashSet},
-    path::Path,
+    net::{IpAddr, Ipv6Addr},
+    path::{Path, PathBuf},
 };
 
 #[allow(dead_code)]
@@ -291,7 +297,8 @@ pub struct BitcoinConfig {
 pub enum ConfigType {
     Bool,
     Int,
-    Float,
+    /// Decimal BTC value, such as a fee rate in BTC/kvB
+    Amount,
     String,
     Path,
     Address,
@@ -302,7 +309,7 @@ impl std::fmt::Display for ConfigType {
         match self {
             ConfigType::Bool => write!(f, "boolean"),
             ConfigType::Int => write!(f, "integer"),
-            ConfigType::Float => write!(f, "float"),
+            ConfigType::Amount => write!(f, "amount (BTC)"),
             ConfigType::String => write!(f, "string"),
             ConfigType::Path => write!(f, "path"),
             ConfigType::Address => write!(f, "address"),
@@ -331,6 +338,10 @@ pub struct ConfigSchema {
     pub config_type: ConfigType,
     pub category: ConfigCategory,
     pub description: String,
+    /// Inclusive lower bound for `Int` options.
+    pub min: Option<i64>,
+    /// Inclusive upper bound for `Int` options.
+    pub max: Option<i64>,
 }
 
 impl ConfigSchema {
@@ -348,8 +359,18 @@ impl ConfigSchema {
             config_type,
             category,
             description: description.to_string(),
+            min: None,
+            max: None,
         }
     }
+
+    /// Restricts an `Int` option to `min..=max`; either bound may be open.
+    #[must_use]
+    pub fn with_range(mut self, min: Option<i64>, max: Option<i64>) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
 }
 
 /// A parsed configuration entry
@@ -360,6 +381,129 @@ pub struct ConfigEntry {
     pub schema: Option<ConfigSchema>,
     pub enabled: bool,
     pub section: Option<String>,
+    /// Why the value read from disk does not fit the schema, if it doesn't.
+    pub validation_error: Option<String>,
+    /// File the value came from when several were merged by
+    /// [`parse_configs`]; `None` for a single file or a default. Saving
+    /// writes the entry back to this file.
+    pub source: Option<PathBuf>,
+}
+
+impl ConfigEntry {
+    /// An enabled, schema-less top-level entry, such as one the user adds.
+    #[must_use]
+    pub fn new(key: &str, value: &str) -> Self {
+        ConfigEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+            schema: None,
+            enabled: true,
+            section: None,
+            validation_error: None,
+            source: None,
+        }
+    }
+
+    /// Whether the value is a secret that is masked wherever it is shown.
+    #[must_use]
+    pub fn is_secret(&self) -> bool {
+        self.key == "rpcpassword"
+    }
+
+    /// Whether bitcoind reads this entry when running on `network`: values
+    /// outside any section always apply, a `[section]` only on its network.
+    #[must_use]
+    pub fn in_effect(&self, network: &str) -> bool {
+        self.section.as_deref().is_none_or(|s| s == network)
+    }
+}
+
+/// The network section bitcoind reads given the top-level `chain=`,
+/// `testnet=1`, `signet=1` or `regtest=1` among `entries`; `main` when none
+/// is set. Disabled entries and values inside sections are ignored, as
+/// bitcoind only honours these toggles at the top level.
+#[must_use]
+pub fn active_network(entries: &[ConfigEntry]) -> &str {
+    let toggle = |key: &str| {
+        entries.iter().any(|e| {
+            e.enabled
+                && e.section.is_none()
+                && e.key == key
+                && matches!(e.value.as_str(), "1" | "true")
+        })
+    };
+    if let Some(chain) = entries
+        .iter()
+        .find(|e| e.enabled && e.section.is_none() && e.key == "chain")
+    {
+        return chain.value.as_str();
+    }
+    if toggle("testnet") {
+        "test"
+    } else if toggle("signet") {
+        "signet"
+    } else if toggle("regtest") {
+        "regtest"
+    } else {
+        "main"
+    }
+}
+
+/// Networks bitcoind knows, by `-chain` name, with the name people call
+/// them by.
+const NETWORKS: [(&str, &str); 5] = [
+    ("main", "mainnet"),
+    ("test", "testnet"),
+    ("testnet4", "testnet4"),
+    ("signet", "signet"),
+    ("regtest", "regtest"),
+];
+
+/// bitcoind's RPC port for a `-chain` name when rpcport is not set.
+#[must_use]
+pub fn default_rpc_port(network: &str) -> u16 {
+    match network {
+        "test" => 18332,
+        "testnet4" => 48332,
+        "signet" => 38332,
+        "regtest" => 18443,
+        _ => 8332,
+    }
+}
+
+/// Flags an `rpcport` in effect on the [`active_network`] that is another
+/// network's default, such as `rpcport=8332` with `signet=1`: most likely a
+/// line copied from a config for the wrong network. Any other port is taken
+/// as deliberate. Like the p2pool [`Warning`]s these never block saving.
+#[must_use]
+pub fn rpc_port_warnings(entries: &[ConfigEntry]) -> Vec<Warning> {
+    let name = |chain: &str| {
+        NETWORKS
+            .iter()
+            .find(|(c, _)| *c == chain)
+            .map_or(chain.to_string(), |(_, name)| (*name).to_string())
+    };
+    let network = active_network(entries);
+    let expected = default_rpc_port(network);
+    entries
+        .iter()
+        .filter(|e| e.enabled && e.key == "rpcport" && e.in_effect(network))
+        .filter_map(|e| e.value.trim().parse::<u16>().ok())
+        .filter(|&port| port != expected)
+        .filter_map(|port| {
+            let (other, _) = NETWORKS
+                .iter()
+                .find(|(chain, _)| default_rpc_port(chain) == port)?;
+            Some(Warning {
+                field: "rpcport",
+                message: format!(
+                    "{port} looks like a {} port; {} uses {expected}",
+                    name(other),
+                    name(network)
+                ),
+            })
+        })
+        .collect()
 }
 
 /// Returns the default schema for all known bitcoin.conf options
@@ -451,28 +595,32 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
             ConfigType::Int,
             ConfigCategory::Core,
             "Database cache size in MiB",
-        ),
+        )
+        .with_range(Some(4), None),
         ConfigSchema::new(
             "maxmempool",
             "300",
             ConfigType::Int,
             ConfigCategory::Core,
             "Maximum mempool size in MiB",
-        ),
+        )
+        .with_range(Some(5), None),
         ConfigSchema::new(
             "maxorphantx",
             "100",
             ConfigType::Int,
             ConfigCategory::Core,
             "Maximum orphan transactions",
-        ),
+        )
+        .with_range(Some(0), None),
         ConfigSchema::new(
             "mempoolexpiry",
             "336",
             ConfigType::Int,
             ConfigCategory::Core,
             "Mempool expiry in hours",
-        ),
+        )
+        .with_range(Some(1), None),
         ConfigSchema::new(
             "par",
             "0",
@@ -641,7 +789,8 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
             ConfigType::Int,
             ConfigCategory::Network,
             "Maximum peer connections",
-        ),
+        )
+        .with_range(Some(0), None),
         ConfigSchema::new(
             "maxreceivebuffer",
             "5000",
@@ -669,7 +818,8 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
             ConfigType::Int,
             ConfigCategory::Network,
             "Connection timeout in milliseconds",
-        ),
+        )
+        .with_range(Some(1), None),
         ConfigSchema::new(
             "maxtimeadjustment",
             "4200",
@@ -929,7 +1079,8 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
             ConfigType::Int,
             ConfigCategory::RPC,
             "RPC worker threads",
-        ),
+        )
+        .with_range(Some(1), None),
         ConfigSchema::new(
             "rpcserialversion",
             "1",
@@ -997,42 +1148,42 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
         ConfigSchema::new(
             "fallbackfee",
             "0.00",
-            ConfigType::Float,
+            ConfigType::Amount,
             ConfigCategory::Wallet,
             "Fallback fee rate",
         ),
         ConfigSchema::new(
             "discardfee",
             "0.0001",
-            ConfigType::Float,
+            ConfigType::Amount,
             ConfigCategory::Wallet,
             "Discard fee threshold",
         ),
         ConfigSchema::new(
             "mintxfee",
             "0.00001",
-            ConfigType::Float,
+            ConfigType::Amount,
             ConfigCategory::Wallet,
             "Minimum transaction fee",
         ),
         ConfigSchema::new(
             "paytxfee",
             "0.00",
-            ConfigType::Float,
+            ConfigType::Amount,
             ConfigCategory::Wallet,
             "Transaction fee rate",
         ),
         ConfigSchema::new(
             "consolidatefeerate",
             "0.0001",
-            ConfigType::Float,
+            ConfigType::Amount,
             ConfigCategory::Wallet,
             "Consolidation fee rate",
         ),
         ConfigSchema::new(
             "maxapsfee",
             "0.00",
-            ConfigType::Float,
+            ConfigType::Amount,
             ConfigCategory::Wallet,
             "Max fee for partial spend avoidance",
         ),
@@ -1042,7 +1193,8 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
             ConfigType::Int,
             ConfigCategory::Wallet,
             "Confirmation target blocks",
-        ),
+        )
+        .with_range(Some(1), Some(1008)),
         ConfigSchema::new(
             "spendzeroconfchange",
             "1",
@@ -1070,7 +1222,8 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
             ConfigType::Int,
             ConfigCategory::Wallet,
             "Keypool size",
-        ),
+        )
+        .with_range(Some(0), None),
         ConfigSchema::new(
             "signer",
             "",
@@ -1159,7 +1312,7 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
         ConfigSchema::new(
             "maxtxfee",
             "0.10",
-            ConfigType::Float,
+            ConfigType::Amount,
             ConfigCategory::Debugging,
             "Maximum transaction fee",
         ),
@@ -1170,11 +1323,13 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
             ConfigType::Int,
             ConfigCategory::Mining,
             "Maximum block weight",
-        ),
+        )
+        // Core refuses weights above the consensus limit of 4,000,000
+        .with_range(Some(4000), Some(4_000_000)),
         ConfigSchema::new(
             "blockmintxfee",
             "0.00001",
-            ConfigType::Float,
+            ConfigType::Amount,
             ConfigCategory::Mining,
             "Minimum block transaction fee",
         ),
@@ -1182,7 +1337,7 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
         ConfigSchema::new(
             "minrelaytxfee",
             "0.00001",
-            ConfigType::Float,
+            ConfigType::Amount,
             ConfigCategory::Relay,
             "Minimum relay fee",
         ),
@@ -1199,7 +1354,8 @@ pub fn get_default_schema() -> Vec<ConfigSchema> {
             ConfigType::Int,
             ConfigCategory::Relay,
             "Maximum OP_RETURN size",
-        ),
+        )
+        .with_range(Some(0), None),
         ConfigSchema::new(
             "bytespersigop",
             "20",
@@ -1285,6 +1441,8 @@ pub fn parse_config(path: &Path) -> Result<Vec<ConfigEntry>> {
                 schema: Some(schema),
                 enabled: false,
                 section: None,
+                validation_error: None,
+                source: None,
             });
         }
         return Ok(entries);
@@ -1317,14 +1475,12 @@ pub fn parse_config(path: &Path) -> Result<Vec<ConfigEntry>> {
         }
     }
 
-    // Process known schema options
+    // Process known schema options, keeping one entry per section that sets them
     for schema in &schema_list {
         let key = &schema.key;
-        let mut value = schema.default.clone();
-        let mut enabled = false;
-        let mut entry_section: Option<String> = None;
+        let mut found: Vec<(String, Option<String>)> = Vec::new();
 
-        'find_section: for section in &sections {
+        for section in &sections {
             let lookup_key = if section.is_empty() {
                 key.clone()
             } else {
@@ -1348,25 +1504,40 @@ pub fn parse_config(path: &Path) -> Result<Vec<ConfigEntry>> {
             };
 
             if let Some(v) = resolved {
-                value = v;
-                enabled = true;
                 found_keys.insert(key.clone());
-                entry_section = if section.is_empty() {
+                let entry_section = if section.is_empty() {
                     None
                 } else {
                     Some((*section).to_string())
                 };
-                break 'find_section;
+                found.push((v, entry_section));
             }
         }
 
-        entries.push(ConfigEntry {
-            key: key.clone(),
-            value,
-            schema: Some(schema.clone()),
-            enabled,
-            section: entry_section,
-        });
+        if found.is_empty() {
+            entries.push(ConfigEntry {
+                key: key.clone(),
+                value: schema.default.clone(),
+                schema: Some(schema.clone()),
+                enabled: false,
+                section: None,
+                validation_error: None,
+                source: None,
+            });
+        }
+        for (value, section) in found {
+            let mut entry = ConfigEntry {
+                key: key.clone(),
+                value,
+                schema: Some(schema.clone()),
+                enabled: true,
+                section,
+                validation_error: None,
+                source: None,
+            };
+            entry.validation_error = validate_value(&entry, &entry.value).err();
+            entries.push(entry);
+        }
     }
 
     // Add unknown config keys (not in schema)
@@ -1393,49 +1564,831 @@ pub fn parse_config(path: &Path) -> Result<Vec<ConfigEntry>> {
                 String::new()
             };
 
+            // `nolisten=1` is bitcoind's spelling of `listen=0`
+            if let Some(base) = negated_bool(config_key, &schema_list) {
+                let negated = if matches!(value.as_str(), "1" | "true") {
+                    "0"
+                } else {
+                    "1"
+                };
+                // Only the same section's `listen` can contradict it
+                if let Some(entry) = entries
+                    .iter_mut()
+                    .find(|e| e.key == base && e.enabled && e.section == *key_section)
+                {
+                    if matches!(entry.value.as_str(), "1" | "true") != (negated == "1") {
+                        entry.validation_error = Some(format!(
+                            "{base}={} conflicts with {config_key}={value}",
+                            entry.value
+                        ));
+                    }
+                } else if let Some(entry) = entries.iter_mut().find(|e| e.key == base && !e.enabled)
+                {
+                    entry.value = negated.to_string();
+                    entry.enabled = true;
+                    entry.section.clone_from(key_section);
+                    entry.validation_error = validate_value(entry, &entry.value).err();
+                } else {
+                    // Set in other sections only: this one gets its own value
+                    let at = entries.iter().rposition(|e| e.key == base);
+                    let mut entry = ConfigEntry {
+                        schema: at.and_then(|i| entries[i].schema.clone()),
+                        section: key_section.clone(),
+                        ..ConfigEntry::new(base, negated)
+                    };
+                    entry.validation_error = validate_value(&entry, &entry.value).err();
+                    entries.insert(at.map_or(entries.len(), |i| i + 1), entry);
+                }
+                continue;
+            }
+
             entries.push(ConfigEntry {
                 key: config_key.clone(),
                 value,
                 schema: None,
                 enabled: true,
                 section: key_section.clone(),
+                validation_error: None,
+                source: None,
             });
         }
     }
 
+    flag_conflicts(&mut entries);
     Ok(entries)
 }
 
+/// Parses each of `paths` with [`parse_config`] and layers the results, so a
+/// key set in a later file, such as a drop-in, overrides the same key in the
+/// same section of an earlier one. A repeatable key such as `addnode` is
+/// overridden as a whole: the later file's values replace every value the
+/// earlier files gave it. Every value that was set records the file it came
+/// from in `source`; keys no file sets stay at their defaults.
+///
+/// # Errors
+/// Returns an error if any of the files cannot be read.
+pub fn parse_configs(paths: &[&Path]) -> Result<Vec<ConfigEntry>> {
+    let mut merged: Vec<ConfigEntry> = Vec::new();
+    for path in paths {
+        // Keys and sections this file has already taken over
+        let mut taken: Vec<(String, Option<String>)> = Vec::new();
+        for mut entry in parse_config(path)? {
+            if !entry.enabled {
+                if !merged.iter().any(|e| e.key == entry.key) {
+                    merged.push(entry);
+                }
+                continue;
+            }
+            entry.source = Some(path.to_path_buf());
+            let slot = (entry.key.clone(), entry.section.clone());
+            if taken.contains(&slot) {
+                // Another value of the same key goes after this file's others
+                let last = merged.iter().rposition(|e| {
+                    e.key == entry.key
+                        && e.section == entry.section
+                        && e.source.as_deref() == Some(*path)
+                });
+                merged.insert(last.map_or(merged.len(), |i| i + 1), entry);
+                continue;
+            }
+            taken.push(slot);
+
+            // Drop the default and every value earlier files gave this key
+            let replaced =
+                |e: &ConfigEntry| e.key == entry.key && (!e.enabled || e.section == entry.section);
+            let first = merged.iter().position(replaced);
+            merged.retain(|e| !replaced(e));
+            let at = first
+                .or_else(|| {
+                    merged
+                        .iter()
+                        .rposition(|e| e.key == entry.key)
+                        .map(|i| i + 1)
+                })
+                .unwrap_or(merged.len());
+            merged.insert(at, entry);
+        }
+    }
+    flag_conflicts(&mut merged);
+    Ok(merged)
+}
+
+/// Parses the bitcoin.conf at `path` together with the files its
+/// `includeconf` lines name, layered with [`parse_configs`] so an included
+/// file overrides the main one, as bitcoind reads them. Relative include
+/// paths are resolved against the directory of `path`. Without includes
+/// this is [`parse_config`].
+///
+/// # Errors
+/// Returns an error if `path` or one of its included files cannot be read.
+pub fn parse_config_with_includes(path: &Path) -> Result<Vec<ConfigEntry>> {
+    let entries = parse_config(path)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let includes: Vec<PathBuf> = entries
+        .iter()
+        .filter(|e| e.enabled && e.key == "includeconf" && e.section.is_none())
+        .map(|e| dir.join(&e.value))
+        .collect();
+    if includes.is_empty() {
+        return Ok(entries);
+    }
+    let mut paths = vec![path];
+    paths.extend(includes.iter().map(PathBuf::as_path));
+    parse_configs(&paths)
+}
+
+/// The boolean option a `noKEY` setting negates, such as `listen` for
+/// `nolisten`, or `None` when `key` is not such a spelling.
+fn negated_bool<'a>(key: &'a str, schema: &[ConfigSchema]) -> Option<&'a str> {
+    let base = key.strip_prefix("no")?;
+    schema
+        .iter()
+        .any(|s| s.key == base && s.config_type == ConfigType::Bool)
+        .then_some(base)
+}
+
+/// Key of an entry PDM keeps only while editing: its value is the path of a
+/// file holding the RPC password, which is read and written as `rpcpassword`
+/// when the config is saved, so the password is never typed into PDM.
+pub const PASSWORD_FILE_KEY: &str = "rpcpassword@file";
+
+/// The password stored in the file at `path`, without its trailing newline.
+///
+/// # Errors
+/// Returns a short human-readable reason when the file cannot be read, is
+/// empty, or holds more than one line.
+pub fn read_password_file(path: &Path) -> std::result::Result<String, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+    let password = text.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        Err(format!("{} is empty", path.display()))
+    } else if password.contains('\n') {
+        Err(format!("{} holds more than one line", path.display()))
+    } else {
+        Ok(password.to_string())
+    }
+}
+
+/// Replaces each enabled [`PASSWORD_FILE_KEY`] entry with an `rpcpassword`
+/// entry holding the password read from its file. An `rpcpassword` already
+/// in the same section takes the new value in place, so the file keeps a
+/// single password line. Disabled file entries are dropped.
+///
+/// # Errors
+/// Returns an error naming the entry when its file cannot be read.
+fn resolve_password_files(entries: &[ConfigEntry]) -> Result<Vec<ConfigEntry>> {
+    let mut resolved: Vec<ConfigEntry> = entries
+        .iter()
+        .filter(|e| e.key != PASSWORD_FILE_KEY)
+        .cloned()
+        .collect();
+    for entry in entries
+        .iter()
+        .filter(|e| e.enabled && e.key == PASSWORD_FILE_KEY)
+    {
+        let password = read_password_file(Path::new(entry.value.trim()))
+            .map_err(|e| anyhow::anyhow!("{PASSWORD_FILE_KEY}: {e}"))?;
+        if let Some(existing) = resolved
+            .iter_mut()
+            .find(|e| e.key == "rpcpassword" && e.section == entry.section)
+        {
+            existing.value = password;
+            existing.enabled = true;
+        } else {
+            resolved.push(ConfigEntry {
+                key: "rpcpassword".to_string(),
+                value: password,
+                schema: None,
+                enabled: true,
+                section: entry.section.clone(),
+                validation_error: None,
+                source: None,
+            });
+        }
+    }
+    Ok(resolved)
+}
+
+/// Reason attached to `prune` and `txindex` when both are turned on.
+pub const PRUNE_TXINDEX_CONFLICT: &str =
+    "prune and txindex are mutually exclusive; bitcoind will not start with both";
+
+/// Reason attached to an entry that repeats an earlier line word for word,
+/// such as a duplicated entry whose value was not changed yet.
+pub const DUPLICATE_ENTRY_CONFLICT: &str =
+    "same key and value as an earlier line; change or remove one";
+
+/// Flags entries whose values are fine alone but clash with another entry.
+///
+/// Runs over the whole list, so it must be re-run after any edit that could
+/// create or resolve a clash. A conflict reason set by an earlier run is
+/// cleared first; per-value errors from [`validate_value`] take precedence.
+/// Checked today are `prune > 0` with `txindex=1` where both apply to one
+/// network (the same section, or either at the top level), and enabled
+/// entries that repeat an earlier one's key, section and value.
+pub fn flag_conflicts(entries: &mut [ConfigEntry]) {
+    for entry in entries.iter_mut() {
+        if matches!(
+            entry.validation_error.as_deref(),
+            Some(PRUNE_TXINDEX_CONFLICT | DUPLICATE_ENTRY_CONFLICT)
+        ) {
+            entry.validation_error = None;
+        }
+    }
+
+    for i in 1..entries.len() {
+        let (earlier, rest) = entries.split_at_mut(i);
+        let entry = &mut rest[0];
+        let repeated = entry.enabled
+            && earlier.iter().any(|e| {
+                e.enabled
+                    && e.key == entry.key
+                    && e.section == entry.section
+                    && e.value == entry.value
+            });
+        if repeated {
+            entry
+                .validation_error
+                .get_or_insert_with(|| DUPLICATE_ENTRY_CONFLICT.to_string());
+        }
+    }
+
+    let pruned = |e: &ConfigEntry| {
+        e.enabled && e.key == "prune" && e.value.parse::<i64>().is_ok_and(|n| n > 0)
+    };
+    let indexed = |e: &ConfigEntry| {
+        e.enabled && e.key == "txindex" && matches!(e.value.as_str(), "1" | "true")
+    };
+    let sections = |matches: &dyn Fn(&ConfigEntry) -> bool| -> Vec<Option<String>> {
+        entries
+            .iter()
+            .filter(|e| matches(e))
+            .map(|e| e.section.clone())
+            .collect()
+    };
+    let (pruned_in, indexed_in) = (sections(&pruned), sections(&indexed));
+    // Top-level settings apply to every network's section
+    let overlaps = |section: &Option<String>, others: &[Option<String>]| {
+        others
+            .iter()
+            .any(|other| section.is_none() || other.is_none() || other == section)
+    };
+    for entry in entries.iter_mut() {
+        if (pruned(&*entry) && overlaps(&entry.section, &indexed_in))
+            || (indexed(&*entry) && overlaps(&entry.section, &pruned_in))
+        {
+            entry
+                .validation_error
+                .get_or_insert_with(|| PRUNE_TXINDEX_CONFLICT.to_string());
+        }
+    }
+}
+
+/// Checks that `value` is acceptable for `entry` before it is committed.
+///
+/// Booleans must be `0`, `1`, `true` or `false`, integers must parse and
+/// respect the schema's range (named in full when bounded on both sides),
+/// amounts must be a non-negative BTC value with at most eight decimals,
+/// keys naming a port must be in `1..=65535`, and addresses with a `:port`
+/// suffix must carry a valid port. A [`PASSWORD_FILE_KEY`] entry must name
+/// a readable, non-empty file. Other entries without a schema accept any
+/// value.
+///
+/// # Errors
+/// Returns a short human-readable reason when the value is rejected.
+pub fn validate_value(entry: &ConfigEntry, value: &str) -> std::result::Result<(), String> {
+    if entry.key == PASSWORD_FILE_KEY {
+        return read_password_file(Path::new(value.trim())).map(|_| ());
+    }
+    let Some(schema) = &entry.schema else {
+        return Ok(());
+    };
+
+    match entry.key.as_str() {
+        "proxy" | "onion" => return validate_proxy(value),
+        // bitcoind reads one network per line and fails on a list
+        "rpcallowip" if value.contains(',') => {
+            return Err("one network per line; add another rpcallowip for each".to_string());
+        }
+        "rpcallowip" => return validate_subnet(value.trim()),
+        _ => {}
+    }
+
+    match schema.config_type {
+        ConfigType::Bool if !matches!(value, "0" | "1" | "true" | "false") => {
+            Err("must be 0, 1, true or false".to_string())
+        }
+        ConfigType::Int if entry.key.ends_with("port") => parse_port(value),
+        ConfigType::Int => match value.parse::<i64>() {
+            Err(_) if value.starts_with(|c: char| c.is_ascii_digit()) => {
+                Err("must be a plain integer without a unit suffix".to_string())
+            }
+            Err(_) => Err("must be an integer".to_string()),
+            // Bounded on both sides, the error names the whole range
+            Ok(n)
+                if matches!((schema.min, schema.max),
+                    (Some(min), Some(max)) if !(min..=max).contains(&n)) =>
+            {
+                Err(format!(
+                    "must be between {} and {}",
+                    schema.min.unwrap_or_default(),
+                    schema.max.unwrap_or_default()
+                ))
+            }
+            Ok(n) if schema.min.is_some_and(|min| n < min) => Err(format!(
+                "must be at least {}",
+                schema.min.unwrap_or_default()
+            )),
+            Ok(n) if schema.max.is_some_and(|max| n > max) => Err(format!(
+                "must be at most {}",
+                schema.max.unwrap_or_default()
+            )),
+            Ok(_) => Ok(()),
+        },
+        ConfigType::Amount => Amount::from_str_in(value, Denomination::Bitcoin)
+            .map(|_| ())
+            .map_err(|_| amount_error(value)),
+        ConfigType::Address if value.trim().is_empty() => {
+            Err("address cannot be empty".to_string())
+        }
+        ConfigType::Address => match value.rsplit_once(':') {
+            // Bare IPv6 addresses contain colons but no port
+            Some((host, port)) if !host.contains(':') || host.ends_with(']') => parse_port(port),
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Accepts a TCP port other than 0.
+fn parse_port(port: &str) -> std::result::Result<(), String> {
+    match port.parse::<u16>() {
+        Ok(p) if p > 0 => Ok(()),
+        _ => Err(format!("{port} is not a valid port (1-65535)")),
+    }
+}
+
+/// Checks a `proxy` or `onion` value in the forms bitcoind accepts: a
+/// SOCKS5 host with an optional port (`127.0.0.1`, `tor.lan:9050`,
+/// `[::1]:9050`; 9050 when omitted), a Unix socket (`unix:/run/tor/socks`),
+/// or `0` to turn the proxy off.
+fn validate_proxy(value: &str) -> std::result::Result<(), String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("address cannot be empty".to_string());
+    }
+    if value == "0" {
+        return Ok(());
+    }
+    if let Some(path) = value.strip_prefix("unix:") {
+        return if path.is_empty() {
+            Err("unix: needs a socket path, e.g. unix:/run/tor/socks".to_string())
+        } else {
+            Ok(())
+        };
+    }
+    if let Some(rest) = value.strip_prefix('[') {
+        let (host, port) = match rest.split_once(']') {
+            Some((host, "")) => (host, None),
+            Some((host, port)) => match port.strip_prefix(':') {
+                Some(port) => (host, Some(port)),
+                None => return Err("must be host:port, e.g. [::1]:9050".to_string()),
+            },
+            None => return Err("must be host:port, e.g. [::1]:9050".to_string()),
+        };
+        if host.parse::<Ipv6Addr>().is_err() {
+            return Err(format!("{host} is not an IPv6 address"));
+        }
+        return port.map_or(Ok(()), parse_port);
+    }
+    match value.rsplit_once(':') {
+        Some((host, _)) if host.contains(':') => {
+            Err("put an IPv6 host in brackets, e.g. [::1]:9050".to_string())
+        }
+        Some((host, port)) if !host.is_empty() => parse_port(port),
+        Some(_) => Err("must be host:port, e.g. 127.0.0.1:9050".to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Checks one `rpcallowip` value: a single IP (`10.0.0.5`, `::1`), a
+/// network/CIDR (`192.168.0.0/24`) or a network/netmask
+/// (`192.168.0.0/255.255.255.0`), the forms bitcoind accepts.
+fn validate_subnet(value: &str) -> std::result::Result<(), String> {
+    let (addr, mask) = match value.split_once('/') {
+        Some((addr, mask)) => (addr, Some(mask)),
+        None => (value, None),
+    };
+    let Ok(addr) = addr.parse::<IpAddr>() else {
+        return Err(format!(
+            "{value} is not an IP or network, e.g. 192.168.0.0/24 or ::1"
+        ));
+    };
+    let Some(mask) = mask else {
+        return Ok(());
+    };
+    let max_bits = if addr.is_ipv4() { 32 } else { 128 };
+    if let Ok(bits) = mask.parse::<u8>() {
+        return if bits <= max_bits {
+            Ok(())
+        } else {
+            Err(format!("/{bits} is longer than the {max_bits}-bit address"))
+        };
+    }
+    match mask.parse::<IpAddr>() {
+        Ok(netmask) if netmask.is_ipv4() == addr.is_ipv4() => Ok(()),
+        _ => Err(format!(
+            "{mask} is not a prefix length (0-{max_bits}) or netmask"
+        )),
+    }
+}
+
+/// Says why `value` is not a BTC amount, naming the common mistakes: a comma
+/// as decimal separator, a sign, or more than eight decimal places.
+fn amount_error(value: &str) -> String {
+    let value = value.trim();
+    if value.starts_with('-') {
+        "cannot be negative".to_string()
+    } else if value.contains(',') {
+        "use '.' as the decimal separator, e.g. 0.0001".to_string()
+    } else if value
+        .split_once('.')
+        .is_some_and(|(_, frac)| frac.len() > 8 && frac.chars().all(|c| c.is_ascii_digit()))
+    {
+        "at most 8 decimal places".to_string()
+    } else {
+        "must be a BTC amount such as 0.0001".to_string()
+    }
+}
+
+/// Checks that `key` can be written as a new `key=value` line: it must be
+/// non-empty and free of `=`, `#`, brackets and whitespace.
+///
+/// # Errors
+/// Returns a short human-readable reason when the key is rejected.
+pub fn validate_new_key(key: &str) -> std::result::Result<(), String> {
+    if key.is_empty() {
+        Err("key cannot be empty".to_string())
+    } else if key.contains('=') {
+        Err("key cannot contain '='".to_string())
+    } else if key
+        .chars()
+        .any(|c| c.is_whitespace() || matches!(c, '#' | '[' | ']'))
+    {
+        Err("key cannot contain spaces, '#' or brackets".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Builds the `rpcauth` value `user:salt$hash` the way Bitcoin Core's
+/// `rpcauth.py` does: the hash is HMAC-SHA256 keyed by the hex salt string
+/// over the password.
+#[must_use]
+pub fn rpcauth_value(user: &str, salt: &str, password: &str) -> String {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(salt.as_bytes());
+    engine.input(password.as_bytes());
+    let hash = hmac::Hmac::<sha256::Hash>::from_engine(engine);
+    format!("{user}:{salt}${hash}")
+}
+
+/// Generates an `rpcauth=...` line for `user` with a random 16-byte salt.
+/// When `password` is `None` a random 32-byte hex password is generated.
+///
+/// Returns the line and the password it was built from; the password is
+/// not recoverable from the line, so callers must show it to the user.
+///
+/// # Errors
+/// Returns an error if the system random number generator fails.
+pub fn generate_rpcauth(user: &str, password: Option<&str>) -> Result<(String, String)> {
+    let random_hex = |len: usize| -> Result<String> {
+        let mut bytes = vec![0u8; len];
+        getrandom::fill(&mut bytes)
+            .map_err(|e| anyhow::anyhow!("random number generator failed: {e}"))?;
+        Ok(bytes.to_lower_hex_string())
+    };
+    let salt = random_hex(16)?;
+    let password = match password {
+        Some(p) => p.to_string(),
+        None => random_hex(32)?,
+    };
+    let line = format!("rpcauth={}", rpcauth_value(user, &salt, &password));
+    Ok((line, password))
+}
+
+/// Groups `entries` by section, `None` being the top level, with sections
+/// in the order they first appear and entries in their original order
+/// within each.
+#[must_use]
+pub fn group_by_section<'a>(
+    entries: impl IntoIterator<Item = &'a ConfigEntry>,
+) -> Vec<(Option<String>, Vec<&'a ConfigEntry>)> {
+    let mut groups: Vec<(Option<String>, Vec<&'a ConfigEntry>)> = Vec::new();
+    for entry in entries {
+        match groups
+            .iter_mut()
+            .find(|(section, _)| *section == entry.section)
+        {
+            Some((_, group)) => group.push(entry),
+            None => groups.push((entry.section.clone(), vec![entry])),
+        }
+    }
+    groups
+}
+
+/// Exports entries as a JSON array for scripting, one object per entry:
+/// `{section, key, value, enabled, is_default}`. `section` is `null` for
+/// top-level keys, and `is_default` is true when the value equals the
+/// schema default. `rpcpassword` and `rpcauth` values are replaced with
+/// `*****` unless `include_secrets` is set.
+#[must_use]
+pub fn config_to_json(entries: &[ConfigEntry], include_secrets: bool) -> serde_json::Value {
+    entries
+        .iter()
+        .map(|entry| {
+            let value = if !include_secrets && (entry.is_secret() || entry.key == "rpcauth") {
+                "*****"
+            } else {
+                entry.value.as_str()
+            };
+            serde_json::json!({
+                "section": entry.section,
+                "key": entry.key,
+                "value": value,
+                "enabled": entry.enabled,
+                "is_default": entry
+                    .schema
+                    .as_ref()
+                    .is_some_and(|schema| schema.default == entry.value),
+            })
+        })
+        .collect()
+}
+
+/// Renders a starter bitcoin.conf listing every known option, grouped by
+/// category. Each option is commented out at its default value, under a
+/// comment with its description, so the file changes nothing until edited.
+#[must_use]
+pub fn default_config_string() -> String {
+    let mut out = String::from(
+        "# bitcoin.conf generated by PDM\n\
+         # Every option is commented out at its default; uncomment to change it.\n",
+    );
+    let mut category = None;
+    for schema in get_default_schema() {
+        if category != Some(schema.category) {
+            category = Some(schema.category);
+            out.push_str(&format!("\n# --- {:?} ---\n", schema.category));
+        }
+        out.push_str(&format!(
+            "\n# {}\n#{}={}\n",
+            schema.description, schema.key, schema.default
+        ));
+    }
+    out
+}
+
 /// Writes enabled entries back to the config file
 ///
 /// # Errors
-/// Returns an error if the file cannot be created or written.
+/// Returns an error if a password file cannot be read, or the file cannot be
+/// created or written.
 pub fn save_config(path: &Path, entries: &[ConfigEntry]) -> Result<()> {
-    use std::collections::BTreeMap;
-    use std::io::Write;
+    let enabled: Vec<ConfigEntry> = resolve_password_files(entries)?
+        .into_iter()
+        .filter(|e| e.enabled)
+        .collect();
+    atomic_write(path, &render_config(&enabled, false))
+}
 
-    let mut file = std::fs::File::create(path)?;
-    let mut sectioned: BTreeMap<String, Vec<&ConfigEntry>> = BTreeMap::new();
+/// Serializes entries back to bitcoin.conf, keeping disabled entries as
+/// `#key=value` comments.
+///
+/// If `path` already exists its text is used as the base: comments, blank
+/// lines and ordering survive, and only the lines of entries are rewritten,
+/// so a comment above a key stays with it. Entries the file does not mention
+/// are appended to their section. Disabled entries still at their schema
+/// default are omitted; use [`write_config_with`] to keep them as comments
+/// too. The file is replaced atomically so an interrupted write never leaves
+/// a truncated config.
+///
+/// An enabled [`PASSWORD_FILE_KEY`] entry is written as `rpcpassword` with
+/// the password read from its file. Entries whose `source` is another file,
+/// such as one pulled in by `includeconf`, are written to that file instead.
+///
+/// # Errors
+/// Returns an error if the existing file or a password file cannot be read,
+/// or the temporary file cannot be written or renamed.
+pub fn write_config(path: &Path, entries: &[ConfigEntry]) -> Result<()> {
+    write_config_with(path, entries, false)
+}
 
-    for entry in entries {
-        if !entry.enabled {
-            continue;
+/// Like [`write_config`], but when `comment_defaults` is set disabled entries
+/// at their schema default are written as comments instead of being omitted.
+///
+/// # Errors
+/// Returns an error if the existing file or a password file cannot be read,
+/// or the temporary file cannot be written or renamed.
+pub fn write_config_with(
+    path: &Path,
+    entries: &[ConfigEntry],
+    comment_defaults: bool,
+) -> Result<()> {
+    let entries = resolve_password_files(entries)?;
+    for target in config_files(path, &entries) {
+        let own: Vec<ConfigEntry> = entries
+            .iter()
+            .filter(|e| e.source.as_deref().unwrap_or(path) == target)
+            .cloned()
+            .collect();
+        let original = if target.exists() {
+            std::fs::read_to_string(&target)?
+        } else {
+            String::new()
+        };
+        atomic_write(
+            &target,
+            &render_config_over(&original, &own, comment_defaults && target == path),
+        )?;
+    }
+    Ok(())
+}
+
+/// `path` followed by every other file an entry came from, in first-seen
+/// order: the files a save of `entries` writes to.
+#[must_use]
+pub fn config_files(path: &Path, entries: &[ConfigEntry]) -> Vec<PathBuf> {
+    let mut files = vec![path.to_path_buf()];
+    for source in entries.iter().filter_map(|e| e.source.as_ref()) {
+        if !files.contains(source) {
+            files.push(source.clone());
+        }
+    }
+    files
+}
+
+/// One line of bitcoin.conf as seen by [`render_config_over`].
+enum ConfLine<'a> {
+    Section(&'a str),
+    Setting(&'a str),
+    /// A `#key=value` line, i.e. a setting someone turned off.
+    Commented(&'a str),
+    Other,
+}
+
+fn classify_line(line: &str) -> ConfLine<'_> {
+    fn setting_key(text: &str) -> Option<&str> {
+        let (key, _) = text.split_once('=')?;
+        let key = key.trim();
+        validate_new_key(key).is_ok().then_some(key)
+    }
+
+    let trimmed = line.trim();
+    if let Some(name) = trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        ConfLine::Section(name.trim())
+    } else if let Some(rest) = trimmed.strip_prefix('#') {
+        setting_key(rest.trim_start()).map_or(ConfLine::Other, ConfLine::Commented)
+    } else {
+        setting_key(trimmed).map_or(ConfLine::Other, ConfLine::Setting)
+    }
+}
+
+/// The line written for `entry`, or `None` when it should be left out.
+fn entry_line(entry: &ConfigEntry, comment_defaults: bool) -> Option<String> {
+    if entry.enabled {
+        return Some(format!("{}={}", entry.key, entry.value));
+    }
+    let is_default = entry
+        .schema
+        .as_ref()
+        .is_some_and(|schema| schema.default == entry.value);
+    (comment_defaults || !is_default).then(|| format!("#{}={}", entry.key, entry.value))
+}
+
+/// Renders entries as INI text: top-level entries first, then one block per
+/// named network section in alphabetical order.
+fn render_config(entries: &[ConfigEntry], comment_defaults: bool) -> String {
+    render_config_over("", entries, comment_defaults)
+}
+
+/// Renders entries on top of `original`, the file's current text.
+///
+/// Each entry replaces the first line that sets its key in its section,
+/// falling back to a commented-out copy of the key. Lines that match no
+/// entry, such as comments or repeated keys, are kept verbatim. Entries
+/// left over are appended to the end of their section, and sections the
+/// file lacks follow in alphabetical order. A `noKEY` line that
+/// [`parse_config`] merged into `KEY` is rewritten as `KEY` in place.
+fn render_config_over(original: &str, entries: &[ConfigEntry], comment_defaults: bool) -> String {
+    let schema = get_default_schema();
+    let find = |section: Option<&str>, key: &str, placed: &[bool]| {
+        entries
+            .iter()
+            .enumerate()
+            .position(|(i, e)| !placed[i] && e.key == key && e.section.as_deref() == section)
+    };
+    // `nolisten` stands for `listen` unless an entry still carries it as is
+    let canonical = |key| match negated_bool(key, &schema) {
+        Some(base) if !entries.iter().any(|e| e.key == key) => base,
+        _ => key,
+    };
+
+    // Keys already set on a live line keep their commented copies untouched
+    let mut live: HashSet<(Option<&str>, &str)> = HashSet::new();
+    let mut section = None;
+    for line in original.lines() {
+        match classify_line(line) {
+            ConfLine::Section(name) => section = Some(name),
+            ConfLine::Setting(key) => {
+                live.insert((section, canonical(key)));
+            }
+            _ => {}
         }
-        match &entry.section {
-            None => writeln!(file, "{}={}", entry.key, entry.value)?,
-            Some(s) => sectioned.entry(s.clone()).or_default().push(entry),
+    }
+
+    // Output blocks in file order; the first holds top-level lines
+    let mut blocks: Vec<(Option<&str>, Vec<String>)> = vec![(None, Vec::new())];
+    let mut placed = vec![false; entries.len()];
+    for line in original.lines() {
+        let section = blocks.last().and_then(|(s, _)| *s);
+        let index = match classify_line(line) {
+            ConfLine::Section(name) => {
+                blocks.push((Some(name), vec![line.to_string()]));
+                continue;
+            }
+            ConfLine::Setting(key) => find(section, canonical(key), &placed),
+            ConfLine::Commented(key) if !live.contains(&(section, key)) => {
+                find(section, key, &placed)
+            }
+            _ => None,
+        };
+        let lines = &mut blocks.last_mut().expect("top-level block").1;
+        match index {
+            Some(i) => {
+                placed[i] = true;
+                match entry_line(&entries[i], comment_defaults) {
+                    Some(l) => lines.push(l),
+                    // A commented key that stays off keeps the user's wording
+                    None if line.trim_start().starts_with('#') => lines.push(line.to_string()),
+                    None => {}
+                }
+            }
+            None => lines.push(line.to_string()),
         }
     }
 
-    // Write each named section
-    for (section, section_entries) in &sectioned {
-        writeln!(file, "\n[{section}]")?;
-        for entry in section_entries {
-            writeln!(file, "{}={}", entry.key, entry.value)?;
+    // Append what the file did not mention, before each block's trailing blanks
+    let mut new_sections: Vec<&str> = Vec::new();
+    let unplaced = entries.iter().zip(&placed).filter(|&(_, p)| !p);
+    for (_, group) in group_by_section(unplaced.map(|(e, _)| e)) {
+        let added: Vec<String> = group
+            .iter()
+            .filter_map(|e| entry_line(e, comment_defaults))
+            .collect();
+        if added.is_empty() {
+            continue;
+        }
+        let section = group[0].section.as_deref();
+        if let Some(name) = section
+            && !blocks.iter().any(|(b, _)| *b == section)
+        {
+            new_sections.push(name);
+            blocks.push((section, vec![format!("[{name}]")]));
+        }
+        if let Some((_, lines)) = blocks.iter_mut().find(|(b, _)| *b == section) {
+            let at = lines.len()
+                - lines
+                    .iter()
+                    .rev()
+                    .take_while(|l| l.trim().is_empty())
+                    .count();
+            lines.splice(at..at, added);
         }
     }
+    // Sections created above go last, alphabetically, set off by a blank line
+    let (mut added, mut blocks): (Vec<_>, Vec<_>) = blocks
+        .into_iter()
+        .partition(|(b, _)| b.is_some_and(|s| new_sections.contains(&s)));
+    added.sort_by_key(|(b, _)| *b);
+    for (_, mut lines) in added {
+        let after_blank = blocks
+            .iter()
+            .rev()
+            .find_map(|(_, l)| l.last())
+            .is_some_and(|l| l.trim().is_empty());
+        if !after_blank {
+            lines.insert(0, String::new());
+        }
+        blocks.push((None, lines));
+    }
 
-    Ok(())
+    let mut out = String::new();
+    for line in blocks.into_iter().flat_map(|(_, lines)| lines) {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
 }
 
 #[cfg(test)]
@@ -1533,7 +2486,7 @@ mod tests {
         assert_eq!(dbcache.config_type, ConfigType::Int);
 
         let fallbackfee = schema.iter().find(|s| s.key == "fallbackfee").unwrap();
-        assert_eq!(fallbackfee.config_type, ConfigType::Float);
+        assert_eq!(fallbackfee.config_type, ConfigType::Amount);
 
         let datadir = schema.iter().find(|s| s.key == "datadir").unwrap();
         assert_eq!(datadir.config_type, ConfigType::Path);
@@ -1542,11 +2495,54 @@ mod tests {
         assert_eq!(rpcbind.config_type, ConfigType::Address);
     }
 
-    // Tests for ConfigSchema::new()
+    #[test]
+    fn get_default_schema_has_expected_defaults() {
+        let schema = get_default_schema();
+        let find = |key: &str| schema.iter().find(|s| s.key == key).unwrap();
+
+        for (key, default, config_type) in [
+            ("txindex", "0", ConfigType::Bool),
+            ("rpcport", "8332", ConfigType::Int),
+            ("dbcache", "450", ConfigType::Int),
+            ("fallbackfee", "0.00", ConfigType::Amount),
+            ("maxtxfee", "0.10", ConfigType::Amount),
+        ] {
+            assert_eq!(find(key).default, default, "{key} default");
+            assert_eq!(find(key).config_type, config_type, "{key} type");
+        }
+    }
 
     #[test]
-    fn config_schema_new_creates_correct_schema() {
-        let schema = ConfigSchema::new(
+    fn get_default_schema_keys_are_unique_and_defaults_valid() {
+        let schema = get_default_schema();
+        let mut keys: Vec<&str> = schema.iter().map(|s| s.key.as_str()).collect();
+        keys.sort_unstable();
+        let before = keys.len();
+        keys.dedup();
+        assert_eq!(keys.len(), before, "duplicate schema keys");
+
+        // Every non-empty default must satisfy its own type
+        for s in schema.iter().filter(|s| !s.default.is_empty()) {
+            let entry = ConfigEntry {
+                enabled: false,
+                schema: Some(s.clone()),
+                ..ConfigEntry::new(&s.key, &s.default)
+            };
+            assert!(
+                validate_value(&entry, &s.default).is_ok(),
+                "{} default {:?} is not a valid {}",
+                s.key,
+                s.default,
+                s.config_type
+            );
+        }
+    }
+
+    // Tests for ConfigSchema::new()
+
+    #[test]
+    fn config_schema_new_creates_correct_schema() {
+        let schema = ConfigSchema::new(
             "testkey",
             "testdefault",
             ConfigType::String,
@@ -1624,6 +2620,61 @@ mod tests {
         assert!(server.enabled);
     }
 
+    #[test]
+    fn parse_config_merges_negated_bool_options() {
+        let (_dir, path) = create_temp_config("nolisten=1\n");
+        let entries = parse_config(&path).unwrap();
+
+        assert!(entries.iter().all(|e| e.key != "nolisten"));
+        let listen = entries.iter().find(|e| e.key == "listen").unwrap();
+        assert_eq!(listen.value, "0");
+        assert!(listen.enabled);
+        assert!(listen.validation_error.is_none());
+
+        let (_dir, path) = create_temp_config("nolisten=0\n");
+        let entries = parse_config(&path).unwrap();
+        let listen = entries.iter().find(|e| e.key == "listen").unwrap();
+        assert_eq!(listen.value, "1");
+    }
+
+    #[test]
+    fn negated_bool_options_stay_in_their_section() {
+        let (_dir, path) = create_temp_config("nolisten=1\n[test]\nlisten=1\n");
+        let entries = parse_config(&path).unwrap();
+
+        let listen: Vec<(Option<&str>, &str)> = entries
+            .iter()
+            .filter(|e| e.key == "listen")
+            .map(|e| (e.section.as_deref(), e.value.as_str()))
+            .collect();
+        assert_eq!(listen, [(Some("test"), "1"), (None, "0")]);
+        assert!(
+            entries
+                .iter()
+                .filter(|e| e.key == "listen")
+                .all(|e| e.enabled && e.validation_error.is_none() && e.schema.is_some())
+        );
+    }
+
+    #[test]
+    fn parse_config_flags_an_option_contradicting_its_negation() {
+        let (_dir, path) = create_temp_config("listen=1\nnolisten=1\n");
+        let entries = parse_config(&path).unwrap();
+
+        assert!(entries.iter().all(|e| e.key != "nolisten"));
+        let listen = entries.iter().find(|e| e.key == "listen").unwrap();
+        assert_eq!(
+            listen.validation_error.as_deref(),
+            Some("listen=1 conflicts with nolisten=1")
+        );
+
+        // Agreeing spellings are not a conflict
+        let (_dir, path) = create_temp_config("listen=0\nnolisten=1\n");
+        let entries = parse_config(&path).unwrap();
+        let listen = entries.iter().find(|e| e.key == "listen").unwrap();
+        assert!(listen.validation_error.is_none());
+    }
+
     #[test]
     fn parse_config_parses_int_values() {
         let (_dir, path) = create_temp_config("dbcache=1000\nport=8334\n");
@@ -1638,6 +2689,211 @@ mod tests {
         assert!(port.enabled);
     }
 
+    #[test]
+    fn parse_config_flags_out_of_range_ports() {
+        let (_dir, path) = create_temp_config("port=0\nrpcport=70000\n");
+        let entries = parse_config(&path).unwrap();
+
+        let port = entries.iter().find(|e| e.key == "port").unwrap();
+        assert_eq!(
+            port.validation_error.as_deref(),
+            Some("0 is not a valid port (1-65535)")
+        );
+        let rpcport = entries.iter().find(|e| e.key == "rpcport").unwrap();
+        assert_eq!(
+            rpcport.validation_error.as_deref(),
+            Some("70000 is not a valid port (1-65535)")
+        );
+    }
+
+    #[test]
+    fn parse_config_flags_prune_with_txindex_on_both_entries() {
+        let (_dir, path) = create_temp_config("prune=550\ntxindex=1\n");
+        let entries = parse_config(&path).unwrap();
+
+        for key in ["prune", "txindex"] {
+            let entry = entries.iter().find(|e| e.key == key).unwrap();
+            assert_eq!(
+                entry.validation_error.as_deref(),
+                Some(PRUNE_TXINDEX_CONFLICT),
+                "{key}"
+            );
+        }
+    }
+
+    #[test]
+    fn rpc_port_warnings_flag_another_networks_default() {
+        let (_dir, path) = create_temp_config("signet=1\nrpcport=8332\n");
+        let entries = parse_config(&path).unwrap();
+        let warnings = rpc_port_warnings(&entries);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "rpcport");
+        assert_eq!(
+            warnings[0].message,
+            "8332 looks like a mainnet port; signet uses 38332"
+        );
+
+        // The network's own default and ports no network uses are fine
+        for conf in [
+            "signet=1\nrpcport=38332\n",
+            "signet=1\nrpcport=9000\n",
+            "rpcport=8332\n",
+            // A section for another network is not read
+            "signet=1\n[main]\nrpcport=8332\n",
+        ] {
+            let (_dir, path) = create_temp_config(conf);
+            let entries = parse_config(&path).unwrap();
+            assert!(rpc_port_warnings(&entries).is_empty(), "{conf}");
+        }
+
+        let (_dir, path) = create_temp_config("rpcport=18332\n");
+        let entries = parse_config(&path).unwrap();
+        assert_eq!(
+            rpc_port_warnings(&entries)[0].message,
+            "18332 looks like a testnet port; mainnet uses 8332"
+        );
+    }
+
+    #[test]
+    fn parse_configs_lets_later_files_override_earlier_ones() {
+        let (dir, base) =
+            create_temp_config("rpcuser=alice\nrpcport=8332\n[test]\nrpcport=18000\n");
+        let drop_in = dir.path().join("override.conf");
+        std::fs::write(&drop_in, "rpcport=9000\ntxindex=1\n").unwrap();
+
+        let entries = parse_configs(&[&base, &drop_in]).unwrap();
+        let set = |key: &str| -> Vec<(&str, Option<&str>, Option<&Path>)> {
+            entries
+                .iter()
+                .filter(|e| e.enabled && e.key == key)
+                .map(|e| (e.value.as_str(), e.section.as_deref(), e.source.as_deref()))
+                .collect()
+        };
+
+        // The drop-in wins for its key and section only
+        assert_eq!(
+            set("rpcport"),
+            [
+                ("9000", None, Some(drop_in.as_path())),
+                ("18000", Some("test"), Some(base.as_path())),
+            ]
+        );
+        assert_eq!(set("rpcuser"), [("alice", None, Some(base.as_path()))]);
+        assert_eq!(set("txindex"), [("1", None, Some(drop_in.as_path()))]);
+
+        // Unset keys keep one default entry, with no file behind it
+        let defaults: Vec<_> = entries.iter().filter(|e| e.key == "server").collect();
+        assert_eq!(defaults.len(), 1);
+        assert!(!defaults[0].enabled);
+        assert!(defaults[0].source.is_none());
+
+        // Reversing the order reverses which file wins
+        let entries = parse_configs(&[&drop_in, &base]).unwrap();
+        let port = entries
+            .iter()
+            .find(|e| e.enabled && e.key == "rpcport" && e.section.is_none())
+            .unwrap();
+        assert_eq!(port.value, "8332");
+        assert_eq!(port.source.as_deref(), Some(base.as_path()));
+    }
+
+    #[test]
+    fn parse_configs_replaces_every_value_of_a_repeated_key() {
+        let (dir, base) = create_temp_config("addnode=a\naddnode=b\naddnode=c\n");
+        let drop_in = dir.path().join("override.conf");
+        std::fs::write(&drop_in, "addnode=x\naddnode=y\n").unwrap();
+
+        let entries = parse_configs(&[&base, &drop_in]).unwrap();
+        let nodes: Vec<&str> = entries
+            .iter()
+            .filter(|e| e.enabled && e.key == "addnode")
+            .map(|e| e.value.as_str())
+            .collect();
+        assert_eq!(nodes, ["x", "y"]);
+    }
+
+    #[test]
+    fn includeconf_files_are_loaded_and_saved_back() {
+        let (dir, path) = create_temp_config("rpcuser=alice\nincludeconf=extra.conf\n");
+        let extra = dir.path().join("extra.conf");
+        std::fs::write(&extra, "rpcport=9000\n").unwrap();
+
+        let mut entries = parse_config_with_includes(&path).unwrap();
+        let port = entries
+            .iter_mut()
+            .find(|e| e.enabled && e.key == "rpcport")
+            .unwrap();
+        assert_eq!(port.source.as_deref(), Some(extra.as_path()));
+        port.value = "9001".to_string();
+        assert_eq!(config_files(&path, &entries), [path.clone(), extra.clone()]);
+
+        write_config(&path, &entries).unwrap();
+        let main = std::fs::read_to_string(&path).unwrap();
+        assert!(main.contains("rpcuser=alice\n"));
+        assert!(!main.contains("rpcport"));
+        assert_eq!(std::fs::read_to_string(&extra).unwrap(), "rpcport=9001\n");
+    }
+
+    #[test]
+    fn flag_conflicts_marks_repeated_identical_lines() {
+        let line = |value: &str| ConfigEntry::new("zmqpubrawtx", value);
+        let mut entries = vec![line("tcp://127.0.0.1:28332"), line("tcp://127.0.0.1:28332")];
+
+        flag_conflicts(&mut entries);
+        assert_eq!(entries[0].validation_error, None);
+        assert_eq!(
+            entries[1].validation_error.as_deref(),
+            Some(DUPLICATE_ENTRY_CONFLICT)
+        );
+
+        entries[1].value = "tcp://127.0.0.1:28333".to_string();
+        flag_conflicts(&mut entries);
+        assert_eq!(entries[1].validation_error, None);
+    }
+
+    #[test]
+    fn parse_config_allows_prune_or_txindex_alone() {
+        for content in ["prune=550\n", "txindex=1\n", "prune=0\ntxindex=1\n"] {
+            let (_dir, path) = create_temp_config(content);
+            let entries = parse_config(&path).unwrap();
+            assert!(
+                entries
+                    .iter()
+                    .filter(|e| e.key == "prune" || e.key == "txindex")
+                    .all(|e| e.validation_error.is_none()),
+                "{content}"
+            );
+        }
+    }
+
+    #[test]
+    fn prune_and_txindex_only_conflict_on_a_shared_network() {
+        let conflicted = |content: &str| {
+            let (_dir, path) = create_temp_config(content);
+            parse_config(&path)
+                .unwrap()
+                .iter()
+                .filter(|e| e.validation_error.as_deref() == Some(PRUNE_TXINDEX_CONFLICT))
+                .count()
+        };
+        assert_eq!(conflicted("[main]\nprune=550\n[test]\ntxindex=1\n"), 0);
+        assert_eq!(conflicted("prune=550\n[test]\ntxindex=1\n"), 2);
+        assert_eq!(conflicted("[test]\nprune=550\ntxindex=1\n"), 2);
+    }
+
+    #[test]
+    fn flag_conflicts_clears_once_resolved() {
+        let (_dir, path) = create_temp_config("prune=550\ntxindex=1\n");
+        let mut entries = parse_config(&path).unwrap();
+
+        let txindex = entries.iter_mut().find(|e| e.key == "txindex").unwrap();
+        txindex.enabled = false;
+        flag_conflicts(&mut entries);
+
+        let prune = entries.iter().find(|e| e.key == "prune").unwrap();
+        assert!(prune.validation_error.is_none());
+    }
+
     #[test]
     fn parse_config_parses_string_values() {
         let (_dir, path) = create_temp_config("rpcuser=myuser\nrpcpassword=mypassword\n");
@@ -1690,6 +2946,33 @@ mod tests {
         assert!(unknown.schema.is_none());
     }
 
+    #[test]
+    fn parse_config_keeps_each_section_and_marks_the_active_one() {
+        let content = "signet=1\n\n[main]\nrpcport=8332\n\n[signet]\nrpcport=38332\n";
+        let (_dir, path) = create_temp_config(content);
+        let entries = parse_config(&path).unwrap();
+
+        let network = active_network(&entries);
+        assert_eq!(network, "signet");
+        let rpcports: Vec<_> = entries
+            .iter()
+            .filter(|e| e.key == "rpcport")
+            .map(|e| (e.value.as_str(), e.in_effect(network)))
+            .collect();
+        assert_eq!(rpcports, [("8332", false), ("38332", true)]);
+        let signet = entries.iter().find(|e| e.key == "signet").unwrap();
+        assert!(signet.in_effect(network));
+    }
+
+    #[test]
+    fn active_network_defaults_to_main_and_honours_chain() {
+        let (_dir, path) = create_temp_config("server=1\n");
+        assert_eq!(active_network(&parse_config(&path).unwrap()), "main");
+
+        let (_dir, path) = create_temp_config("chain=regtest\n");
+        assert_eq!(active_network(&parse_config(&path).unwrap()), "regtest");
+    }
+
     #[test]
     fn parse_config_handles_section_values() {
         let content = r#"
@@ -1811,13 +3094,7 @@ zmqpubhashtx=tcp://127.0.0.1:28333
 
     #[test]
     fn config_entry_clone_works() {
-        let entry = ConfigEntry {
-            key: "test".to_string(),
-            value: "value".to_string(),
-            schema: None,
-            enabled: true,
-            section: None,
-        };
+        let entry = ConfigEntry::new("test", "value");
         let cloned = entry.clone();
         assert_eq!(entry.key, cloned.key);
         assert_eq!(entry.value, cloned.value);
@@ -1849,27 +3126,12 @@ zmqpubhashtx=tcp://127.0.0.1:28333
         let path = dir.path().join("out.conf");
 
         let entries = vec![
+            ConfigEntry::new("rpcuser", "alice"),
             ConfigEntry {
-                key: "rpcuser".to_string(),
-                value: "alice".to_string(),
-                enabled: true,
-                schema: None,
-                section: None,
-            },
-            ConfigEntry {
-                key: "rpcport".to_string(),
-                value: "8332".to_string(),
                 enabled: false,
-                schema: None,
-                section: None,
-            },
-            ConfigEntry {
-                key: "server".to_string(),
-                value: "1".to_string(),
-                enabled: true,
-                schema: None,
-                section: None,
+                ..ConfigEntry::new("rpcport", "8332")
             },
+            ConfigEntry::new("server", "1"),
         ];
 
         save_config(&path, &entries).unwrap();
@@ -1965,19 +3227,10 @@ zmqpubhashtx=tcp://127.0.0.1:28333
     fn save_config_sections_written_after_top_level() {
         // Entries with mixed sections: top-level first, then named sections
         let entries = vec![
+            ConfigEntry::new("daemon", "1"),
             ConfigEntry {
-                key: "daemon".to_string(),
-                value: "1".to_string(),
-                enabled: true,
-                schema: None,
-                section: None,
-            },
-            ConfigEntry {
-                key: "rpcport".to_string(),
-                value: "18332".to_string(),
-                enabled: true,
-                schema: None,
                 section: Some("test".to_string()),
+                ..ConfigEntry::new("rpcport", "18332")
             },
         ];
 
@@ -1994,4 +3247,561 @@ zmqpubhashtx=tcp://127.0.0.1:28333
         );
         assert!(content.contains("rpcport=18332"));
     }
+
+    #[test]
+    fn write_config_comments_disabled_entries() {
+        let entries = vec![
+            ConfigEntry::new("rpcuser", "alice"),
+            ConfigEntry {
+                enabled: false,
+                section: Some("regtest".to_string()),
+                ..ConfigEntry::new("rpcport", "18443")
+            },
+        ];
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        write_config(&path, &entries).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "rpcuser=alice\n\n[regtest]\n#rpcport=18443\n");
+        assert!(!dir.path().join(".bitcoin.conf.tmp").exists());
+    }
+
+    #[test]
+    fn write_config_reads_rpcpassword_from_its_file() {
+        let (dir, path) = create_temp_config("rpcuser=alice\nrpcpassword=old\n");
+        let secret = dir.path().join("rpc.secret");
+        std::fs::write(&secret, "s3cret\n").unwrap();
+        let mut entries = parse_config(&path).unwrap();
+        let file_entry = ConfigEntry::new(PASSWORD_FILE_KEY, &secret.display().to_string());
+        assert_eq!(validate_value(&file_entry, &file_entry.value), Ok(()));
+        entries.push(file_entry);
+
+        write_config(&path, &entries).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, "rpcuser=alice\nrpcpassword=s3cret\n");
+
+        // An unreadable file stops the save and leaves the config alone
+        std::fs::remove_file(&secret).unwrap();
+        let err = write_config(&path, &entries).unwrap_err();
+        assert!(err.to_string().starts_with("rpcpassword@file: cannot read"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), written);
+    }
+
+    #[test]
+    fn read_password_file_rejects_empty_and_multiline_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret = dir.path().join("rpc.secret");
+
+        std::fs::write(&secret, "\n").unwrap();
+        assert!(
+            read_password_file(&secret)
+                .unwrap_err()
+                .ends_with("is empty")
+        );
+        std::fs::write(&secret, "one\ntwo\n").unwrap();
+        assert!(
+            read_password_file(&secret)
+                .unwrap_err()
+                .ends_with("holds more than one line")
+        );
+        std::fs::write(&secret, "pw\r\n").unwrap();
+        assert_eq!(read_password_file(&secret).unwrap(), "pw");
+    }
+
+    #[test]
+    fn write_config_omits_disabled_defaults_unless_requested() {
+        let schema = ConfigSchema::new(
+            "dbcache",
+            "450",
+            ConfigType::Int,
+            ConfigCategory::Core,
+            "Database cache size in MiB",
+        );
+        let entries = vec![ConfigEntry {
+            enabled: false,
+            schema: Some(schema),
+            ..ConfigEntry::new("dbcache", "450")
+        }];
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+
+        write_config(&path, &entries).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().is_empty());
+
+        write_config_with(&path, &entries, true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "#dbcache=450\n");
+    }
+
+    #[test]
+    fn write_config_roundtrip_with_parse() {
+        let (_dir, path) = create_temp_config(
+            "server=1\nrpcuser=bob\n\n[test]\nrpcport=18332\n\n[regtest]\nfallbackfee=0.0002\n",
+        );
+
+        let entries = parse_config(&path).unwrap();
+        write_config(&path, &entries).unwrap();
+        let reparsed = parse_config(&path).unwrap();
+
+        let enabled = |list: &[ConfigEntry]| {
+            let mut set: Vec<(String, String, Option<String>)> = list
+                .iter()
+                .filter(|e| e.enabled)
+                .map(|e| (e.key.clone(), e.value.clone(), e.section.clone()))
+                .collect();
+            set.sort();
+            set
+        };
+        assert_eq!(enabled(&entries), enabled(&reparsed));
+        assert_eq!(entries.len(), reparsed.len());
+    }
+
+    #[test]
+    fn write_config_roundtrip_preserves_signet_section() {
+        let (_dir, path) =
+            create_temp_config("server=1\n\n[signet]\nrpcport=38332\nsignetchallenge=51\n");
+
+        let entries = parse_config(&path).unwrap();
+        let rpcport = entries.iter().find(|e| e.key == "rpcport").unwrap();
+        assert_eq!(rpcport.section.as_deref(), Some("signet"));
+
+        write_config(&path, &entries).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        let header = written.find("[signet]").expect("signet header written");
+        // Global keys stay above the section, its values below it
+        assert!(written.find("server=1").unwrap() < header);
+        assert!(written.find("rpcport=38332").unwrap() > header);
+
+        let reparsed = parse_config(&path).unwrap();
+        for key in ["rpcport", "signetchallenge"] {
+            let entry = reparsed.iter().find(|e| e.key == key).unwrap();
+            assert_eq!(entry.section.as_deref(), Some("signet"), "{key}");
+            assert!(entry.enabled);
+        }
+        let server = reparsed.iter().find(|e| e.key == "server").unwrap();
+        assert_eq!(server.section, None);
+    }
+
+    #[test]
+    fn write_config_rewrites_a_negated_option_in_place() {
+        let (_dir, path) = create_temp_config("server=1\nnolisten=1\n");
+
+        let mut entries = parse_config(&path).unwrap();
+        let listen = entries.iter_mut().find(|e| e.key == "listen").unwrap();
+        listen.value = "1".to_string();
+        write_config(&path, &entries).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, "server=1\nlisten=1\n");
+    }
+
+    #[test]
+    fn write_config_keeps_comments_attached_to_their_keys() {
+        let original = "# Main options\nserver=1\n\n# explanatory comment\n# about the index\ntxindex=1\n\n[test]\n# test rpc\nrpcport=18332\n";
+        let (_dir, path) = create_temp_config(original);
+
+        let mut entries = parse_config(&path).unwrap();
+        let server = entries.iter_mut().find(|e| e.key == "server").unwrap();
+        server.value = "0".to_string();
+        write_config(&path, &entries).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, original.replace("server=1", "server=0"));
+    }
+
+    #[test]
+    fn write_config_edits_in_place_and_appends_new_keys_to_their_section() {
+        let (_dir, path) = create_temp_config("#txindex=1\nserver=1\n\n[test]\nrpcport=18332\n\n");
+
+        let mut entries = parse_config(&path).unwrap();
+        for entry in &mut entries {
+            match entry.key.as_str() {
+                "txindex" => {
+                    entry.value = "1".to_string();
+                    entry.enabled = true;
+                }
+                "server" => entry.enabled = false,
+                _ => {}
+            }
+        }
+        entries.push(ConfigEntry {
+            section: Some("test".to_string()),
+            ..ConfigEntry::new("myopt", "1")
+        });
+        entries.push(ConfigEntry {
+            section: Some("regtest".to_string()),
+            ..ConfigEntry::new("rpcport", "18443")
+        });
+        write_config(&path, &entries).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            written,
+            "txindex=1\n#server=1\n\n[test]\nrpcport=18332\nmyopt=1\n\n[regtest]\nrpcport=18443\n"
+        );
+    }
+
+    #[test]
+    fn group_by_section_keeps_first_seen_order() {
+        let (_dir, path) =
+            create_temp_config("server=1\n[test]\nrpcport=18000\n[main]\nrpcport=8000\n");
+        let entries = parse_config(&path).unwrap();
+
+        // The parser lists [main] before [test], whatever the file's order
+        let groups = group_by_section(&entries);
+        let sections: Vec<Option<&str>> = groups.iter().map(|(s, _)| s.as_deref()).collect();
+        assert_eq!(sections, [None, Some("main"), Some("test")]);
+
+        for (section, group) in &groups {
+            let expected: Vec<&str> = entries
+                .iter()
+                .filter(|e| e.section == *section)
+                .map(|e| e.key.as_str())
+                .collect();
+            let keys: Vec<&str> = group.iter().map(|e| e.key.as_str()).collect();
+            assert_eq!(keys, expected, "{section:?}");
+        }
+        assert_eq!(groups[2].1[0].value, "18000");
+    }
+
+    #[test]
+    fn config_to_json_emits_one_object_per_entry() {
+        let (_dir, path) = create_temp_config("dbcache=450\n\n[test]\nmyopt=1\n");
+        let entries = parse_config(&path).unwrap();
+        let json = config_to_json(&entries, false);
+
+        let array = json.as_array().unwrap();
+        assert_eq!(array.len(), entries.len());
+        let find = |key: &str| array.iter().find(|v| v["key"] == key).unwrap().clone();
+        assert_eq!(
+            find("dbcache"),
+            serde_json::json!({
+                "section": null,
+                "key": "dbcache",
+                "value": "450",
+                "enabled": true,
+                "is_default": true,
+            })
+        );
+        assert_eq!(
+            find("myopt"),
+            serde_json::json!({
+                "section": "test",
+                "key": "myopt",
+                "value": "1",
+                "enabled": true,
+                "is_default": false,
+            })
+        );
+    }
+
+    #[test]
+    fn config_to_json_masks_credentials_unless_asked() {
+        let (_dir, path) =
+            create_temp_config("rpcpassword=s3cret\nrpcauth=alice:salt$hash\nrpcuser=bob\n");
+        let entries = parse_config(&path).unwrap();
+        let value = |json: &serde_json::Value, key: &str| {
+            json.as_array()
+                .unwrap()
+                .iter()
+                .find(|v| v["key"] == key && v["enabled"] == true)
+                .unwrap()["value"]
+                .clone()
+        };
+
+        let masked = config_to_json(&entries, false);
+        assert_eq!(value(&masked, "rpcpassword"), "*****");
+        assert_eq!(value(&masked, "rpcauth"), "*****");
+        assert_eq!(value(&masked, "rpcuser"), "bob");
+
+        let clear = config_to_json(&entries, true);
+        assert_eq!(value(&clear, "rpcpassword"), "s3cret");
+        assert_eq!(value(&clear, "rpcauth"), "alice:salt$hash");
+    }
+
+    #[test]
+    fn validate_new_key_rejects_empty_and_separators() {
+        assert!(validate_new_key("myopt").is_ok());
+        assert!(validate_new_key("").is_err());
+        assert!(validate_new_key("my=opt").is_err());
+        assert!(validate_new_key("my opt").is_err());
+        assert!(validate_new_key("#myopt").is_err());
+    }
+
+    #[test]
+    fn schemaless_entry_survives_write_and_parse() {
+        let (_dir, path) = create_temp_config("server=1\n");
+        let mut entries = parse_config(&path).unwrap();
+        entries.push(ConfigEntry::new("myopt", "1"));
+
+        write_config(&path, &entries).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("myopt=1"));
+
+        let reparsed = parse_config(&path).unwrap();
+        let myopt = reparsed.iter().find(|e| e.key == "myopt").unwrap();
+        assert!(myopt.enabled);
+        assert!(myopt.schema.is_none());
+        assert_eq!(myopt.value, "1");
+    }
+
+    #[test]
+    fn rpcauth_value_matches_known_vector() {
+        // Reference computed with Bitcoin Core's share/rpcauth/rpcauth.py scheme
+        assert_eq!(
+            rpcauth_value("alice", "cb77f0957de88ff388cf817ddbc7273", "secretpassword"),
+            "alice:cb77f0957de88ff388cf817ddbc7273$\
+             963d734542c0276076de35bd769c6b2249f33940ef61c002617ecd709ac67ad4"
+        );
+    }
+
+    #[test]
+    fn generate_rpcauth_has_user_salt_hash_format() {
+        let (line, password) = generate_rpcauth("alice", None).unwrap();
+        let value = line.strip_prefix("rpcauth=").unwrap();
+        let (user, rest) = value.split_once(':').unwrap();
+        let (salt, hash) = rest.split_once('$').unwrap();
+
+        assert_eq!(user, "alice");
+        assert_eq!(salt.len(), 32);
+        assert_eq!(hash.len(), 64);
+        assert!(
+            salt.chars()
+                .chain(hash.chars())
+                .all(|c| c.is_ascii_hexdigit())
+        );
+        assert_eq!(password.len(), 64);
+        // The hash validates against the salt and the returned password
+        assert_eq!(value, rpcauth_value("alice", salt, &password));
+    }
+
+    #[test]
+    fn generate_rpcauth_uses_supplied_password_and_fresh_salt() {
+        let (first, password) = generate_rpcauth("bob", Some("hunter2")).unwrap();
+        let (second, _) = generate_rpcauth("bob", Some("hunter2")).unwrap();
+        assert_eq!(password, "hunter2");
+        assert_ne!(first, second, "each call must draw a new salt");
+    }
+
+    fn validation_error_for(entries: &[ConfigEntry], key: &str) -> Option<String> {
+        entries
+            .iter()
+            .find(|e| e.key == key)
+            .and_then(|e| e.validation_error.clone())
+    }
+
+    #[test]
+    fn parse_config_flags_values_that_do_not_match_schema() {
+        let (_dir, path) = create_temp_config(
+            "txindex=maybe\nrpcport=notanumber\ndbcache=-5\nfallbackfee=0.0002\nserver=true\n",
+        );
+        let entries = parse_config(&path).unwrap();
+
+        assert_eq!(
+            validation_error_for(&entries, "txindex").as_deref(),
+            Some("must be 0, 1, true or false")
+        );
+        assert!(validation_error_for(&entries, "rpcport").is_some());
+        assert_eq!(
+            validation_error_for(&entries, "dbcache").as_deref(),
+            Some("must be at least 4")
+        );
+        assert!(validation_error_for(&entries, "fallbackfee").is_none());
+        assert!(validation_error_for(&entries, "server").is_none());
+        // Options absent from the file keep their defaults and no error
+        assert!(validation_error_for(&entries, "maxmempool").is_none());
+    }
+
+    #[test]
+    fn parse_config_flags_out_of_range_block_weight_and_dbcache() {
+        let (_dir, path) = create_temp_config("blockmaxweight=4000001\ndbcache=1\n");
+        let entries = parse_config(&path).unwrap();
+
+        assert_eq!(
+            validation_error_for(&entries, "blockmaxweight").as_deref(),
+            Some("must be between 4000 and 4000000")
+        );
+        assert_eq!(
+            validation_error_for(&entries, "dbcache").as_deref(),
+            Some("must be at least 4")
+        );
+
+        let (_dir, path) = create_temp_config("blockmaxweight=4000000\ndbcache=4\n");
+        let entries = parse_config(&path).unwrap();
+        assert!(validation_error_for(&entries, "blockmaxweight").is_none());
+        assert!(validation_error_for(&entries, "dbcache").is_none());
+    }
+
+    #[test]
+    fn validate_value_checks_proxy_host_and_port() {
+        let proxy = schema_entry("proxy", ConfigType::Address);
+        assert!(validate_value(&proxy, "127.0.0.1:9050").is_ok());
+        assert!(validate_value(&proxy, "tor.lan:9050").is_ok());
+        assert!(validate_value(&proxy, "[::1]:9050").is_ok());
+        // Core's default port, socket and off forms
+        assert!(validate_value(&proxy, "127.0.0.1").is_ok());
+        assert!(validate_value(&proxy, "[::1]").is_ok());
+        assert!(validate_value(&proxy, "unix:/run/tor/socks").is_ok());
+        assert!(validate_value(&schema_entry("onion", ConfigType::Address), "0").is_ok());
+        assert_eq!(
+            validate_value(&proxy, "unix:").unwrap_err(),
+            "unix: needs a socket path, e.g. unix:/run/tor/socks"
+        );
+        assert_eq!(
+            validate_value(&proxy, "::1:9050").unwrap_err(),
+            "put an IPv6 host in brackets, e.g. [::1]:9050"
+        );
+        assert!(validate_value(&proxy, "127.0.0.1:0").is_err());
+        assert!(validate_value(&schema_entry("onion", ConfigType::Address), ":9050").is_err());
+    }
+
+    #[test]
+    fn validate_value_checks_rpcallowip_networks() {
+        let allow = schema_entry("rpcallowip", ConfigType::String);
+        assert!(validate_value(&allow, "192.168.0.0/24").is_ok());
+        assert!(validate_value(&allow, "10.0.0.5").is_ok());
+        assert!(validate_value(&allow, "::1").is_ok());
+        assert!(validate_value(&allow, "fd00::/8").is_ok());
+        assert!(validate_value(&allow, "192.168.0.0/255.255.255.0").is_ok());
+        assert_eq!(
+            validate_value(&allow, "127.0.0.1, 192.168.0.0/16").unwrap_err(),
+            "one network per line; add another rpcallowip for each"
+        );
+
+        assert_eq!(
+            validate_value(&allow, "192.168.0.0/33").unwrap_err(),
+            "/33 is longer than the 32-bit address"
+        );
+        assert!(validate_value(&allow, "192.168.0/24").is_err());
+        assert!(validate_value(&allow, "192.168.0.0/abc").is_err());
+        assert!(validate_value(&allow, "192.168.0.0/ffff::").is_err());
+        assert!(validate_value(&allow, "*").is_err());
+    }
+
+    #[test]
+    fn parse_config_flags_malformed_proxy_and_rpcallowip() {
+        let (_dir, path) = create_temp_config("proxy=:9050\nrpcallowip=10.0.0.0/40\n");
+        let entries = parse_config(&path).unwrap();
+        assert_eq!(
+            validation_error_for(&entries, "proxy").as_deref(),
+            Some("must be host:port, e.g. 127.0.0.1:9050")
+        );
+        assert_eq!(
+            validation_error_for(&entries, "rpcallowip").as_deref(),
+            Some("/40 is longer than the 32-bit address")
+        );
+
+        let (_dir, path) = create_temp_config("proxy=127.0.0.1:9050\nrpcallowip=10.0.0.0/8\n");
+        let entries = parse_config(&path).unwrap();
+        assert!(validation_error_for(&entries, "proxy").is_none());
+        assert!(validation_error_for(&entries, "rpcallowip").is_none());
+    }
+
+    fn schema_entry(key: &str, config_type: ConfigType) -> ConfigEntry {
+        ConfigEntry {
+            enabled: false,
+            schema: Some(ConfigSchema::new(
+                key,
+                "",
+                config_type,
+                ConfigCategory::Core,
+                "",
+            )),
+            ..ConfigEntry::new(key, "")
+        }
+    }
+
+    #[test]
+    fn validate_value_checks_types_and_ports() {
+        assert!(validate_value(&schema_entry("server", ConfigType::Bool), "1").is_ok());
+        assert!(validate_value(&schema_entry("server", ConfigType::Bool), "yes").is_err());
+        assert!(validate_value(&schema_entry("server", ConfigType::Bool), "false").is_ok());
+
+        assert!(validate_value(&schema_entry("rpcport", ConfigType::Int), "8332").is_ok());
+        assert!(validate_value(&schema_entry("rpcport", ConfigType::Int), "70000").is_err());
+        assert!(validate_value(&schema_entry("rpcport", ConfigType::Int), "0").is_err());
+        assert!(validate_value(&schema_entry("dbcache", ConfigType::Int), "abc").is_err());
+
+        let mut dbcache = schema_entry("dbcache", ConfigType::Int);
+        dbcache.schema = dbcache.schema.map(|s| s.with_range(Some(4), Some(100)));
+        assert!(validate_value(&dbcache, "4").is_ok());
+        assert!(validate_value(&dbcache, "-5").is_err());
+        assert!(validate_value(&dbcache, "101").is_err());
+
+        let fee = schema_entry("fallbackfee", ConfigType::Amount);
+        assert!(validate_value(&fee, "0.0002").is_ok());
+        assert!(validate_value(&fee, "0").is_ok());
+        assert!(validate_value(&fee, "-0.1").is_err());
+        assert!(validate_value(&fee, "0.000000001").is_err());
+        assert!(validate_value(&fee, "cheap").is_err());
+
+        let bind = schema_entry("bind", ConfigType::Address);
+        assert!(validate_value(&bind, "127.0.0.1:8333").is_ok());
+        assert!(validate_value(&bind, "[::1]:8333").is_ok());
+        assert!(validate_value(&bind, "::1").is_ok());
+        assert!(validate_value(&bind, "127.0.0.1:notaport").is_err());
+        assert!(validate_value(&bind, "").is_err());
+    }
+
+    #[test]
+    fn parse_flags_malformed_fees_and_mempool_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        std::fs::write(
+            &path,
+            "mintxfee=0.00001\n\
+             fallbackfee=0,0001\n\
+             paytxfee=-0.001\n\
+             maxtxfee=0.000000001\n\
+             maxmempool=300MB\n",
+        )
+        .unwrap();
+        let entries = parse_config(&path).unwrap();
+
+        assert!(validation_error_for(&entries, "mintxfee").is_none());
+        assert_eq!(
+            validation_error_for(&entries, "fallbackfee").as_deref(),
+            Some("use '.' as the decimal separator, e.g. 0.0001")
+        );
+        assert_eq!(
+            validation_error_for(&entries, "paytxfee").as_deref(),
+            Some("cannot be negative")
+        );
+        assert_eq!(
+            validation_error_for(&entries, "maxtxfee").as_deref(),
+            Some("at most 8 decimal places")
+        );
+        assert_eq!(
+            validation_error_for(&entries, "maxmempool").as_deref(),
+            Some("must be a plain integer without a unit suffix")
+        );
+    }
+
+    #[test]
+    fn validate_value_accepts_anything_without_schema() {
+        let entry = ConfigEntry::new("custom", "");
+        assert!(validate_value(&entry, "whatever").is_ok());
+    }
+
+    #[test]
+    fn default_config_string_comments_out_every_option() {
+        let template = default_config_string();
+
+        assert!(template.contains("\n# Maintain full transaction index\n#txindex=0\n"));
+        assert!(template.contains("# --- RPC ---"));
+        for schema in get_default_schema() {
+            assert!(
+                template.contains(&format!("\n#{}={}\n", schema.key, schema.default)),
+                "{} missing",
+                schema.key
+            );
+        }
+
+        // Parsing the template sets nothing
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        std::fs::write(&path, &template).unwrap();
+        assert!(parse_config(&path).unwrap().iter().all(|e| !e.enabled));
+    }
 }
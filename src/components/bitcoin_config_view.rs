@@ -2,12 +2,18 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::app::{App, AppAction};
-use crate::bitcoin_config::ConfigEntry;
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::app::{App, AppAction, SaveTarget};
+use crate::bitcoin_config::{
+    ConfigEntry, PASSWORD_FILE_KEY, active_network, rpc_port_warnings, validate_value,
+};
+use crate::components::list_filter::ListFilter;
+use crate::components::scrollbar::render_rows_scrollbar;
+use crate::components::value_text::{edit_block, truncate_middle, wrap_columns};
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
 };
 use std::path::Path;
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
@@ -67,16 +73,93 @@ fn shorten_path(path: &Path, max_len: usize, home: &str) -> String {
     format!("\u{2026}{suffix}")
 }
 
+/// The `  ✗ reason` an enabled entry that fails validation shows after its
+/// value.
+fn error_suffix(entry: &ConfigEntry) -> Option<String> {
+    entry
+        .validation_error
+        .as_ref()
+        .filter(|_| entry.enabled)
+        .map(|reason| format!("  ✗ {reason}"))
+}
+
+/// Returns `(display_string, style)` for a config entry value: the value
+/// itself when enabled, otherwise a dimmed placeholder naming the default.
+/// The RPC password is masked, and a password file shows its path only.
+#[must_use]
+pub fn entry_display(entry: &ConfigEntry, theme: &Theme) -> (String, Style) {
+    if let Some(suffix) = error_suffix(entry) {
+        (format!("{}{suffix}", entry.value), theme.error)
+    } else if entry.enabled && entry.is_secret() {
+        ("*****".to_string(), theme.set_value)
+    } else if entry.enabled && entry.key == PASSWORD_FILE_KEY {
+        (
+            format!("{} (password: *****, read on save)", entry.value),
+            theme.set_value,
+        )
+    } else if entry.enabled {
+        (entry.value.clone(), theme.set_value)
+    } else {
+        let placeholder = entry
+            .schema
+            .as_ref()
+            .filter(|s| !s.default.is_empty())
+            .map_or_else(
+                || "not set".to_string(),
+                |s| format!("default: {}", s.default),
+            );
+        (format!("({placeholder})"), theme.default_value)
+    }
+}
+
+/// Narrowest content area that still gets the detail panel beside the list;
+/// below it the list takes the whole width except while editing.
+const DETAIL_MIN_WIDTH: u16 = 50;
+
 #[derive(Debug, Clone)]
 pub struct BitcoinConfigView {
     pub selected_index: usize,
     pub editing: bool,
     pub edit_input: String,
+    /// Why `edit_input` would be rejected on commit, rechecked on every
+    /// keystroke; Enter does nothing while it is set.
+    pub edit_error: Option<String>,
     pub save_message: Option<String>,
     pub warning_message: Option<String>,
     pub sidebar_focused: bool,
     /// True when entries have been committed (via `CommitEdit`) but not yet saved to disk.
     pub dirty: bool,
+    /// `/` filter over key, section and value.
+    pub filter: ListFilter,
+    /// Selection and scroll offset of the entry list, kept across frames so
+    /// the list only scrolls once the selection leaves the viewport.
+    pub list_state: ListState,
+    /// Username typed at the `a` prompt that generates an `rpcauth` entry.
+    pub rpcauth_input: Option<String>,
+    /// `key=value` typed at the `n` prompt that adds a schema-less entry.
+    pub add_input: Option<String>,
+    /// Entry whose value is wrapped over several rows instead of being
+    /// shortened to fit, toggled with `x`. Held by identity so it stays with
+    /// the entry when others are inserted above it.
+    pub expanded: Option<EntryId>,
+    /// Height in rows of each visible entry as last drawn, so clicks and
+    /// PageUp/PageDown account for an expanded entry.
+    pub row_heights: Vec<u16>,
+}
+
+/// An entry's section, key and position among the entries sharing both,
+/// which survives inserts and reloads where its index would not.
+pub type EntryId = (Option<String>, String, usize);
+
+/// The [`EntryId`] of `entries[index]`, if there is one.
+#[must_use]
+pub fn entry_id(entries: &[ConfigEntry], index: usize) -> Option<EntryId> {
+    let entry = entries.get(index)?;
+    let nth = entries[..index]
+        .iter()
+        .filter(|e| e.key == entry.key && e.section == entry.section)
+        .count();
+    Some((entry.section.clone(), entry.key.clone(), nth))
 }
 
 impl BitcoinConfigView {
@@ -86,16 +169,148 @@ impl BitcoinConfigView {
             selected_index: 0,
             editing: false,
             edit_input: String::new(),
+            edit_error: None,
             save_message: None,
             warning_message: None,
             sidebar_focused: true,
             dirty: false,
+            filter: ListFilter::default(),
+            list_state: ListState::default(),
+            rpcauth_input: None,
+            add_input: None,
+            expanded: None,
+            row_heights: Vec::new(),
+        }
+    }
+
+    /// Whether `entries[index]` is the expanded entry.
+    #[must_use]
+    pub fn is_expanded(&self, entries: &[ConfigEntry], index: usize) -> bool {
+        self.expanded.is_some() && self.expanded == entry_id(entries, index)
+    }
+
+    /// Moves the selection down by `n` rows, clamped to the last of `len` entries.
+    pub fn page_down(&mut self, n: usize, len: usize) {
+        self.move_selection(isize::try_from(n).unwrap_or(isize::MAX), len);
+        self.save_message = None;
+    }
+
+    /// Moves the selection up by `n` rows, clamped to the first entry.
+    pub fn page_up(&mut self, n: usize, len: usize) {
+        self.move_selection(isize::try_from(n).map_or(isize::MIN, |n| -n), len);
+        self.save_message = None;
+    }
+
+    /// Moves the selection by `delta` rows within the visible (filtered) entries.
+    fn move_selection(&mut self, delta: isize, len: usize) {
+        if let Some(index) = self.filter.step(self.selected_index, delta, len) {
+            self.selected_index = index;
         }
     }
 
+    /// Recomputes the filter matches and keeps the selection on a visible entry.
+    pub fn refresh_filter(&mut self, entries: &[ConfigEntry]) {
+        self.filter.refresh(entries.iter().map(|e| {
+            [
+                e.key.as_str(),
+                e.section.as_deref().unwrap_or(""),
+                // A masked value must not be found by guessing at it
+                if e.is_secret() { "" } else { e.value.as_str() },
+            ]
+        }));
+        self.move_selection(0, entries.len());
+    }
+
+    /// Handles a key while the `rpcauth` username prompt is open.
+    fn handle_rpcauth_input(&mut self, key: KeyEvent) -> AppAction {
+        let Some(input) = self.rpcauth_input.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            // `:` separates user from salt in the generated value
+            KeyCode::Char(':') => {}
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter if !input.trim().is_empty() => {
+                let user = input.trim().to_string();
+                self.rpcauth_input = None;
+                return AppAction::GenerateRpcAuth(user);
+            }
+            KeyCode::Esc => self.rpcauth_input = None,
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    /// Handles a key while the `n` new-entry prompt is open.
+    fn handle_add_input(&mut self, key: KeyEvent) -> AppAction {
+        let Some(input) = self.add_input.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => match input.split_once('=') {
+                Some((k, v)) => {
+                    let action =
+                        AppAction::AddBitcoinEntry(k.trim().to_string(), v.trim().to_string());
+                    self.add_input = None;
+                    return action;
+                }
+                None => self.warning_message = Some("Use key=value".to_string()),
+            },
+            KeyCode::Esc => self.add_input = None,
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    /// Opens the input box on entry `index` holding `value`, checked at once
+    /// so an unusable starting value cannot be committed unseen.
+    pub fn start_edit(&mut self, index: usize, value: String, entries: &[ConfigEntry]) {
+        self.selected_index = index;
+        self.edit_input = value;
+        self.editing = true;
+        self.check_edit(entries);
+    }
+
+    /// Validates `edit_input` against the selected entry, as the commit
+    /// will, so the input box can show the result before Enter. A password
+    /// file is only read on commit, not on every keystroke.
+    pub fn check_edit(&mut self, entries: &[ConfigEntry]) {
+        self.edit_error = entries.get(self.selected_index).and_then(|entry| {
+            if entry.key == PASSWORD_FILE_KEY {
+                return self
+                    .edit_input
+                    .trim()
+                    .is_empty()
+                    .then(|| "path cannot be empty".to_string());
+            }
+            validate_value(entry, &self.edit_input).err()
+        });
+    }
+
     pub fn handle_input(&mut self, key: KeyEvent, entries: &[ConfigEntry]) -> AppAction {
+        if self.rpcauth_input.is_some() {
+            return self.handle_rpcauth_input(key);
+        }
+        if self.add_input.is_some() {
+            return self.handle_add_input(key);
+        }
+        if self.filter.typing {
+            if self.filter.handle_input(key) {
+                self.refresh_filter(entries);
+            }
+            return AppAction::None;
+        }
+
         if self.editing {
             match key.code {
+                KeyCode::Enter if self.edit_error.is_some() => AppAction::None,
                 KeyCode::Enter => {
                     let action =
                         AppAction::CommitEdit(self.selected_index, self.edit_input.clone());
@@ -107,14 +322,17 @@ impl BitcoinConfigView {
                 KeyCode::Esc => {
                     self.editing = false;
                     self.edit_input.clear();
+                    self.edit_error = None;
                     AppAction::None
                 }
                 KeyCode::Backspace => {
                     self.edit_input.pop();
+                    self.check_edit(entries);
                     AppAction::None
                 }
                 KeyCode::Char(c) => {
                     self.edit_input.push(c);
+                    self.check_edit(entries);
                     AppAction::None
                 }
                 _ => AppAction::None,
@@ -122,29 +340,93 @@ impl BitcoinConfigView {
         } else {
             match key.code {
                 KeyCode::Up => {
-                    if self.selected_index > 0 {
-                        self.selected_index -= 1;
-                    }
+                    self.move_selection(-1, entries.len());
                     self.save_message = None;
                     AppAction::None
                 }
                 KeyCode::Down => {
-                    if self.selected_index + 1 < entries.len() {
-                        self.selected_index += 1;
-                    }
+                    self.move_selection(1, entries.len());
+                    self.save_message = None;
+                    AppAction::None
+                }
+                KeyCode::Home => {
+                    self.move_selection(isize::MIN, entries.len());
+                    self.save_message = None;
+                    AppAction::None
+                }
+                KeyCode::End => {
+                    self.move_selection(isize::MAX, entries.len());
                     self.save_message = None;
                     AppAction::None
                 }
                 KeyCode::Enter => {
-                    if !entries.is_empty() {
-                        self.edit_input
-                            .clone_from(&entries[self.selected_index].value);
-                        self.editing = true;
+                    if self.filter.contains(self.selected_index, entries.len()) {
+                        let value = entries[self.selected_index].value.clone();
+                        self.start_edit(self.selected_index, value, entries);
                         self.save_message = None;
                     }
                     AppAction::None
                 }
-                KeyCode::Char('s') => AppAction::SaveBitcoinConfig,
+                KeyCode::Char(' ') if self.filter.contains(self.selected_index, entries.len()) => {
+                    self.save_message = None;
+                    AppAction::ToggleBitcoinEntry(self.selected_index)
+                }
+                KeyCode::Char('D') if self.filter.contains(self.selected_index, entries.len()) => {
+                    self.save_message = None;
+                    AppAction::DuplicateBitcoinEntry(self.selected_index)
+                }
+                KeyCode::Char('/') => {
+                    self.filter.typing = true;
+                    self.refresh_filter(entries);
+                    AppAction::None
+                }
+                KeyCode::Char('s') => AppAction::RequestSave(SaveTarget::BitcoinConfig),
+                KeyCode::Char('d') => AppAction::ShowDiff(SaveTarget::BitcoinConfig),
+                KeyCode::Char('y')
+                    if self.filter.contains(self.selected_index, entries.len())
+                        && entries[self.selected_index].enabled =>
+                {
+                    self.save_message = None;
+                    AppAction::CopyValue(
+                        SaveTarget::BitcoinConfig,
+                        entries[self.selected_index].value.clone(),
+                    )
+                }
+                KeyCode::Char('x') if self.filter.contains(self.selected_index, entries.len()) => {
+                    self.expanded = if self.is_expanded(entries, self.selected_index) {
+                        None
+                    } else {
+                        entry_id(entries, self.selected_index)
+                    };
+                    AppAction::None
+                }
+                KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.save_message = None;
+                    AppAction::Redo(SaveTarget::BitcoinConfig)
+                }
+                KeyCode::Char('r') => {
+                    self.save_message = None;
+                    AppAction::RequestReload(SaveTarget::BitcoinConfig)
+                }
+                KeyCode::Char('u') => {
+                    self.save_message = None;
+                    AppAction::Undo(SaveTarget::BitcoinConfig)
+                }
+                KeyCode::Char('a') => {
+                    self.save_message = None;
+                    self.rpcauth_input = Some(String::new());
+                    AppAction::None
+                }
+                KeyCode::Char('n') => {
+                    self.save_message = None;
+                    self.warning_message = None;
+                    self.add_input = Some(String::new());
+                    AppAction::None
+                }
+                KeyCode::Esc if self.filter.is_active() => {
+                    self.filter.clear();
+                    AppAction::None
+                }
                 KeyCode::Esc => {
                     self.sidebar_focused = true;
                     self.save_message = None;
@@ -158,6 +440,26 @@ impl BitcoinConfigView {
     #[allow(clippy::too_many_lines)] // Renders two panels with multiple layout passes
     pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
         const FIXED: usize = 33;
+        let theme = app.theme;
+        if let Some(load) = &app.bitcoin_loading {
+            let p = Paragraph::new(format!(
+                "{} Loading {}…",
+                load.spinner(),
+                shorten_path(
+                    &load.path,
+                    usize::from(area.width.saturating_sub(14)),
+                    &app.home_dir
+                )
+            ))
+            .style(theme.description)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Bitcoin Config "),
+            );
+            f.render_widget(p, area);
+            return;
+        }
         if app.bitcoin_conf_path.is_none() {
             let p = Paragraph::new("Press [Enter] to select a bitcoin.conf file").block(
                 Block::default()
@@ -168,75 +470,163 @@ impl BitcoinConfigView {
             return;
         }
 
-        let panels = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
-            .split(area);
+        // The edit field lives in the detail panel, so editing always shows it
+        let show_detail = area.width >= DETAIL_MIN_WIDTH || app.bitcoin_config_view.editing;
+        let panels = if show_detail {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .split(area)
+        } else {
+            [area].into()
+        };
+
+        // Entries may have been edited since the filter last ran
+        if app.bitcoin_config_view.filter.is_active() {
+            app.bitcoin_config_view.refresh_filter(&app.bitcoin_data);
+        }
+        let visible = app
+            .bitcoin_config_view
+            .filter
+            .visible(app.bitcoin_data.len());
 
         // Left panel: scrollable entry list
-        let items: Vec<ListItem> = app
-            .bitcoin_data
+        let network = active_network(&app.bitcoin_data).to_string();
+        let warnings = rpc_port_warnings(&app.bitcoin_data);
+        // Columns left for `key = value` inside the border and highlight symbol
+        let row_width = usize::from(panels[0].width).saturating_sub(5);
+        let expanded = (0..app.bitcoin_data.len())
+            .find(|&i| app.bitcoin_config_view.is_expanded(&app.bitcoin_data, i));
+        let items: Vec<ListItem> = visible
             .iter()
-            .map(|entry| {
+            .filter_map(|&i| app.bitcoin_data.get(i).map(|entry| (i, entry)))
+            .map(|(i, entry)| {
                 let label = entry.schema.as_ref().map_or("", |s| s.description.as_str());
 
-                let (value_display, value_style) = if entry.enabled {
-                    (
-                        entry.value.clone(),
-                        Style::default()
-                            .fg(Color::White)
-                            .add_modifier(Modifier::BOLD),
-                    )
+                let (value_display, mut value_style) = entry_display(entry, &theme);
+                // Values that don't fit their schema turn the whole row red
+                let mut key_style = if entry.enabled && entry.validation_error.is_some() {
+                    theme.error
                 } else {
-                    let placeholder = entry
-                        .schema
-                        .as_ref()
-                        .filter(|s| !s.default.is_empty())
-                        .map_or_else(
-                            || "not set".to_string(),
-                            |s| format!("default: {}", s.default),
-                        );
-                    (
-                        format!("({placeholder})"),
-                        Style::default().fg(Color::DarkGray),
-                    )
+                    theme.key
                 };
+                // Another network's section is kept but bitcoind won't read it
+                let in_effect = entry.in_effect(&network);
+                let warning = warnings
+                    .iter()
+                    .find(|w| entry.enabled && in_effect && w.field == entry.key);
+                if warning.is_some() && entry.validation_error.is_none() {
+                    key_style = theme.warning;
+                }
+                if !in_effect {
+                    key_style = theme.dimmed;
+                    value_style = theme.dimmed;
+                }
 
-                ListItem::new(vec![
-                    Line::from(Span::styled(label, Style::default().fg(Color::Gray))),
-                    Line::from(vec![
-                        Span::styled(
-                            format!("{} = ", entry.key),
-                            Style::default().fg(Color::Cyan),
+                // Network-specific values carry their `[section]` header
+                let mut header = Vec::new();
+                if let Some(section) = &entry.section {
+                    let (label, style) = if in_effect {
+                        (format!("[{section}] "), theme.section_label)
+                    } else {
+                        (format!("[{section}] inactive "), theme.dimmed)
+                    };
+                    header.push(Span::styled(label, style));
+                }
+                header.push(Span::styled(label, theme.description));
+
+                let key_label = format!("{} = ", entry.key);
+                // The error and warning suffixes stay whole; the value gives way
+                let error_suffix = error_suffix(entry).unwrap_or_default();
+                let warning_suffix =
+                    warning.map_or_else(String::new, |w| format!("  ⚠ {}", w.message));
+                let value_width = row_width
+                    .saturating_sub(key_label.width())
+                    .saturating_sub(warning_suffix.width());
+                let mut lines = vec![Line::from(header)];
+                if expanded == Some(i) {
+                    // Continuation rows line up under the start of the value
+                    let indent = " ".repeat(key_label.width());
+                    let mut pieces = wrap_columns(&value_display, value_width).into_iter();
+                    lines.push(Line::from(vec![
+                        Span::styled(key_label, key_style),
+                        Span::styled(pieces.next().unwrap_or_default(), value_style),
+                    ]));
+                    lines.extend(pieces.map(|piece| {
+                        Line::from(vec![
+                            Span::raw(indent.clone()),
+                            Span::styled(piece, value_style),
+                        ])
+                    }));
+                } else {
+                    let shortened = match value_display.strip_suffix(&error_suffix) {
+                        Some(value) if !error_suffix.is_empty() => format!(
+                            "{}{error_suffix}",
+                            truncate_middle(
+                                value,
+                                value_width.saturating_sub(error_suffix.width())
+                            )
                         ),
-                        Span::styled(value_display, value_style),
-                    ]),
-                ])
+                        _ => truncate_middle(&value_display, value_width),
+                    };
+                    lines.push(Line::from(vec![
+                        Span::styled(key_label, key_style),
+                        Span::styled(shortened, value_style),
+                    ]));
+                }
+                // Soft warnings follow the end of the value
+                if let Some(last) = lines.last_mut() {
+                    last.push_span(Span::styled(warning_suffix, theme.warning));
+                }
+                ListItem::new(lines)
             })
             .collect();
 
-        let mut list_state = ListState::default();
-        list_state.select(Some(app.bitcoin_config_view.selected_index));
+        app.bitcoin_config_view.row_heights = items
+            .iter()
+            .map(|item| u16::try_from(item.height()).unwrap_or(u16::MAX))
+            .collect();
+        let selected_row = visible
+            .iter()
+            .position(|&i| i == app.bitcoin_config_view.selected_index);
+        app.bitcoin_config_view.list_state.select(selected_row);
 
         // Border style: dim both panels when the user is navigating the main sidebar
         let panel_style = if app.bitcoin_config_view.sidebar_focused {
-            Style::default().fg(Color::DarkGray)
+            theme.dimmed
         } else {
             Style::default()
         };
 
         let dirty = app.bitcoin_config_view.dirty;
-        let path_max = (panels[0].width as usize).saturating_sub(FIXED);
+        let filter_suffix = app.bitcoin_config_view.filter.title_suffix();
+        let path_max = (panels[0].width as usize)
+            .saturating_sub(FIXED)
+            .saturating_sub(filter_suffix.width());
         let title = match &app.bitcoin_conf_path {
+            _ if app.bitcoin_config_view.add_input.is_some() => format!(
+                " New entry (key=value): {}_ ",
+                app.bitcoin_config_view
+                    .add_input
+                    .as_deref()
+                    .unwrap_or_default()
+            ),
+            _ if app.bitcoin_config_view.rpcauth_input.is_some() => format!(
+                " RPC auth user: {}_ ",
+                app.bitcoin_config_view
+                    .rpcauth_input
+                    .as_deref()
+                    .unwrap_or_default()
+            ),
             Some(path) => format!(
-                " {}Bitcoin Configuration --- {} ",
+                " {}Bitcoin Configuration --- {}{filter_suffix} ",
                 if dirty { "● " } else { "" },
                 shorten_path(path, path_max, &app.home_dir)
             ),
             None => " Bitcoin Configuration ".to_string(),
         };
         let title_style = if dirty {
-            Style::default().fg(Color::Yellow)
+            theme.warning
         } else {
             Style::default()
         };
@@ -249,9 +639,21 @@ impl BitcoinConfigView {
                     .title_style(title_style)
                     .border_style(panel_style),
             )
-            .highlight_style(Style::default().bg(Color::DarkGray));
+            .highlight_style(theme.list_highlight)
+            .highlight_symbol(">> ");
+
+        f.render_stateful_widget(list, panels[0], &mut app.bitcoin_config_view.list_state);
+        render_rows_scrollbar(
+            f,
+            panels[0],
+            &app.bitcoin_config_view.row_heights,
+            app.bitcoin_config_view.list_state.offset(),
+        );
+        app.list_area = panels[0];
 
-        f.render_stateful_widget(list, panels[0], &mut list_state);
+        if !show_detail {
+            return;
+        }
 
         // Right panel: detail and edit field
         let right_block = Block::default()
@@ -261,27 +663,44 @@ impl BitcoinConfigView {
         let inner = right_block.inner(panels[1]);
         f.render_widget(right_block, panels[1]);
 
-        let selected_entry = app.bitcoin_data.get(app.bitcoin_config_view.selected_index);
+        let selected_entry = app
+            .bitcoin_data
+            .get(app.bitcoin_config_view.selected_index)
+            .filter(|_| {
+                app.bitcoin_config_view.filter.contains(
+                    app.bitcoin_config_view.selected_index,
+                    app.bitcoin_data.len(),
+                )
+            });
         let editing = app.bitcoin_config_view.editing;
         let edit_input = app.bitcoin_config_view.edit_input.clone();
 
         if let Some(entry) = selected_entry {
-            let description = entry
-                .schema
-                .as_ref()
-                .map_or("Unknown option", |s| s.description.as_str());
-            let type_label = entry
-                .schema
-                .as_ref()
-                .map(|s| format!("{}", s.config_type))
-                .unwrap_or_default();
+            let (description, type_label, default) = match &entry.schema {
+                Some(s) => (
+                    s.description.as_str(),
+                    format!("Type: {}", s.config_type),
+                    if s.default.is_empty() {
+                        "Default: none".to_string()
+                    } else {
+                        format!("Default: {}", s.default)
+                    },
+                ),
+                None => ("Unknown/custom option", String::new(), String::new()),
+            };
+            // Approximate wrapped height, as for modals
+            let description_rows = description
+                .width()
+                .div_ceil(usize::from(inner.width.max(1)))
+                .max(1);
 
             let rows = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
-                    Constraint::Length(2), // description
+                    Constraint::Length(u16::try_from(description_rows).unwrap_or(u16::MAX)),
                     Constraint::Length(1), // type
-                    Constraint::Length(1), // spacer
+                    Constraint::Length(1), // default
+                    Constraint::Length(1), // source file, if merged
                     Constraint::Length(1), // "Value:" label
                     Constraint::Length(3), // value / input box
                     Constraint::Min(0),
@@ -289,58 +708,43 @@ impl BitcoinConfigView {
                 .split(inner);
 
             f.render_widget(
-                Paragraph::new(description).style(Style::default().fg(Color::White)),
+                Paragraph::new(description)
+                    .style(theme.text)
+                    .wrap(Wrap { trim: true }),
                 rows[0],
             );
-            f.render_widget(
-                Paragraph::new(format!("Type: {type_label}"))
-                    .style(Style::default().fg(Color::Gray)),
-                rows[1],
-            );
-            f.render_widget(
-                Paragraph::new("Value:").style(Style::default().fg(Color::Gray)),
-                rows[3],
-            );
+            f.render_widget(Paragraph::new(type_label).style(theme.description), rows[1]);
+            f.render_widget(Paragraph::new(default).style(theme.description), rows[2]);
+            if let Some(source) = &entry.source {
+                f.render_widget(
+                    Paragraph::new(format!("From: {}", source.display())).style(theme.description),
+                    rows[3],
+                );
+            }
+            f.render_widget(Paragraph::new("Value:").style(theme.description), rows[4]);
 
             if editing {
                 f.render_widget(
                     Paragraph::new(edit_input.as_str())
-                        .block(Block::default().borders(Borders::ALL))
-                        .style(Style::default().fg(Color::Yellow)),
-                    rows[4],
+                        .block(edit_block(
+                            app.bitcoin_config_view.edit_error.as_deref(),
+                            &theme,
+                        ))
+                        .style(theme.warning),
+                    rows[5],
                 );
                 let cursor_x =
-                    (rows[4].x + 1 + u16::try_from(edit_input.chars().count()).unwrap_or(u16::MAX))
-                        .min(rows[4].x + rows[4].width.saturating_sub(2));
-                let cursor_y = rows[4].y + 1;
+                    (rows[5].x + 1 + u16::try_from(edit_input.chars().count()).unwrap_or(u16::MAX))
+                        .min(rows[5].x + rows[5].width.saturating_sub(2));
+                let cursor_y = rows[5].y + 1;
                 f.set_cursor_position((cursor_x, cursor_y));
             } else {
-                let (display, style) = if entry.enabled {
-                    (
-                        entry.value.clone(),
-                        Style::default()
-                            .fg(Color::White)
-                            .add_modifier(Modifier::BOLD),
-                    )
-                } else {
-                    let placeholder = entry
-                        .schema
-                        .as_ref()
-                        .filter(|s| !s.default.is_empty())
-                        .map_or_else(
-                            || "not set".to_string(),
-                            |s| format!("default: {}", s.default),
-                        );
-                    (
-                        format!("({placeholder})"),
-                        Style::default().fg(Color::DarkGray),
-                    )
-                };
+                let (display, style) = entry_display(entry, &theme);
                 f.render_widget(
                     Paragraph::new(display)
                         .block(Block::default().borders(Borders::ALL))
                         .style(style),
-                    rows[4],
+                    rows[5],
                 );
             }
         }
@@ -358,15 +762,13 @@ mod tests {
     use super::*;
     use crate::app::AppAction;
     use crate::bitcoin_config::ConfigEntry;
+    use crate::components::test_render::buffer_rows;
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
     fn entry(key: &str, value: &str, enabled: bool) -> ConfigEntry {
         ConfigEntry {
-            key: key.to_string(),
-            value: value.to_string(),
             enabled,
-            schema: None,
-            section: None,
+            ..ConfigEntry::new(key, value)
         }
     }
 
@@ -478,6 +880,42 @@ mod tests {
         assert!(view.edit_input.is_empty());
     }
 
+    #[test]
+    fn editing_validates_each_keystroke_and_blocks_invalid_commits() {
+        let schema = crate::bitcoin_config::get_default_schema()
+            .into_iter()
+            .find(|s| s.key == "rpcport")
+            .unwrap();
+        let entries = vec![ConfigEntry {
+            schema: Some(schema),
+            ..entry("rpcport", "8332", true)
+        }];
+        let mut view = BitcoinConfigView::new();
+        view.sidebar_focused = false;
+
+        view.handle_input(key(KeyCode::Enter), &entries);
+        assert!(view.editing);
+        assert!(view.edit_error.is_none());
+
+        for _ in 0..4 {
+            view.handle_input(key(KeyCode::Backspace), &entries);
+        }
+        view.handle_input(key(KeyCode::Char('x')), &entries);
+        assert!(view.edit_error.is_some());
+        let action = view.handle_input(key(KeyCode::Enter), &entries);
+        assert!(matches!(action, AppAction::None));
+        assert!(view.editing, "an invalid value stays in the editor");
+
+        view.handle_input(key(KeyCode::Backspace), &entries);
+        for c in "8333".chars() {
+            view.handle_input(key(KeyCode::Char(c)), &entries);
+        }
+        assert!(view.edit_error.is_none());
+        let action = view.handle_input(key(KeyCode::Enter), &entries);
+        assert!(matches!(action, AppAction::CommitEdit(0, ref v) if v == "8333"));
+        assert!(!view.editing);
+    }
+
     #[test]
     fn editing_esc_cancels_without_committing() {
         let mut view = BitcoinConfigView::new();
@@ -543,6 +981,44 @@ mod tests {
         assert_eq!(view.selected_index, 0);
     }
 
+    #[test]
+    fn browsing_home_and_end_jump_to_bounds() {
+        let mut view = BitcoinConfigView::new();
+        let entries = vec![
+            entry("a", "1", true),
+            entry("b", "2", true),
+            entry("c", "3", true),
+        ];
+
+        view.handle_input(key(KeyCode::End), &entries);
+        assert_eq!(view.selected_index, 2);
+
+        view.handle_input(key(KeyCode::Home), &entries);
+        assert_eq!(view.selected_index, 0);
+    }
+
+    #[test]
+    fn browsing_end_on_empty_entries_stays_at_zero() {
+        let mut view = BitcoinConfigView::new();
+        let entries: Vec<ConfigEntry> = vec![];
+
+        view.handle_input(key(KeyCode::End), &entries);
+        assert_eq!(view.selected_index, 0);
+    }
+
+    #[test]
+    fn page_down_jumps_and_clamps_at_last_entry() {
+        let mut view = BitcoinConfigView::new();
+        view.page_down(5, 20);
+        assert_eq!(view.selected_index, 5);
+
+        view.page_down(50, 20);
+        assert_eq!(view.selected_index, 19);
+
+        view.page_up(50, 20);
+        assert_eq!(view.selected_index, 0);
+    }
+
     #[test]
     fn browsing_enter_starts_editing_with_current_value() {
         let mut view = BitcoinConfigView::new();
@@ -568,7 +1044,10 @@ mod tests {
         let entries = vec![entry("rpcuser", "alice", true)];
 
         let action = view.handle_input(key(KeyCode::Char('s')), &entries);
-        assert!(matches!(action, AppAction::SaveBitcoinConfig));
+        assert!(matches!(
+            action,
+            AppAction::RequestSave(SaveTarget::BitcoinConfig)
+        ));
     }
 
     #[test]
@@ -618,7 +1097,10 @@ mod tests {
         let entries = vec![entry("rpcuser", "alice", true)];
 
         let action = view.handle_input(key(KeyCode::Char('s')), &entries);
-        assert!(matches!(action, AppAction::SaveBitcoinConfig));
+        assert!(matches!(
+            action,
+            AppAction::RequestSave(SaveTarget::BitcoinConfig)
+        ));
         assert_eq!(
             view.save_message.as_deref(),
             Some("Configuration correctly saved"),
@@ -702,4 +1184,390 @@ mod tests {
 
         assert!(output.contains("Bitcoin Configuration"));
     }
+
+    /// Renders the view `width` x 12 with `entries`, the first selected,
+    /// and returns the rows from column `from` on.
+    fn render_columns(entries: Vec<ConfigEntry>, width: u16, from: u16) -> String {
+        let mut app = crate::app::App::new();
+        app.bitcoin_conf_path = Some(std::path::PathBuf::from("/tmp/bitcoin.conf"));
+        app.bitcoin_data = entries;
+        render_app_columns(&mut app, width, from)
+    }
+
+    /// Like [`render_columns`], for an app whose view state the test has set up.
+    fn render_app_columns(app: &mut crate::app::App, width: u16, from: u16) -> String {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let mut terminal = Terminal::new(TestBackend::new(width, 12)).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                BitcoinConfigView::render(f, app, area);
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let area = buffer.area;
+        buffer_rows(
+            buffer,
+            Rect {
+                x: from,
+                width: area.width - from,
+                ..area
+            },
+        )
+    }
+
+    #[test]
+    fn test_detail_panel_describes_the_selected_entry() {
+        use crate::bitcoin_config::{ConfigCategory, ConfigSchema, ConfigType};
+
+        let mut dbcache = entry("dbcache", "1000", true);
+        dbcache.schema = Some(ConfigSchema::new(
+            "dbcache",
+            "450",
+            ConfigType::Int,
+            ConfigCategory::Core,
+            "Database cache size in MiB",
+        ));
+
+        // The detail panel is the right 55 of 100 columns
+        insta::assert_snapshot!(render_columns(vec![dbcache], 100, 45));
+    }
+
+    #[test]
+    fn test_invalid_entry_carries_its_reason_inline() {
+        let mut txindex = entry("txindex", "maybe", true);
+        txindex.validation_error = Some("must be 0 or 1".to_string());
+        let server = entry("server", "1", true);
+
+        // Skip the title row, which holds the shortened path
+        let rows = render_columns(vec![txindex, server], 40, 0)
+            .lines()
+            .skip(1)
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(rows);
+    }
+
+    const RPCAUTH: &str = "alice:0123456789abcdef0123456789abcdef$\
+        00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff";
+
+    #[test]
+    fn long_values_are_shortened_in_the_middle() {
+        let output = render_columns(vec![entry("rpcauth", RPCAUTH, true)], 40, 0);
+        assert!(output.contains("rpcauth = alice:012345…aabbccddeeff"));
+    }
+
+    #[test]
+    fn test_expanded_entry_wraps_its_value() {
+        let mut app = crate::app::App::new();
+        app.bitcoin_conf_path = Some(std::path::PathBuf::from("/tmp/bitcoin.conf"));
+        app.bitcoin_data = vec![entry("rpcauth", RPCAUTH, true), entry("server", "1", true)];
+        app.bitcoin_config_view
+            .handle_input(key(KeyCode::Char('x')), &app.bitcoin_data);
+        assert!(app.bitcoin_config_view.is_expanded(&app.bitcoin_data, 0));
+
+        // Skip the title row, which holds the shortened path
+        let rows = render_app_columns(&mut app, 40, 0)
+            .lines()
+            .skip(1)
+            .collect::<Vec<_>>()
+            .join("\n");
+        insta::assert_snapshot!(rows);
+
+        // The expansion follows the entry when another is inserted above it
+        app.bitcoin_data.insert(0, entry("daemon", "1", true));
+        assert!(app.bitcoin_config_view.is_expanded(&app.bitcoin_data, 1));
+        assert!(!app.bitcoin_config_view.is_expanded(&app.bitcoin_data, 0));
+
+        // A second press collapses it again
+        app.bitcoin_config_view.selected_index = 1;
+        app.bitcoin_config_view
+            .handle_input(key(KeyCode::Char('x')), &app.bitcoin_data);
+        assert_eq!(app.bitcoin_config_view.expanded, None);
+    }
+
+    #[test]
+    fn long_invalid_values_keep_their_whole_reason() {
+        let mut invalid = entry("rpcauth", RPCAUTH, true);
+        invalid.validation_error = Some("bad hash".to_string());
+        let output = render_columns(vec![invalid], 40, 0);
+        assert!(output.contains("…"));
+        assert!(output.contains("  ✗ bad hash"));
+    }
+
+    #[test]
+    fn rpcport_from_another_network_carries_a_warning() {
+        let entries = vec![entry("signet", "1", true), entry("rpcport", "8332", true)];
+        let output = render_columns(entries, 160, 0);
+        assert!(output.contains("rpcport = 8332  ⚠ 8332 looks like a mainnet port"));
+
+        let entries = vec![entry("signet", "1", true), entry("rpcport", "38332", true)];
+        assert!(!render_columns(entries, 160, 0).contains('⚠'));
+    }
+
+    #[test]
+    fn detail_panel_flags_options_without_schema() {
+        let output = render_columns(vec![entry("myopt", "1", true)], 100, 45);
+        assert!(output.contains("Unknown/custom option"));
+        assert!(!output.contains("Type:"));
+    }
+
+    #[test]
+    fn narrow_view_gives_the_list_the_whole_width() {
+        let output = render_columns(vec![entry("myopt", "1", true)], 40, 0);
+        assert!(!output.contains("Detail"));
+        assert!(output.contains("myopt = 1"));
+    }
+
+    #[test]
+    fn render_labels_entries_with_their_network_section() {
+        use crate::app::App;
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let mut app = App::new();
+        app.bitcoin_conf_path = Some(std::path::PathBuf::from("/tmp/bitcoin.conf"));
+        let mut signet = entry("rpcport", "38332", true);
+        signet.section = Some("signet".to_string());
+        app.bitcoin_data = vec![entry("server", "1", true), signet];
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 20)).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                BitcoinConfigView::render(f, &mut app, area);
+            })
+            .unwrap();
+        let rows: Vec<String> = terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(120)
+            .map(|row| row.iter().map(|c| c.symbol()).collect())
+            .collect();
+
+        // Only the sectioned entry gets a header, directly above its value
+        assert_eq!(rows.iter().filter(|r| r.contains("[signet]")).count(), 1);
+        let header = rows.iter().position(|r| r.contains("[signet]")).unwrap();
+        assert!(rows[header + 1].contains("rpcport = 38332"));
+    }
+
+    #[test]
+    fn entries_of_another_network_render_inactive() {
+        let mut main = entry("rpcport", "8332", true);
+        main.section = Some("main".to_string());
+        let mut signet = entry("rpcport", "38332", true);
+        signet.section = Some("signet".to_string());
+
+        let output = render_columns(vec![entry("signet", "1", true), main, signet], 100, 0);
+
+        assert!(output.contains("[main] inactive"));
+        assert!(output.contains("[signet]"));
+        assert!(!output.contains("[signet] inactive"));
+    }
+
+    #[test]
+    fn a_prompts_for_rpcauth_user_and_returns_generate_action() {
+        let mut view = BitcoinConfigView::new();
+        view.sidebar_focused = false;
+        let entries = vec![entry("server", "1", true)];
+
+        view.handle_input(key(KeyCode::Char('a')), &entries);
+        assert_eq!(view.rpcauth_input.as_deref(), Some(""));
+
+        // Empty names are not accepted and `:` cannot be typed
+        assert!(matches!(
+            view.handle_input(key(KeyCode::Enter), &entries),
+            AppAction::None
+        ));
+        for c in "al:ice".chars() {
+            view.handle_input(key(KeyCode::Char(c)), &entries);
+        }
+        assert_eq!(view.rpcauth_input.as_deref(), Some("alice"));
+
+        let action = view.handle_input(key(KeyCode::Enter), &entries);
+        assert!(matches!(action, AppAction::GenerateRpcAuth(ref u) if u == "alice"));
+        assert!(view.rpcauth_input.is_none());
+    }
+
+    #[test]
+    fn n_prompts_for_key_value_and_returns_add_action() {
+        let mut view = BitcoinConfigView::new();
+        view.sidebar_focused = false;
+        let entries = vec![entry("server", "1", true)];
+
+        view.handle_input(key(KeyCode::Char('n')), &entries);
+        for c in "myopt".chars() {
+            view.handle_input(key(KeyCode::Char(c)), &entries);
+        }
+        // Without `=` the prompt stays open and explains the format
+        assert!(matches!(
+            view.handle_input(key(KeyCode::Enter), &entries),
+            AppAction::None
+        ));
+        assert!(view.add_input.is_some());
+        assert_eq!(view.warning_message.as_deref(), Some("Use key=value"));
+
+        for c in "=1".chars() {
+            view.handle_input(key(KeyCode::Char(c)), &entries);
+        }
+        let action = view.handle_input(key(KeyCode::Enter), &entries);
+        assert!(
+            matches!(action, AppAction::AddBitcoinEntry(ref k, ref v) if k == "myopt" && v == "1")
+        );
+        assert!(view.add_input.is_none());
+    }
+
+    #[test]
+    fn esc_closes_rpcauth_prompt_without_action() {
+        let mut view = BitcoinConfigView::new();
+        view.sidebar_focused = false;
+        view.rpcauth_input = Some("bob".to_string());
+
+        let action = view.handle_input(key(KeyCode::Esc), &[]);
+        assert!(matches!(action, AppAction::None));
+        assert!(view.rpcauth_input.is_none());
+        // Esc only closed the prompt, focus stays on the config
+        assert!(!view.sidebar_focused);
+    }
+
+    #[test]
+    fn space_returns_toggle_action() {
+        let mut view = BitcoinConfigView::new();
+        view.sidebar_focused = false;
+        view.selected_index = 1;
+        let entries = vec![entry("a", "1", true), entry("b", "2", false)];
+
+        let action = view.handle_input(key(KeyCode::Char(' ')), &entries);
+        assert!(matches!(action, AppAction::ToggleBitcoinEntry(1)));
+    }
+
+    #[test]
+    fn shift_d_duplicates_the_selected_entry() {
+        let mut view = BitcoinConfigView::new();
+        view.sidebar_focused = false;
+        view.selected_index = 1;
+        let entries = vec![
+            entry("rpcuser", "alice", true),
+            entry("zmqpubrawtx", "tcp://127.0.0.1:28332", true),
+        ];
+
+        let action = view.handle_input(
+            KeyEvent::new(KeyCode::Char('D'), KeyModifiers::SHIFT),
+            &entries,
+        );
+        assert!(matches!(action, AppAction::DuplicateBitcoinEntry(1)));
+    }
+
+    #[test]
+    fn y_copies_the_selected_entry_value() {
+        let mut view = BitcoinConfigView::new();
+        view.sidebar_focused = false;
+        view.selected_index = 1;
+        let entries = vec![
+            entry("rpcuser", "alice", true),
+            entry("rpcpassword", "hunter2", true),
+            entry("rpcport", "8332", false),
+        ];
+
+        let action = view.handle_input(key(KeyCode::Char('y')), &entries);
+        assert!(matches!(
+            action,
+            AppAction::CopyValue(SaveTarget::BitcoinConfig, ref v) if v == "hunter2"
+        ));
+
+        // Unset entries have nothing to copy
+        view.selected_index = 2;
+        let action = view.handle_input(key(KeyCode::Char('y')), &entries);
+        assert!(matches!(action, AppAction::None));
+    }
+
+    #[test]
+    fn space_while_editing_inserts_text() {
+        let mut view = BitcoinConfigView::new();
+        view.editing = true;
+        let entries = vec![entry("a", "1", true)];
+
+        let action = view.handle_input(key(KeyCode::Char(' ')), &entries);
+        assert!(matches!(action, AppAction::None));
+        assert_eq!(view.edit_input, " ");
+    }
+
+    #[test]
+    fn entry_display_flags_invalid_values_in_error_color() {
+        let theme = Theme::dark();
+        let mut bad = entry("txindex", "maybe", true);
+        bad.validation_error = Some("must be 0, 1, true or false".to_string());
+
+        let (text, style) = entry_display(&bad, &theme);
+        assert_eq!(text, "maybe  ✗ must be 0, 1, true or false");
+        assert_eq!(style, theme.error);
+
+        // A disabled entry shows its placeholder, not the stale error
+        bad.enabled = false;
+        assert_eq!(entry_display(&bad, &theme).1, theme.default_value);
+    }
+
+    #[test]
+    fn entry_display_masks_the_rpc_password() {
+        let theme = Theme::dark();
+        assert_eq!(
+            entry_display(&entry("rpcpassword", "hunter2", true), &theme).0,
+            "*****"
+        );
+        assert_eq!(
+            entry_display(&entry(PASSWORD_FILE_KEY, "/run/rpc.secret", true), &theme).0,
+            "/run/rpc.secret (password: *****, read on save)"
+        );
+    }
+
+    #[test]
+    fn entry_display_shows_placeholder_when_disabled() {
+        assert_eq!(entry_display(&entry("a", "1", true), &Theme::dark()).0, "1");
+        assert_eq!(
+            entry_display(&entry("a", "1", false), &Theme::dark()).0,
+            "(not set)"
+        );
+    }
+
+    #[test]
+    fn filter_matches_key_section_and_value() {
+        let mut view = BitcoinConfigView::new();
+        let mut testnet = entry("rpcuser", "alice", true);
+        testnet.section = Some("test".to_string());
+        let entries = vec![
+            entry("rpcport", "8332", true),
+            entry("server", "1", true),
+            testnet,
+            entry("bind", "127.0.0.1", true),
+        ];
+
+        view.handle_input(key(KeyCode::Char('/')), &entries);
+        for c in "TEST".chars() {
+            view.handle_input(key(KeyCode::Char(c)), &entries);
+        }
+        assert_eq!(view.filter.filtered_indices, vec![2]);
+        assert_eq!(view.selected_index, 2);
+
+        view.handle_input(key(KeyCode::Esc), &entries);
+        assert!(!view.filter.is_active());
+        assert!(!view.filter.typing);
+    }
+
+    #[test]
+    fn filter_does_not_match_the_masked_password() {
+        let mut view = BitcoinConfigView::new();
+        let entries = vec![
+            entry("rpcpassword", "hunter2", true),
+            entry("rpcuser", "hunter", true),
+        ];
+
+        view.handle_input(key(KeyCode::Char('/')), &entries);
+        for c in "hunter2".chars() {
+            view.handle_input(key(KeyCode::Char(c)), &entries);
+        }
+        assert!(view.filter.filtered_indices.is_empty());
+    }
 }
@@ -36,7 +36,7 @@ impl BitcoinStatusView {
         let tabs = Tabs::new(BITCOIN_STATUS_TABS.to_vec())
             .block(Block::default().borders(Borders::ALL).title(" Info "))
             .select(app.bitcoin_status_tab)
-            .highlight_style(Style::default().bg(Color::Gray).fg(Color::Black));
+            .highlight_style(app.theme.sidebar_highlight);
 
         f.render_widget(tabs, outer[0]);
 
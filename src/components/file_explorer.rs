@@ -3,13 +3,239 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::app::{App, AppAction};
+use crate::components::scrollbar::render_list_scrollbar;
+use crate::theme::Theme;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, List, ListItem, ListState},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
 };
+use std::cmp::Ordering;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Ordering applied to entries by `load_directory`. Directories always sort
+/// before files, and `..` always comes first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortMode {
+    /// Case-insensitive natural order (`file2` before `file10`).
+    #[default]
+    Name,
+    /// Smallest first.
+    Size,
+    /// Most recently modified first.
+    ModifiedDesc,
+}
+
+impl SortMode {
+    /// Returns the mode that follows `self` when cycling with the sort key.
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Name => SortMode::Size,
+            SortMode::Size => SortMode::ModifiedDesc,
+            SortMode::ModifiedDesc => SortMode::Name,
+        }
+    }
+}
+
+/// What the explorer hands back when the user confirms a choice.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectMode {
+    /// A file; directories are entered.
+    #[default]
+    File,
+    /// A directory, such as a data dir. Files are hidden and the current
+    /// directory is offered as the first entry.
+    Directory,
+}
+
+impl std::fmt::Display for SortMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortMode::Name => write!(f, "name"),
+            SortMode::Size => write!(f, "size"),
+            SortMode::ModifiedDesc => write!(f, "newest"),
+        }
+    }
+}
+
+/// Compares two names case-insensitively, treating runs of digits as numbers
+/// so that `file2` sorts before `file10`.
+#[must_use]
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let mut x_num = String::new();
+                while let Some(c) = a_chars.peek().copied().filter(char::is_ascii_digit) {
+                    x_num.push(c);
+                    a_chars.next();
+                }
+                let mut y_num = String::new();
+                while let Some(c) = b_chars.peek().copied().filter(char::is_ascii_digit) {
+                    y_num.push(c);
+                    b_chars.next();
+                }
+                // Compare numerically without overflow: strip leading zeros,
+                // then the longer run is larger, then lexicographically
+                let x_trim = x_num.trim_start_matches('0');
+                let y_trim = y_num.trim_start_matches('0');
+                let ord = x_trim
+                    .len()
+                    .cmp(&y_trim.len())
+                    .then_with(|| x_trim.cmp(y_trim))
+                    .then_with(|| x_num.len().cmp(&y_num.len()));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ord = x.to_lowercase().cmp(y.to_lowercase());
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+/// Scores `candidate` against `query` as an fzf-style subsequence match,
+/// ignoring case, or `None` when the query's characters do not all appear in
+/// order. Consecutive characters and characters starting a word (after `.`,
+/// `_`, `-` or a space) score higher, and each character skipped between two
+/// matches costs a point. Every occurrence of the first query character is
+/// tried as a starting point and the best score wins.
+#[must_use]
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query.to_lowercase().chars().collect();
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let Some((&first, rest)) = query.split_first() else {
+        return Some(0);
+    };
+    let word_start = |at: usize| at == 0 || matches!(candidate[at - 1], '.' | '_' | '-' | ' ');
+
+    let mut best = None;
+    for start in (0..candidate.len()).filter(|&i| candidate[i] == first) {
+        let mut score = 16 + if word_start(start) { 8 } else { 0 };
+        let mut last = start;
+        let mut matched = true;
+        for &q in rest {
+            let Some(offset) = candidate[last + 1..].iter().position(|&c| c == q) else {
+                matched = false;
+                break;
+            };
+            let at = last + 1 + offset;
+            score += 16;
+            if offset == 0 {
+                score += 8;
+            }
+            if word_start(at) {
+                score += 8;
+            }
+            score -= i64::try_from(offset).unwrap_or(i64::MAX);
+            last = at;
+        }
+        if matched && best.is_none_or(|top| score > top) {
+            best = Some(score);
+        }
+    }
+    best
+}
+
+/// Maximum number of bookmarks reachable through the `1`–`9` jump keys.
+pub const MAX_BOOKMARKS: usize = 9;
+
+/// Bookmarks offered before the user has saved any: the default bitcoin
+/// data directory and the directory pdm was started from.
+#[must_use]
+pub fn default_bookmarks(cwd: &Path) -> Vec<PathBuf> {
+    let mut bookmarks = Vec::new();
+    if let Ok(home) = std::env::var("HOME")
+        && !home.is_empty()
+    {
+        bookmarks.push(PathBuf::from(home).join(".bitcoin"));
+    }
+    if !bookmarks.iter().any(|b| b == cwd) {
+        bookmarks.push(cwd.to_path_buf());
+    }
+    bookmarks
+}
+
+/// Turns a `read_dir` failure into a message for the explorer list area.
+fn describe_read_error(e: &std::io::Error) -> String {
+    match e.kind() {
+        std::io::ErrorKind::PermissionDenied => {
+            "Permission denied: this directory cannot be read".to_string()
+        }
+        std::io::ErrorKind::NotFound => "Directory not found".to_string(),
+        _ => format!("Cannot read directory: {e}"),
+    }
+}
+
+/// Formats a byte count as a short human-readable size (`512B`, `1.2K`, `3.4M`).
+#[must_use]
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+    #[allow(clippy::cast_precision_loss)] // Display only; one decimal place
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1}{}", UNITS[unit])
+}
+
+/// Formats a modification time as `YYYY-MM-DD HH:MM` (UTC).
+#[must_use]
+pub fn format_mtime(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let days = i64::try_from(secs / 86_400).unwrap_or(0);
+    let rem = secs % 86_400;
+
+    // Civil-from-days (Howard Hinnant), valid for the proleptic Gregorian calendar
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        rem / 3600,
+        (rem % 3600) / 60
+    )
+}
+
+/// Most entries [`FileExplorer::load_directory`] lists from one directory.
+/// Every row is laid out on each frame, so a directory holding tens of
+/// thousands of files would otherwise stall the UI; the rest are counted
+/// but not listed.
+pub const MAX_DIR_ENTRIES: usize = 5_000;
+
+/// Narrowest explorer that flows an overflowing listing into columns.
+const GRID_MIN_WIDTH: u16 = 80;
+/// Longest name, in columns, that still gets the multi-column listing; past
+/// it the single column with sizes and dates reads better.
+const GRID_MAX_NAME: usize = 24;
 
 /// `FileExplorer` maintains the current directory, a sorted list of entries,
 /// and the currently selected index. It supports navigating directories,
@@ -20,10 +246,44 @@ pub struct FileExplorer {
     pub current_dir: PathBuf,
     /// Sorted list of files and folders in `current_dir`.
     pub files: Vec<PathBuf>,
+    /// Metadata for each entry in `files`, captured at `load_directory` time
+    /// so rendering does not `stat` every row on every frame. `None` for the
+    /// virtual `..` and directory-selection entries.
+    pub metadata: Vec<Option<fs::Metadata>>,
+    /// For each entry in `files`, the target of the symlink if the entry is
+    /// one (resolved with `fs::read_link`), otherwise `None`.
+    pub link_targets: Vec<Option<PathBuf>>,
     /// Index of the currently selected item.
     pub selected_index: usize,
-    /// When true, the explorer is in directory-selection mode.
-    pub allow_dir_select: bool,
+    /// Whether the explorer picks a file or a directory.
+    pub select_mode: SelectMode,
+    /// Ordering applied when the directory is (re)loaded.
+    pub sort_mode: SortMode,
+    /// Whether entries whose name starts with `.` are listed.
+    pub show_hidden: bool,
+    /// Frequently used directories, reachable from the bookmark overlay.
+    pub bookmarks: Vec<PathBuf>,
+    /// When true, the bookmark overlay is shown and captures input.
+    pub show_bookmarks: bool,
+    /// Why `current_dir` could not be listed, if reading it failed.
+    pub load_error: Option<String>,
+    /// Transient notice shown in the title (e.g. an unavailable bookmark).
+    /// Cleared whenever a directory is loaded.
+    pub message: Option<String>,
+    /// Name being typed for a new directory; `Some` while the prompt is open.
+    pub new_dir_input: Option<String>,
+    /// Entries per column while the last render laid the listing out in
+    /// columns, `None` for the single-column list. Left and Right move
+    /// across columns only in the former.
+    pub grid_rows: Option<usize>,
+    /// Query being typed to jump to an entry; `Some` while the search
+    /// prompt is open.
+    pub search: Option<String>,
+    /// When true, the search matches the query as a subsequence of the name
+    /// (see [`fuzzy_score`]) rather than as a prefix.
+    pub fuzzy_search: bool,
+    /// Entry waiting for a y/n confirmation before it is deleted.
+    pub pending_delete: Option<PathBuf>,
 }
 
 impl Default for FileExplorer {
@@ -36,236 +296,1641 @@ impl FileExplorer {
     /// Creates a new `FileExplorer` starting at the process working directory.
     #[must_use]
     pub fn new() -> Self {
-        let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-        let mut explorer = Self {
+        Self::with_dir(std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")))
+    }
+
+    /// Creates a new `FileExplorer` listing `current_dir`, whatever the
+    /// process working directory is.
+    #[must_use]
+    pub fn with_dir(current_dir: PathBuf) -> Self {
+        let mut explorer = Self::unlisted(current_dir);
+        explorer.bookmarks = default_bookmarks(&explorer.current_dir);
+        explorer.load_directory();
+        explorer
+    }
+
+    /// An explorer on `current_dir` with nothing listed or bookmarked yet;
+    /// [`FileExplorer::load_directory`] fills in the listing.
+    fn unlisted(current_dir: PathBuf) -> Self {
+        Self {
             current_dir,
             files: Vec::new(),
+            metadata: Vec::new(),
+            link_targets: Vec::new(),
             selected_index: 0,
-            allow_dir_select: false,
-        };
-        explorer.load_directory();
-        explorer
+            select_mode: SelectMode::File,
+            sort_mode: SortMode::default(),
+            show_hidden: true,
+            bookmarks: Vec::new(),
+            show_bookmarks: false,
+            load_error: None,
+            message: None,
+            new_dir_input: None,
+            grid_rows: None,
+            search: None,
+            fuzzy_search: false,
+            pending_delete: None,
+        }
+    }
+
+    /// True when confirming hands back a directory rather than a file.
+    #[must_use]
+    pub fn selects_directories(&self) -> bool {
+        self.select_mode == SelectMode::Directory
+    }
+
+    /// Switches to `mode`, reloading the listing when it changes since
+    /// directory mode offers the current directory and hides files.
+    pub fn set_select_mode(&mut self, mode: SelectMode) {
+        if self.select_mode != mode {
+            self.select_mode = mode;
+            self.load_directory();
+        }
+    }
+
+    /// Moves back to the process working directory when the remembered
+    /// `current_dir` no longer exists, so a reopened explorer never lists a
+    /// deleted directory.
+    pub fn ensure_current_dir(&mut self) {
+        if !self.current_dir.is_dir() {
+            self.current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+            self.load_directory();
+        }
     }
 
     /// Loads the contents of `current_dir` into `files`.
     ///
     /// Directories are listed first, followed by files. If the directory
     /// has a parent, a virtual `..` entry is added to allow navigating upward.
+    /// Only the first [`MAX_DIR_ENTRIES`] entries in that order are listed,
+    /// with `message` saying how many there were.
+    ///
+    /// Names starting with `.` are skipped unless `show_hidden` is set.
+    /// Reloading the same directory, e.g. after changing the sort order,
+    /// keeps the selection on the entry it was on; otherwise, or when that
+    /// entry is gone, the first entry is selected.
     pub fn load_directory(&mut self) {
+        let previous = self
+            .files
+            .get(self.selected_index)
+            .filter(|p| p.parent() == Some(self.current_dir.as_path()))
+            .cloned();
         self.files.clear();
+        self.metadata.clear();
+        self.link_targets.clear();
         self.selected_index = 0;
+        self.load_error = None;
+        self.message = None;
 
-        if self.allow_dir_select {
+        if self.selects_directories() {
             self.files.push(self.current_dir.clone());
+            self.metadata.push(None);
+            self.link_targets.push(None);
         }
 
         if self.current_dir.parent().is_some() {
             self.files.push(self.current_dir.join(".."));
+            self.metadata.push(None);
+            self.link_targets.push(None);
+        }
+
+        let entries = match fs::read_dir(&self.current_dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.load_error = Some(describe_read_error(&e));
+                return;
+            }
+        };
+
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+
+        let show_hidden = self.show_hidden;
+        let entries = entries
+            .flatten()
+            .filter(|e| show_hidden || !e.file_name().to_string_lossy().starts_with('.'));
+        for entry in entries {
+            let path = entry.path();
+            // `fs::metadata` follows symlinks (None when the link is
+            // broken); `symlink_metadata` describes the link itself.
+            let meta = fs::metadata(&path).ok();
+            let link = fs::symlink_metadata(&path)
+                .ok()
+                .filter(|m| m.file_type().is_symlink())
+                .map(|_| fs::read_link(&path).unwrap_or_default());
+            if meta.as_ref().is_some_and(fs::Metadata::is_dir) {
+                dirs.push((path, meta, link));
+            } else if !self.selects_directories() {
+                files.push((path, meta, link));
+            }
+        }
+
+        let mode = self.sort_mode;
+        let compare = |a: &(PathBuf, Option<fs::Metadata>, Option<PathBuf>),
+                       b: &(PathBuf, Option<fs::Metadata>, Option<PathBuf>)| {
+            let by_name = || {
+                natural_cmp(
+                    &a.0.file_name().unwrap_or_default().to_string_lossy(),
+                    &b.0.file_name().unwrap_or_default().to_string_lossy(),
+                )
+            };
+            match mode {
+                SortMode::Name => by_name(),
+                SortMode::Size => {
+                    let size = |m: &Option<fs::Metadata>| m.as_ref().map_or(0, fs::Metadata::len);
+                    size(&a.1).cmp(&size(&b.1)).then_with(by_name)
+                }
+                SortMode::ModifiedDesc => {
+                    let mtime =
+                        |m: &Option<fs::Metadata>| m.as_ref().and_then(|m| m.modified().ok());
+                    mtime(&b.1).cmp(&mtime(&a.1)).then_with(by_name)
+                }
+            }
+        };
+        dirs.sort_by(compare);
+        files.sort_by(compare);
+
+        let total = dirs.len() + files.len();
+        if total > MAX_DIR_ENTRIES {
+            self.message = Some(format!(
+                "… (truncated: {MAX_DIR_ENTRIES} of {total} entries)"
+            ));
+        }
+
+        for (path, meta, link) in dirs.into_iter().chain(files).take(MAX_DIR_ENTRIES) {
+            self.files.push(path);
+            self.metadata.push(meta);
+            self.link_targets.push(link);
+        }
+        if let Some(index) = previous.and_then(|prev| self.files.iter().position(|p| *p == prev)) {
+            self.selected_index = index;
+        }
+    }
+
+    /// Returns `true` if the entry at `index` is a symlink whose target
+    /// does not exist.
+    #[must_use]
+    pub fn is_broken_link(&self, index: usize) -> bool {
+        self.link_targets.get(index).is_some_and(Option::is_some)
+            && self.metadata.get(index).is_some_and(Option::is_none)
+    }
+
+    /// Renders `current_dir` as a breadcrumb (`/ › home › user`) that fits in
+    /// `max_width` display columns. Leading components are replaced by `…`
+    /// when the full path is too long.
+    #[must_use]
+    pub fn breadcrumb(&self, max_width: usize) -> String {
+        const SEP: &str = " › ";
+
+        let mut parts: Vec<String> = Vec::new();
+        for component in self.current_dir.components() {
+            match component {
+                Component::Prefix(prefix) => {
+                    parts.push(prefix.as_os_str().to_string_lossy().into_owned());
+                }
+                Component::RootDir if parts.is_empty() => parts.push("/".to_string()),
+                Component::Normal(name) => parts.push(name.to_string_lossy().into_owned()),
+                Component::RootDir | Component::CurDir | Component::ParentDir => {}
+            }
+        }
+
+        let full = parts.join(SEP);
+        if full.width() <= max_width {
+            return full;
+        }
+
+        // Keep as many trailing components as fit behind an ellipsis
+        let ellipsis = format!("\u{2026}{SEP}");
+        let mut kept: Vec<&str> = Vec::new();
+        let mut width = ellipsis.width();
+        for part in parts.iter().rev() {
+            let extra = part.width() + if kept.is_empty() { 0 } else { SEP.width() };
+            if width + extra > max_width {
+                break;
+            }
+            width += extra;
+            kept.push(part.as_str());
+        }
+
+        if kept.is_empty() {
+            // Not even the last component fits: truncate it from the left
+            let last = parts.last().map_or("", String::as_str);
+            let avail = max_width.saturating_sub(1);
+            let mut width_acc = 0usize;
+            let mut suffix: Vec<char> = Vec::new();
+            for c in last.chars().rev() {
+                let cw = UnicodeWidthChar::width(c).unwrap_or(1);
+                if width_acc + cw > avail {
+                    break;
+                }
+                width_acc += cw;
+                suffix.push(c);
+            }
+            return format!("\u{2026}{}", suffix.into_iter().rev().collect::<String>());
+        }
+
+        kept.reverse();
+        format!("{ellipsis}{}", kept.join(SEP))
+    }
+
+    /// Moves the selection to the next entry.
+    pub fn next(&mut self) {
+        if !self.files.is_empty() {
+            self.selected_index = (self.selected_index + 1) % self.files.len();
+        }
+    }
+
+    /// Moves the selection to the previous entry.
+    pub fn previous(&mut self) {
+        if !self.files.is_empty() {
+            if self.selected_index == 0 {
+                self.selected_index = self.files.len() - 1;
+            } else {
+                self.selected_index -= 1;
+            }
+        }
+    }
+
+    /// Moves the selection `delta` columns right (or left when negative) in
+    /// the multi-column listing, keeping its row. A move into the partly
+    /// filled last column lands on its last entry; a move past either edge,
+    /// or any move in the single-column list, does nothing.
+    pub fn move_column(&mut self, delta: isize) {
+        let Some(rows) = self.grid_rows.filter(|&rows| rows > 0) else {
+            return;
+        };
+        let len = self.files.len();
+        let column = self.selected_index / rows;
+        let last_column = len.saturating_sub(1) / rows;
+        let Some(target) = column
+            .checked_add_signed(delta)
+            .filter(|&c| c <= last_column)
+        else {
+            return;
+        };
+        let row = self.selected_index % rows;
+        self.selected_index = (target * rows + row).min(len - 1);
+    }
+
+    /// Adds `path` to the bookmarks unless it is already present or the list
+    /// is full. Returns `true` if the list changed.
+    pub fn add_bookmark(&mut self, path: PathBuf) -> bool {
+        if self.bookmarks.len() >= MAX_BOOKMARKS || self.bookmarks.contains(&path) {
+            return false;
         }
+        self.bookmarks.push(path);
+        true
+    }
+
+    /// Removes the bookmark at `index`. Returns `true` if one was removed.
+    pub fn remove_bookmark(&mut self, index: usize) -> bool {
+        if index < self.bookmarks.len() {
+            self.bookmarks.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Navigates to the bookmark at `index`.
+    ///
+    /// Returns `false` without navigating when the index is out of range or
+    /// the bookmarked directory no longer exists; the latter sets `message`.
+    pub fn goto_bookmark(&mut self, index: usize) -> bool {
+        let Some(target) = self.bookmarks.get(index).cloned() else {
+            return false;
+        };
+        if !target.is_dir() {
+            self.message = Some(format!("Bookmark unavailable: {}", target.display()));
+            return false;
+        }
+        self.current_dir = target;
+        self.load_directory();
+        true
+    }
+
+    /// Creates `name` as a subdirectory of `current_dir`, reloads the listing
+    /// and selects the new entry.
+    ///
+    /// Returns `false` and sets `message` when the name is empty, contains a
+    /// path separator, or the directory cannot be created.
+    pub fn create_directory(&mut self, name: &str) -> bool {
+        let name = name.trim();
+        if name.is_empty() {
+            self.message = Some("Directory name cannot be empty".to_string());
+            return false;
+        }
+        if name.contains('/') || name.contains(std::path::MAIN_SEPARATOR) {
+            self.message = Some("Directory name cannot contain path separators".to_string());
+            return false;
+        }
+        if name == "." || name == ".." {
+            self.message = Some(format!("Invalid directory name: {name}"));
+            return false;
+        }
+
+        let target = self.current_dir.join(name);
+        if let Err(e) = fs::create_dir(&target) {
+            self.message = Some(match e.kind() {
+                std::io::ErrorKind::AlreadyExists => format!("Already exists: {name}"),
+                _ => format!("Cannot create {name}: {e}"),
+            });
+            return false;
+        }
+
+        self.load_directory();
+        if let Some(idx) = self.files.iter().position(|p| p == &target) {
+            self.selected_index = idx;
+        }
+        true
+    }
+
+    /// Asks for confirmation before deleting the highlighted entry. Refuses
+    /// `..` and the "use this directory" entry, setting `message` instead.
+    pub fn request_delete(&mut self) {
+        let Some(path) = self.files.get(self.selected_index).cloned() else {
+            return;
+        };
+        if path.ends_with("..") || path == self.current_dir {
+            self.message = Some("Cannot delete this entry".to_string());
+            return;
+        }
+        self.message = None;
+        self.pending_delete = Some(path);
+    }
+
+    /// Deletes a file, symlink or empty directory and reloads the listing,
+    /// keeping the selection at the same position (clamped to the shorter
+    /// list). Returns `false` and sets `message` when the entry is `..`, a
+    /// non-empty directory, or removal fails.
+    pub fn delete_entry(&mut self, path: &Path) -> bool {
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        if path.ends_with("..") || path == self.current_dir {
+            self.message = Some("Cannot delete this entry".to_string());
+            return false;
+        }
+
+        // `symlink_metadata` so a link to a directory removes the link only
+        let is_dir = fs::symlink_metadata(path).is_ok_and(|m| m.is_dir());
+        let result = if is_dir {
+            fs::remove_dir(path)
+        } else {
+            fs::remove_file(path)
+        };
+        if let Err(e) = result {
+            self.message = Some(match e.kind() {
+                std::io::ErrorKind::DirectoryNotEmpty => format!("Directory not empty: {name}"),
+                _ => format!("Cannot delete {name}: {e}"),
+            });
+            return false;
+        }
+
+        let index = self.selected_index;
+        self.load_directory();
+        self.selected_index = index.min(self.files.len().saturating_sub(1));
+        self.message = Some(format!("Deleted {name}"));
+        true
+    }
+
+    /// Input handling while the new-directory prompt is open.
+    fn handle_new_dir_input(&mut self, key: KeyEvent) -> AppAction {
+        let Some(input) = self.new_dir_input.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                if let Some(name) = self.new_dir_input.take() {
+                    self.create_directory(&name);
+                }
+            }
+            KeyCode::Esc => self.new_dir_input = None,
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    /// Input handling while a deletion waits for confirmation: `y` deletes,
+    /// `n` or Esc keeps the entry.
+    fn handle_delete_confirm(&mut self, key: KeyEvent) -> AppAction {
+        match key.code {
+            KeyCode::Char('y' | 'Y') => {
+                if let Some(path) = self.pending_delete.take() {
+                    self.delete_entry(&path);
+                }
+            }
+            KeyCode::Char('n' | 'N') | KeyCode::Esc => self.pending_delete = None,
+            _ => {}
+        }
+        AppAction::None
+    }
+
+    /// Input handling while the search prompt is open. Every edit moves the
+    /// selection to the best match; Enter then opens it like the Enter key
+    /// in the listing and Esc closes the prompt where the selection is.
+    fn handle_search_input(&mut self, key: KeyEvent) -> AppAction {
+        let Some(query) = self.search.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char(c) => query.push(c),
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            KeyCode::Tab => self.fuzzy_search = !self.fuzzy_search,
+            KeyCode::Enter => {
+                self.search = None;
+                self.message = None;
+                return self
+                    .select()
+                    .map_or(AppAction::None, AppAction::FileSelected);
+            }
+            KeyCode::Esc => {
+                self.search = None;
+                self.message = None;
+                return AppAction::None;
+            }
+            _ => return AppAction::None,
+        }
+        self.select_search_match();
+        AppAction::None
+    }
+
+    /// Selects the entry best matching the search query: the highest
+    /// [`fuzzy_score`] in fuzzy mode, otherwise the first name starting with
+    /// the query. Leaves the selection alone and sets `message` to
+    /// "no match" when nothing matches.
+    fn select_search_match(&mut self) {
+        let Some(query) = self.search.as_deref().filter(|q| !q.is_empty()) else {
+            self.message = None;
+            return;
+        };
+        let lower = query.to_lowercase();
+        let mut best: Option<(usize, i64)> = None;
+        for (i, path) in self.files.iter().enumerate() {
+            if path.ends_with("..") || (self.selects_directories() && *path == self.current_dir) {
+                continue;
+            }
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let score = if self.fuzzy_search {
+                fuzzy_score(query, &name)
+            } else {
+                name.to_lowercase().starts_with(&lower).then_some(0)
+            };
+            if let Some(score) = score
+                && best.is_none_or(|(_, top)| score > top)
+            {
+                best = Some((i, score));
+            }
+        }
+        match best {
+            Some((i, _)) => {
+                self.selected_index = i;
+                self.message = None;
+            }
+            None => self.message = Some("no match".to_string()),
+        }
+    }
+
+    /// Input handling while the bookmark overlay is open.
+    fn handle_bookmark_input(&mut self, key: KeyEvent) -> AppAction {
+        match key.code {
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = c as usize - '1' as usize;
+                if index < self.bookmarks.len() {
+                    self.show_bookmarks = false;
+                    self.goto_bookmark(index);
+                }
+                AppAction::None
+            }
+            KeyCode::Char('a') => {
+                if self.add_bookmark(self.current_dir.clone()) {
+                    AppAction::BookmarksChanged
+                } else {
+                    AppAction::None
+                }
+            }
+            KeyCode::Char('x') => {
+                let pos = self.bookmarks.iter().position(|b| b == &self.current_dir);
+                if pos.is_some_and(|i| self.remove_bookmark(i)) {
+                    AppAction::BookmarksChanged
+                } else {
+                    AppAction::None
+                }
+            }
+            KeyCode::Char('b') | KeyCode::Esc => {
+                self.show_bookmarks = false;
+                AppAction::None
+            }
+            _ => AppAction::None,
+        }
+    }
+
+    /// Moves the selection to the first entry. No-op on an empty list.
+    pub fn first(&mut self) {
+        if !self.files.is_empty() {
+            self.selected_index = 0;
+        }
+    }
+
+    /// Moves the selection to the last entry. No-op on an empty list.
+    pub fn last(&mut self) {
+        if !self.files.is_empty() {
+            self.selected_index = self.files.len() - 1;
+        }
+    }
+
+    /// Moves the selection down by `n` entries, stopping at the last entry.
+    pub fn page_down(&mut self, n: usize) {
+        if !self.files.is_empty() {
+            self.selected_index = self
+                .selected_index
+                .saturating_add(n)
+                .min(self.files.len() - 1);
+        }
+    }
+
+    /// Moves the selection up by `n` entries, stopping at the first entry.
+    pub fn page_up(&mut self, n: usize) {
+        self.selected_index = self.selected_index.saturating_sub(n);
+    }
+
+    /// Selects the current entry.
+    ///
+    /// - If it is a directory, enters that directory.
+    /// - If it is `..`, moves to the parent directory.
+    /// - If it is a file, returns its path.
+    pub fn select(&mut self) -> Option<PathBuf> {
+        if self.files.is_empty() {
+            return None;
+        }
+
+        let selected = self.files[self.selected_index].clone();
+
+        if self.is_broken_link(self.selected_index) {
+            let name = selected.file_name().unwrap_or_default().to_string_lossy();
+            self.message = Some(format!("Broken symlink: {name}"));
+            return None;
+        }
+
+        if self.selects_directories() && selected == self.current_dir {
+            return Some(selected);
+        }
+
+        if selected.ends_with("..") {
+            if let Some(parent) = self.current_dir.parent() {
+                self.current_dir = parent.to_path_buf();
+                self.load_directory();
+            }
+        } else if selected.is_dir() {
+            self.current_dir = selected;
+            self.load_directory();
+        } else {
+            return Some(selected);
+        }
+
+        None
+    }
+
+    pub fn handle_input(&mut self, key: KeyEvent) -> AppAction {
+        if self.show_bookmarks {
+            return self.handle_bookmark_input(key);
+        }
+        if self.new_dir_input.is_some() {
+            return self.handle_new_dir_input(key);
+        }
+        if self.search.is_some() {
+            return self.handle_search_input(key);
+        }
+        if self.pending_delete.is_some() {
+            return self.handle_delete_confirm(key);
+        }
+
+        match key.code {
+            KeyCode::Up => {
+                self.previous();
+                AppAction::None
+            }
+            KeyCode::Down => {
+                self.next();
+                AppAction::None
+            }
+            KeyCode::Left => {
+                self.move_column(-1);
+                AppAction::None
+            }
+            KeyCode::Right => {
+                self.move_column(1);
+                AppAction::None
+            }
+            KeyCode::Enter => {
+                if let Some(path) = self.select() {
+                    return AppAction::FileSelected(path);
+                }
+                AppAction::None
+            }
+            KeyCode::Backspace => {
+                if let Some(parent) = self.current_dir.parent() {
+                    self.current_dir = parent.to_path_buf();
+                    self.load_directory();
+                }
+                AppAction::None
+            }
+            KeyCode::Char('b') => {
+                self.show_bookmarks = true;
+                AppAction::None
+            }
+            KeyCode::Char('s') => {
+                self.sort_mode = self.sort_mode.next();
+                self.load_directory();
+                AppAction::None
+            }
+            KeyCode::Char('.') => {
+                self.show_hidden = !self.show_hidden;
+                self.load_directory();
+                AppAction::None
+            }
+            KeyCode::Char('n') => {
+                self.message = None;
+                self.new_dir_input = Some(String::new());
+                AppAction::None
+            }
+            KeyCode::Char('/') => {
+                self.message = None;
+                self.search = Some(String::new());
+                AppAction::None
+            }
+            KeyCode::Char('D') => {
+                self.request_delete();
+                AppAction::None
+            }
+            KeyCode::Char('d') if self.selects_directories() => {
+                AppAction::FileSelected(self.current_dir.clone())
+            }
+            KeyCode::Esc => AppAction::CloseModal,
+            _ => AppAction::None,
+        }
+    }
+
+    /// The entry at `index` as the multi-column listing shows it: its bare
+    /// name, with `/` after directories and `@` after symlinks like `ls -F`.
+    fn short_name(&self, index: usize) -> String {
+        let path = &self.files[index];
+        if self.selects_directories() && *path == self.current_dir {
+            return "[✓ Use this directory]".to_string();
+        }
+        if path.ends_with("..") {
+            return "../".to_string();
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let is_dir = self
+            .metadata
+            .get(index)
+            .and_then(Option::as_ref)
+            .map_or_else(|| path.is_dir(), fs::Metadata::is_dir);
+        if self.link_targets.get(index).is_some_and(Option::is_some) {
+            format!("{name}@")
+        } else if is_dir {
+            format!("{name}/")
+        } else {
+            name.into_owned()
+        }
+    }
+
+    /// Flows the entries top to bottom into columns as wide as the longest
+    /// name, scrolled sideways so the selected column is on screen. Returns
+    /// the number of rows per column, or `None` without drawing anything
+    /// when the single-column list suits `inner` better: the entries fit its
+    /// height, the explorer is narrower than [`GRID_MIN_WIDTH`] or a name is
+    /// longer than [`GRID_MAX_NAME`].
+    fn render_grid(
+        f: &mut Frame,
+        explorer: &FileExplorer,
+        theme: &Theme,
+        inner: Rect,
+    ) -> Option<usize> {
+        let rows = usize::from(inner.height);
+        let len = explorer.files.len();
+        if inner.width + 2 < GRID_MIN_WIDTH || rows == 0 || len <= rows {
+            return None;
+        }
+        let names: Vec<String> = (0..len).map(|i| explorer.short_name(i)).collect();
+        let widest = names.iter().map(|n| n.width()).max().unwrap_or(0);
+        if widest > GRID_MAX_NAME {
+            return None;
+        }
+
+        // A selection marker in front, two spaces of gutter behind
+        let column_width = widest + 3;
+        let visible = (usize::from(inner.width) / column_width).max(1);
+        let selected_column = explorer.selected_index / rows;
+        let first = selected_column.saturating_sub(visible - 1);
+        for (slot, column) in (first..len.div_ceil(rows)).take(visible).enumerate() {
+            let lines: Vec<Line> = (column * rows..len.min((column + 1) * rows))
+                .map(|i| {
+                    let mut style = if explorer.is_broken_link(i) {
+                        theme.error
+                    } else {
+                        Style::default()
+                    };
+                    let marker = if i == explorer.selected_index {
+                        style = style.patch(theme.list_highlight);
+                        ">"
+                    } else {
+                        " "
+                    };
+                    let pad = widest - names[i].width();
+                    Line::from(Span::styled(
+                        format!("{marker}{}{}", names[i], " ".repeat(pad)),
+                        style,
+                    ))
+                })
+                .collect();
+            let x = inner.x + u16::try_from(slot * column_width).unwrap_or(u16::MAX);
+            let cell = Rect {
+                x,
+                width: u16::try_from(column_width)
+                    .unwrap_or(u16::MAX)
+                    .min(inner.right() - x),
+                ..inner
+            };
+            f.render_widget(Paragraph::new(lines), cell);
+        }
+        Some(rows)
+    }
+
+    pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
+        let theme = app.theme;
+        let dir_select = app.explorer.selects_directories();
+        let sentinel = app.explorer.current_dir.clone();
+        // Borders take two columns, the highlight symbol three more
+        let row_width = usize::from(area.width.saturating_sub(5));
+
+        let files: Vec<ListItem> = app
+            .explorer
+            .files
+            .iter()
+            .enumerate()
+            .map(|(idx, path)| {
+                let meta = app.explorer.metadata.get(idx).and_then(Option::as_ref);
+                let link = app.explorer.link_targets.get(idx).and_then(Option::as_ref);
+                let broken = app.explorer.is_broken_link(idx);
+                let (display_name, detail) = if dir_select && path == &sentinel {
+                    ("[✓ Use this directory]".to_string(), String::new())
+                } else if path.ends_with("..") {
+                    ("📁 ..".to_string(), String::new())
+                } else if let Some(target) = link {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    let detail = if broken {
+                        format!("{:>7}", "broken")
+                    } else if meta.is_some_and(fs::Metadata::is_dir) {
+                        format!("{:>7}", "<DIR>")
+                    } else {
+                        format!(
+                            "{:>7}",
+                            meta.map(|m| human_size(m.len())).unwrap_or_default()
+                        )
+                    };
+                    (format!("🔗 {name} -> {}", target.display()), detail)
+                } else {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    let is_dir = meta.map_or_else(|| path.is_dir(), fs::Metadata::is_dir);
+                    let modified = meta
+                        .and_then(|m| m.modified().ok())
+                        .map(format_mtime)
+                        .unwrap_or_default();
+                    if is_dir {
+                        (format!("📁 {name}"), format!("{:>7}  {modified}", "<DIR>"))
+                    } else {
+                        let size = meta.map(|m| human_size(m.len())).unwrap_or_default();
+                        (format!("📄 {name}"), format!("{size:>7}  {modified}"))
+                    }
+                };
+                let pad = row_width
+                    .saturating_sub(display_name.width() + detail.width())
+                    .max(1);
+                let (name_style, detail_style) = if broken {
+                    (theme.error, theme.error)
+                } else {
+                    (Style::default(), theme.dimmed)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(display_name, name_style),
+                    Span::raw(" ".repeat(pad)),
+                    Span::styled(detail, detail_style),
+                ]))
+            })
+            .collect();
+
+        let row_count = files.len();
+        let mut state = ListState::default();
+        state.select(Some(app.explorer.selected_index));
+
+        let label = if dir_select {
+            "Select Directory"
+        } else {
+            "Select File"
+        };
+        let label = format!("{label} [{}]", app.explorer.sort_mode);
+        // Borders plus the label, the ": " separator and padding spaces
+        let crumb_max = usize::from(area.width.saturating_sub(2)).saturating_sub(label.len() + 4);
+        let mode = if app.explorer.fuzzy_search {
+            "Fuzzy search"
+        } else {
+            "Search"
+        };
+        let title = if let Some(path) = &app.explorer.pending_delete {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            format!(" Delete {name}? (y/n) ")
+        } else {
+            match (
+                &app.explorer.new_dir_input,
+                &app.explorer.search,
+                &app.explorer.message,
+            ) {
+                (Some(input), _, _) => format!(" New directory: {input}_ "),
+                (None, Some(query), Some(msg)) => format!(" {mode}: {query}_ ({msg}) "),
+                (None, Some(query), None) => format!(" {mode}: {query}_ "),
+                (None, None, Some(msg)) => format!(" {label}: {msg} "),
+                (None, None, None) => {
+                    format!(" {label}: {} ", app.explorer.breadcrumb(crumb_max))
+                }
+            }
+        };
+
+        let block = Block::default().borders(Borders::ALL).title(title);
+        app.explorer.grid_rows = Self::render_grid(f, &app.explorer, &theme, block.inner(area));
+        if app.explorer.grid_rows.is_some() {
+            f.render_widget(block, area);
+        } else {
+            let list = List::new(files)
+                .block(block)
+                .highlight_style(theme.list_highlight)
+                .highlight_symbol(">> ");
+            f.render_stateful_widget(list, area, &mut state);
+            render_list_scrollbar(f, area, row_count, 1, state.offset());
+        }
+
+        if let Some(err) = &app.explorer.load_error {
+            // Place the message just below the remaining (`..`) entries
+            let inner = area.inner(Margin::new(1, 1));
+            let offset = u16::try_from(app.explorer.files.len())
+                .unwrap_or(u16::MAX)
+                .saturating_add(1);
+            if offset < inner.height {
+                let msg_area = Rect {
+                    y: inner.y + offset,
+                    height: 1,
+                    ..inner
+                };
+                f.render_widget(
+                    Paragraph::new(format!("⚠ {err}"))
+                        .style(theme.error.add_modifier(Modifier::REVERSED)),
+                    msg_area,
+                );
+            }
+        }
+
+        if app.explorer.show_bookmarks {
+            Self::render_bookmarks(f, &app.explorer, &theme, area);
+        }
+    }
+
+    /// Draws the numbered bookmark overlay centred over `area`.
+    fn render_bookmarks(f: &mut Frame, explorer: &FileExplorer, theme: &Theme, area: Rect) {
+        let items: Vec<ListItem> = explorer
+            .bookmarks
+            .iter()
+            .enumerate()
+            .map(|(idx, path)| {
+                let label = format!(" {}  {}", idx + 1, path.display());
+                if path.is_dir() {
+                    ListItem::new(label)
+                } else {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(label, theme.dimmed),
+                        Span::styled(" (unavailable)", theme.error),
+                    ]))
+                }
+            })
+            .collect();
+
+        let height = u16::try_from(items.len())
+            .unwrap_or(u16::MAX)
+            .saturating_add(2)
+            .max(3)
+            .min(area.height);
+        let width = (area.width / 4 * 3).max(20).min(area.width);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Bookmarks [1-9] jump  [a] add  [x] remove "),
+        );
+        f.render_widget(Clear, popup);
+        f.render_widget(list, popup);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::test_render::buffer_rows;
+    use std::fs::File;
+
+    fn setup_temp_fs() -> PathBuf {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let mut base = std::env::temp_dir();
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+
+        base.push(format!("pdm_file_explorer_test_{}", unique));
+
+        fs::create_dir_all(&base).unwrap();
+        fs::create_dir(base.join("folder")).unwrap();
+        File::create(base.join("file.txt")).unwrap();
+
+        base
+    }
+
+    fn setup_temp_fs_empty() -> PathBuf {
+        let base = setup_temp_fs();
+        fs::remove_dir(base.join("folder")).unwrap();
+        fs::remove_file(base.join("file.txt")).unwrap();
+        base
+    }
+
+    #[test]
+    fn loads_directory_entries() {
+        let dir = setup_temp_fs();
+        let mut explorer = FileExplorer::unlisted(dir);
+
+        explorer.load_directory();
+        assert!(explorer.files.len() >= 2);
+    }
+
+    #[test]
+    fn load_directory_caches_metadata_per_entry() {
+        let dir = setup_temp_fs();
+        fs::write(dir.join("file.txt"), b"hello").unwrap();
+        let mut explorer = FileExplorer::unlisted(dir.clone());
+
+        explorer.load_directory();
+        assert_eq!(explorer.files.len(), explorer.metadata.len());
+
+        // ".." carries no metadata
+        assert!(explorer.metadata[0].is_none());
+
+        let file_idx = explorer
+            .files
+            .iter()
+            .position(|p| p == &dir.join("file.txt"))
+            .unwrap();
+        let meta = explorer.metadata[file_idx].as_ref().unwrap();
+        assert_eq!(meta.len(), 5);
+    }
+
+    #[test]
+    fn breadcrumb_short_path_shows_all_components() {
+        let explorer = FileExplorer::unlisted(PathBuf::from("/home/user/.bitcoin"));
+        assert_eq!(explorer.breadcrumb(80), "/ › home › user › .bitcoin");
+    }
+
+    #[test]
+    fn breadcrumb_long_path_is_truncated_with_ellipsis() {
+        let explorer = FileExplorer::unlisted(PathBuf::from(
+            "/very/deeply/nested/directory/tree/for/configs",
+        ));
+        let crumb = explorer.breadcrumb(24);
+        assert!(crumb.width() <= 24, "got {crumb}");
+        assert!(crumb.starts_with('\u{2026}'));
+        assert!(crumb.ends_with("for › configs"));
+
+        // A single component wider than the budget is cut from the left
+        let crumb = explorer.breadcrumb(4);
+        assert!(crumb.width() <= 4, "got {crumb}");
+        assert!(crumb.starts_with('\u{2026}'));
+    }
+
+    #[test]
+    fn breadcrumb_filesystem_root() {
+        let explorer = FileExplorer::unlisted(PathBuf::from("/"));
+        assert_eq!(explorer.breadcrumb(80), "/");
+    }
+
+    #[test]
+    fn add_and_remove_bookmarks() {
+        let mut explorer = FileExplorer::unlisted(PathBuf::from("/"));
+
+        assert!(explorer.add_bookmark(PathBuf::from("/a")));
+        assert!(explorer.add_bookmark(PathBuf::from("/b")));
+        // Duplicates are ignored
+        assert!(!explorer.add_bookmark(PathBuf::from("/a")));
+        assert_eq!(explorer.bookmarks.len(), 2);
+
+        assert!(explorer.remove_bookmark(0));
+        assert_eq!(explorer.bookmarks, vec![PathBuf::from("/b")]);
+        assert!(!explorer.remove_bookmark(5));
+    }
+
+    #[test]
+    fn goto_bookmark_navigates_to_existing_dir() {
+        let base = setup_temp_fs();
+        let folder = base.join("folder");
+        let mut explorer = FileExplorer {
+            bookmarks: vec![folder.clone()],
+            ..FileExplorer::unlisted(base.clone())
+        };
+
+        assert!(explorer.goto_bookmark(0));
+        assert_eq!(explorer.current_dir, folder);
+    }
+
+    #[test]
+    fn goto_bookmark_skips_missing_path() {
+        let base = setup_temp_fs();
+        let missing = base.join("does-not-exist");
+        let mut explorer = FileExplorer {
+            bookmarks: vec![missing],
+            ..FileExplorer::unlisted(base.clone())
+        };
+
+        assert!(!explorer.goto_bookmark(0));
+        assert_eq!(explorer.current_dir, base);
+        assert!(explorer.message.as_deref().unwrap().contains("unavailable"));
+    }
+
+    #[test]
+    fn bookmark_overlay_keys() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let base = setup_temp_fs();
+        let folder = base.join("folder");
+        let mut explorer = FileExplorer {
+            bookmarks: vec![folder.clone()],
+            ..FileExplorer::unlisted(base.clone())
+        };
+        let press = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::empty());
+
+        explorer.handle_input(press('b'));
+        assert!(explorer.show_bookmarks);
+
+        // Adding the current directory requests persistence
+        let action = explorer.handle_input(press('a'));
+        assert!(matches!(action, AppAction::BookmarksChanged));
+        assert_eq!(explorer.bookmarks, vec![folder.clone(), base.clone()]);
+
+        // Number keys jump and close the overlay
+        explorer.handle_input(press('1'));
+        assert!(!explorer.show_bookmarks);
+        assert_eq!(explorer.current_dir, folder);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_detected_and_broken_links_not_selectable() {
+        use std::os::unix::fs::symlink;
+
+        let base = setup_temp_fs();
+        symlink(base.join("folder"), base.join("linkdir")).unwrap();
+        symlink(base.join("missing"), base.join("dangling")).unwrap();
+
+        let mut explorer = FileExplorer::unlisted(base.clone());
+        explorer.load_directory();
+
+        let pos = |name: &str| {
+            explorer
+                .files
+                .iter()
+                .position(|p| p == &base.join(name))
+                .unwrap()
+        };
+        let folder_idx = pos("folder");
+        let link_idx = pos("linkdir");
+        let dangling_idx = pos("dangling");
+        let file_idx = pos("file.txt");
+
+        // Symlinked directories sort with directories, before files
+        assert!(link_idx < file_idx);
+        assert!(folder_idx < file_idx);
+        assert_eq!(explorer.link_targets[link_idx], Some(base.join("folder")));
+        assert!(!explorer.is_broken_link(link_idx));
+
+        // A dangling link is flagged and cannot be selected
+        assert!(explorer.is_broken_link(dangling_idx));
+        explorer.selected_index = dangling_idx;
+        assert!(explorer.select().is_none());
+        assert_eq!(explorer.current_dir, base);
+        assert!(explorer.message.is_some());
+
+        // A link to a directory still navigates into it
+        explorer.load_directory();
+        explorer.selected_index = link_idx;
+        assert!(explorer.select().is_none());
+        assert_eq!(explorer.current_dir, base.join("linkdir"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_directory_sets_load_error_and_keeps_parent_entry() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let base = setup_temp_fs();
+        let locked = base.join("locked");
+        fs::create_dir(&locked).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+
+        // Privileged users bypass permission bits; nothing to assert then
+        if fs::read_dir(&locked).is_ok() {
+            fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+            return;
+        }
+
+        let mut explorer = FileExplorer::unlisted(locked.clone());
+        explorer.load_directory();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let err = explorer
+            .load_error
+            .as_deref()
+            .expect("load_error must be set");
+        assert!(err.contains("Permission denied"), "got {err}");
+        assert_eq!(explorer.files.len(), 1);
+        assert!(explorer.files[0].ends_with(".."));
+    }
+
+    #[test]
+    fn missing_directory_reports_not_found() {
+        let base = setup_temp_fs();
+        let mut explorer = FileExplorer::unlisted(base.join("gone"));
+        explorer.load_directory();
+
+        assert_eq!(explorer.load_error.as_deref(), Some("Directory not found"));
+        assert!(explorer.files[0].ends_with(".."));
+    }
+
+    fn sorted_names(explorer: &FileExplorer) -> Vec<String> {
+        explorer
+            .files
+            .iter()
+            .map(|p| {
+                p.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    }
+
+    /// Builds a directory with one subdirectory and three files of distinct
+    /// sizes and modification times.
+    fn setup_sort_fs() -> PathBuf {
+        use std::time::Duration;
+
+        let base = setup_temp_fs_empty();
+        fs::create_dir(base.join("zdir")).unwrap();
+        for (name, size, age) in [("file10", 30, 300), ("File2", 10, 100), ("file1", 20, 200)] {
+            let path = base.join(name);
+            fs::write(&path, vec![b'x'; size]).unwrap();
+            let file = File::options().write(true).open(&path).unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(age))
+                .unwrap();
+        }
+        base
+    }
+
+    fn sort_explorer(dir: PathBuf, sort_mode: SortMode) -> FileExplorer {
+        let mut explorer = FileExplorer {
+            sort_mode,
+            ..FileExplorer::unlisted(dir)
+        };
+        explorer.load_directory();
+        explorer
+    }
+
+    #[test]
+    fn sort_by_name_is_natural_and_case_insensitive() {
+        let explorer = sort_explorer(setup_sort_fs(), SortMode::Name);
+        assert_eq!(
+            sorted_names(&explorer),
+            vec!["..", "zdir", "file1", "File2", "file10"]
+        );
+    }
+
+    #[test]
+    fn sort_by_size_keeps_directories_first() {
+        let explorer = sort_explorer(setup_sort_fs(), SortMode::Size);
+        assert_eq!(
+            sorted_names(&explorer),
+            vec!["..", "zdir", "File2", "file1", "file10"]
+        );
+    }
+
+    #[test]
+    fn sort_by_modified_desc_lists_newest_first() {
+        let explorer = sort_explorer(setup_sort_fs(), SortMode::ModifiedDesc);
+        assert_eq!(
+            sorted_names(&explorer),
+            vec!["..", "zdir", "File2", "file1", "file10"]
+        );
+    }
+
+    #[test]
+    fn sort_key_cycles_modes() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let mut explorer = sort_explorer(setup_sort_fs(), SortMode::Name);
+        let press = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::empty());
+
+        explorer.handle_input(press);
+        assert_eq!(explorer.sort_mode, SortMode::Size);
+        explorer.handle_input(press);
+        assert_eq!(explorer.sort_mode, SortMode::ModifiedDesc);
+        explorer.handle_input(press);
+        assert_eq!(explorer.sort_mode, SortMode::Name);
+    }
+
+    #[test]
+    fn resorting_keeps_the_selection_on_the_same_entry() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let base = setup_sort_fs();
+        let mut explorer = sort_explorer(base.clone(), SortMode::Name);
+        explorer.selected_index = 3;
+        assert_eq!(explorer.files[3], base.join("File2"));
+
+        // Smallest of the three files, so it moves up when sorted by size
+        explorer.handle_input(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::empty()));
+        assert_eq!(explorer.sort_mode, SortMode::Size);
+        assert_eq!(explorer.selected_index, 2);
+        assert_eq!(explorer.files[2], base.join("File2"));
+
+        // Once the entry is gone the selection falls back to the top
+        fs::remove_file(base.join("File2")).unwrap();
+        explorer.load_directory();
+        assert_eq!(explorer.selected_index, 0);
+
+        // Entering another directory always starts at the top
+        explorer.selected_index = 1;
+        assert!(explorer.select().is_none());
+        assert_eq!(explorer.current_dir, base.join("zdir"));
+        assert_eq!(explorer.selected_index, 0);
+    }
+
+    #[test]
+    fn toggling_hidden_files_keeps_a_visible_selection() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let base = setup_temp_fs();
+        File::create(base.join(".cookie")).unwrap();
+        fs::create_dir(base.join(".cache")).unwrap();
+        let mut explorer = sort_explorer(base.clone(), SortMode::Name);
+        let press = || KeyEvent::new(KeyCode::Char('.'), KeyModifiers::empty());
+        assert_eq!(
+            sorted_names(&explorer),
+            ["..", ".cache", "folder", ".cookie", "file.txt"]
+        );
+        explorer.selected_index = 4;
+
+        explorer.handle_input(press());
+        assert!(!explorer.show_hidden);
+        assert_eq!(sorted_names(&explorer), ["..", "folder", "file.txt"]);
+        assert_eq!(
+            explorer.files[explorer.selected_index],
+            base.join("file.txt")
+        );
+
+        // A hidden entry disappears, so the selection falls back to the top
+        explorer.handle_input(press());
+        explorer.selected_index = 3;
+        explorer.handle_input(press());
+        assert_eq!(explorer.selected_index, 0);
+    }
+
+    #[test]
+    fn create_directory_selects_new_entry() {
+        let base = setup_temp_fs();
+        let mut explorer = sort_explorer(base.clone(), SortMode::Name);
+
+        assert!(explorer.create_directory("configs"));
+        assert!(base.join("configs").is_dir());
+        assert_eq!(
+            explorer.files[explorer.selected_index],
+            base.join("configs")
+        );
+        assert!(explorer.message.is_none());
+    }
+
+    #[test]
+    fn create_directory_via_prompt() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let base = setup_temp_fs();
+        let mut explorer = sort_explorer(base.clone(), SortMode::Name);
+        let press = |code| KeyEvent::new(code, KeyModifiers::empty());
+
+        explorer.handle_input(press(KeyCode::Char('n')));
+        assert_eq!(explorer.new_dir_input.as_deref(), Some(""));
+        for c in "new".chars() {
+            explorer.handle_input(press(KeyCode::Char(c)));
+        }
+        explorer.handle_input(press(KeyCode::Enter));
+
+        assert!(explorer.new_dir_input.is_none());
+        assert!(base.join("new").is_dir());
+        assert_eq!(explorer.files[explorer.selected_index], base.join("new"));
+    }
+
+    #[test]
+    fn create_directory_rejects_separators_and_duplicates() {
+        let base = setup_temp_fs();
+        let mut explorer = sort_explorer(base.clone(), SortMode::Name);
 
-        if let Ok(entries) = fs::read_dir(&self.current_dir) {
-            let mut dirs = Vec::new();
-            let mut files = Vec::new();
+        assert!(!explorer.create_directory("a/b"));
+        assert!(!base.join("a").exists());
+        assert!(explorer.message.as_deref().unwrap().contains("separator"));
 
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    dirs.push(path);
-                } else if !self.allow_dir_select {
-                    files.push(path);
-                }
-            }
+        assert!(!explorer.create_directory("   "));
+        assert!(!explorer.create_directory("folder"));
+        assert_eq!(explorer.message.as_deref(), Some("Already exists: folder"));
+    }
 
-            dirs.sort();
-            files.sort();
+    #[test]
+    fn fuzzy_score_matches_subsequences() {
+        assert!(fuzzy_score("btcf", "bitcoin.conf").is_some());
+        assert!(fuzzy_score("BTCF", "bitcoin.conf").is_some());
+        assert_eq!(fuzzy_score("fctb", "bitcoin.conf"), None);
+        assert_eq!(fuzzy_score("btcx", "bitcoin.conf"), None);
+    }
 
-            self.files.append(&mut dirs);
-            self.files.append(&mut files);
-        }
+    #[test]
+    fn fuzzy_score_prefers_consecutive_and_word_start_matches() {
+        let tight = fuzzy_score("conf", "bitcoin.conf").unwrap();
+        let scattered = fuzzy_score("conf", "cold_snowfall").unwrap();
+        assert!(tight > scattered);
+
+        let start = fuzzy_score("p2", "p2pool.toml").unwrap();
+        let middle = fuzzy_score("p2", "backup2.toml").unwrap();
+        assert!(start > middle);
     }
 
-    /// Moves the selection to the next entry.
-    pub fn next(&mut self) {
-        if !self.files.is_empty() {
-            self.selected_index = (self.selected_index + 1) % self.files.len();
+    #[test]
+    fn fuzzy_search_selects_best_match_and_reports_no_match() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let base = setup_temp_fs();
+        File::create(base.join("bitcoin.conf")).unwrap();
+        File::create(base.join("bitcoin_backup.cfg")).unwrap();
+        let mut explorer = sort_explorer(base.clone(), SortMode::Name);
+        let press = |code| KeyEvent::new(code, KeyModifiers::empty());
+
+        explorer.handle_input(press(KeyCode::Char('/')));
+        explorer.handle_input(press(KeyCode::Tab));
+        assert!(explorer.fuzzy_search);
+        for c in "btcf".chars() {
+            explorer.handle_input(press(KeyCode::Char(c)));
         }
+        assert_eq!(
+            explorer.files[explorer.selected_index],
+            base.join("bitcoin.conf")
+        );
+        assert!(explorer.message.is_none());
+
+        explorer.handle_input(press(KeyCode::Char('z')));
+        assert_eq!(
+            explorer.files[explorer.selected_index],
+            base.join("bitcoin.conf")
+        );
+        assert_eq!(explorer.message.as_deref(), Some("no match"));
+
+        explorer.handle_input(press(KeyCode::Esc));
+        assert!(explorer.search.is_none());
+        assert!(explorer.message.is_none());
     }
 
-    /// Moves the selection to the previous entry.
-    pub fn previous(&mut self) {
-        if !self.files.is_empty() {
-            if self.selected_index == 0 {
-                self.selected_index = self.files.len() - 1;
-            } else {
-                self.selected_index -= 1;
-            }
-        }
+    #[test]
+    fn plain_search_matches_name_prefixes() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let base = setup_temp_fs();
+        File::create(base.join("bitcoin.conf")).unwrap();
+        let mut explorer = sort_explorer(base.clone(), SortMode::Name);
+        let press = |code| KeyEvent::new(code, KeyModifiers::empty());
+
+        explorer.handle_input(press(KeyCode::Char('/')));
+        explorer.handle_input(press(KeyCode::Char('b')));
+        explorer.handle_input(press(KeyCode::Char('t')));
+        assert_eq!(explorer.message.as_deref(), Some("no match"));
+
+        explorer.handle_input(press(KeyCode::Backspace));
+        assert_eq!(
+            explorer.files[explorer.selected_index],
+            base.join("bitcoin.conf")
+        );
+        let action = explorer.handle_input(press(KeyCode::Enter));
+        assert!(matches!(action, AppAction::FileSelected(p) if p == base.join("bitcoin.conf")));
     }
 
-    /// Selects the current entry.
-    ///
-    /// - If it is a directory, enters that directory.
-    /// - If it is `..`, moves to the parent directory.
-    /// - If it is a file, returns its path.
-    pub fn select(&mut self) -> Option<PathBuf> {
-        if self.files.is_empty() {
-            return None;
-        }
+    #[test]
+    fn delete_removes_file_after_confirmation() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-        let selected = self.files[self.selected_index].clone();
+        let base = setup_temp_fs();
+        let mut explorer = sort_explorer(base.clone(), SortMode::Name);
+        let press = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::empty());
+        let file = base.join("file.txt");
+        explorer.selected_index = explorer.files.iter().position(|p| p == &file).unwrap();
+
+        explorer.handle_input(press('D'));
+        assert_eq!(explorer.pending_delete.as_ref(), Some(&file));
+        explorer.handle_input(press('n'));
+        assert!(explorer.pending_delete.is_none());
+        assert!(file.exists());
+
+        explorer.handle_input(press('D'));
+        explorer.handle_input(press('y'));
+        assert!(!file.exists());
+        assert!(!explorer.files.contains(&file));
+        // file.txt was the last entry, so the selection clamps to the new end
+        assert_eq!(explorer.selected_index, explorer.files.len() - 1);
+        assert_eq!(explorer.message.as_deref(), Some("Deleted file.txt"));
+
+        // Uppercase answers work as in the other y/n prompts
+        let folder = base.join("folder");
+        explorer.selected_index = explorer.files.iter().position(|p| p == &folder).unwrap();
+        explorer.handle_input(press('D'));
+        explorer.handle_input(press('N'));
+        assert!(explorer.pending_delete.is_none());
+        assert!(folder.exists());
+        explorer.handle_input(press('D'));
+        explorer.handle_input(press('Y'));
+        assert!(!folder.exists());
+    }
 
-        if self.allow_dir_select && selected == self.current_dir {
-            return Some(selected);
-        }
+    #[test]
+    fn delete_refuses_parent_entry_and_non_empty_directories() {
+        let base = setup_temp_fs();
+        let mut explorer = sort_explorer(base.clone(), SortMode::Name);
 
-        if selected.ends_with("..") {
-            if let Some(parent) = self.current_dir.parent() {
-                self.current_dir = parent.to_path_buf();
-                self.load_directory();
-            }
-        } else if selected.is_dir() {
-            self.current_dir = selected;
-            self.load_directory();
-        } else {
-            return Some(selected);
-        }
+        explorer.selected_index = 0;
+        assert!(explorer.files[0].ends_with(".."));
+        explorer.request_delete();
+        assert!(explorer.pending_delete.is_none());
+        assert!(!explorer.delete_entry(&base.join("..")));
+        assert!(base.exists());
+
+        File::create(base.join("folder").join("inner.txt")).unwrap();
+        assert!(!explorer.delete_entry(&base.join("folder")));
+        assert!(base.join("folder").is_dir());
+        assert_eq!(
+            explorer.message.as_deref(),
+            Some("Directory not empty: folder")
+        );
+    }
 
-        None
+    #[test]
+    fn natural_cmp_orders_numeric_runs() {
+        assert_eq!(natural_cmp("file2", "file10"), Ordering::Less);
+        assert_eq!(natural_cmp("File2", "file2"), Ordering::Equal);
+        assert_eq!(natural_cmp("a", "B"), Ordering::Less);
+        assert_eq!(natural_cmp("v1.9", "v1.10"), Ordering::Less);
+        assert_eq!(natural_cmp("file", "file1"), Ordering::Less);
     }
 
-    pub fn handle_input(&mut self, key: KeyEvent) -> AppAction {
-        match key.code {
-            KeyCode::Up => {
-                self.previous();
-                AppAction::None
-            }
-            KeyCode::Down => {
-                self.next();
-                AppAction::None
-            }
-            KeyCode::Enter => {
-                if let Some(path) = self.select() {
-                    return AppAction::FileSelected(path);
-                }
-                AppAction::None
-            }
-            KeyCode::Backspace => {
-                if let Some(parent) = self.current_dir.parent() {
-                    self.current_dir = parent.to_path_buf();
-                    self.load_directory();
-                }
-                AppAction::None
-            }
-            KeyCode::Esc => AppAction::CloseModal,
-            _ => AppAction::None,
-        }
+    #[test]
+    fn natural_cmp_handles_config_backups_and_shared_prefixes() {
+        assert_eq!(natural_cmp("backup2.conf", "backup10.conf"), Ordering::Less);
+        assert_eq!(
+            natural_cmp("Backup10.conf", "backup2.conf"),
+            Ordering::Greater
+        );
+        // Same number after the shared prefix: the rest decides
+        assert_eq!(natural_cmp("file1a", "file1B"), Ordering::Less);
+        assert_eq!(natural_cmp("file007", "file7"), Ordering::Greater);
+        assert_eq!(natural_cmp("file7", "file7"), Ordering::Equal);
     }
 
-    pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
-        let allow_dir_select = app.explorer.allow_dir_select;
-        let sentinel = app.explorer.current_dir.clone();
+    #[test]
+    fn human_size_boundaries() {
+        assert_eq!(human_size(0), "0B");
+        assert_eq!(human_size(1023), "1023B");
+        assert_eq!(human_size(1024), "1.0K");
+        assert_eq!(human_size(1536), "1.5K");
+        assert_eq!(human_size(1_048_576), "1.0M");
+        assert_eq!(human_size(3 * 1024 * 1024 * 1024), "3.0G");
+    }
 
-        let files: Vec<ListItem> = app
-            .explorer
-            .files
-            .iter()
-            .map(|path| {
-                let display_name = if allow_dir_select && path == &sentinel {
-                    "[✓ Use this directory]".to_string()
-                } else if path.ends_with("..") {
-                    "📁 ..".to_string()
-                } else {
-                    let name = path.file_name().unwrap_or_default().to_string_lossy();
-                    if path.is_dir() {
-                        format!("📁 {name}")
-                    } else {
-                        format!("📄 {name}")
-                    }
-                };
-                ListItem::new(display_name)
-            })
-            .collect();
+    #[test]
+    fn format_mtime_renders_utc_timestamp() {
+        use std::time::Duration;
 
-        let mut state = ListState::default();
-        state.select(Some(app.explorer.selected_index));
+        assert_eq!(format_mtime(UNIX_EPOCH), "1970-01-01 00:00");
+        // 2024-02-29 12:34:00 UTC
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_210_040);
+        assert_eq!(format_mtime(leap_day), "2024-02-29 12:34");
+    }
 
-        let title = if allow_dir_select {
-            format!(
-                " Select Directory (Current: {}) ",
-                app.explorer.current_dir.display()
-            )
-        } else {
-            format!(
-                " Select File (Current: {}) ",
-                app.explorer.current_dir.display()
-            )
+    #[test]
+    fn next_and_previous_wrap() {
+        let dir = setup_temp_fs();
+        let mut explorer = FileExplorer {
+            files: vec![PathBuf::from("a"), PathBuf::from("b")],
+            ..FileExplorer::unlisted(dir)
         };
 
-        let list = List::new(files)
-            .block(Block::default().borders(Borders::ALL).title(title))
-            .highlight_style(Style::default().bg(Color::Blue).fg(Color::White))
-            .highlight_symbol(">> ");
+        explorer.next();
+        assert_eq!(explorer.selected_index, 1);
 
-        f.render_stateful_widget(list, area, &mut state);
-    }
-}
+        explorer.next();
+        assert_eq!(explorer.selected_index, 0);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs::File;
+        explorer.previous();
+        assert_eq!(explorer.selected_index, 1);
+    }
 
-    fn setup_temp_fs() -> PathBuf {
-        use std::time::{SystemTime, UNIX_EPOCH};
+    #[test]
+    fn first_and_last_jump_to_list_bounds() {
+        let dir = setup_temp_fs();
+        let mut explorer = FileExplorer {
+            files: vec![PathBuf::from("a"), PathBuf::from("b"), PathBuf::from("c")],
+            selected_index: 1,
+            ..FileExplorer::unlisted(dir)
+        };
 
-        let mut base = std::env::temp_dir();
+        explorer.last();
+        assert_eq!(explorer.selected_index, 2);
 
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
+        explorer.first();
+        assert_eq!(explorer.selected_index, 0);
+    }
 
-        base.push(format!("pdm_file_explorer_test_{}", unique));
+    #[test]
+    fn first_and_last_on_empty_list_are_noops() {
+        let dir = setup_temp_fs();
+        let mut explorer = FileExplorer::unlisted(dir);
 
-        fs::create_dir_all(&base).unwrap();
-        fs::create_dir(base.join("folder")).unwrap();
-        File::create(base.join("file.txt")).unwrap();
+        explorer.last();
+        assert_eq!(explorer.selected_index, 0);
 
-        base
+        explorer.first();
+        assert_eq!(explorer.selected_index, 0);
     }
 
     #[test]
-    fn loads_directory_entries() {
+    fn page_down_jumps_by_page_from_start() {
         let dir = setup_temp_fs();
         let mut explorer = FileExplorer {
-            current_dir: dir,
-            files: vec![],
-            selected_index: 0,
-            allow_dir_select: false,
+            files: (0..30).map(|i| PathBuf::from(format!("f{i}"))).collect(),
+            ..FileExplorer::unlisted(dir)
         };
 
-        explorer.load_directory();
-        assert!(explorer.files.len() >= 2);
+        explorer.page_down(10);
+        assert_eq!(explorer.selected_index, 10);
+
+        explorer.page_up(4);
+        assert_eq!(explorer.selected_index, 6);
     }
 
     #[test]
-    fn next_and_previous_wrap() {
+    fn page_down_and_up_clamp_without_wrapping() {
         let dir = setup_temp_fs();
         let mut explorer = FileExplorer {
-            current_dir: dir,
-            files: vec![PathBuf::from("a"), PathBuf::from("b")],
-            selected_index: 0,
-            allow_dir_select: false,
+            files: vec![PathBuf::from("a"), PathBuf::from("b"), PathBuf::from("c")],
+            selected_index: 1,
+            ..FileExplorer::unlisted(dir)
         };
 
-        explorer.next();
-        assert_eq!(explorer.selected_index, 1);
+        explorer.page_down(10);
+        assert_eq!(explorer.selected_index, 2);
 
-        explorer.next();
+        explorer.page_up(10);
         assert_eq!(explorer.selected_index, 0);
+    }
 
-        explorer.previous();
-        assert_eq!(explorer.selected_index, 1);
+    #[test]
+    fn page_down_on_empty_list_is_noop() {
+        let dir = setup_temp_fs();
+        let mut explorer = FileExplorer::unlisted(dir);
+
+        explorer.page_down(5);
+        assert_eq!(explorer.selected_index, 0);
     }
 
     #[test]
@@ -274,10 +1939,8 @@ mod tests {
         let file = dir.join("file.txt");
 
         let mut explorer = FileExplorer {
-            current_dir: dir,
             files: vec![file.clone()],
-            selected_index: 0,
-            allow_dir_select: false,
+            ..FileExplorer::unlisted(dir)
         };
 
         let result = explorer.select();
@@ -290,12 +1953,7 @@ mod tests {
         let child = base.join("child");
         fs::create_dir(&child).unwrap();
 
-        let mut explorer = FileExplorer {
-            current_dir: child.clone(),
-            files: vec![],
-            selected_index: 0,
-            allow_dir_select: false,
-        };
+        let mut explorer = FileExplorer::unlisted(child.clone());
 
         explorer.load_directory();
 
@@ -317,10 +1975,8 @@ mod tests {
         let folder = base.join("folder");
 
         let mut explorer = FileExplorer {
-            current_dir: base.clone(),
             files: vec![folder.clone()],
-            selected_index: 0,
-            allow_dir_select: false,
+            ..FileExplorer::unlisted(base.clone())
         };
 
         let result = explorer.select();
@@ -338,10 +1994,9 @@ mod tests {
     fn previous_decrements_when_not_zero() {
         let dir = setup_temp_fs();
         let mut explorer = FileExplorer {
-            current_dir: dir,
             files: vec![PathBuf::from("a"), PathBuf::from("b"), PathBuf::from("c")],
             selected_index: 2,
-            allow_dir_select: false,
+            ..FileExplorer::unlisted(dir)
         };
 
         explorer.previous();
@@ -355,12 +2010,7 @@ mod tests {
         let base = setup_temp_fs();
         let child = base.join("folder");
 
-        let mut explorer = FileExplorer {
-            current_dir: child.clone(),
-            files: vec![],
-            selected_index: 0,
-            allow_dir_select: false,
-        };
+        let mut explorer = FileExplorer::unlisted(child.clone());
         explorer.load_directory();
 
         let action =
@@ -374,25 +2024,18 @@ mod tests {
         use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
         let dir = setup_temp_fs();
-        let mut explorer = FileExplorer {
-            current_dir: dir,
-            files: vec![],
-            selected_index: 0,
-            allow_dir_select: false,
-        };
+        let mut explorer = FileExplorer::unlisted(dir);
 
         let action = explorer.handle_input(KeyEvent::new(KeyCode::Esc, KeyModifiers::empty()));
         assert!(matches!(action, crate::app::AppAction::CloseModal));
     }
 
     #[test]
-    fn allow_dir_select_prepends_sentinel_and_hides_files() {
+    fn directory_mode_prepends_sentinel_and_hides_files() {
         let base = setup_temp_fs();
         let mut explorer = FileExplorer {
-            current_dir: base.clone(),
-            files: Vec::new(),
-            selected_index: 0,
-            allow_dir_select: true,
+            select_mode: SelectMode::Directory,
+            ..FileExplorer::unlisted(base.clone())
         };
         explorer.load_directory();
 
@@ -416,13 +2059,11 @@ mod tests {
     }
 
     #[test]
-    fn allow_dir_select_sentinel_returns_current_dir() {
+    fn directory_mode_sentinel_returns_current_dir() {
         let base = setup_temp_fs();
         let mut explorer = FileExplorer {
-            current_dir: base.clone(),
-            files: Vec::new(),
-            selected_index: 0,
-            allow_dir_select: true,
+            select_mode: SelectMode::Directory,
+            ..FileExplorer::unlisted(base.clone())
         };
         explorer.load_directory();
         // Select index 0 (sentinel)
@@ -431,15 +2072,31 @@ mod tests {
     }
 
     #[test]
-    fn allow_dir_select_subdir_still_navigates() {
+    fn directory_mode_d_confirms_current_dir() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+        let base = setup_temp_fs();
+        let mut explorer = sort_explorer(base.clone(), SortMode::Name);
+        let press = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::empty());
+
+        // In file mode `d` is not bound
+        assert!(matches!(explorer.handle_input(press('d')), AppAction::None));
+
+        explorer.set_select_mode(SelectMode::Directory);
+        assert_eq!(explorer.files[0], base);
+        explorer.selected_index = explorer.files.len() - 1;
+        let action = explorer.handle_input(press('d'));
+        assert!(matches!(action, AppAction::FileSelected(p) if p == base));
+    }
+
+    #[test]
+    fn directory_mode_subdir_still_navigates() {
         let base = setup_temp_fs();
         let folder = base.join("folder");
 
         let mut explorer = FileExplorer {
-            current_dir: base.clone(),
-            files: Vec::new(),
-            selected_index: 0,
-            allow_dir_select: true,
+            select_mode: SelectMode::Directory,
+            ..FileExplorer::unlisted(base.clone())
         };
         explorer.load_directory();
 
@@ -457,6 +2114,168 @@ mod tests {
         assert_eq!(explorer.current_dir, folder);
     }
 
+    #[test]
+    fn load_directory_truncates_very_large_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..MAX_DIR_ENTRIES + 10 {
+            File::create(dir.path().join(format!("block{i}.dat"))).unwrap();
+        }
+        let mut explorer = FileExplorer::new();
+        explorer.current_dir = dir.path().to_path_buf();
+
+        explorer.load_directory();
+
+        // The cap plus the `..` entry
+        assert_eq!(explorer.files.len(), MAX_DIR_ENTRIES + 1);
+        assert_eq!(
+            explorer.message.as_deref(),
+            Some("… (truncated: 5000 of 5010 entries)")
+        );
+    }
+
+    #[test]
+    fn truncation_keeps_sorted_directories_and_ignores_hidden_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..MAX_DIR_ENTRIES {
+            File::create(dir.path().join(format!("block{i}.dat"))).unwrap();
+        }
+        for i in 0..10 {
+            File::create(dir.path().join(format!(".lock{i}"))).unwrap();
+        }
+        let mut explorer = FileExplorer::unlisted(dir.path().to_path_buf());
+        explorer.show_hidden = false;
+        explorer.load_directory();
+        assert_eq!(explorer.files.len(), MAX_DIR_ENTRIES + 1);
+        assert!(explorer.message.is_none());
+
+        // Sorted last by name, but directories come first and stay listed
+        fs::create_dir(dir.path().join("zz-blocks")).unwrap();
+        explorer.load_directory();
+        assert_eq!(explorer.files[1], dir.path().join("zz-blocks"));
+        assert_eq!(
+            explorer.message.as_deref(),
+            Some("… (truncated: 5000 of 5001 entries)")
+        );
+    }
+
+    /// Renders an explorer over a directory of `count` files named
+    /// `f01.txt` onwards in a `width` x 8 area and returns the rows below the
+    /// title, which holds the temporary directory's path.
+    fn render_listing(app: &mut App, count: usize, width: u16) -> (tempfile::TempDir, String) {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let dir = tempfile::tempdir().unwrap();
+        for i in 1..=count {
+            File::create(dir.path().join(format!("f{i:02}.txt"))).unwrap();
+        }
+        app.explorer.current_dir = dir.path().to_path_buf();
+        app.explorer.load_directory();
+
+        let mut terminal = Terminal::new(TestBackend::new(width, 8)).unwrap();
+        terminal
+            .draw(|f| FileExplorer::render(f, app, f.area()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows = buffer_rows(
+            buffer,
+            Rect {
+                y: 1,
+                height: buffer.area.height - 1,
+                ..buffer.area
+            },
+        );
+        (dir, rows)
+    }
+
+    #[test]
+    fn test_wide_explorer_flows_short_names_into_columns() {
+        let mut app = App::new();
+        let (_dir, rows) = render_listing(&mut app, 20, 80);
+        assert_eq!(app.explorer.grid_rows, Some(6));
+        insta::assert_snapshot!(rows);
+    }
+
+    #[test]
+    fn test_explorer_over_a_fixed_tree_renders_reproducibly() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+        use std::time::Duration;
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("alpha")).unwrap();
+        fs::write(dir.path().join("bitcoin.conf"), "server=1\n").unwrap();
+        fs::write(dir.path().join("p2pool.toml"), [b'#'; 2048]).unwrap();
+        // Pin the dates column; 2024-02-29 12:34:00 UTC
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_210_040);
+        for name in ["alpha", "bitcoin.conf", "p2pool.toml"] {
+            File::open(dir.path().join(name))
+                .unwrap()
+                .set_modified(leap_day)
+                .unwrap();
+        }
+
+        let mut app = App::with_start_dir(Some(dir.path().to_path_buf()));
+        assert_eq!(app.explorer.current_dir, dir.path());
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+        terminal
+            .draw(|f| FileExplorer::render(f, &mut app, f.area()))
+            .unwrap();
+        // Skip the title, which holds the temporary directory's path
+        let buffer = terminal.backend().buffer();
+        let rows = buffer_rows(
+            buffer,
+            Rect {
+                y: 1,
+                height: buffer.area.height - 1,
+                ..buffer.area
+            },
+        );
+        insta::assert_snapshot!(rows);
+    }
+
+    #[test]
+    fn narrow_or_short_listings_keep_a_single_column() {
+        let mut app = App::new();
+        let (_dir, _) = render_listing(&mut app, 20, 60);
+        assert_eq!(app.explorer.grid_rows, None);
+
+        // Everything fits the height, so sizes and dates stay visible
+        let (_dir, _) = render_listing(&mut app, 3, 120);
+        assert_eq!(app.explorer.grid_rows, None);
+    }
+
+    #[test]
+    fn left_and_right_move_across_columns() {
+        use crossterm::event::KeyModifiers;
+
+        let press = |code| KeyEvent::new(code, KeyModifiers::empty());
+        let mut explorer = FileExplorer::new();
+        explorer.files = (0..21).map(|i| PathBuf::from(format!("f{i}"))).collect();
+        explorer.selected_index = 2;
+
+        // Without columns the keys do nothing
+        explorer.handle_input(press(KeyCode::Right));
+        assert_eq!(explorer.selected_index, 2);
+
+        explorer.grid_rows = Some(6);
+        explorer.handle_input(press(KeyCode::Right));
+        assert_eq!(explorer.selected_index, 8);
+        explorer.handle_input(press(KeyCode::Right));
+        explorer.handle_input(press(KeyCode::Right));
+        // The last column only holds 18..=20
+        assert_eq!(explorer.selected_index, 20);
+        explorer.handle_input(press(KeyCode::Right));
+        assert_eq!(explorer.selected_index, 20);
+        explorer.handle_input(press(KeyCode::Left));
+        assert_eq!(explorer.selected_index, 14);
+
+        explorer.selected_index = 3;
+        explorer.handle_input(press(KeyCode::Left));
+        assert_eq!(explorer.selected_index, 3);
+    }
+
     #[test]
     fn render_displays_files_and_dirs() {
         use crate::app::App;
@@ -0,0 +1,192 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// Case-insensitive `/` filter shared by the config list views.
+///
+/// The underlying entries are never mutated: `filtered_indices` holds the
+/// positions of matching entries, so a selection made in the filtered list
+/// still refers to the real data.
+#[derive(Debug, Clone, Default)]
+pub struct ListFilter {
+    /// True while the query is being typed and captures input.
+    pub typing: bool,
+    pub query: String,
+    /// Indices into the entry list that match `query`, in order.
+    pub filtered_indices: Vec<usize>,
+    /// Set by a view that drops more entries from `filtered_indices` after
+    /// [`Self::refresh`], such as the p2pool view hiding defaults, so the
+    /// list stays restricted while the query is empty.
+    pub narrowed: bool,
+}
+
+impl ListFilter {
+    /// True when a non-empty query or a `narrowed` view restricts the
+    /// visible entries.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty() || self.narrowed
+    }
+
+    /// Recomputes `filtered_indices` from one haystack per entry; an entry
+    /// matches when any of its fields contains the query.
+    pub fn refresh<'a, I, F>(&mut self, entries: I)
+    where
+        I: IntoIterator<Item = F>,
+        F: IntoIterator<Item = &'a str>,
+    {
+        let needle = self.query.to_lowercase();
+        self.filtered_indices = entries
+            .into_iter()
+            .enumerate()
+            .filter(|(_, fields)| {
+                fields
+                    .into_iter()
+                    .any(|f| f.to_lowercase().contains(&needle))
+            })
+            .map(|(i, _)| i)
+            .collect();
+    }
+
+    /// Entry indices currently shown: the matches when filtering, otherwise
+    /// every index below `len`.
+    #[must_use]
+    pub fn visible(&self, len: usize) -> Vec<usize> {
+        if self.is_active() {
+            self.filtered_indices.clone()
+        } else {
+            (0..len).collect()
+        }
+    }
+
+    /// True when `index` is one of the visible entries.
+    #[must_use]
+    pub fn contains(&self, index: usize, len: usize) -> bool {
+        if self.is_active() {
+            self.filtered_indices.contains(&index)
+        } else {
+            index < len
+        }
+    }
+
+    /// Moves `current` by `delta` rows within the visible entries, clamping
+    /// at either end. Returns `None` when nothing is visible.
+    #[must_use]
+    pub fn step(&self, current: usize, delta: isize, len: usize) -> Option<usize> {
+        let visible = self.visible(len);
+        let last = visible.len().checked_sub(1)?;
+        let pos = visible.iter().position(|&i| i == current).unwrap_or(0);
+        let target = pos.saturating_add_signed(delta).min(last);
+        Some(visible[target])
+    }
+
+    /// Handles a key while `typing`. Returns `true` when the query changed.
+    pub fn handle_input(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                true
+            }
+            KeyCode::Backspace => self.query.pop().is_some(),
+            KeyCode::Enter => {
+                self.typing = false;
+                false
+            }
+            KeyCode::Esc => {
+                self.clear();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Drops the query and shows every entry again. A `narrowed` view must
+    /// refresh afterwards to hide its entries again.
+    pub fn clear(&mut self) {
+        self.typing = false;
+        self.query.clear();
+        self.filtered_indices.clear();
+    }
+
+    /// Title suffix describing the filter, e.g. ` [/port: 2 matches]`.
+    #[must_use]
+    pub fn title_suffix(&self) -> String {
+        if self.typing {
+            format!(
+                " [/{}_: {}]",
+                self.query,
+                match_count(self.filtered_indices.len())
+            )
+        } else if !self.query.is_empty() {
+            format!(
+                " [/{}: {}]",
+                self.query,
+                match_count(self.filtered_indices.len())
+            )
+        } else {
+            String::new()
+        }
+    }
+}
+
+fn match_count(n: usize) -> String {
+    if n == 1 {
+        "1 match".to_string()
+    } else {
+        format!("{n} matches")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(query: &str, entries: &[[&str; 2]]) -> ListFilter {
+        let mut f = ListFilter {
+            query: query.to_string(),
+            ..ListFilter::default()
+        };
+        f.refresh(entries.iter().map(|e| e.iter().copied()));
+        f
+    }
+
+    #[test]
+    fn refresh_matches_any_field_case_insensitively() {
+        let f = filter(
+            "PORT",
+            &[["rpcport", "8332"], ["server", "1"], ["bind", "port.local"]],
+        );
+        assert_eq!(f.filtered_indices, vec![0, 2]);
+    }
+
+    #[test]
+    fn visible_is_everything_without_query() {
+        let f = filter("", &[["a", ""], ["b", ""]]);
+        assert_eq!(f.visible(2), vec![0, 1]);
+    }
+
+    #[test]
+    fn step_moves_within_matches_and_clamps() {
+        let f = filter("x", &[["x", ""], ["a", ""], ["x", ""], ["x", ""]]);
+        assert_eq!(f.step(0, 1, 4), Some(2));
+        assert_eq!(f.step(2, 10, 4), Some(3));
+        assert_eq!(f.step(2, -10, 4), Some(0));
+        // A selection outside the matches snaps to the first match
+        assert_eq!(f.step(1, 0, 4), Some(0));
+
+        let empty = filter("zzz", &[["a", ""]]);
+        assert_eq!(empty.step(0, 1, 1), None);
+    }
+
+    #[test]
+    fn title_suffix_reports_query_and_count() {
+        let mut f = filter("port", &[["rpcport", ""], ["port", ""]]);
+        assert_eq!(f.title_suffix(), " [/port: 2 matches]");
+        f.typing = true;
+        assert_eq!(f.title_suffix(), " [/port_: 2 matches]");
+        f.clear();
+        assert_eq!(f.title_suffix(), "");
+    }
+}
@@ -6,10 +6,15 @@ pub mod bitcoin_config_view;
 pub mod bitcoin_status_view;
 pub mod file_explorer;
 pub mod home_view;
+pub mod list_filter;
 pub mod ln_config_view;
 pub mod ln_status_view;
 pub mod p2pool_config_view;
 pub mod p2pool_status_view;
+pub mod scrollbar;
 pub mod settings_view;
 pub mod shares_market_view;
 pub mod status_bar;
+#[cfg(test)]
+pub(crate) mod test_render;
+pub mod value_text;
@@ -2,47 +2,70 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::app::{App, AppAction};
-use crate::p2poolv2_config::{FieldKind, P2PoolConfigEntry, flatten_config};
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::app::{App, AppAction, ConfigWarnings, SaveTarget};
+use crate::components::list_filter::ListFilter;
+use crate::components::scrollbar::render_rows_scrollbar;
+use crate::components::value_text::{edit_block, truncate_middle, wrap_columns};
+use crate::p2poolv2_config::{FieldKind, Origin, P2PoolConfigEntry, group_by_section};
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
 };
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug, Clone)]
 pub struct P2PoolConfigView {
     pub selected_index: usize,
     pub editing: bool,
     pub edit_input: String,
+    /// Why `edit_input` would be rejected on commit, from
+    /// [`App::p2pool_edit_error`] after every keystroke; Enter does nothing
+    /// while it is set.
+    pub edit_error: Option<String>,
     pub save_message: Option<String>,
     pub warning_message: Option<String>,
     pub sidebar_focused: bool,
+    /// True when edits have been committed to the config but not yet saved to disk.
+    pub dirty: bool,
+    /// `/` filter over key, section and value.
+    pub filter: ListFilter,
+    /// Selection and scroll offset of the entry list, kept across frames so
+    /// the list only scrolls once the selection leaves the viewport.
+    pub list_state: ListState,
+    /// `section.key=value` typed at the `n` prompt that adds a custom entry.
+    pub add_input: Option<String>,
+    /// Sensitive entry whose value `v` has unmasked; hidden again once the
+    /// selection moves off it.
+    pub revealed: Option<usize>,
+    /// Entry whose value is wrapped over several rows instead of being
+    /// shortened to fit, toggled with `x`. Held as `section.key` so it stays
+    /// with the entry when custom entries are added.
+    pub expanded: Option<String>,
+    /// Height in rows of each visible entry as last drawn, so clicks and
+    /// PageUp/PageDown account for an expanded entry.
+    pub row_heights: Vec<u16>,
 }
 
-/// Returns `(display_string, style)` for a config entry value.
-pub fn entry_display(entry: &P2PoolConfigEntry) -> (String, Style) {
+/// Returns `(display_string, style)` for a config entry value. Sensitive
+/// values are masked unless `revealed`, and shown in the warning color when
+/// they are.
+pub fn entry_display(entry: &P2PoolConfigEntry, theme: &Theme, revealed: bool) -> (String, Style) {
     if entry.enabled {
-        let v = if entry.schema.sensitive {
-            "••••••••".to_string()
+        if !entry.schema.sensitive {
+            (entry.value.clone(), theme.set_value)
+        } else if revealed {
+            (entry.value.clone(), theme.warning)
         } else {
-            entry.value.clone()
-        };
-        (
-            v,
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        )
+            ("••••••••".to_string(), theme.set_value)
+        }
     } else {
         let placeholder = match &entry.schema.kind {
             FieldKind::Optional { default: Some(d) } => format!("default: {}", d),
             _ => "not set".to_string(),
         };
-        (
-            format!("({})", placeholder),
-            Style::default().fg(Color::DarkGray),
-        )
+        (format!("({})", placeholder), theme.default_value)
     }
 }
 
@@ -62,17 +85,176 @@ impl P2PoolConfigView {
             selected_index: 0,
             editing: false,
             edit_input: String::new(),
+            edit_error: None,
             save_message: None,
             warning_message: None,
             sidebar_focused: true,
+            dirty: false,
+            filter: ListFilter::default(),
+            list_state: ListState::default(),
+            add_input: None,
+            revealed: None,
+            expanded: None,
+            row_heights: Vec::new(),
+        }
+    }
+
+    /// Whether `entries[index]` is the expanded entry.
+    #[must_use]
+    pub fn is_expanded(&self, entries: &[P2PoolConfigEntry], index: usize) -> bool {
+        self.expanded.is_some() && self.expanded == entries.get(index).map(P2PoolConfigEntry::field)
+    }
+
+    /// Moves the selection down by `n` rows, clamped to the last of `len` entries.
+    pub fn page_down(&mut self, n: usize, len: usize) {
+        self.move_selection(isize::try_from(n).unwrap_or(isize::MAX), len);
+        self.save_message = None;
+    }
+
+    /// Moves the selection up by `n` rows, clamped to the first entry.
+    pub fn page_up(&mut self, n: usize, len: usize) {
+        self.move_selection(isize::try_from(n).map_or(isize::MIN, |n| -n), len);
+        self.save_message = None;
+    }
+
+    /// Selects entry `index`. A secret revealed on another row is masked
+    /// again, so every way of moving the selection, mouse included, goes
+    /// through here.
+    pub fn select(&mut self, index: usize) {
+        if index != self.selected_index {
+            self.revealed = None;
         }
+        self.selected_index = index;
+    }
+
+    /// Moves the selection by `delta` rows within the visible (filtered) entries.
+    fn move_selection(&mut self, delta: isize, len: usize) {
+        if let Some(index) = self.filter.step(self.selected_index, delta, len) {
+            self.select(index);
+        }
+    }
+
+    /// Moves to the first entry of the next section, or with `forward` unset
+    /// the nearest section start above the selection, wrapping around at
+    /// either end. Only entries the filter shows count, so a section whose
+    /// first rows are hidden starts at its first visible one; custom keys
+    /// listed after the known fields join their section's group.
+    fn jump_section(&mut self, forward: bool, entries: &[P2PoolConfigEntry]) {
+        let visible = self.filter.visible(entries.len());
+        let starts: Vec<usize> = group_by_section(visible.iter().map(|&i| &entries[i]))
+            .iter()
+            .filter_map(|(_, group)| entries.iter().position(|e| std::ptr::eq(e, group[0])))
+            .collect();
+        let target = if forward {
+            starts
+                .iter()
+                .find(|&&i| i > self.selected_index)
+                .or(starts.first())
+        } else {
+            starts
+                .iter()
+                .rev()
+                .find(|&&i| i < self.selected_index)
+                .or(starts.last())
+        };
+        if let Some(&index) = target {
+            self.select(index);
+        }
+    }
+
+    /// Recomputes the filter matches and keeps the selection on a visible entry.
+    /// Sensitive values are not searched so a query cannot probe secrets.
+    /// With the filter `narrowed`, entries left at their defaults are hidden.
+    pub fn refresh_filter(&mut self, entries: &[P2PoolConfigEntry]) {
+        let sections: Vec<String> = entries.iter().map(|e| e.section.to_string()).collect();
+        self.filter
+            .refresh(entries.iter().zip(&sections).map(|(e, section)| {
+                [
+                    e.key.as_str(),
+                    section.as_str(),
+                    if e.schema.sensitive {
+                        ""
+                    } else {
+                        e.value.as_str()
+                    },
+                ]
+            }));
+        if self.filter.narrowed {
+            self.filter
+                .filtered_indices
+                .retain(|&i| !entries[i].is_default());
+        }
+        self.move_selection(0, entries.len());
+    }
+
+    /// Shows only the entries the user configured, or every entry again.
+    /// A selection that gets hidden moves to the next entry still shown.
+    fn toggle_non_default(&mut self, entries: &[P2PoolConfigEntry]) {
+        let selected = self.selected_index;
+        self.filter.narrowed = !self.filter.narrowed;
+        self.refresh_filter(entries);
+        if let Some(&next) = self
+            .filter
+            .visible(entries.len())
+            .iter()
+            .find(|&&i| i >= selected)
+        {
+            self.select(next);
+        }
+    }
+
+    /// Handles a key while the `n` new-entry prompt is open.
+    fn handle_add_input(&mut self, key: KeyEvent) -> AppAction {
+        let Some(input) = self.add_input.as_mut() else {
+            return AppAction::None;
+        };
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => {
+                let parsed = input.split_once('=').and_then(|(path, value)| {
+                    let (section, key) = path.trim().split_once('.')?;
+                    Some((
+                        section.to_string(),
+                        key.to_string(),
+                        value.trim().to_string(),
+                    ))
+                });
+                match parsed {
+                    Some((section, key, value)) => {
+                        self.add_input = None;
+                        return AppAction::AddP2PoolEntry(section, key, value);
+                    }
+                    None => {
+                        self.warning_message = Some("Use section.key=value".to_string());
+                    }
+                }
+            }
+            KeyCode::Esc => self.add_input = None,
+            _ => {}
+        }
+        AppAction::None
     }
 
     pub fn handle_input(&mut self, key: KeyEvent, entries: &[P2PoolConfigEntry]) -> AppAction {
         self.save_message = None;
 
+        if self.add_input.is_some() {
+            return self.handle_add_input(key);
+        }
+
+        if self.filter.typing {
+            if self.filter.handle_input(key) {
+                self.refresh_filter(entries);
+            }
+            return AppAction::None;
+        }
+
         if self.editing {
             match key.code {
+                KeyCode::Enter if self.edit_error.is_some() => AppAction::None,
                 KeyCode::Enter => {
                     let action =
                         AppAction::CommitP2PoolEdit(self.selected_index, self.edit_input.clone());
@@ -98,27 +280,107 @@ impl P2PoolConfigView {
         } else {
             match key.code {
                 KeyCode::Up => {
-                    if self.selected_index > 0 {
-                        self.selected_index -= 1;
-                    }
+                    self.move_selection(-1, entries.len());
                     AppAction::None
                 }
                 KeyCode::Down => {
-                    if self.selected_index + 1 < entries.len() {
-                        self.selected_index += 1;
-                    }
+                    self.move_selection(1, entries.len());
+                    AppAction::None
+                }
+                KeyCode::Home => {
+                    self.move_selection(isize::MIN, entries.len());
+                    AppAction::None
+                }
+                KeyCode::End => {
+                    self.move_selection(isize::MAX, entries.len());
+                    AppAction::None
+                }
+                KeyCode::Char(']' | '}') => {
+                    self.jump_section(true, entries);
+                    AppAction::None
+                }
+                KeyCode::Char('[' | '{') => {
+                    self.jump_section(false, entries);
                     AppAction::None
                 }
                 KeyCode::Enter => {
-                    if !entries.is_empty() {
+                    if self.filter.contains(self.selected_index, entries.len()) {
                         self.edit_input = entries[self.selected_index].value.clone();
                         self.editing = true;
                     }
                     AppAction::None
                 }
-                KeyCode::Char('s') => AppAction::SaveP2PoolConfig,
+                KeyCode::Char('/') => {
+                    self.filter.typing = true;
+                    self.refresh_filter(entries);
+                    AppAction::None
+                }
+                KeyCode::Char('m') => {
+                    self.toggle_non_default(entries);
+                    AppAction::None
+                }
+                // A list gains a copy of its last item, ready to edit in place
+                KeyCode::Char('D')
+                    if self.filter.contains(self.selected_index, entries.len())
+                        && entries[self.selected_index].schema.type_hint == "CSV" =>
+                {
+                    let value = &entries[self.selected_index].value;
+                    self.edit_input = match value.rsplit(',').next().map(str::trim) {
+                        Some(last) if !last.is_empty() => format!("{value},{last}"),
+                        _ => value.clone(),
+                    };
+                    self.editing = true;
+                    AppAction::None
+                }
+                KeyCode::Char('s') => AppAction::RequestSave(SaveTarget::P2PoolConfig),
+                KeyCode::Char('d') => AppAction::ShowDiff(SaveTarget::P2PoolConfig),
+                // Entries hold the real value; only the display is masked
+                KeyCode::Char('y')
+                    if self.filter.contains(self.selected_index, entries.len())
+                        && entries[self.selected_index].enabled =>
+                {
+                    AppAction::CopyValue(
+                        SaveTarget::P2PoolConfig,
+                        entries[self.selected_index].value.clone(),
+                    )
+                }
+                KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    AppAction::Redo(SaveTarget::P2PoolConfig)
+                }
+                KeyCode::Char('r') => AppAction::RequestReload(SaveTarget::P2PoolConfig),
+                KeyCode::Char('u') => AppAction::Undo(SaveTarget::P2PoolConfig),
+                KeyCode::Char('v')
+                    if self.filter.contains(self.selected_index, entries.len())
+                        && entries[self.selected_index].schema.sensitive =>
+                {
+                    self.revealed = if self.revealed == Some(self.selected_index) {
+                        None
+                    } else {
+                        Some(self.selected_index)
+                    };
+                    AppAction::None
+                }
+                KeyCode::Char('x') if self.filter.contains(self.selected_index, entries.len()) => {
+                    self.expanded = if self.is_expanded(entries, self.selected_index) {
+                        None
+                    } else {
+                        Some(entries[self.selected_index].field())
+                    };
+                    AppAction::None
+                }
+                KeyCode::Char('n') => {
+                    self.warning_message = None;
+                    self.add_input = Some(String::new());
+                    AppAction::None
+                }
+                KeyCode::Esc if !self.filter.query.is_empty() => {
+                    self.filter.clear();
+                    self.refresh_filter(entries);
+                    AppAction::None
+                }
                 KeyCode::Esc => {
                     self.sidebar_focused = true;
+                    self.revealed = None;
                     AppAction::None
                 }
                 _ => AppAction::None,
@@ -126,7 +388,10 @@ impl P2PoolConfigView {
         }
     }
 
-    pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
+    /// Draws the config list and detail panel; `warnings` are the frame's
+    /// [`App::config_warnings`], marked on the rows they are about.
+    pub fn render(f: &mut Frame, app: &mut App, warnings: &ConfigWarnings, area: Rect) {
+        let theme = app.theme;
         if app.p2pool_conf_path.is_none() {
             // Show warning if there is one,
             let msg = app
@@ -136,7 +401,7 @@ impl P2PoolConfigView {
                 .unwrap_or("Press [Enter] to select a p2poolv2 config file");
 
             let style = if app.p2pool_config_view.warning_message.is_some() {
-                Style::default().fg(Color::Red)
+                theme.error
             } else {
                 Style::default()
             };
@@ -150,11 +415,8 @@ impl P2PoolConfigView {
             return;
         }
 
-        let entries: Vec<P2PoolConfigEntry> = app
-            .p2pool_config
-            .as_ref()
-            .map(|cfg| flatten_config(cfg))
-            .unwrap_or_default();
+        let entries = app.p2pool_entries();
+        let warnings = warnings.p2pool();
 
         // Status bar (warning or save message)
         // Warning (red) takes priority over save message (green).
@@ -162,12 +424,12 @@ impl P2PoolConfigView {
             .p2pool_config_view
             .warning_message
             .as_deref()
-            .map(|msg| (msg, Style::default().fg(Color::Red)))
+            .map(|msg| (msg, theme.error))
             .or_else(|| {
                 app.p2pool_config_view
                     .save_message
                     .as_deref()
-                    .map(|msg| (msg, Style::default().fg(Color::Green)))
+                    .map(|msg| (msg, theme.success))
             });
 
         // If there is a message, crave a 3-row strip off the top and render the rest of the view beneath it.
@@ -192,56 +454,141 @@ impl P2PoolConfigView {
             .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
             .split(content_area);
 
-        // Left panel: scrollable entry list
-        let items: Vec<ListItem> = entries
+        // Entries may have been edited since the filter last ran
+        if app.p2pool_config_view.filter.is_active() {
+            app.p2pool_config_view.refresh_filter(&entries);
+        }
+        let visible = app.p2pool_config_view.filter.visible(entries.len());
+
+        // Left panel: scrollable entry list. Columns left for `key = value`
+        // inside the border and highlight symbol:
+        let row_width = usize::from(panels[0].width).saturating_sub(5);
+        let items: Vec<ListItem> = visible
             .iter()
-            .map(|entry| {
-                let (value_display, value_style) = entry_display(entry);
-
-                ListItem::new(vec![
-                    Line::from(vec![
-                        Span::styled(
-                            format!("[{}] ", entry.section),
-                            Style::default().fg(Color::Blue),
-                        ),
-                        Span::styled(
-                            entry.schema.description.as_str(),
-                            Style::default().fg(Color::Gray),
-                        ),
-                    ]),
-                    Line::from(vec![
-                        Span::styled(
-                            format!("{} = ", entry.key),
-                            Style::default().fg(Color::Cyan),
-                        ),
-                        Span::styled(value_display, value_style),
-                    ]),
-                ])
+            .filter_map(|&i| entries.get(i).map(|entry| (i, entry)))
+            .map(|(i, entry)| {
+                let revealed = app.p2pool_config_view.revealed == Some(i);
+                let (value_display, value_style) = entry_display(entry, &theme, revealed);
+                let field = entry.field();
+                let warning = warnings.iter().find(|w| entry.enabled && w.field == field);
+                let key_style = if warning.is_some() {
+                    theme.warning
+                } else {
+                    theme.key
+                };
+
+                let key_label = format!("{} = ", entry.key);
+                let env_suffix = if entry.origin == Origin::Env {
+                    format!("  [env {}]", entry.env_var())
+                } else {
+                    String::new()
+                };
+                let warning_suffix =
+                    warning.map_or_else(String::new, |w| format!("  ⚠ {}", w.message));
+                let value_width = row_width
+                    .saturating_sub(key_label.width())
+                    .saturating_sub(env_suffix.width())
+                    .saturating_sub(warning_suffix.width());
+                // Continuation rows line up under the start of the value
+                let indent = " ".repeat(key_label.width());
+                let pieces = if app.p2pool_config_view.is_expanded(&entries, i) {
+                    wrap_columns(&value_display, value_width)
+                } else {
+                    vec![truncate_middle(&value_display, value_width)]
+                };
+                let last = pieces.len() - 1;
+
+                let mut lines = vec![Line::from(vec![
+                    Span::styled(format!("[{}] ", entry.section), theme.section_label),
+                    Span::styled(entry.schema.description.as_str(), theme.description),
+                ])];
+                for (n, piece) in pieces.into_iter().enumerate() {
+                    let mut spans = vec![if n == 0 {
+                        Span::styled(key_label.clone(), key_style)
+                    } else {
+                        Span::raw(indent.clone())
+                    }];
+                    spans.push(Span::styled(piece, value_style));
+                    // Origin and warning follow the end of the value
+                    if n == last {
+                        spans.push(Span::styled(env_suffix.clone(), theme.warning));
+                        spans.push(Span::styled(warning_suffix.clone(), theme.warning));
+                    }
+                    lines.push(Line::from(spans));
+                }
+                ListItem::new(lines)
             })
             .collect();
 
-        let mut list_state = ListState::default();
-        list_state.select(Some(app.p2pool_config_view.selected_index));
+        app.p2pool_config_view.row_heights = items
+            .iter()
+            .map(|item| u16::try_from(item.height()).unwrap_or(u16::MAX))
+            .collect();
+        let selected_row = visible
+            .iter()
+            .position(|&i| i == app.p2pool_config_view.selected_index);
+        app.p2pool_config_view.list_state.select(selected_row);
 
+        let dirty = app.p2pool_config_view.dirty;
         let title = match &app.p2pool_conf_path {
-            Some(path) => format!(" P2Pool Configuration --- {} ", path.display()),
+            _ if app.p2pool_config_view.add_input.is_some() => format!(
+                " New entry (section.key=value): {}_ ",
+                app.p2pool_config_view
+                    .add_input
+                    .as_deref()
+                    .unwrap_or_default()
+            ),
+            Some(path) => format!(
+                " {}P2Pool Configuration --- {}{}{} ",
+                if dirty { "● " } else { "" },
+                path.display(),
+                if app.p2pool_config_view.filter.narrowed {
+                    " [non-default]"
+                } else {
+                    ""
+                },
+                app.p2pool_config_view.filter.title_suffix()
+            ),
             None => " P2Pool Configuration ".to_string(),
         };
+        let title_style = if dirty {
+            theme.warning
+        } else {
+            Style::default()
+        };
 
         let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title(title))
-            .highlight_style(Style::default().bg(Color::DarkGray));
-
-        f.render_stateful_widget(list, panels[0], &mut list_state);
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .title_style(title_style),
+            )
+            .highlight_style(theme.list_highlight)
+            .highlight_symbol(">> ");
+
+        f.render_stateful_widget(list, panels[0], &mut app.p2pool_config_view.list_state);
+        render_rows_scrollbar(
+            f,
+            panels[0],
+            &app.p2pool_config_view.row_heights,
+            app.p2pool_config_view.list_state.offset(),
+        );
+        app.list_area = panels[0];
 
         // Right panel: detail + edit
         let right_block = Block::default().borders(Borders::ALL).title(" Detail ");
         let inner = right_block.inner(panels[1]);
         f.render_widget(right_block, panels[1]);
 
-        let selected_entry = entries.get(app.p2pool_config_view.selected_index);
+        let selected = app.p2pool_config_view.selected_index;
+        let selected_entry = entries
+            .get(selected)
+            .filter(|_| visible.contains(&selected));
         let editing = app.p2pool_config_view.editing;
         let edit_input = app.p2pool_config_view.edit_input.clone();
+        let revealed =
+            app.p2pool_config_view.revealed == Some(app.p2pool_config_view.selected_index);
 
         if let Some(entry) = selected_entry {
             let rows = Layout::default()
@@ -258,8 +605,7 @@ impl P2PoolConfigView {
                 .split(inner);
 
             f.render_widget(
-                Paragraph::new(entry.schema.description.as_str())
-                    .style(Style::default().fg(Color::White)),
+                Paragraph::new(entry.schema.description.as_str()).style(theme.text),
                 rows[0],
             );
             f.render_widget(
@@ -267,23 +613,26 @@ impl P2PoolConfigView {
                     "[{}]  type: {}",
                     entry.section, entry.schema.type_hint
                 ))
-                .style(Style::default().fg(Color::Gray)),
+                .style(theme.description),
                 rows[1],
             );
-            f.render_widget(
-                Paragraph::new("Value:").style(Style::default().fg(Color::Gray)),
-                rows[3],
-            );
+            f.render_widget(Paragraph::new("Value:").style(theme.description), rows[3]);
 
             if editing {
                 f.render_widget(
-                    Paragraph::new(edit_display(&edit_input, entry.schema.sensitive))
-                        .block(Block::default().borders(Borders::ALL))
-                        .style(Style::default().fg(Color::Yellow)),
+                    Paragraph::new(edit_display(
+                        &edit_input,
+                        entry.schema.sensitive && !revealed,
+                    ))
+                    .block(edit_block(
+                        app.p2pool_config_view.edit_error.as_deref(),
+                        &theme,
+                    ))
+                    .style(theme.warning),
                     rows[4],
                 );
             } else {
-                let (display, style) = entry_display(entry);
+                let (display, style) = entry_display(entry, &theme, revealed);
                 f.render_widget(
                     Paragraph::new(display)
                         .block(Block::default().borders(Borders::ALL))
@@ -293,10 +642,12 @@ impl P2PoolConfigView {
             }
 
             if entry.schema.sensitive {
-                f.render_widget(
-                    Paragraph::new("⚠ sensitive field").style(Style::default().fg(Color::Yellow)),
-                    rows[5],
-                );
+                let notice = if revealed {
+                    "⚠ sensitive field, revealed (v to hide)"
+                } else {
+                    "⚠ sensitive field (v to reveal)"
+                };
+                f.render_widget(Paragraph::new(notice).style(theme.warning), rows[5]);
             }
         }
     }
@@ -311,7 +662,9 @@ impl Default for P2PoolConfigView {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::p2poolv2_config::{ConfigSection, FieldKind, P2PoolConfigEntry, P2PoolFieldSchema};
+    use crate::p2poolv2_config::{
+        ConfigSection, FieldKind, P2PoolConfigEntry, P2PoolFieldSchema, flatten_config,
+    };
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
     use p2poolv2_config::Config;
     use ratatui::{Terminal, backend::TestBackend};
@@ -329,6 +682,7 @@ mod tests {
                 type_hint: "String".to_string(),
                 sensitive: false,
             },
+            origin: Origin::File,
         }
     }
 
@@ -491,6 +845,40 @@ port = 3030
         assert_eq!(view.selected_index, 0);
     }
 
+    #[test]
+    fn browsing_home_and_end_jump_to_bounds() {
+        let mut view = P2PoolConfigView::new();
+        let entries = vec![
+            make_entry("a", "1", true),
+            make_entry("b", "2", true),
+            make_entry("c", "3", true),
+        ];
+        view.handle_input(key(KeyCode::End), &entries);
+        assert_eq!(view.selected_index, 2);
+        view.handle_input(key(KeyCode::Home), &entries);
+        assert_eq!(view.selected_index, 0);
+    }
+
+    #[test]
+    fn browsing_end_on_empty_entries_stays_at_zero() {
+        let mut view = P2PoolConfigView::new();
+        view.handle_input(key(KeyCode::End), &[]);
+        assert_eq!(view.selected_index, 0);
+    }
+
+    #[test]
+    fn page_down_jumps_and_clamps_at_last_entry() {
+        let mut view = P2PoolConfigView::new();
+        view.page_down(4, 10);
+        assert_eq!(view.selected_index, 4);
+
+        view.page_down(100, 10);
+        assert_eq!(view.selected_index, 9);
+
+        view.page_up(3, 10);
+        assert_eq!(view.selected_index, 6);
+    }
+
     #[test]
     fn browsing_enter_starts_editing_with_current_value() {
         let mut view = P2PoolConfigView::new();
@@ -507,12 +895,33 @@ port = 3030
         assert!(!view.editing);
     }
 
+    #[test]
+    fn browsing_u_and_ctrl_r_undo_and_redo() {
+        let mut view = P2PoolConfigView::new();
+        let entries = vec![make_entry("hostname", "127.0.0.1", true)];
+        let undo = view.handle_input(key(KeyCode::Char('u')), &entries);
+        let redo = view.handle_input(
+            KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+            &entries,
+        );
+        let reload = view.handle_input(key(KeyCode::Char('r')), &entries);
+        assert!(matches!(undo, AppAction::Undo(SaveTarget::P2PoolConfig)));
+        assert!(matches!(redo, AppAction::Redo(SaveTarget::P2PoolConfig)));
+        assert!(matches!(
+            reload,
+            AppAction::RequestReload(SaveTarget::P2PoolConfig)
+        ));
+    }
+
     #[test]
     fn browsing_s_returns_save_action() {
         let mut view = P2PoolConfigView::new();
         let entries = vec![make_entry("hostname", "127.0.0.1", true)];
         let action = view.handle_input(key(KeyCode::Char('s')), &entries);
-        assert!(matches!(action, AppAction::SaveP2PoolConfig));
+        assert!(matches!(
+            action,
+            AppAction::RequestSave(SaveTarget::P2PoolConfig)
+        ));
     }
 
     #[test]
@@ -524,6 +933,64 @@ port = 3030
         assert!(view.sidebar_focused);
     }
 
+    /// Two stratum entries followed by two network ones.
+    fn sectioned_entries() -> Vec<P2PoolConfigEntry> {
+        let mut entries = vec![
+            make_entry("hostname", "127.0.0.1", true),
+            make_entry("port", "3333", true),
+            make_entry("listen_address", "/ip4/0.0.0.0/tcp/6884", true),
+            make_entry("dial_peers", "", true),
+        ];
+        for entry in &mut entries[2..] {
+            entry.section = ConfigSection::Network;
+        }
+        entries
+    }
+
+    #[test]
+    fn brackets_jump_between_section_starts_and_wrap() {
+        let mut view = P2PoolConfigView::new();
+        view.sidebar_focused = false;
+        let entries = sectioned_entries();
+
+        view.selected_index = 3;
+        view.handle_input(key(KeyCode::Char(']')), &entries);
+        assert_eq!(view.selected_index, 0, "wraps to the first stratum entry");
+        view.handle_input(key(KeyCode::Char('}')), &entries);
+        assert_eq!(view.selected_index, 2);
+
+        view.selected_index = 3;
+        view.handle_input(key(KeyCode::Char('[')), &entries);
+        assert_eq!(view.selected_index, 2, "start of the current section first");
+        view.handle_input(key(KeyCode::Char('[')), &entries);
+        assert_eq!(view.selected_index, 0);
+        view.handle_input(key(KeyCode::Char('{')), &entries);
+        assert_eq!(view.selected_index, 2, "wraps to the last section");
+
+        // A custom key added to stratum is listed last but joins its section
+        let mut entries = entries;
+        entries.push(make_entry("extra_threads", "4", true));
+        view.selected_index = 3;
+        view.handle_input(key(KeyCode::Char(']')), &entries);
+        assert_eq!(view.selected_index, 0);
+    }
+
+    #[test]
+    fn y_copies_the_real_value_of_a_masked_entry() {
+        let mut view = P2PoolConfigView::new();
+        view.sidebar_focused = false;
+        view.selected_index = 1;
+        let mut password = make_entry("password", "secret", true);
+        password.schema.sensitive = true;
+        let entries = vec![make_entry("username", "alice", true), password];
+
+        let action = view.handle_input(key(KeyCode::Char('y')), &entries);
+        assert!(matches!(
+            action,
+            AppAction::CopyValue(SaveTarget::P2PoolConfig, ref v) if v == "secret"
+        ));
+    }
+
     #[test]
     fn any_key_clears_save_message() {
         let mut view = P2PoolConfigView::new();
@@ -536,7 +1003,7 @@ port = 3030
     #[test]
     fn entry_display_enabled_non_sensitive() {
         let entry = make_entry("host", "127.0.0.1", true);
-        let (display, style) = entry_display(&entry);
+        let (display, style) = entry_display(&entry, &Theme::dark(), false);
         assert_eq!(display, "127.0.0.1");
         assert_eq!(style.fg, Some(Color::White));
     }
@@ -545,17 +1012,49 @@ port = 3030
     fn entry_display_enabled_sensitive() {
         let mut entry = make_entry("pass", "secret", true);
         entry.schema.sensitive = true;
-        let (display, _) = entry_display(&entry);
+        let (display, _) = entry_display(&entry, &Theme::dark(), false);
         assert_eq!(display, "••••••••");
     }
 
+    #[test]
+    fn entry_display_revealed_sensitive_shows_value() {
+        let mut entry = make_entry("pass", "secret", true);
+        entry.schema.sensitive = true;
+        let theme = Theme::dark();
+        let (display, style) = entry_display(&entry, &theme, true);
+        assert_eq!(display, "secret");
+        assert_eq!(style, theme.warning);
+    }
+
+    #[test]
+    fn v_reveals_the_selected_secret_until_the_selection_moves() {
+        let mut view = P2PoolConfigView::new();
+        view.sidebar_focused = false;
+        let mut password = make_entry("password", "secret", true);
+        password.schema.sensitive = true;
+        let entries = vec![password, make_entry("username", "alice", true)];
+
+        view.handle_input(key(KeyCode::Char('v')), &entries);
+        assert_eq!(view.revealed, Some(0));
+        view.handle_input(key(KeyCode::Char('v')), &entries);
+        assert_eq!(view.revealed, None);
+
+        view.handle_input(key(KeyCode::Char('v')), &entries);
+        view.handle_input(key(KeyCode::Down), &entries);
+        assert_eq!(view.revealed, None);
+
+        // Non-sensitive entries have nothing to reveal
+        view.handle_input(key(KeyCode::Char('v')), &entries);
+        assert_eq!(view.revealed, None);
+    }
+
     #[test]
     fn entry_display_disabled_with_default() {
         let mut entry = make_entry("port", "", false);
         entry.schema.kind = FieldKind::Optional {
             default: Some("3333".into()),
         };
-        let (display, style) = entry_display(&entry);
+        let (display, style) = entry_display(&entry, &Theme::dark(), false);
         assert_eq!(display, "(default: 3333)");
         assert_eq!(style.fg, Some(Color::DarkGray));
     }
@@ -563,7 +1062,7 @@ port = 3030
     #[test]
     fn entry_display_disabled_no_default() {
         let entry = make_entry("port", "", false);
-        let (display, _) = entry_display(&entry);
+        let (display, _) = entry_display(&entry, &Theme::dark(), false);
         assert_eq!(display, "(not set)");
     }
 
@@ -590,7 +1089,10 @@ port = 3030
         app.p2pool_conf_path = None;
         app.p2pool_config_view.warning_message = None;
         terminal
-            .draw(|f| P2PoolConfigView::render(f, &mut app, f.size()))
+            .draw(|f| {
+                let warnings = app.config_warnings();
+                P2PoolConfigView::render(f, &mut app, &warnings, f.size());
+            })
             .unwrap();
         assert!(buffer_text(&terminal).contains("Press [Enter] to select"));
     }
@@ -603,7 +1105,10 @@ port = 3030
         app.p2pool_conf_path = None;
         app.p2pool_config_view.warning_message = Some("File not found".into());
         terminal
-            .draw(|f| P2PoolConfigView::render(f, &mut app, f.size()))
+            .draw(|f| {
+                let warnings = app.config_warnings();
+                P2PoolConfigView::render(f, &mut app, &warnings, f.size());
+            })
             .unwrap();
         assert!(buffer_text(&terminal).contains("File not found"));
     }
@@ -633,7 +1138,10 @@ port = 3030
         app.p2pool_config_view.edit_input = "secret123".into();
 
         terminal
-            .draw(|f| P2PoolConfigView::render(f, &mut app, f.size()))
+            .draw(|f| {
+                let warnings = app.config_warnings();
+                P2PoolConfigView::render(f, &mut app, &warnings, f.size());
+            })
             .unwrap();
 
         let text = buffer_text(&terminal);
@@ -642,4 +1150,261 @@ port = 3030
         assert!(text.contains("Value:")); // detail panel rendered
         assert!(text.contains("_")); // editing cursor present
     }
+
+    #[test]
+    fn scrolling_past_viewport_keeps_offset_across_frames() {
+        use std::path::PathBuf;
+
+        let mut app = App::default();
+        app.p2pool_conf_path = Some(PathBuf::from("test.toml"));
+        let cfg = make_config();
+        let entries = flatten_config(&cfg);
+        app.p2pool_config = Some(cfg);
+        app.p2pool_config_view.sidebar_focused = false;
+
+        // 12 rows leave 10 inside the borders: five two-line entries
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        let mut draw = |app: &mut App| {
+            terminal
+                .draw(|f| {
+                    let area = f.area();
+                    let warnings = app.config_warnings();
+                    P2PoolConfigView::render(f, app, &warnings, area);
+                })
+                .unwrap();
+            buffer_text(&terminal)
+        };
+        assert!(
+            entries.len() > 6,
+            "test config must be taller than the viewport"
+        );
+
+        for _ in 0..entries.len() {
+            app.p2pool_config_view
+                .handle_input(key(KeyCode::Down), &entries);
+            draw(&mut app);
+        }
+        let last = entries.len() - 1;
+        assert_eq!(app.p2pool_config_view.selected_index, last);
+        assert_eq!(app.p2pool_config_view.list_state.offset(), last - 4);
+
+        // Moving up inside the viewport must not scroll back
+        app.p2pool_config_view
+            .handle_input(key(KeyCode::Up), &entries);
+        let text = draw(&mut app);
+        assert_eq!(app.p2pool_config_view.list_state.offset(), last - 4);
+        assert!(text.contains(">> "));
+        assert!(text.contains(&format!("{} = ", entries[last - 1].key)));
+    }
+
+    fn press_all(view: &mut P2PoolConfigView, entries: &[P2PoolConfigEntry], text: &str) {
+        for c in text.chars() {
+            view.handle_input(key(KeyCode::Char(c)), entries);
+        }
+    }
+
+    #[test]
+    fn filter_port_matches_stratum_and_api_ports() {
+        let entries = flatten_config(&make_config());
+        let mut view = P2PoolConfigView::new();
+        view.sidebar_focused = false;
+
+        view.handle_input(key(KeyCode::Char('/')), &entries);
+        assert!(view.filter.typing);
+        press_all(&mut view, &entries, "port");
+        view.handle_input(key(KeyCode::Enter), &entries);
+        assert!(!view.filter.typing);
+
+        let matched: Vec<(String, &str)> = view
+            .filter
+            .filtered_indices
+            .iter()
+            .map(|&i| (entries[i].section.to_string(), entries[i].key.as_str()))
+            .collect();
+        assert_eq!(
+            matched,
+            vec![("stratum".to_string(), "port"), ("api".to_string(), "port")]
+        );
+        // Selection maps back to the real entry
+        assert_eq!(entries[view.selected_index].key, "port");
+        assert_eq!(view.filter.title_suffix(), " [/port: 2 matches]");
+    }
+
+    #[test]
+    fn filter_navigation_stays_within_matches() {
+        let entries = flatten_config(&make_config());
+        let mut view = P2PoolConfigView::new();
+
+        view.handle_input(key(KeyCode::Char('/')), &entries);
+        press_all(&mut view, &entries, "PORT");
+        view.handle_input(key(KeyCode::Enter), &entries);
+
+        let first = view.filter.filtered_indices[0];
+        let second = view.filter.filtered_indices[1];
+        assert_eq!(view.selected_index, first);
+        view.handle_input(key(KeyCode::Down), &entries);
+        assert_eq!(view.selected_index, second);
+        view.handle_input(key(KeyCode::Down), &entries);
+        assert_eq!(view.selected_index, second);
+
+        // Esc clears the filter before leaving the panel
+        view.handle_input(key(KeyCode::Esc), &entries);
+        assert!(!view.filter.is_active());
+        assert!(!view.sidebar_focused);
+    }
+
+    #[test]
+    fn duplicate_appends_the_last_list_item() {
+        let mut entries = flatten_config(&make_config());
+        let peers = entries.iter().position(|e| e.key == "dial_peers").unwrap();
+        entries[peers].value = "/ip4/10.0.0.2/tcp/6884".to_string();
+        let mut view = P2PoolConfigView::new();
+        view.sidebar_focused = false;
+        view.selected_index = peers;
+
+        view.handle_input(key(KeyCode::Char('D')), &entries);
+        assert!(view.editing);
+        assert_eq!(
+            view.edit_input,
+            "/ip4/10.0.0.2/tcp/6884,/ip4/10.0.0.2/tcp/6884"
+        );
+
+        // Scalar fields have a single slot, so there is nothing to duplicate
+        let mut view = P2PoolConfigView::new();
+        view.sidebar_focused = false;
+        view.selected_index = entries.iter().position(|e| e.key == "port").unwrap();
+        view.handle_input(key(KeyCode::Char('D')), &entries);
+        assert!(!view.editing);
+    }
+
+    #[test]
+    fn non_default_toggle_hides_defaulted_entries() {
+        let entries = flatten_config(&make_config());
+        assert!(entries.iter().any(P2PoolConfigEntry::is_default));
+        let mut view = P2PoolConfigView::new();
+        view.sidebar_focused = false;
+        let hidden = entries
+            .iter()
+            .position(P2PoolConfigEntry::is_default)
+            .unwrap();
+        view.selected_index = hidden;
+
+        view.handle_input(key(KeyCode::Char('m')), &entries);
+        let visible = view.filter.visible(entries.len());
+        assert!(!visible.is_empty());
+        assert!(visible.iter().all(|&i| !entries[i].is_default()));
+        assert_eq!(
+            visible.len(),
+            entries.iter().filter(|e| !e.is_default()).count()
+        );
+        // The selection moves to the next entry still shown
+        assert!(visible.contains(&view.selected_index));
+        assert!(view.selected_index > hidden || view.selected_index == *visible.last().unwrap());
+
+        // A query searches only within the configured entries
+        view.handle_input(key(KeyCode::Char('/')), &entries);
+        press_all(&mut view, &entries, "port");
+        view.handle_input(key(KeyCode::Enter), &entries);
+        assert!(
+            view.filter
+                .filtered_indices
+                .iter()
+                .all(|&i| !entries[i].is_default())
+        );
+
+        // Esc drops the query but keeps defaults hidden
+        view.handle_input(key(KeyCode::Esc), &entries);
+        assert_eq!(view.filter.visible(entries.len()), visible);
+        assert!(!view.sidebar_focused);
+
+        view.handle_input(key(KeyCode::Char('m')), &entries);
+        assert_eq!(view.filter.visible(entries.len()).len(), entries.len());
+    }
+
+    #[test]
+    fn filter_does_not_search_sensitive_values() {
+        let entries = flatten_config(&make_config());
+        let mut view = P2PoolConfigView::new();
+
+        view.handle_input(key(KeyCode::Char('/')), &entries);
+        press_all(&mut view, &entries, "rpcpassword");
+
+        assert!(view.filter.filtered_indices.is_empty());
+        view.handle_input(key(KeyCode::Enter), &entries);
+        view.handle_input(key(KeyCode::Enter), &entries);
+        assert!(!view.editing, "no entry is selectable without matches");
+    }
+
+    #[test]
+    fn render_marks_env_overridden_entries() {
+        use std::path::PathBuf;
+
+        let mut app = App::default();
+        app.p2pool_conf_path = Some(PathBuf::from("test.toml"));
+        let cfg = make_config();
+        let entries = flatten_config(&cfg);
+        app.p2pool_origins = entries
+            .iter()
+            .map(|e| {
+                if e.key == "hostname" {
+                    Origin::Env
+                } else {
+                    Origin::File
+                }
+            })
+            .collect();
+        app.p2pool_config = Some(cfg);
+        app.p2pool_config_view.sidebar_focused = false;
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                let warnings = app.config_warnings();
+                P2PoolConfigView::render(f, &mut app, &warnings, area);
+            })
+            .unwrap();
+
+        let text = buffer_text(&terminal);
+        assert!(text.contains("[env P2POOL_STRATUM_HOSTNAME]"));
+        assert!(!text.contains("[env P2POOL_STRATUM_PORT]"));
+    }
+
+    #[test]
+    fn render_differs_between_dark_and_light_themes() {
+        use std::path::PathBuf;
+
+        let mut app = App::default();
+        app.p2pool_conf_path = Some(PathBuf::from("test.toml"));
+        app.p2pool_config = Some(make_config());
+
+        let render = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+            terminal
+                .draw(|f| {
+                    let area = f.area();
+                    let warnings = app.config_warnings();
+                    P2PoolConfigView::render(f, app, &warnings, area);
+                })
+                .unwrap();
+            terminal.backend().buffer().clone()
+        };
+
+        let dark = render(&mut app);
+        app.theme = app.theme.next();
+        let light = render(&mut app);
+
+        let text = |b: &ratatui::buffer::Buffer| {
+            b.content().iter().map(|c| c.symbol()).collect::<String>()
+        };
+        assert_eq!(text(&dark), text(&light), "themes must only change styles");
+        assert_ne!(dark, light);
+
+        // Section labels pick up the theme's section_label color
+        let section_fg = |b: &ratatui::buffer::Buffer| {
+            b.content().iter().find(|c| c.symbol() == "[").map(|c| c.fg)
+        };
+        assert_eq!(section_fg(&dark), Some(Color::Blue));
+        assert_eq!(section_fg(&light), Some(Color::Magenta));
+    }
 }
@@ -0,0 +1,83 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use ratatui::{
+    prelude::*,
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+};
+
+/// Draws a scrollbar over the right border of the bordered list in `area`
+/// when its `len` items, `item_height` rows each, don't fit. `offset` is the
+/// list's first visible item, as left in its `ListState` by the last render.
+pub fn render_list_scrollbar(
+    f: &mut Frame,
+    area: Rect,
+    len: usize,
+    item_height: u16,
+    offset: usize,
+) {
+    render_rows_scrollbar(f, area, &vec![item_height; len], offset);
+}
+
+/// Like [`render_list_scrollbar`] for items of differing heights, such as a
+/// config list with one entry expanded: `heights` holds each item's rows.
+pub fn render_rows_scrollbar(f: &mut Frame, area: Rect, heights: &[u16], offset: usize) {
+    let rows = |items: &[u16]| items.iter().map(|&h| usize::from(h)).sum::<usize>();
+    let total = rows(heights);
+    let visible = usize::from(area.height.saturating_sub(2));
+    if total <= visible {
+        return;
+    }
+
+    // The track replaces the border, so only the thumb stands out
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .track_symbol(Some("│"))
+        .thumb_symbol("█");
+    let mut state = ScrollbarState::new(total)
+        .position(rows(&heights[..offset.min(heights.len())]))
+        .viewport_content_length(visible);
+    f.render_stateful_widget(scrollbar, area.inner(Margin::new(0, 1)), &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::test_render::buffer_rows;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::widgets::{Block, Borders, List, ListState};
+
+    /// Draws `len` one-row items in a 12x7 bordered list with `selected`
+    /// highlighted, plus the scrollbar, and returns the rows.
+    fn render_list(len: usize, selected: usize) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(12, 7)).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                let items: Vec<String> = (0..len).map(|i| format!("item {i}")).collect();
+                let list = List::new(items)
+                    .block(Block::default().borders(Borders::ALL))
+                    .highlight_symbol(">> ");
+                let mut state = ListState::default();
+                state.select(Some(selected));
+                f.render_stateful_widget(list, area, &mut state);
+                render_list_scrollbar(f, area, len, 1, state.offset());
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer_rows(buffer, buffer.area)
+    }
+
+    #[test]
+    fn test_scrollbar_thumb_follows_the_list_offset() {
+        insta::assert_snapshot!(render_list(20, 10));
+    }
+
+    #[test]
+    fn no_scrollbar_when_the_list_fits() {
+        assert!(!render_list(5, 0).contains('█'));
+    }
+}
@@ -10,7 +10,7 @@ use ratatui::{
 };
 
 /// Number of settings fields.
-pub const FIELD_COUNT: usize = 5;
+pub const FIELD_COUNT: usize = 6;
 
 /// Describes how a settings field behaves when Enter is pressed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,6 +19,8 @@ pub enum FieldKind {
     FilePicker,
     /// Opens a file-explorer dialog in directory-selection mode.
     DirectoryPicker,
+    /// Flips an on/off setting in place.
+    Toggle,
 }
 
 /// All settings fields in display order.  Each entry is `(label, kind)`.
@@ -28,6 +30,7 @@ pub const FIELDS: [(&str, FieldKind); FIELD_COUNT] = [
     ("LN config path", FieldKind::FilePicker),
     ("Shares Market config path", FieldKind::FilePicker),
     ("Settings directory", FieldKind::DirectoryPicker),
+    ("Skip backups on save", FieldKind::Toggle),
 ];
 
 #[derive(Debug, Clone)]
@@ -68,7 +71,12 @@ impl SettingsView {
                 }
                 AppAction::None
             }
-            KeyCode::Enter => AppAction::OpenExplorerForSettings(self.selected_index),
+            KeyCode::Enter => match FIELDS[self.selected_index].1 {
+                FieldKind::Toggle => AppAction::ToggleSettingsField(self.selected_index),
+                FieldKind::FilePicker | FieldKind::DirectoryPicker => {
+                    AppAction::OpenExplorerForSettings(self.selected_index)
+                }
+            },
             KeyCode::Backspace => AppAction::ClearSettingsField(self.selected_index),
             KeyCode::Esc => {
                 self.sidebar_focused = true;
@@ -79,6 +87,7 @@ impl SettingsView {
     }
 
     pub fn render(f: &mut Frame, app: &mut App, area: Rect) {
+        let theme = app.theme;
         let values: [Option<String>; FIELD_COUNT] = [
             app.settings
                 .bitcoin_conf_path
@@ -100,6 +109,8 @@ impl SettingsView {
                 .settings_dir_override
                 .as_ref()
                 .map(|p| p.to_string_lossy().into_owned()),
+            // "Off" is the default, so only "On" is styled as a set value
+            app.settings.skip_backups.then(|| "On".to_string()),
         ];
 
         let items: Vec<ListItem> = (0..FIELD_COUNT)
@@ -107,12 +118,7 @@ impl SettingsView {
                 let (label, _kind) = FIELDS[idx];
                 let val = &values[idx];
                 let (display, style) = match val {
-                    Some(v) => (
-                        v.clone(),
-                        Style::default()
-                            .fg(Color::White)
-                            .add_modifier(Modifier::BOLD),
-                    ),
+                    Some(v) => (v.clone(), theme.set_value),
                     None => {
                         if idx == 4 {
                             let path = if app.config_dir.as_os_str().is_empty() {
@@ -120,17 +126,16 @@ impl SettingsView {
                             } else {
                                 app.config_dir.to_string_lossy().into_owned()
                             };
-                            (path, Style::default().fg(Color::DarkGray))
+                            (path, theme.default_value)
+                        } else if idx == 5 {
+                            ("Off".to_string(), theme.default_value)
                         } else {
-                            (
-                                "(not set)".to_string(),
-                                Style::default().fg(Color::DarkGray),
-                            )
+                            ("(not set)".to_string(), theme.default_value)
                         }
                     }
                 };
                 ListItem::new(vec![
-                    Line::from(Span::styled(label, Style::default().fg(Color::Gray))),
+                    Line::from(Span::styled(label, theme.description)),
                     Line::from(Span::styled(display, style)),
                 ])
             })
@@ -140,7 +145,7 @@ impl SettingsView {
         list_state.select(Some(app.settings_view.selected_index));
 
         let panel_style = if app.settings_view.sidebar_focused {
-            Style::default().fg(Color::DarkGray)
+            theme.dimmed
         } else {
             Style::default()
         };
@@ -152,7 +157,7 @@ impl SettingsView {
                     .title(" Settings ")
                     .border_style(panel_style),
             )
-            .highlight_style(Style::default().bg(Color::DarkGray));
+            .highlight_style(theme.list_highlight);
 
         f.render_stateful_widget(list, area, &mut list_state);
     }
@@ -221,9 +226,12 @@ mod tests {
     }
 
     #[test]
-    fn browsing_enter_opens_explorer_for_all_fields() {
+    fn browsing_enter_opens_explorer_for_all_picker_fields() {
         let mut view = content_focused_view();
         for idx in 0..FIELD_COUNT {
+            if FIELDS[idx].1 == FieldKind::Toggle {
+                continue;
+            }
             view.selected_index = idx;
             let action = view.handle_input(key(KeyCode::Enter));
             assert!(
@@ -233,6 +241,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn browsing_enter_on_toggle_field_toggles_it() {
+        let mut view = content_focused_view();
+        view.selected_index = 5;
+        let action = view.handle_input(key(KeyCode::Enter));
+        assert!(matches!(action, AppAction::ToggleSettingsField(5)));
+    }
+
     #[test]
     fn browsing_esc_sets_sidebar_focused_flag() {
         let mut view = content_focused_view();
@@ -2,23 +2,260 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::app::{App, CurrentScreen};
+use crate::app::{App, ConfigWarnings, CurrentScreen};
+use crate::bitcoin_config::rpc_port_warnings;
 use crate::components::settings_view::{FIELDS, FieldKind};
+use crate::theme::Theme;
 use ratatui::{prelude::*, widgets::Paragraph};
 
 #[derive(Clone, Debug)]
 pub struct StatusBar;
 
-fn hint(key: &str, desc: &str) -> Vec<Span<'static>> {
+fn hint(key: &str, desc: &str, theme: &Theme) -> Vec<Span<'static>> {
     vec![
-        Span::styled(
-            format!(" {key} "),
-            Style::default().bg(Color::DarkGray).fg(Color::White),
-        ),
-        Span::styled(format!(" {desc}  "), Style::default().fg(Color::DarkGray)),
+        Span::styled(format!(" {key} "), theme.list_highlight.patch(theme.text)),
+        Span::styled(format!(" {desc}  "), theme.dimmed),
     ]
 }
 
+/// Errors and warnings shown on the rows of the config on screen, as
+/// `(errors, warnings)`: invalid bitcoin.conf values are errors, while
+/// mismatches between the two configs, odd `[network]` limits and keys
+/// p2pool ignores are warnings. Zero for other screens and unloaded files.
+/// `warnings` are the frame's [`App::config_warnings`].
+#[must_use]
+pub fn problem_counts(app: &App, warnings: &ConfigWarnings) -> (usize, usize) {
+    match app.current_screen {
+        CurrentScreen::BitcoinConfig if app.bitcoin_conf_path.is_some() => {
+            let errors = app
+                .bitcoin_data
+                .iter()
+                .filter(|e| e.enabled && e.validation_error.is_some())
+                .count();
+            (
+                errors,
+                warnings.mismatches.len() + rpc_port_warnings(&app.bitcoin_data).len(),
+            )
+        }
+        CurrentScreen::P2PoolConfig if app.p2pool_config.is_some() => {
+            (0, warnings.p2pool().len() + app.p2pool_unknown_keys.len())
+        }
+        _ => (0, 0),
+    }
+}
+
+/// `✗ 1 error · 2 warnings`, leaving out a zero count; `None` when both are.
+fn problem_summary(errors: usize, warnings: usize) -> Option<String> {
+    let plural = |n: usize, word: &str| format!("{n} {word}{}", if n == 1 { "" } else { "s" });
+    match (errors, warnings) {
+        (0, 0) => None,
+        (0, w) => Some(format!("⚠ {}", plural(w, "warning"))),
+        (e, 0) => Some(format!("✗ {}", plural(e, "error"))),
+        (e, w) => Some(format!(
+            "✗ {} · {}",
+            plural(e, "error"),
+            plural(w, "warning")
+        )),
+    }
+}
+
+/// Key hints for the current screen and its state, as `(key, description)`
+/// pairs in display order. Status messages are rendered separately.
+#[must_use]
+pub fn footer_hints(app: &App) -> Vec<(&'static str, &'static str)> {
+    if app.pending_reload.is_some() {
+        return vec![("y", "Reload"), ("n/Esc", "Keep edits")];
+    }
+    if app.pending_save.is_some() {
+        return vec![("y", "Overwrite"), ("n/Esc", "Cancel")];
+    }
+    match app.current_screen {
+        CurrentScreen::Exiting => vec![("y", "Quit"), ("n/Esc", "Cancel")],
+        CurrentScreen::FileExplorer if app.explorer.pending_delete.is_some() => {
+            vec![("y", "Delete"), ("n/Esc", "Keep")]
+        }
+        CurrentScreen::FileExplorer if app.explorer.search.is_some() => {
+            vec![("Enter", "Open"), ("Tab", "Fuzzy"), ("Esc", "Close search")]
+        }
+        CurrentScreen::FileExplorer => {
+            let mut hints = vec![
+                // Left and Right only move in the multi-column listing
+                if app.explorer.grid_rows.is_some() {
+                    ("↑↓←→", "Navigate")
+                } else {
+                    ("↑↓", "Navigate")
+                },
+                ("Enter", "Select"),
+                ("⌫", "Parent folder"),
+                ("b", "Bookmarks"),
+                ("s", "Sort"),
+                (".", "Hidden files"),
+                ("n", "New folder"),
+                ("D", "Delete"),
+                ("/", "Search"),
+                ("Esc", "Cancel"),
+            ];
+            if app.explorer.selects_directories() {
+                hints.insert(2, ("d", "Use this directory"));
+            }
+            hints
+        }
+        CurrentScreen::BitcoinConfig if app.bitcoin_conf_path.is_some() => {
+            let view = &app.bitcoin_config_view;
+            if view.save_message.is_some() {
+                vec![]
+            } else if view.rpcauth_input.is_some() {
+                vec![("Enter", "Generate"), ("Esc", "Cancel")]
+            } else if view.add_input.is_some() {
+                vec![("Enter", "Add"), ("Esc", "Cancel")]
+            } else if view.filter.typing {
+                vec![("Enter", "Apply filter"), ("Esc", "Clear filter")]
+            } else if view.editing {
+                vec![("Enter", "Confirm"), ("Esc", "Cancel")]
+            } else if view.sidebar_focused {
+                vec![("↑↓", "Navigate sidebar"), ("Enter", "Focus config")]
+            } else {
+                let expand = if view.is_expanded(&app.bitcoin_data, view.selected_index) {
+                    "Collapse"
+                } else {
+                    "Expand"
+                };
+                vec![
+                    ("↑↓", "Navigate"),
+                    ("Enter", "Edit"),
+                    ("Space", "Toggle"),
+                    ("u", "Undo"),
+                    ("Ctrl+r", "Redo"),
+                    ("/", "Filter"),
+                    ("n", "New entry"),
+                    ("D", "Duplicate"),
+                    ("a", "RPC auth"),
+                    ("y", "Copy"),
+                    ("x", expand),
+                    ("o", "Open file"),
+                    ("r", "Reload"),
+                    ("d", "Diff"),
+                    ("s", "Save"),
+                    ("Esc", "Back"),
+                ]
+            }
+        }
+        CurrentScreen::P2PoolConfig if app.p2pool_conf_path.is_some() => {
+            let view = &app.p2pool_config_view;
+            if view.add_input.is_some() {
+                vec![("Enter", "Add"), ("Esc", "Cancel")]
+            } else if view.filter.typing {
+                vec![("Enter", "Apply filter"), ("Esc", "Clear filter")]
+            } else if view.editing {
+                vec![("Enter", "Confirm"), ("Esc", "Cancel")]
+            } else if view.sidebar_focused {
+                vec![("↑↓", "Navigate sidebar"), ("Enter", "Focus config")]
+            } else {
+                let expand = if view.is_expanded(&app.p2pool_entries(), view.selected_index) {
+                    "Collapse"
+                } else {
+                    "Expand"
+                };
+                let defaults = if view.filter.narrowed {
+                    "Show all"
+                } else {
+                    "Non-default"
+                };
+                let mut hints = vec![
+                    ("↑↓", "Navigate"),
+                    ("[]", "Section"),
+                    ("Enter", "Edit"),
+                    ("u", "Undo"),
+                    ("Ctrl+r", "Redo"),
+                    ("/", "Filter"),
+                    ("m", defaults),
+                    ("n", "New entry"),
+                    ("y", "Copy"),
+                    ("x", expand),
+                ];
+                let selected = app.p2pool_entries().into_iter().nth(view.selected_index);
+                if selected
+                    .as_ref()
+                    .is_some_and(|e| e.schema.type_hint == "CSV")
+                {
+                    hints.push(("D", "Duplicate"));
+                }
+                let sensitive = selected.is_some_and(|e| e.schema.sensitive);
+                if sensitive {
+                    let label = if view.revealed.is_some() {
+                        "Hide"
+                    } else {
+                        "Reveal"
+                    };
+                    hints.push(("v", label));
+                }
+                hints.extend([
+                    ("o", "Open file"),
+                    ("r", "Reload"),
+                    ("d", "Diff"),
+                    ("s", "Save"),
+                    ("Esc", "Back"),
+                ]);
+                hints
+            }
+        }
+        CurrentScreen::BitcoinConfig => {
+            if app.bitcoin_config_view.warning_message.is_some() {
+                vec![("Enter", "Try again")]
+            } else {
+                vec![
+                    ("↑↓", "Navigate sidebar"),
+                    ("Enter", "Open file"),
+                    ("Esc", "Back"),
+                ]
+            }
+        }
+        CurrentScreen::Settings => {
+            if app.settings_view.save_error.is_some() {
+                vec![]
+            } else if app.settings_view.sidebar_focused {
+                vec![("↑↓", "Navigate sidebar"), ("Enter", "Focus settings")]
+            } else {
+                let s = &app.settings;
+                let idx = app.settings_view.selected_index;
+                let field_is_set = match idx {
+                    0 => s.bitcoin_conf_path.is_some(),
+                    1 => s.p2pool_conf_path.is_some(),
+                    2 => s.ln_conf_path.is_some(),
+                    3 => s.shares_market_conf_path.is_some(),
+                    4 => s.settings_dir_override.is_some(),
+                    5 => s.skip_backups,
+                    _ => false,
+                };
+                let mut hints = vec![("↑↓", "Navigate")];
+                if let Some(&(_, kind)) = FIELDS.get(idx) {
+                    let label = match kind {
+                        FieldKind::FilePicker => "Browse file",
+                        FieldKind::DirectoryPicker => "Browse dir",
+                        FieldKind::Toggle => "Toggle",
+                    };
+                    hints.push(("Enter", label));
+                }
+                if field_is_set {
+                    hints.push(("⌫", "Clear"));
+                }
+                hints.push(("Esc", "Back"));
+                hints
+            }
+        }
+        CurrentScreen::BitcoinStatus => vec![
+            ("↑↓", "Navigate sidebar"),
+            ("←→", "Switch tab"),
+            ("q", "Quit"),
+        ],
+        _ => vec![
+            ("↑↓", "Navigate sidebar"),
+            ("Enter", "Select"),
+            ("q", "Quit"),
+        ],
+    }
+}
+
 impl StatusBar {
     #[must_use]
     pub fn new() -> Self {
@@ -26,101 +263,48 @@ impl StatusBar {
     }
 
     // Status bar
-    pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    pub fn render(f: &mut Frame, app: &App, warnings: &ConfigWarnings, area: Rect) {
+        let theme = app.theme;
         let mut spans: Vec<Span> = Vec::new();
 
+        // Status messages come first, then the key hints for the screen
         match app.current_screen {
-            CurrentScreen::FileExplorer => {
-                spans.extend(hint("↑↓", "Navigate"));
-                spans.extend(hint("Enter", "Select"));
-                spans.extend(hint("⌫", "Parent folder"));
-                spans.extend(hint("Esc", "Cancel"));
-            }
-            CurrentScreen::BitcoinConfig if app.bitcoin_conf_path.is_some() => {
-                if let Some(msg) = &app.bitcoin_config_view.save_message {
-                    spans.push(Span::styled(
-                        format!(" ✓ {msg}  "),
-                        Style::default().fg(Color::Green),
-                    ));
-                } else if app.bitcoin_config_view.editing {
-                    spans.extend(hint("Enter", "Confirm"));
-                    spans.extend(hint("Esc", "Cancel"));
-                } else if app.bitcoin_config_view.sidebar_focused {
-                    spans.extend(hint("↑↓", "Navigate sidebar"));
-                    spans.extend(hint("Enter", "Focus config"));
-                } else {
-                    spans.extend(hint("↑↓", "Navigate"));
-                    spans.extend(hint("Enter", "Edit"));
-                    spans.extend(hint("s", "Save"));
-                    spans.extend(hint("Esc", "Back"));
-                }
-            }
-            CurrentScreen::P2PoolConfig if app.p2pool_conf_path.is_some() => {
-                spans.extend(hint("↑↓", "Navigate"));
-                spans.extend(hint("Enter", "Open file"));
-                spans.extend(hint("q", "Quit"));
-            }
             CurrentScreen::BitcoinConfig => {
                 if let Some(msg) = &app.bitcoin_config_view.warning_message {
-                    spans.push(Span::styled(
-                        format!(" ⚠ {msg}  "),
-                        Style::default().fg(Color::Yellow),
-                    ));
-                    spans.extend(hint("Enter", "Try again"));
-                } else {
-                    spans.extend(hint("↑↓", "Navigate sidebar"));
-                    spans.extend(hint("Enter", "Open file"));
-                    spans.extend(hint("Esc", "Back"));
+                    spans.push(Span::styled(format!(" ⚠ {msg}  "), theme.warning));
+                }
+                if let Some(msg) = app
+                    .bitcoin_config_view
+                    .save_message
+                    .as_ref()
+                    .filter(|_| app.bitcoin_conf_path.is_some())
+                {
+                    spans.push(Span::styled(format!(" ✓ {msg}  "), theme.success));
                 }
             }
             CurrentScreen::Settings => {
                 if let Some(err) = &app.settings_view.save_error {
-                    spans.push(Span::styled(
-                        format!(" ⚠ {err}  "),
-                        Style::default().fg(Color::Red),
-                    ));
-                } else if app.settings_view.sidebar_focused {
-                    spans.extend(hint("↑↓", "Navigate sidebar"));
-                    spans.extend(hint("Enter", "Focus settings"));
-                } else {
-                    let s = &app.settings;
-                    let idx = app.settings_view.selected_index;
-                    let field_is_set = match idx {
-                        0 => s.bitcoin_conf_path.is_some(),
-                        1 => s.p2pool_conf_path.is_some(),
-                        2 => s.ln_conf_path.is_some(),
-                        3 => s.shares_market_conf_path.is_some(),
-                        4 => s.settings_dir_override.is_some(),
-                        _ => false,
-                    };
-                    spans.extend(hint("↑↓", "Navigate"));
-                    if let Some(&(_, kind)) = FIELDS.get(idx) {
-                        let label = if matches!(kind, FieldKind::DirectoryPicker) {
-                            "Browse dir"
-                        } else {
-                            "Browse file"
-                        };
-                        spans.extend(hint("Enter", label));
-                    }
-                    if field_is_set {
-                        spans.extend(hint("⌫", "Clear"));
-                    }
-                    spans.extend(hint("Esc", "Back"));
+                    spans.push(Span::styled(format!(" ⚠ {err}  "), theme.error));
                 }
             }
-            CurrentScreen::BitcoinStatus => {
-                spans.extend(hint("↑↓", "Navigate sidebar"));
-                spans.extend(hint("←→", "Switch tab"));
-                spans.extend(hint("q", "Quit"));
-            }
-            _ => {
-                spans.extend(hint("↑↓", "Navigate sidebar"));
-                spans.extend(hint("Enter", "Select"));
-                spans.extend(hint("q", "Quit"));
-            }
+            _ => {}
         }
 
-        let bar = Paragraph::new(Line::from(spans)).style(Style::default().bg(Color::Black));
+        let (errors, warning_count) = problem_counts(app, warnings);
+        if let Some(summary) = problem_summary(errors, warning_count) {
+            let style = if errors > 0 {
+                theme.error
+            } else {
+                theme.warning
+            };
+            spans.push(Span::styled(format!(" {summary}  "), style));
+        }
+
+        for (key, desc) in footer_hints(app) {
+            spans.extend(hint(key, desc, &theme));
+        }
+
+        let bar = Paragraph::new(Line::from(spans)).style(theme.status_bar);
         f.render_widget(bar, area);
     }
 }
@@ -135,6 +319,7 @@ impl Default for StatusBar {
 mod tests {
     use super::*;
     use crate::app::{App, CurrentScreen};
+    use crate::components::test_render::buffer_rows;
     use ratatui::{Terminal, backend::TestBackend};
 
     fn render_status_bar(app: &App) -> String {
@@ -143,7 +328,7 @@ mod tests {
         terminal
             .draw(|f| {
                 let area = f.area();
-                StatusBar::render(f, app, area);
+                StatusBar::render(f, app, &app.config_warnings(), area);
             })
             .unwrap();
         terminal
@@ -165,6 +350,56 @@ mod tests {
         assert!(output.contains("Parent folder"));
     }
 
+    #[test]
+    fn directory_mode_offers_use_this_directory() {
+        use crate::components::file_explorer::SelectMode;
+
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::FileExplorer;
+        assert!(!hint_labels(&app).contains(&"Use this directory"));
+
+        app.explorer.select_mode = SelectMode::Directory;
+        assert_eq!(
+            hint_labels(&app)[..3],
+            ["Navigate", "Select", "Use this directory"]
+        );
+    }
+
+    #[test]
+    fn invalid_entries_are_counted_in_the_footer() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::BitcoinConfig;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        std::fs::write(&path, "txindex=maybe\nserver=1\n").unwrap();
+        app.bitcoin_data = crate::bitcoin_config::parse_config(&path).unwrap();
+        app.bitcoin_conf_path = Some(path);
+        assert_eq!(problem_counts(&app, &app.config_warnings()), (1, 0));
+        assert!(render_status_bar(&app).contains("✗ 1 error"));
+
+        app.bitcoin_data[0].enabled = false;
+        assert_eq!(problem_counts(&app, &app.config_warnings()), (0, 0));
+        assert!(!render_status_bar(&app).contains("error"));
+
+        // A port copied from another network is only a warning
+        let signet = dir.path().join("signet.conf");
+        std::fs::write(&signet, "signet=1\nrpcport=8332\n").unwrap();
+        app.bitcoin_data = crate::bitcoin_config::parse_config(&signet).unwrap();
+        assert_eq!(problem_counts(&app, &app.config_warnings()), (0, 1));
+        assert!(render_status_bar(&app).contains("⚠ 1 warning"));
+    }
+
+    #[test]
+    fn problem_summary_leaves_out_zero_counts() {
+        assert_eq!(problem_summary(0, 0), None);
+        assert_eq!(problem_summary(0, 1).as_deref(), Some("⚠ 1 warning"));
+        assert_eq!(problem_summary(2, 0).as_deref(), Some("✗ 2 errors"));
+        assert_eq!(
+            problem_summary(1, 3).as_deref(),
+            Some("✗ 1 error · 3 warnings")
+        );
+    }
+
     #[test]
     fn bitcoin_config_no_file_shows_open_file() {
         let mut app = App::new();
@@ -217,6 +452,12 @@ mod tests {
         assert!(output.contains("Edit"));
         assert!(output.contains("Save"));
         assert!(output.contains("Back"));
+        assert!(hint_labels(&app).contains(&"Copy"));
+        assert!(hint_labels(&app).contains(&"Redo"));
+
+        app.bitcoin_data = vec![crate::bitcoin_config::ConfigEntry::new("server", "1")];
+        app.bitcoin_config_view.expanded = Some((None, "server".to_string(), 0));
+        assert!(hint_labels(&app).contains(&"Collapse"));
     }
 
     #[test]
@@ -279,6 +520,18 @@ mod tests {
         assert!(output.contains("Back"));
     }
 
+    #[test]
+    fn settings_content_focused_toggle_field_shows_toggle() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Settings;
+        app.settings_view.sidebar_focused = false;
+        // field 5 is Toggle
+        app.settings_view.selected_index = 5;
+        let output = render_status_bar(&app);
+        assert!(output.contains("Toggle"));
+        assert!(!output.contains("Browse"));
+    }
+
     #[test]
     fn settings_content_focused_field_set_shows_clear_hint() {
         let mut app = App::new();
@@ -363,4 +616,181 @@ mod tests {
         let output = render_status_bar(&app);
         assert!(output.contains("Clear"));
     }
+
+    fn hint_labels(app: &App) -> Vec<&'static str> {
+        footer_hints(app)
+            .into_iter()
+            .map(|(_, desc)| desc)
+            .collect()
+    }
+
+    #[test]
+    fn footer_hints_for_each_screen() {
+        let cases = [
+            (
+                CurrentScreen::Home,
+                vec!["Navigate sidebar", "Select", "Quit"],
+            ),
+            (
+                CurrentScreen::BitcoinConfig,
+                vec!["Navigate sidebar", "Open file", "Back"],
+            ),
+            (
+                CurrentScreen::BitcoinStatus,
+                vec!["Navigate sidebar", "Switch tab", "Quit"],
+            ),
+            (
+                CurrentScreen::P2PoolConfig,
+                vec!["Navigate sidebar", "Select", "Quit"],
+            ),
+            (
+                CurrentScreen::P2PoolStatus,
+                vec!["Navigate sidebar", "Select", "Quit"],
+            ),
+            (
+                CurrentScreen::LNConfig,
+                vec!["Navigate sidebar", "Select", "Quit"],
+            ),
+            (
+                CurrentScreen::LNStatus,
+                vec!["Navigate sidebar", "Select", "Quit"],
+            ),
+            (
+                CurrentScreen::SharesMarket,
+                vec!["Navigate sidebar", "Select", "Quit"],
+            ),
+            (
+                CurrentScreen::Settings,
+                vec!["Navigate sidebar", "Focus settings"],
+            ),
+            (CurrentScreen::Exiting, vec!["Quit", "Cancel"]),
+            (
+                CurrentScreen::FileExplorer,
+                vec![
+                    "Navigate",
+                    "Select",
+                    "Parent folder",
+                    "Bookmarks",
+                    "Sort",
+                    "Hidden files",
+                    "New folder",
+                    "Delete",
+                    "Search",
+                    "Cancel",
+                ],
+            ),
+        ];
+
+        for (screen, expected) in cases {
+            let mut app = App::new();
+            app.current_screen = screen;
+            assert_eq!(hint_labels(&app), expected, "{screen:?}");
+        }
+    }
+
+    /// The footer as drawn at 80 columns, without the padding after the
+    /// last hint.
+    fn footer_at_80_columns(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(80, 1)).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                StatusBar::render(f, app, &app.config_warnings(), area);
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer_rows(buffer, buffer.area).trim_end().to_string()
+    }
+
+    #[test]
+    fn footer_snapshots_for_each_screen() {
+        let screens = [
+            ("home", CurrentScreen::Home),
+            ("bitcoin_config", CurrentScreen::BitcoinConfig),
+            ("bitcoin_status", CurrentScreen::BitcoinStatus),
+            ("p2pool_config", CurrentScreen::P2PoolConfig),
+            ("p2pool_status", CurrentScreen::P2PoolStatus),
+            ("ln_config", CurrentScreen::LNConfig),
+            ("ln_status", CurrentScreen::LNStatus),
+            ("shares_market", CurrentScreen::SharesMarket),
+            ("settings", CurrentScreen::Settings),
+            ("exiting", CurrentScreen::Exiting),
+            ("file_explorer", CurrentScreen::FileExplorer),
+        ];
+        for (name, screen) in screens {
+            let mut app = App::new();
+            app.current_screen = screen;
+            insta::assert_snapshot!(format!("footer_{name}"), footer_at_80_columns(&app));
+        }
+
+        // Loaded configs offer the most hints, so clipping shows there first
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::BitcoinConfig;
+        app.bitcoin_conf_path = Some(std::path::PathBuf::from("/tmp/bitcoin.conf"));
+        app.bitcoin_config_view.sidebar_focused = false;
+        insta::assert_snapshot!("footer_bitcoin_config_loaded", footer_at_80_columns(&app));
+
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::P2PoolConfig;
+        app.p2pool_conf_path = Some(std::path::PathBuf::from("/tmp/p2pool.toml"));
+        app.p2pool_config_view.sidebar_focused = false;
+        insta::assert_snapshot!("footer_p2pool_config_loaded", footer_at_80_columns(&app));
+    }
+
+    #[test]
+    fn footer_updates_when_screen_changes() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Home;
+        assert!(render_status_bar(&app).contains("Quit"));
+
+        app.current_screen = CurrentScreen::FileExplorer;
+        let output = render_status_bar(&app);
+        assert!(output.contains("Bookmarks"));
+        assert!(!output.contains("Quit"));
+    }
+
+    #[test]
+    fn p2pool_config_with_file_shows_edit_hints() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::P2PoolConfig;
+        app.p2pool_conf_path = Some(std::path::PathBuf::from("/tmp/p2pool.toml"));
+        app.p2pool_config_view.sidebar_focused = false;
+        assert_eq!(
+            hint_labels(&app),
+            vec![
+                "Navigate",
+                "Section",
+                "Edit",
+                "Undo",
+                "Redo",
+                "Filter",
+                "Non-default",
+                "New entry",
+                "Copy",
+                "Expand",
+                "Open file",
+                "Reload",
+                "Diff",
+                "Save",
+                "Back"
+            ]
+        );
+
+        app.p2pool_config_view.add_input = Some(String::new());
+        assert_eq!(hint_labels(&app), vec!["Add", "Cancel"]);
+        app.p2pool_config_view.add_input = None;
+
+        app.p2pool_config_view.editing = true;
+        assert_eq!(hint_labels(&app), vec!["Confirm", "Cancel"]);
+    }
+
+    #[test]
+    fn filter_typing_shows_apply_and_clear() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::BitcoinConfig;
+        app.bitcoin_conf_path = Some(std::path::PathBuf::from("/tmp/bitcoin.conf"));
+        app.bitcoin_config_view.sidebar_focused = false;
+        app.bitcoin_config_view.filter.typing = true;
+        assert_eq!(hint_labels(&app), vec!["Apply filter", "Clear filter"]);
+    }
 }
@@ -0,0 +1,20 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Reads rendered test buffers back as text for snapshot tests.
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+
+/// The symbols of `area` in `buffer`, one line per row.
+pub fn buffer_rows(buffer: &Buffer, area: Rect) -> String {
+    (area.top()..area.bottom())
+        .map(|y| {
+            (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
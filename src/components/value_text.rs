@@ -0,0 +1,128 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::theme::Theme;
+use ratatui::widgets::{Block, Borders};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Shortens `s` to `width` display columns by replacing its middle with "…",
+/// so both the start and the end of a long value (a hash, a path, an
+/// `rpcauth` line) stay visible.
+#[must_use]
+pub fn truncate_middle(s: &str, width: usize) -> String {
+    if s.width() <= width {
+        return s.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    // The head gets the odd column
+    let avail = width - 1;
+    let tail_max = avail / 2;
+    let head_max = avail - tail_max;
+
+    let mut head = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > head_max {
+            break;
+        }
+        used += w;
+        head.push(c);
+    }
+
+    let mut tail: Vec<char> = Vec::new();
+    let mut used = 0;
+    for c in s.chars().rev() {
+        let w = c.width().unwrap_or(0);
+        if used + w > tail_max {
+            break;
+        }
+        used += w;
+        tail.push(c);
+    }
+
+    format!(
+        "{head}\u{2026}{}",
+        tail.into_iter().rev().collect::<String>()
+    )
+}
+
+/// Splits `s` into pieces of at most `width` display columns each, for
+/// showing a value over several rows. Always returns at least one piece.
+#[must_use]
+pub fn wrap_columns(s: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![String::new()];
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > width && used > 0 {
+            lines.push(String::new());
+            used = 0;
+        }
+        used += w;
+        if let Some(line) = lines.last_mut() {
+            line.push(c);
+        }
+    }
+    lines
+}
+
+/// Border for the value being edited: green while it would be accepted,
+/// red and titled with the reason while it would not.
+#[must_use]
+pub fn edit_block<'a>(error: Option<&'a str>, theme: &Theme) -> Block<'a> {
+    let block = Block::default().borders(Borders::ALL);
+    match error {
+        Some(reason) => block
+            .border_style(theme.error)
+            .title(format!(" ✗ {reason} "))
+            .title_style(theme.error),
+        None => block.border_style(theme.success),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_middle_leaves_short_values_alone() {
+        assert_eq!(truncate_middle("server", 6), "server");
+        assert_eq!(truncate_middle("", 0), "");
+    }
+
+    #[test]
+    fn truncate_middle_keeps_both_ends() {
+        assert_eq!(truncate_middle("0123456789", 7), "012…789");
+        assert_eq!(truncate_middle("0123456789", 6), "012…89");
+    }
+
+    #[test]
+    fn truncate_middle_respects_wide_chars() {
+        // Each ideograph takes two columns; a half-fitting one is dropped
+        assert_eq!(truncate_middle("日本語のテキスト", 8), "日本…ト");
+    }
+
+    #[test]
+    fn truncate_middle_handles_tiny_widths() {
+        assert_eq!(truncate_middle("abcdef", 1), "…");
+        assert_eq!(truncate_middle("abcdef", 0), "");
+    }
+
+    #[test]
+    fn wrap_columns_splits_at_width() {
+        assert_eq!(wrap_columns("abcdefg", 3), ["abc", "def", "g"]);
+        assert_eq!(wrap_columns("abc", 3), ["abc"]);
+        assert_eq!(wrap_columns("", 3), [""]);
+    }
+
+    #[test]
+    fn wrap_columns_never_splits_a_wide_char() {
+        assert_eq!(wrap_columns("日本語", 3), ["日", "本", "語"]);
+    }
+}
@@ -0,0 +1,161 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use std::path::Path;
+
+/// Which parser a config file belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigKind {
+    Bitcoin,
+    P2Pool,
+    Unknown,
+}
+
+/// Tables only a p2pool config has, whether TOML, JSON or YAML.
+const P2POOL_SECTIONS: &[&str] = &[
+    "stratum",
+    "bitcoinrpc",
+    "network",
+    "store",
+    "logging",
+    "api",
+];
+
+/// Network sections bitcoin.conf may contain.
+const BITCOIN_SECTIONS: &[&str] = &["main", "test", "testnet4", "signet", "regtest"];
+
+/// Classifies `text` by its contents alone.
+///
+/// A p2pool section wins, as a TOML `[stratum]` header, a top-level JSON
+/// key or an unindented YAML `stratum:`; otherwise a bitcoin network section
+/// or a bare `key=value` line marks bitcoin.conf. TOML quoting is not enough
+/// on its own, since neither format requires it.
+#[must_use]
+pub fn detect_config_text(text: &str) -> ConfigKind {
+    if text.trim_start().starts_with('{') {
+        let json = serde_json::from_str::<serde_json::Value>(text).unwrap_or_default();
+        let p2pool = json
+            .as_object()
+            .is_some_and(|o| o.keys().any(|k| P2POOL_SECTIONS.contains(&k.as_str())));
+        return if p2pool {
+            ConfigKind::P2Pool
+        } else {
+            ConfigKind::Unknown
+        };
+    }
+
+    let mut bitcoin = false;
+    for raw in text.lines() {
+        if let Some(name) = raw.trim_end().strip_suffix(':')
+            && P2POOL_SECTIONS.contains(&name)
+        {
+            return ConfigKind::P2Pool;
+        }
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.split(']').next()) {
+            let name = name.trim();
+            if P2POOL_SECTIONS.contains(&name) {
+                return ConfigKind::P2Pool;
+            }
+            bitcoin |= BITCOIN_SECTIONS.contains(&name);
+        } else if let Some((key, _)) = line.split_once('=') {
+            // bitcoin.conf never puts spaces around `=`; TOML usually does
+            bitcoin |= !key.is_empty() && !key.ends_with(' ');
+        }
+    }
+    if bitcoin {
+        ConfigKind::Bitcoin
+    } else {
+        ConfigKind::Unknown
+    }
+}
+
+/// Classifies the file at `path`, falling back to its extension (`.toml`,
+/// `.json`, `.yaml` or `.yml` for p2pool, `.conf` for bitcoin) when the
+/// contents are inconclusive or unreadable.
+#[must_use]
+pub fn detect_config_kind(path: &Path) -> ConfigKind {
+    let by_contents = std::fs::read_to_string(path)
+        .map(|text| detect_config_text(&text))
+        .unwrap_or(ConfigKind::Unknown);
+    if by_contents != ConfigKind::Unknown {
+        return by_contents;
+    }
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml" | "json" | "yaml" | "yml") => ConfigKind::P2Pool,
+        Some("conf") => ConfigKind::Bitcoin,
+        _ => ConfigKind::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BITCOIN_CONF: &str = "\
+# Generated by bitcoin-conf
+server=1
+txindex=1
+rpcuser=alice
+
+[test]
+rpcport=18332
+";
+
+    const P2POOL_TOML: &str = r#"
+[stratum]
+hostname = "127.0.0.1"
+port = 3333
+network = "signet"
+
+[bitcoinrpc]
+url = "http://127.0.0.1:38332"
+username = "rpcuser"
+password = "rpcpassword"
+"#;
+
+    #[test]
+    fn detect_config_text_classifies_sample_configs() {
+        assert_eq!(detect_config_text(BITCOIN_CONF), ConfigKind::Bitcoin);
+        assert_eq!(detect_config_text(P2POOL_TOML), ConfigKind::P2Pool);
+        assert_eq!(
+            detect_config_text("# only a comment\n"),
+            ConfigKind::Unknown
+        );
+        assert_eq!(detect_config_text("[regtest]\n"), ConfigKind::Bitcoin);
+    }
+
+    #[test]
+    fn detect_config_text_finds_p2pool_sections_in_json_and_yaml() {
+        let json = r#"{"stratum": {"port": 3333}, "bitcoinrpc": {"url": "http://h?a=1"}}"#;
+        assert_eq!(detect_config_text(json), ConfigKind::P2Pool);
+        assert_eq!(detect_config_text(r#"{"server": 1}"#), ConfigKind::Unknown);
+
+        let yaml = "# p2pool\nstratum:\n  hostname: 127.0.0.1\n  port: 3333\n";
+        assert_eq!(detect_config_text(yaml), ConfigKind::P2Pool);
+        // Only a top-level key names a section
+        assert_eq!(detect_config_text("logs:\n  api:\n"), ConfigKind::Unknown);
+    }
+
+    #[test]
+    fn detect_config_kind_prefers_contents_over_extension() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let misnamed = dir.path().join("p2pool.conf");
+        std::fs::write(&misnamed, P2POOL_TOML).unwrap();
+        assert_eq!(detect_config_kind(&misnamed), ConfigKind::P2Pool);
+
+        let empty = dir.path().join("new.toml");
+        std::fs::write(&empty, "").unwrap();
+        assert_eq!(detect_config_kind(&empty), ConfigKind::P2Pool);
+
+        assert_eq!(
+            detect_config_kind(&dir.path().join("missing")),
+            ConfigKind::Unknown
+        );
+    }
+}
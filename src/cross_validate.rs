@@ -0,0 +1,271 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Checks that a p2pool config can reach the bitcoind a bitcoin.conf sets up.
+
+use crate::bitcoin_config::{ConfigEntry as BitcoinEntry, default_rpc_port};
+use crate::p2poolv2_config::{ConfigSection, Endpoint, P2PoolConfigEntry as P2PoolEntry};
+use crate::warning::Warning;
+
+/// Compares p2pool's `stratum.zmqpubhashblock` and `bitcoinrpc` settings
+/// with the bitcoin.conf options they must agree with: `zmqpubhashblock`,
+/// `rpcbind`, `rpcport`, and `rpcuser`/`rpcpassword` or `rpcauth`.
+///
+/// Only bitcoin.conf values outside any section or in the section of
+/// p2pool's `stratum.network` count. Unparseable p2pool URLs are left to
+/// p2pool's own validation. Secrets never appear in the messages.
+#[must_use]
+pub fn cross_validate(bitcoin: &[BitcoinEntry], p2pool: &[P2PoolEntry]) -> Vec<Warning> {
+    let p2pool_value = |section: ConfigSection, key: &str| {
+        p2pool
+            .iter()
+            .find(|e| e.enabled && e.section == section && e.key == key)
+            .map(|e| e.value.as_str())
+    };
+    let network = p2pool_value(ConfigSection::Stratum, "network").unwrap_or("main");
+    let bitcoin_values = |key: &str| -> Vec<&str> {
+        bitcoin
+            .iter()
+            .filter(|e| e.enabled && e.key == key && e.in_effect(network))
+            .map(|e| e.value.as_str())
+            .collect()
+    };
+
+    let mut warnings = Vec::new();
+
+    if let Some(zmq) = p2pool_value(ConfigSection::Stratum, "zmqpubhashblock")
+        && let Ok(endpoint) = Endpoint::parse(zmq, &["tcp"])
+    {
+        let published = bitcoin_values("zmqpubhashblock");
+        if published.is_empty() {
+            warnings.push(Warning {
+                field: "stratum.zmqpubhashblock",
+                message: format!(
+                    "bitcoin.conf does not set zmqpubhashblock; p2pool listens on {zmq}"
+                ),
+            });
+        } else if !published.iter().any(|url| {
+            Endpoint::parse(url, &["tcp"])
+                .is_ok_and(|bound| reaches(&bound.host, bound.port, &endpoint))
+        }) {
+            warnings.push(Warning {
+                field: "stratum.zmqpubhashblock",
+                message: format!(
+                    "{zmq} does not match bitcoin.conf zmqpubhashblock={}",
+                    published.join(", ")
+                ),
+            });
+        }
+    }
+
+    if let Some(url) = p2pool_value(ConfigSection::BitcoinRpc, "url")
+        && let Ok(endpoint) = Endpoint::parse(url, &["http", "https"])
+    {
+        let ports = bitcoin_values("rpcport");
+        let default_port = default_rpc_port(network).to_string();
+        let ports = if ports.is_empty() {
+            vec![default_port.as_str()]
+        } else {
+            ports
+        };
+        if !ports
+            .iter()
+            .any(|p| p.parse::<u16>().ok() == Some(endpoint.port))
+        {
+            warnings.push(Warning {
+                field: "bitcoinrpc.url",
+                message: format!(
+                    "port {} does not match bitcoin.conf rpcport {}",
+                    endpoint.port,
+                    ports.join(", ")
+                ),
+            });
+        }
+
+        // Without rpcbind, bitcoind only answers RPC on localhost
+        let binds = bitcoin_values("rpcbind");
+        let binds = if binds.is_empty() {
+            vec!["127.0.0.1", "::1"]
+        } else {
+            binds
+        };
+        if !binds
+            .iter()
+            .any(|bind| reaches(bind_host(bind), endpoint.port, &endpoint))
+        {
+            warnings.push(Warning {
+                field: "bitcoinrpc.url",
+                message: format!(
+                    "host {} is not one bitcoind binds RPC to ({})",
+                    endpoint.host,
+                    binds.join(", ")
+                ),
+            });
+        }
+    }
+
+    if let Some(user) = p2pool_value(ConfigSection::BitcoinRpc, "username") {
+        let rpcuser = bitcoin_values("rpcuser");
+        let prefix = format!("{user}:");
+        let has_rpcauth = bitcoin_values("rpcauth")
+            .iter()
+            .any(|auth| auth.starts_with(&prefix));
+        if rpcuser.contains(&user) {
+            let password = p2pool_value(ConfigSection::BitcoinRpc, "password").unwrap_or_default();
+            let rpcpassword = bitcoin_values("rpcpassword");
+            if !has_rpcauth && !rpcpassword.is_empty() && !rpcpassword.contains(&password) {
+                warnings.push(Warning {
+                    field: "bitcoinrpc.password",
+                    message: format!("does not match bitcoin.conf rpcpassword for {user}"),
+                });
+            }
+        } else if !has_rpcauth {
+            warnings.push(Warning {
+                field: "bitcoinrpc.username",
+                message: format!("bitcoin.conf has no rpcuser or rpcauth entry for {user}"),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Whether a socket bound to `host` accepts connections made to `target`.
+fn reaches(host: &str, port: u16, target: &Endpoint) -> bool {
+    let loopback = |h: &str| matches!(h, "127.0.0.1" | "localhost" | "::1");
+    port == target.port
+        && (host == target.host
+            || matches!(host, "0.0.0.0" | "::" | "*")
+            || (loopback(host) && loopback(&target.host)))
+}
+
+/// Host part of an `rpcbind` value, which may carry a port and brackets.
+fn bind_host(bind: &str) -> &str {
+    if let Some(rest) = bind.strip_prefix('[') {
+        return rest.split(']').next().unwrap_or_default();
+    }
+    match bind.split_once(':') {
+        // A single colon separates an IPv4 address or name from its port
+        Some((host, port)) if !port.contains(':') => host,
+        _ => bind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn btc(key: &str, value: &str) -> BitcoinEntry {
+        BitcoinEntry::new(key, value)
+    }
+
+    fn p2pool(section: ConfigSection, key: &str, value: &str) -> P2PoolEntry {
+        P2PoolEntry::required(section, key, value.to_string(), "", "String")
+    }
+
+    /// A p2pool config that a stock regtest bitcoin.conf would satisfy.
+    fn p2pool_entries(zmq: &str) -> Vec<P2PoolEntry> {
+        vec![
+            p2pool(ConfigSection::Stratum, "network", "regtest"),
+            p2pool(ConfigSection::Stratum, "zmqpubhashblock", zmq),
+            p2pool(ConfigSection::BitcoinRpc, "url", "http://127.0.0.1:18443"),
+            p2pool(ConfigSection::BitcoinRpc, "username", "p2pool"),
+            p2pool(ConfigSection::BitcoinRpc, "password", "secret"),
+        ]
+    }
+
+    fn bitcoin_entries(zmq: &str) -> Vec<BitcoinEntry> {
+        vec![
+            btc("zmqpubhashblock", zmq),
+            btc("rpcuser", "p2pool"),
+            btc("rpcpassword", "secret"),
+        ]
+    }
+
+    #[test]
+    fn matching_zmq_endpoints_raise_no_warning() {
+        let warnings = cross_validate(
+            &bitcoin_entries("tcp://127.0.0.1:28332"),
+            &p2pool_entries("tcp://127.0.0.1:28332"),
+        );
+        assert_eq!(warnings, vec![]);
+
+        // bitcoind publishing on every interface also reaches p2pool
+        let warnings = cross_validate(
+            &bitcoin_entries("tcp://0.0.0.0:28332"),
+            &p2pool_entries("tcp://127.0.0.1:28332"),
+        );
+        assert_eq!(warnings, vec![]);
+    }
+
+    #[test]
+    fn mismatched_zmq_port_is_reported() {
+        let warnings = cross_validate(
+            &bitcoin_entries("tcp://127.0.0.1:28333"),
+            &p2pool_entries("tcp://127.0.0.1:28332"),
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "stratum.zmqpubhashblock");
+        assert!(warnings[0].message.contains("tcp://127.0.0.1:28333"));
+    }
+
+    #[test]
+    fn missing_bitcoin_zmq_is_reported() {
+        let mut bitcoin = bitcoin_entries("tcp://127.0.0.1:28332");
+        bitcoin[0].enabled = false;
+        let warnings = cross_validate(&bitcoin, &p2pool_entries("tcp://127.0.0.1:28332"));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("does not set zmqpubhashblock"));
+    }
+
+    #[test]
+    fn zmq_in_another_network_section_does_not_count() {
+        let mut bitcoin = bitcoin_entries("tcp://127.0.0.1:28332");
+        bitcoin[0].section = Some("signet".to_string());
+        let warnings = cross_validate(&bitcoin, &p2pool_entries("tcp://127.0.0.1:28332"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "stratum.zmqpubhashblock");
+    }
+
+    #[test]
+    fn rpc_port_and_credentials_are_compared() {
+        let zmq = "tcp://127.0.0.1:28332";
+        let mut bitcoin = bitcoin_entries(zmq);
+        bitcoin.push(btc("rpcport", "18444"));
+        bitcoin[2].value = "other".to_string();
+
+        let warnings = cross_validate(&bitcoin, &p2pool_entries(zmq));
+        let fields: Vec<_> = warnings.iter().map(|w| w.field).collect();
+        assert_eq!(fields, ["bitcoinrpc.url", "bitcoinrpc.password"]);
+        assert!(!warnings[1].message.contains("other"));
+    }
+
+    #[test]
+    fn rpcauth_for_the_user_satisfies_credentials() {
+        let zmq = "tcp://127.0.0.1:28332";
+        let bitcoin = vec![
+            btc("zmqpubhashblock", zmq),
+            btc("rpcauth", "p2pool:cb77f0957de88ff388cf817ddbc7273$abc"),
+        ];
+        assert_eq!(cross_validate(&bitcoin, &p2pool_entries(zmq)), vec![]);
+
+        let bitcoin = vec![btc("zmqpubhashblock", zmq)];
+        let warnings = cross_validate(&bitcoin, &p2pool_entries(zmq));
+        assert_eq!(warnings[0].field, "bitcoinrpc.username");
+    }
+
+    #[test]
+    fn rpc_host_must_be_bound() {
+        let zmq = "tcp://127.0.0.1:28332";
+        let mut entries = p2pool_entries(zmq);
+        entries[2].value = "http://10.0.0.5:18443".to_string();
+        let warnings = cross_validate(&bitcoin_entries(zmq), &entries);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("10.0.0.5"));
+
+        let mut bitcoin = bitcoin_entries(zmq);
+        bitcoin.push(btc("rpcbind", "10.0.0.5:18443"));
+        assert_eq!(cross_validate(&bitcoin, &entries), vec![]);
+    }
+}
@@ -0,0 +1,303 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Compares the entries PDM holds with the ones on disk, for the diff shown
+//! before saving.
+
+use crate::bitcoin_config::ConfigEntry as BitcoinEntry;
+use crate::p2poolv2_config::P2PoolConfigEntry as P2PoolEntry;
+use std::fmt;
+
+/// How an entry differs between the file on disk and the edited entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Set only in the edited entries.
+    Added,
+    /// Set only on disk.
+    Removed,
+    /// Set in both with different values.
+    Changed,
+}
+
+impl DiffKind {
+    /// The `+`, `-` or `~` marker leading the entry's diff line.
+    #[must_use]
+    pub fn marker(self) -> char {
+        match self {
+            DiffKind::Added => '+',
+            DiffKind::Removed => '-',
+            DiffKind::Changed => '~',
+        }
+    }
+}
+
+/// One entry that differs, named by its section and key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    /// `key` outside any section, otherwise `section.key`.
+    pub name: String,
+    /// The value on disk, `None` for added entries.
+    pub old: Option<String>,
+    /// The edited value, `None` for removed entries.
+    pub new: Option<String>,
+}
+
+impl fmt::Display for DiffLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let marker = self.kind.marker();
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, "{marker} {} = {old} → {new}", self.name),
+            (Some(value), None) | (None, Some(value)) => {
+                write!(f, "{marker} {} = {value}", self.name)
+            }
+            (None, None) => write!(f, "{marker} {}", self.name),
+        }
+    }
+}
+
+/// Lists the `(name, value)` pairs in `new` that are missing from or differ
+/// in `old`, and those in `old` missing from `new`. Changed and removed
+/// entries come first in `old`'s order, followed by added ones in `new`'s.
+/// Names are matched exactly, so they should carry the section.
+///
+/// A name may repeat, as bitcoin.conf keys such as `addnode` do. Its values
+/// are compared as a multiset: a value present on both sides is unchanged
+/// wherever it sits, and the values left over are paired up in order.
+#[must_use]
+pub fn diff_entries(old: &[(String, String)], new: &[(String, String)]) -> Vec<DiffLine> {
+    let mut old_open = vec![true; old.len()];
+    let mut new_open = vec![true; new.len()];
+    for (i, pair) in old.iter().enumerate() {
+        if let Some(j) = (0..new.len()).find(|&j| new_open[j] && new[j] == *pair) {
+            old_open[i] = false;
+            new_open[j] = false;
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (name, value) in old
+        .iter()
+        .zip(&old_open)
+        .filter_map(|(p, &o)| o.then_some(p))
+    {
+        match (0..new.len()).find(|&j| new_open[j] && new[j].0 == *name) {
+            Some(j) => {
+                new_open[j] = false;
+                lines.push(DiffLine {
+                    kind: DiffKind::Changed,
+                    name: name.clone(),
+                    old: Some(value.clone()),
+                    new: Some(new[j].1.clone()),
+                });
+            }
+            None => lines.push(DiffLine {
+                kind: DiffKind::Removed,
+                name: name.clone(),
+                old: Some(value.clone()),
+                new: None,
+            }),
+        }
+    }
+    for (name, value) in new
+        .iter()
+        .zip(&new_open)
+        .filter_map(|(p, &o)| o.then_some(p))
+    {
+        lines.push(DiffLine {
+            kind: DiffKind::Added,
+            name: name.clone(),
+            old: None,
+            new: Some(value.clone()),
+        });
+    }
+    lines
+}
+
+/// What changed between the bitcoin entries `on_disk` and the `edited` ones.
+/// Only enabled entries count, named `key` or `section.key`, and the RPC
+/// password is shown as `*****` like in the entry list.
+#[must_use]
+pub fn bitcoin_diff(on_disk: &[BitcoinEntry], edited: &[BitcoinEntry]) -> Vec<DiffLine> {
+    let pairs = |entries: &[BitcoinEntry]| -> Vec<(String, String)> {
+        entries
+            .iter()
+            .filter(|e| e.enabled)
+            .map(|e| {
+                let name = match &e.section {
+                    Some(section) => format!("{section}.{}", e.key),
+                    None => e.key.clone(),
+                };
+                (name, e.value.clone())
+            })
+            .collect()
+    };
+    let secret = |name: &str| {
+        on_disk
+            .iter()
+            .chain(edited)
+            .any(|e| e.is_secret() && name.rsplit('.').next() == Some(e.key.as_str()))
+    };
+
+    let mut lines = diff_entries(&pairs(on_disk), &pairs(edited));
+    for line in &mut lines {
+        if secret(&line.name) {
+            for value in [&mut line.old, &mut line.new].into_iter().flatten() {
+                *value = "*****".to_string();
+            }
+        }
+    }
+    lines
+}
+
+/// What changed between the p2pool entries `on_disk` and the `edited` ones,
+/// named `section.key`. Only enabled entries count, and sensitive values are
+/// shown as `*****` so a changed secret is listed without being revealed.
+#[must_use]
+pub fn p2pool_diff(on_disk: &[P2PoolEntry], edited: &[P2PoolEntry]) -> Vec<DiffLine> {
+    let pairs = |entries: &[P2PoolEntry]| -> Vec<(String, String)> {
+        entries
+            .iter()
+            .filter(|e| e.enabled)
+            .map(|e| (e.field(), e.value.clone()))
+            .collect()
+    };
+    let sensitive = |name: &str| {
+        on_disk
+            .iter()
+            .chain(edited)
+            .any(|e| e.schema.sensitive && e.field() == name)
+    };
+
+    let mut lines = diff_entries(&pairs(on_disk), &pairs(edited));
+    for line in &mut lines {
+        if sensitive(&line.name) {
+            for value in [&mut line.old, &mut line.new].into_iter().flatten() {
+                *value = "*****".to_string();
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(n, v)| ((*n).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn changed_value_is_marked_with_both_values() {
+        let old = pairs(&[("server", "1"), ("rpcport", "8332")]);
+        let new = pairs(&[("server", "1"), ("rpcport", "18332")]);
+
+        let diff = diff_entries(&old, &new);
+        assert_eq!(
+            diff,
+            vec![DiffLine {
+                kind: DiffKind::Changed,
+                name: "rpcport".to_string(),
+                old: Some("8332".to_string()),
+                new: Some("18332".to_string()),
+            }]
+        );
+        assert_eq!(diff[0].to_string(), "~ rpcport = 8332 → 18332");
+    }
+
+    #[test]
+    fn added_and_removed_entries_follow_changes() {
+        let old = pairs(&[("txindex", "1"), ("test.rpcport", "18332")]);
+        let new = pairs(&[("test.rpcport", "18443"), ("main.rpcport", "8332")]);
+
+        let lines: Vec<String> = diff_entries(&old, &new)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "- txindex = 1",
+                "~ test.rpcport = 18332 → 18443",
+                "+ main.rpcport = 8332",
+            ]
+        );
+    }
+
+    #[test]
+    fn p2pool_diff_masks_changed_secrets() {
+        let secret = |value: &str| {
+            let mut entry = P2PoolEntry::custom("bitcoinrpc", "password", value);
+            entry.schema.sensitive = true;
+            entry
+        };
+        let on_disk = [
+            secret("old-secret"),
+            P2PoolEntry::custom("stratum", "port", "3333"),
+        ];
+        let edited = [
+            secret("new-secret"),
+            P2PoolEntry::custom("stratum", "port", "3334"),
+        ];
+
+        let lines: Vec<String> = p2pool_diff(&on_disk, &edited)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "~ bitcoinrpc.password = ***** → *****",
+                "~ stratum.port = 3333 → 3334",
+            ]
+        );
+    }
+
+    #[test]
+    fn repeated_keys_are_compared_as_a_multiset() {
+        let old = pairs(&[("addnode", "a"), ("addnode", "b"), ("addnode", "c")]);
+
+        // Reordering the values is not a change
+        let reordered = pairs(&[("addnode", "c"), ("addnode", "a"), ("addnode", "b")]);
+        assert!(diff_entries(&old, &reordered).is_empty());
+
+        // Removing the first value leaves the others unchanged
+        let lines: Vec<String> = diff_entries(&old, &pairs(&[("addnode", "b"), ("addnode", "c")]))
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(lines, vec!["- addnode = a"]);
+
+        let lines: Vec<String> = diff_entries(
+            &old,
+            &pairs(&[("addnode", "a"), ("addnode", "x"), ("addnode", "c")]),
+        )
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        assert_eq!(lines, vec!["~ addnode = b → x"]);
+    }
+
+    #[test]
+    fn bitcoin_diff_masks_the_rpc_password() {
+        let on_disk = [BitcoinEntry::new("rpcpassword", "old-secret")];
+        let edited = [BitcoinEntry::new("rpcpassword", "new-secret")];
+
+        let lines: Vec<String> = bitcoin_diff(&on_disk, &edited)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(lines, vec!["~ rpcpassword = ***** → *****"]);
+    }
+
+    #[test]
+    fn identical_entries_have_no_diff() {
+        let entries = pairs(&[("server", "1")]);
+        assert!(diff_entries(&entries, &entries).is_empty());
+    }
+}
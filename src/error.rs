@@ -0,0 +1,181 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::p2poolv2_config::{MAX_BASIS_POINTS, NETWORK_NAMES};
+use std::fmt;
+
+/// Why a p2pool config failed to load.
+///
+/// Each variant names the offending field so the UI and downstream tools
+/// can react to it without parsing the message. `Display` gives the same
+/// one-line text the TUI shows.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not valid TOML, or JSON or YAML for those extensions.
+    Toml(String),
+    /// The TOML does not have the shape of a p2pool config.
+    Deserialize(String),
+    /// [`ConfigError::Deserialize`] traced back to the value that caused it.
+    /// `line` and `column` are 1-based.
+    InvalidValue {
+        key: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// A `port` key is not an integer in `1..=65535`.
+    InvalidPort { field: String, value: String },
+    /// `stratum.network` is not a network name PDM knows.
+    UnknownNetwork(String),
+    /// An address is malformed or belongs to another network.
+    InvalidAddress {
+        field: &'static str,
+        value: String,
+        reason: String,
+    },
+    /// `donation` or `fee` is above 100%.
+    BasisPointsOutOfRange { field: &'static str, value: u16 },
+    /// `donation` and `fee` together are above 100%.
+    PayoutTooHigh { total: u32 },
+    /// A non-zero `donation` or `fee` has no address to pay.
+    MissingPayoutAddress { field: &'static str, value: u16 },
+    /// A difficulty is below the one that should bound it from below.
+    DifficultyOrder {
+        field: &'static str,
+        value: u64,
+        bound_field: &'static str,
+        bound: u64,
+    },
+    /// Two listening endpoints share a port on overlapping hosts.
+    PortConflict {
+        first: &'static str,
+        second: &'static str,
+        port: u16,
+        host: String,
+    },
+    /// A URL or multiaddr is malformed or uses the wrong scheme.
+    InvalidEndpoint { field: &'static str, reason: String },
+    /// Only one of `api.auth_user` and `api.auth_token` is set.
+    IncompleteApiAuth,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "{e}"),
+            ConfigError::Toml(msg) | ConfigError::Deserialize(msg) => write!(f, "{msg}"),
+            ConfigError::InvalidValue {
+                key,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "invalid value for {key} at line {line}, column {column}: {message}"
+            ),
+            ConfigError::InvalidPort { field, value } => {
+                write!(f, "{field} = {value} is not a valid port (1-65535)")
+            }
+            ConfigError::UnknownNetwork(name) => {
+                write!(
+                    f,
+                    "unknown network '{name}'; expected one of {NETWORK_NAMES}"
+                )
+            }
+            ConfigError::InvalidAddress { field, reason, .. } => {
+                write!(f, "Invalid {field}: {reason}")
+            }
+            ConfigError::BasisPointsOutOfRange { field, value } => write!(
+                f,
+                "{field} = {value} exceeds {MAX_BASIS_POINTS} basis points (100%)"
+            ),
+            ConfigError::PayoutTooHigh { total } => write!(
+                f,
+                "donation + fee = {total} exceeds {MAX_BASIS_POINTS} basis points (100%)"
+            ),
+            ConfigError::MissingPayoutAddress { field, value } => {
+                write!(f, "{field} = {value} needs a {field}_address")
+            }
+            ConfigError::DifficultyOrder {
+                field,
+                value,
+                bound_field,
+                bound,
+            } => write!(f, "{field} ({value}) is below {bound_field} ({bound})"),
+            ConfigError::PortConflict {
+                first,
+                second,
+                port,
+                host,
+            } => write!(f, "{first} and {second} both use port {port} on {host}"),
+            ConfigError::InvalidEndpoint { field, reason } => {
+                write!(f, "Invalid {field}: {reason}")
+            }
+            ConfigError::IncompleteApiAuth => {
+                write!(f, "api auth requires both auth_user and auth_token")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_names_the_field() {
+        let err = ConfigError::InvalidPort {
+            field: "stratum.port".to_string(),
+            value: "0".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "stratum.port = 0 is not a valid port (1-65535)"
+        );
+
+        let err = ConfigError::MissingPayoutAddress {
+            field: "fee",
+            value: 100,
+        };
+        assert_eq!(err.to_string(), "fee = 100 needs a fee_address");
+
+        let err = ConfigError::DifficultyOrder {
+            field: "start_difficulty",
+            value: 50,
+            bound_field: "minimum_difficulty",
+            bound: 100,
+        };
+        assert_eq!(
+            err.to_string(),
+            "start_difficulty (50) is below minimum_difficulty (100)"
+        );
+    }
+
+    #[test]
+    fn converts_to_anyhow_with_the_same_message() {
+        let err = anyhow::Error::from(ConfigError::PayoutTooHigh { total: 11_000 });
+        assert_eq!(
+            err.to_string(),
+            "donation + fee = 11000 exceeds 10000 basis points (100%)"
+        );
+        assert!(err.downcast_ref::<ConfigError>().is_some());
+    }
+}
@@ -2,9 +2,90 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+//! Config parsers for bitcoind and p2pool, plus the PDM terminal UI.
+//!
+//! The parsers are always built. Everything that draws to a terminal sits
+//! behind the default `tui` feature, so depending on this crate with
+//! `default-features = false` pulls in neither `ratatui` nor `crossterm`.
+
+#[cfg(feature = "tui")]
 pub mod app;
 pub mod bitcoin_config;
+#[cfg(feature = "tui")]
 pub mod components;
+pub mod config_kind;
+pub mod cross_validate;
+pub mod diff;
+pub mod error;
+#[cfg(feature = "tui")]
+pub mod loader;
 pub mod p2poolv2_config;
+#[cfg(feature = "tui")]
 pub mod settings;
+#[cfg(feature = "tui")]
+pub mod theme;
+#[cfg(feature = "tui")]
 pub mod ui;
+pub mod util;
+pub mod warning;
+#[cfg(feature = "tui")]
+pub mod watcher;
+
+pub use bitcoin_config::ConfigEntry as BitcoinEntry;
+pub use p2poolv2_config::P2PoolConfigEntry as P2PoolEntry;
+
+use std::path::Path;
+
+/// Parses a bitcoin.conf into one entry per known option, followed by any
+/// keys the schema doesn't know. Options absent from the file come back
+/// disabled with their default value.
+///
+/// # Examples
+///
+/// ```
+/// let dir = tempfile::tempdir()?;
+/// let path = dir.path().join("bitcoin.conf");
+/// std::fs::write(&path, "server=1\n[test]\nrpcport=18332\n")?;
+///
+/// let entries = pdm::parse_bitcoin(&path)?;
+/// let rpcport = entries
+///     .iter()
+///     .find(|e| e.key == "rpcport" && e.enabled)
+///     .unwrap();
+/// assert_eq!(rpcport.section.as_deref(), Some("test"));
+/// assert_eq!(rpcport.value, "18332");
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+/// Returns an error if the file cannot be read or is not valid INI.
+pub fn parse_bitcoin(path: &Path) -> anyhow::Result<Vec<BitcoinEntry>> {
+    bitcoin_config::parse_config(path)
+}
+
+/// Loads and validates a p2pool TOML config and flattens it into one entry
+/// per field, each tagged with whether its value came from the file, an
+/// environment variable or a default.
+///
+/// # Examples
+///
+/// ```no_run
+/// let entries = pdm::parse_p2pool("p2pool.toml".as_ref())?;
+/// for entry in entries.iter().filter(|e| e.enabled) {
+///     println!("{}.{} = {}", entry.section, entry.key, entry.value);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+/// Returns an error if the file cannot be read, does not deserialize, or
+/// fails validation (bad ports, addresses, endpoints and so on).
+pub fn parse_p2pool(path: &Path) -> anyhow::Result<Vec<P2PoolEntry>> {
+    let cfg = p2poolv2_config::load_config(&path.to_string_lossy())?;
+    let mut entries = p2poolv2_config::flatten_config(&cfg);
+    let origins = p2poolv2_config::read_origins(path, &cfg);
+    for (entry, origin) in entries.iter_mut().zip(origins) {
+        entry.origin = origin;
+    }
+    Ok(entries)
+}
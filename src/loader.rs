@@ -0,0 +1,71 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::bitcoin_config::{ConfigEntry, parse_config_with_includes};
+use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, TryRecvError, channel};
+use std::time::{Duration, Instant};
+
+/// Frames of the spinner shown while a file is parsed.
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// How long each spinner frame stays on screen.
+const SPINNER_FRAME: Duration = Duration::from_millis(100);
+
+/// A bitcoin.conf being parsed on a background thread, so a large file or a
+/// slow filesystem never freezes the screen. The event loop polls it with
+/// [`BitcoinLoad::try_finish`] on every tick.
+pub struct BitcoinLoad {
+    pub path: PathBuf,
+    started: Instant,
+    result: Receiver<Result<Vec<ConfigEntry>>>,
+}
+
+impl BitcoinLoad {
+    /// Starts parsing `path` on a new thread.
+    #[must_use]
+    pub fn spawn(path: PathBuf) -> BitcoinLoad {
+        let (tx, result) = channel();
+        let parse_path = path.clone();
+        std::thread::spawn(move || {
+            // The receiver is gone only if the load was abandoned
+            let _ = tx.send(parse_config_with_includes(&parse_path));
+        });
+        BitcoinLoad {
+            path,
+            started: Instant::now(),
+            result,
+        }
+    }
+
+    /// The parsed entries once the thread is done, `None` while it is still
+    /// running. A thread that died without a result reports an error.
+    pub fn try_finish(&self) -> Option<Result<Vec<ConfigEntry>>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(Err(anyhow::anyhow!("the parser stopped unexpectedly")))
+            }
+        }
+    }
+
+    /// Blocks until the thread is done and returns its result.
+    ///
+    /// # Errors
+    /// Returns the parse error, or an error if the thread died without one.
+    pub fn wait(self) -> Result<Vec<ConfigEntry>> {
+        self.result
+            .recv()
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("the parser stopped unexpectedly")))
+    }
+
+    /// The spinner frame for the time elapsed since the load started.
+    #[must_use]
+    pub fn spinner(&self) -> char {
+        let frame = self.started.elapsed().as_millis() / SPINNER_FRAME.as_millis();
+        SPINNER[usize::try_from(frame).unwrap_or_default() % SPINNER.len()]
+    }
+}
@@ -4,45 +4,141 @@
 
 use p2poolv2_config::Config as P2PoolConfig;
 use pdm::app::{
-    App, AppAction, CurrentScreen, ExplorerTrigger, MAX_BITCOIN_STATUS_TAB, MAX_SIDEBAR_INDEX,
+    App, AppAction, CurrentScreen, Edit, EditHistory, ExplorerTrigger, MAX_BITCOIN_STATUS_TAB,
+    MAX_SIDEBAR_INDEX, P2PoolEdit, SIDEBAR_ITEMS, SaveTarget,
 };
 use pdm::bitcoin_config::{
-    parse_config as parse_bitcoin_config, save_config as save_bitcoin_config,
+    ConfigEntry as BitcoinEntry, PASSWORD_FILE_KEY, config_files,
+    flag_conflicts as flag_bitcoin_conflicts, generate_rpcauth,
+    parse_config_with_includes as parse_bitcoin_config, validate_new_key,
+    validate_value as validate_bitcoin_value, write_config as save_bitcoin_config,
 };
+use pdm::components::file_explorer::SelectMode;
 use pdm::components::settings_view::{FIELDS, FieldKind};
-use pdm::p2poolv2_config::{apply_edit as apply_p2pool_edit, flatten_config};
-use pdm::settings::{load_settings, save_settings};
+use pdm::config_kind::{ConfigKind, detect_config_kind};
+use pdm::diff::{DiffLine, bitcoin_diff, p2pool_diff};
+use pdm::loader::BitcoinLoad;
+use pdm::p2poolv2_config::{
+    P2PoolConfigEntry, apply_edit as apply_p2pool_edit,
+    check_save_format as check_p2pool_save_format, flatten_config,
+    load_config as load_p2pool_config, read_origins as read_p2pool_origins,
+    read_unknown_keys as read_p2pool_unknown_keys, store_path_warnings, to_toml_string,
+    validate_config as validate_p2pool_config, write_config_with as save_p2pool_config,
+};
+use pdm::settings::{
+    load_bookmarks, load_last_dir, load_settings, load_theme_overrides, save_bookmarks,
+    save_last_dir, save_settings,
+};
 use pdm::ui;
+use pdm::util::backup_file;
+use pdm::watcher::ConfigWatcher;
 use std::ops::ControlFlow;
+use std::time::Duration;
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
+use ratatui::layout::{Margin, Position, Rect};
 use ratatui::{Terminal, backend::Backend, backend::CrosstermBackend};
 use std::io;
 
 fn main() -> Result<()> {
+    // `--json`, `--env`, `--check` and `--init`, each taking a path, run
+    // without the TUI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(path) = flag_value(&args, "--init")? {
+        init_config(&path, args.iter().any(|a| a == "--force"))?;
+        println!("{}: written", path.display());
+        return Ok(());
+    }
+    let include_secrets = args.iter().any(|a| a == "--include-secrets");
+    if let Some(path) = flag_value(&args, "--json")? {
+        println!("{:#}", export_json(&path, include_secrets)?);
+        return Ok(());
+    }
+    if let Some(path) = flag_value(&args, "--env")? {
+        print!("{}", export_env(&path, include_secrets)?);
+        return Ok(());
+    }
+    if let Some(path) = flag_value(&args, "--check")? {
+        let problems = check_config(&path, args.iter().any(|a| a == "--check-paths"))?;
+        for problem in &problems {
+            eprintln!("{}: {problem}", path.display());
+        }
+        if !problems.is_empty() {
+            std::process::exit(1);
+        }
+        println!("{}: ok", path.display());
+        return Ok(());
+    }
+
+    // `--bitcoin <path>` and `--p2pool <path>` preload configs; a bad path
+    // must be reported before the terminal is taken over
+    let preload = flag_value(&args, "--bitcoin")
+        .and_then(|bitcoin| App::with_args(bitcoin, flag_value(&args, "--p2pool")?));
+    let mut app = match preload {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("pdm: {e:#}");
+            std::process::exit(1);
+        }
+    };
+
     // Setup Terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Run App
-    let mut app = App::new();
     app.settings = load_settings();
+    app.load_state();
+    let (overrides, warnings) = load_theme_overrides();
+    app.theme = overrides.apply(app.theme);
+    app.theme_overrides = overrides;
+    if !warnings.is_empty() {
+        app.notice = Some(format!(
+            "Some colors in theme.toml were not applied:\n\n{}",
+            warnings.join("\n")
+        ));
+    }
+    if let Some(bookmarks) = load_bookmarks() {
+        app.explorer.bookmarks = bookmarks;
+    }
+    if let Some(dir) = load_last_dir() {
+        app.explorer.current_dir = dir;
+        app.explorer.load_directory();
+    }
+    let start_dir = app.explorer.current_dir.clone();
     bootstrap_from_settings(&mut app);
-    let res = run_app(&mut terminal, &mut app);
+    let res = run_app(&mut terminal, &mut app, poll_event);
+    if app.explorer.current_dir != start_dir {
+        // Losing the last directory only costs a little navigation next time
+        let _ = save_last_dir(&app.explorer.current_dir);
+    }
+    // Reported once the terminal is back; a lost session only means
+    // starting fresh
+    let state_saved = app.save_state();
 
     // Restore Terminal
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
     terminal.show_cursor()?;
 
+    if let Err(err) = state_saved {
+        eprintln!("Warning: could not save session state: {err:#}");
+    }
     if let Err(err) = res {
         eprintln!("Error: {err:#}");
     }
@@ -50,6 +146,156 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Returns the path following `flag`, if the flag is present.
+///
+/// # Errors
+/// Returns an error if the flag is the last argument.
+fn flag_value(args: &[String], flag: &str) -> Result<Option<std::path::PathBuf>> {
+    let Some(pos) = args.iter().position(|a| a == flag) else {
+        return Ok(None);
+    };
+    args.get(pos + 1)
+        .map(|p| Some(std::path::PathBuf::from(p)))
+        .ok_or_else(|| anyhow::anyhow!("{flag} needs a config file path"))
+}
+
+/// Writes a starter config to `path`: a p2pool TOML when the name ends in
+/// `.toml`, a bitcoin.conf otherwise.
+///
+/// # Errors
+/// Returns an error if `path` exists and `force` is false, or if the file
+/// cannot be written.
+fn init_config(path: &std::path::Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists; pass --force to overwrite",
+            path.display()
+        );
+    }
+    let template = if path.extension().is_some_and(|ext| ext == "toml") {
+        pdm::p2poolv2_config::default_config_string()
+    } else {
+        pdm::bitcoin_config::default_config_string()
+    };
+    pdm::util::atomic_write(path, &template)
+}
+
+/// Lints the config at `path` and returns one line per problem; an empty
+/// list means the config is valid. With `check_paths`, a p2pool config's
+/// `store.path` is also checked against the filesystem.
+///
+/// # Errors
+/// Returns an error if the file does not exist or cannot be read.
+fn check_config(path: &std::path::Path, check_paths: bool) -> Result<Vec<String>> {
+    if !path.exists() {
+        anyhow::bail!("{} does not exist", path.display());
+    }
+    if detect_config_kind(path) == ConfigKind::P2Pool {
+        return Ok(match load_p2pool_config(&path.to_string_lossy()) {
+            Ok(cfg) if check_paths => store_path_warnings(
+                &flatten_config(&cfg),
+                &std::env::current_dir().unwrap_or_default(),
+            )
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+            Ok(_) => Vec::new(),
+            Err(e) => vec![e.to_string()],
+        });
+    }
+
+    let entries = parse_bitcoin_config(path)?;
+    if !entries.iter().any(|e| e.enabled && e.schema.is_some()) {
+        return Ok(vec!["does not appear to be a Bitcoin config".to_string()]);
+    }
+    Ok(bitcoin_problems(&entries))
+}
+
+/// One line per enabled entry that fails validation, such as
+/// `[test] rpcport: 0 is not a valid port (1-65535)`. Values are checked
+/// again, so an entry changed since it was last validated still counts.
+fn bitcoin_problems(entries: &[BitcoinEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .filter(|e| e.enabled)
+        .filter_map(|e| {
+            let reason = e
+                .validation_error
+                .clone()
+                .or_else(|| validate_bitcoin_value(e, &e.value).err())?;
+            Some(match &e.section {
+                Some(section) => format!("[{section}] {}: {reason}", e.key),
+                None => format!("{}: {reason}", e.key),
+            })
+        })
+        .collect()
+}
+
+/// Errors that keep the target from being saved: invalid bitcoin entries,
+/// or whatever makes the p2pool config fail [`validate_p2pool_config`].
+/// Warnings never block a save.
+fn save_blockers(app: &App, target: SaveTarget) -> Vec<String> {
+    match target {
+        SaveTarget::BitcoinConfig => bitcoin_problems(&app.bitcoin_data),
+        SaveTarget::P2PoolConfig => app
+            .p2pool_config
+            .as_ref()
+            .and_then(|cfg| validate_p2pool_config(cfg).err())
+            .map(|e| vec![e.to_string()])
+            .unwrap_or_default(),
+    }
+}
+
+/// Parses the config at `path` and exports it as JSON, reading it as a
+/// p2pool config when [`detect_config_kind`] says so and as bitcoin.conf
+/// otherwise. Passwords are masked unless `include_secrets` is set.
+fn export_json(path: &std::path::Path, include_secrets: bool) -> Result<serde_json::Value> {
+    if detect_config_kind(path) == ConfigKind::P2Pool {
+        let cfg = load_p2pool_config(&path.to_string_lossy())?;
+        Ok(pdm::p2poolv2_config::config_to_json(
+            &flatten_config(&cfg),
+            include_secrets,
+        ))
+    } else {
+        if !path.exists() {
+            anyhow::bail!("{} does not exist", path.display());
+        }
+        let entries = parse_bitcoin_config(path)?;
+        Ok(pdm::bitcoin_config::config_to_json(
+            &entries,
+            include_secrets,
+        ))
+    }
+}
+
+/// Loads the p2pool config at `path` and renders it as `NAME=value` lines,
+/// usable as a systemd `EnvironmentFile` or sourced by a shell. Values with
+/// characters a shell would interpret are single-quoted.
+///
+/// # Errors
+/// Returns an error if `path` is not a p2pool config or fails to load.
+fn export_env(path: &std::path::Path, include_secrets: bool) -> Result<String> {
+    if detect_config_kind(path) != ConfigKind::P2Pool {
+        anyhow::bail!("{} is not a p2pool config", path.display());
+    }
+    let cfg = load_p2pool_config(&path.to_string_lossy())?;
+    let vars = pdm::p2poolv2_config::p2pool_to_env(&flatten_config(&cfg), include_secrets);
+    Ok(vars
+        .into_iter()
+        .map(|(name, value)| {
+            let plain = !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "/.:,_-+@%".contains(c));
+            if plain {
+                format!("{name}={value}\n")
+            } else {
+                format!("{name}='{}'\n", value.replace('\'', r"'\''"))
+            }
+        })
+        .collect())
+}
+
 fn sidebar_nav(key: KeyCode, app: &mut App) -> AppAction {
     match key {
         KeyCode::Up if app.sidebar_index > 0 => {
@@ -60,39 +306,384 @@ fn sidebar_nav(key: KeyCode, app: &mut App) -> AppAction {
             app.sidebar_index += 1;
             AppAction::ToggleMenu
         }
+        KeyCode::Backspace | KeyCode::Esc => AppAction::GoBack,
         _ => AppAction::None,
     }
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
+/// Translates vim-style letters into the arrow keys every screen already
+/// handles: `j`/`k` move down/up and `h`/`l` left/right. In the explorer
+/// `h` goes to the parent directory and `l` descends like Enter. Callers
+/// must skip this while a text field is capturing letters.
+fn vim_key(key: KeyEvent, screen: CurrentScreen) -> KeyEvent {
+    if !key.modifiers.is_empty() && key.modifiers != KeyModifiers::SHIFT {
+        return key;
+    }
+    let code = match (key.code, screen) {
+        (KeyCode::Char('j'), _) => KeyCode::Down,
+        (KeyCode::Char('k'), _) => KeyCode::Up,
+        (KeyCode::Char('h'), CurrentScreen::FileExplorer) => KeyCode::Backspace,
+        (KeyCode::Char('l'), CurrentScreen::FileExplorer) => KeyCode::Enter,
+        (KeyCode::Char('h'), _) => KeyCode::Left,
+        (KeyCode::Char('l'), _) => KeyCode::Right,
+        _ => return key,
+    };
+    KeyEvent::new(code, KeyModifiers::empty())
+}
+
+/// True while a text field has focus and letters must reach it unchanged.
+fn text_input_active(app: &App) -> bool {
+    (app.current_screen == CurrentScreen::BitcoinConfig
+        && !app.bitcoin_config_view.sidebar_focused
+        && (app.bitcoin_config_view.editing
+            || app.bitcoin_config_view.filter.typing
+            || app.bitcoin_config_view.rpcauth_input.is_some()
+            || app.bitcoin_config_view.add_input.is_some()))
+        || (app.current_screen == CurrentScreen::P2PoolConfig
+            && !app.p2pool_config_view.sidebar_focused
+            && (app.p2pool_config_view.editing
+                || app.p2pool_config_view.filter.typing
+                || app.p2pool_config_view.add_input.is_some()))
+        || (app.current_screen == CurrentScreen::FileExplorer
+            && (app.explorer.new_dir_input.is_some() || app.explorer.search.is_some()))
+}
+
+/// What `o` does on the current screen: open the explorer for the config
+/// the screen edits, so the picked file is loaded back into it. A screen
+/// with unsaved edits refuses, as the new file would replace them. `None` on
+/// screens without a config file.
+fn open_file_action(app: &mut App) -> Option<AppAction> {
+    let (trigger, dirty) = match app.current_screen {
+        CurrentScreen::BitcoinConfig => (
+            ExplorerTrigger::BitcoinConfig,
+            app.bitcoin_config_view.dirty,
+        ),
+        CurrentScreen::P2PoolConfig => {
+            (ExplorerTrigger::P2PoolConfig, app.p2pool_config_view.dirty)
+        }
+        _ => return None,
+    };
+    if dirty {
+        app.notice = Some("Save or reload the current file before opening another.".to_string());
+        return Some(AppAction::None);
+    }
+    Some(AppAction::OpenExplorer(trigger))
+}
+
+/// True while a modal or prompt is waiting for a key.
+fn modal_open(app: &App) -> bool {
+    app.error.is_some()
+        || app.notice.is_some()
+        || app.diff.is_some()
+        || app.pending_save.is_some()
+        || app.pending_reload.is_some()
+        || app.current_screen == CurrentScreen::Exiting
+}
+
+/// Index of the item under (`column`, `row`) in a bordered list drawn in
+/// `area`, counted from the first visible item, for items `item_height`
+/// rows tall.
+fn list_row(area: Rect, column: u16, row: u16, item_height: u16) -> Option<usize> {
+    let inner = area.inner(Margin::new(1, 1));
+    inner
+        .contains(Position::new(column, row))
+        .then(|| usize::from((row - inner.y) / item_height.max(1)))
+}
+
+/// Index, counted from `offset`, of the entry drawn `row` rows below the top
+/// of a list whose visible entries are `heights` rows tall.
+fn entry_at_row(heights: &[u16], offset: usize, row: usize) -> Option<usize> {
+    let mut bottom = 0;
+    heights.iter().skip(offset).position(|&height| {
+        bottom += usize::from(height);
+        row < bottom
+    })
+}
+
+/// Row heights of the current config screen's visible entries and its first
+/// visible one, as last drawn.
+fn config_rows(app: &App) -> (&[u16], usize) {
+    if app.current_screen == CurrentScreen::BitcoinConfig {
+        let view = &app.bitcoin_config_view;
+        (&view.row_heights, view.list_state.offset())
+    } else {
+        let view = &app.p2pool_config_view;
+        (&view.row_heights, view.list_state.offset())
+    }
+}
+
+/// Translates a click or scroll into the action the matching key would
+/// trigger: the sidebar behaves like ↑↓ plus Enter, the entry list of a
+/// loaded config screen like moving the selection. Config rows are two
+/// lines tall, or more for an expanded entry.
+fn mouse_action(app: &mut App, mouse: MouseEvent) -> AppAction {
+    let on_sidebar = list_row(app.sidebar_area, mouse.column, mouse.row, 1)
+        .filter(|&index| index < SIDEBAR_ITEMS.len());
+    let config_open = match app.current_screen {
+        CurrentScreen::BitcoinConfig => app.bitcoin_conf_path.is_some(),
+        CurrentScreen::P2PoolConfig => app.p2pool_conf_path.is_some(),
+        _ => false,
+    };
+    let on_list = list_row(app.list_area, mouse.column, mouse.row, 1)
+        .filter(|_| config_open)
+        .and_then(|row| {
+            let (heights, offset) = config_rows(app);
+            entry_at_row(heights, offset, row)
+        });
+
+    let code = match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some(index) = on_sidebar {
+                app.sidebar_index = index;
+                return AppAction::ToggleMenu;
+            }
+            if let Some(row) = on_list {
+                select_list_row(app, row);
+            }
+            return AppAction::None;
+        }
+        MouseEventKind::ScrollDown => KeyCode::Down,
+        MouseEventKind::ScrollUp => KeyCode::Up,
+        _ => return AppAction::None,
+    };
+    if on_sidebar.is_some() {
+        return sidebar_nav(code, app);
+    }
+    if on_list.is_none() {
+        return AppAction::None;
+    }
+    let key = KeyEvent::new(code, KeyModifiers::empty());
+    if app.current_screen == CurrentScreen::BitcoinConfig {
+        app.bitcoin_config_view.sidebar_focused = false;
+        app.bitcoin_config_view.handle_input(key, &app.bitcoin_data)
+    } else {
+        let entries = app.p2pool_entries();
+        app.p2pool_config_view.sidebar_focused = false;
+        app.p2pool_config_view.handle_input(key, &entries)
+    }
+}
+
+/// Selects the config entry shown `row` items below the top of the list,
+/// and moves focus from the sidebar to the list.
+fn select_list_row(app: &mut App, row: usize) {
+    if app.current_screen == CurrentScreen::BitcoinConfig {
+        let view = &mut app.bitcoin_config_view;
+        let visible = view.filter.visible(app.bitcoin_data.len());
+        if let Some(&index) = visible.get(view.list_state.offset() + row) {
+            view.selected_index = index;
+            view.sidebar_focused = false;
+        }
+    } else {
+        let len = app.p2pool_entries().len();
+        let view = &mut app.p2pool_config_view;
+        if let Some(&index) = view.filter.visible(len).get(view.list_state.offset() + row) {
+            view.select(index);
+            view.sidebar_focused = false;
+        }
+    }
+}
+
+/// Number of entries PageUp/PageDown should skip for a list whose items are
+/// `item_height` rows tall, based on the last rendered viewport.
+fn page_size(app: &App, item_height: u16) -> usize {
+    usize::from((app.viewport_height / item_height.max(1)).max(1))
+}
+
+/// Number of config entries PageUp/PageDown should skip: as many as fit in
+/// the last rendered viewport from the first visible one, each counted at
+/// its drawn height. Before the first draw rows are taken as two lines tall.
+fn config_page_size(app: &App) -> usize {
+    let (heights, offset) = config_rows(app);
+    if heights.is_empty() {
+        return page_size(app, 2);
+    }
+    let mut used = 0u32;
+    let fitting = heights
+        .iter()
+        .skip(offset)
+        .take_while(|&&height| {
+            used += u32::from(height);
+            used <= u32::from(app.viewport_height)
+        })
+        .count();
+    fitting.max(1)
+}
+
+/// Redraws at the new size so the cached layout (`viewport_height`,
+/// `sidebar_area`, `list_area`) matches the terminal before the next click
+/// or page key is mapped through it. The draw also scrolls each list so its
+/// selection stays inside the resized viewport.
+fn handle_resize<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    width: u16,
+    height: u16,
+) -> Result<()>
+where
+    <B as Backend>::Error: Send + Sync + 'static,
+{
+    terminal.resize(Rect::new(0, 0, width, height))?;
+    terminal.draw(|f| ui::ui(f, app))?;
+    Ok(())
+}
+
+/// How long the event loop waits for input before redrawing on a tick.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Waits up to `timeout` for a terminal event; `None` is a tick.
+fn poll_event(timeout: Duration) -> Result<Option<Event>> {
+    if event::poll(timeout)? {
+        Ok(Some(event::read()?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Runs the UI until quit. `next_event` supplies input: [`poll_event`] in
+/// the real terminal, a scripted sequence in tests. The screen is redrawn
+/// after every event and every tick, so state that changes without input,
+/// such as a reloaded config, shows up within one `POLL_INTERVAL`.
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    mut next_event: impl FnMut(Duration) -> Result<Option<Event>>,
+) -> Result<()>
 where
     <B as Backend>::Error: Send + Sync + 'static,
 {
+    // Without a watcher PDM still works; it just won't notice outside edits
+    let mut watcher = ConfigWatcher::new().ok();
     loop {
+        poll_bitcoin_load(app);
         terminal.draw(|f| ui::ui(f, app))?;
 
-        if let Event::Key(key) = event::read()? {
+        if let Some(watcher) = watcher.as_mut() {
+            let paths: Vec<&std::path::Path> = [&app.bitcoin_conf_path, &app.p2pool_conf_path]
+                .into_iter()
+                .flatten()
+                .map(std::path::PathBuf::as_path)
+                .collect();
+            watcher.watch(&paths);
+            for path in watcher.changed() {
+                if let Some(target) = watched_target(app, &path) {
+                    handle_action(AppAction::ConfigChanged(target), app)?;
+                }
+            }
+        }
+
+        let Some(event) = next_event(POLL_INTERVAL)? else {
+            continue;
+        };
+        if let Event::Mouse(mouse) = event {
+            if !modal_open(app) && !text_input_active(app) {
+                let action = mouse_action(app, mouse);
+                if handle_action(action, app)?.is_break() {
+                    return Ok(());
+                }
+            }
+            continue;
+        }
+        if let Event::Resize(width, height) = event {
+            handle_resize(terminal, app, width, height)?;
+            continue;
+        }
+        if let Event::Key(key) = event {
             if key.kind != KeyEventKind::Press {
                 continue;
             }
 
             // Ctrl-C is always a hard exit.
             // 'q' is suppressed while a text-input field is active.
-            let text_input_active = (app.current_screen == CurrentScreen::BitcoinConfig
-                && !app.bitcoin_config_view.sidebar_focused
-                && app.bitcoin_config_view.editing)
-                || (app.current_screen == CurrentScreen::P2PoolConfig
-                    && !app.p2pool_config_view.sidebar_focused
-                    && app.p2pool_config_view.editing);
-
-            if (key.modifiers == KeyModifiers::CONTROL && key.code == KeyCode::Char('c'))
-                || (!text_input_active && key.code == KeyCode::Char('q'))
-            {
+            let text_input_active = text_input_active(app);
+
+            let key = if text_input_active {
+                key
+            } else {
+                vim_key(key, app.current_screen)
+            };
+
+            if key.modifiers == KeyModifiers::CONTROL && key.code == KeyCode::Char('c') {
                 return Ok(());
             }
 
+            // Any other key dismisses the error, notice or diff modal
+            if app.error.take().is_some()
+                || app.notice.take().is_some()
+                || app.diff.take().is_some()
+            {
+                continue;
+            }
+
+            if app.pending_reload.is_some() {
+                let action = confirm_reload_key(app, key);
+                handle_action(action, app)?;
+                continue;
+            }
+
+            if app.pending_save.is_some() {
+                let action = confirm_save_key(app, key);
+                if handle_action(action, app)?.is_break() {
+                    return Ok(());
+                }
+                continue;
+            }
+
+            if !text_input_active && key.code == KeyCode::Char('q') {
+                handle_action(AppAction::RequestQuit, app)?;
+                continue;
+            }
+
+            if !text_input_active && key.code == KeyCode::Char('t') {
+                app.theme = app.theme_overrides.apply(app.theme.next());
+                continue;
+            }
+
+            // Ctrl-S saves without the overwrite prompt, but only valid data
+            if !text_input_active
+                && key.modifiers == KeyModifiers::CONTROL
+                && key.code == KeyCode::Char('s')
+            {
+                let target = match app.current_screen {
+                    CurrentScreen::BitcoinConfig => Some(SaveTarget::BitcoinConfig),
+                    CurrentScreen::P2PoolConfig => Some(SaveTarget::P2PoolConfig),
+                    _ => None,
+                };
+                if let Some(target) = target {
+                    handle_action(AppAction::SaveValidated(target), app)?;
+                }
+                continue;
+            }
+
+            if !text_input_active
+                && key.code == KeyCode::Char('o')
+                && let Some(action) = open_file_action(app)
+            {
+                handle_action(action, app)?;
+                continue;
+            }
+
             let action = match app.current_screen {
-                CurrentScreen::FileExplorer => app.explorer.handle_input(key),
+                CurrentScreen::FileExplorer => match key.code {
+                    KeyCode::PageDown => {
+                        let page = page_size(app, 1);
+                        app.explorer.page_down(page);
+                        AppAction::None
+                    }
+                    KeyCode::PageUp => {
+                        let page = page_size(app, 1);
+                        app.explorer.page_up(page);
+                        AppAction::None
+                    }
+                    KeyCode::Home => {
+                        app.explorer.first();
+                        AppAction::None
+                    }
+                    KeyCode::End => {
+                        app.explorer.last();
+                        AppAction::None
+                    }
+                    _ => app.explorer.handle_input(key),
+                },
 
                 CurrentScreen::BitcoinStatus => match key.code {
                     KeyCode::Left => {
@@ -110,6 +701,11 @@ where
                     k => sidebar_nav(k, app),
                 },
 
+                // The old entries are not shown while a new file is parsed
+                CurrentScreen::BitcoinConfig if app.bitcoin_loading.is_some() => {
+                    sidebar_nav(key.code, app)
+                }
+
                 CurrentScreen::BitcoinConfig => {
                     if app.bitcoin_conf_path.is_some() {
                         if app.bitcoin_config_view.sidebar_focused {
@@ -121,8 +717,24 @@ where
                                 k => sidebar_nav(k, app),
                             }
                         } else {
-                            let entries = &app.bitcoin_data;
-                            app.bitcoin_config_view.handle_input(key, entries)
+                            let page = config_page_size(app);
+                            let editing = app.bitcoin_config_view.editing;
+                            match key.code {
+                                KeyCode::PageDown if !editing => {
+                                    let len = app.bitcoin_data.len();
+                                    app.bitcoin_config_view.page_down(page, len);
+                                    AppAction::None
+                                }
+                                KeyCode::PageUp if !editing => {
+                                    let len = app.bitcoin_data.len();
+                                    app.bitcoin_config_view.page_up(page, len);
+                                    AppAction::None
+                                }
+                                _ => {
+                                    let entries = &app.bitcoin_data;
+                                    app.bitcoin_config_view.handle_input(key, entries)
+                                }
+                            }
                         }
                     } else {
                         match key.code {
@@ -149,12 +761,25 @@ where
                             }
                         } else {
                             // Build flat entry list and delegate to the view
-                            let entries = app
-                                .p2pool_config
-                                .as_ref()
-                                .map(|cfg| flatten_config(cfg))
-                                .unwrap_or_default();
-                            app.p2pool_config_view.handle_input(key, &entries)
+                            let entries = app.p2pool_entries();
+                            let page = config_page_size(app);
+                            let editing = app.p2pool_config_view.editing;
+                            match key.code {
+                                KeyCode::PageDown if !editing => {
+                                    app.p2pool_config_view.page_down(page, entries.len());
+                                    AppAction::None
+                                }
+                                KeyCode::PageUp if !editing => {
+                                    app.p2pool_config_view.page_up(page, entries.len());
+                                    AppAction::None
+                                }
+                                _ => {
+                                    let action = app.p2pool_config_view.handle_input(key, &entries);
+                                    // Rechecked per keystroke so the input box shows it
+                                    app.p2pool_config_view.edit_error = app.p2pool_edit_error();
+                                    action
+                                }
+                            }
                         }
                     } else {
                         match key.code {
@@ -182,6 +807,12 @@ where
                     }
                 }
 
+                CurrentScreen::Exiting => match key.code {
+                    KeyCode::Char('y' | 'Y') => AppAction::Quit,
+                    KeyCode::Char('n' | 'N') | KeyCode::Esc => AppAction::CancelQuit,
+                    _ => AppAction::None,
+                },
+
                 _ => sidebar_nav(key.code, app),
             };
 
@@ -192,11 +823,176 @@ where
     }
 }
 
-/// Pre-populate app state from `app.settings`. Called once at startup after
+/// Answers the overwrite confirmation: `y` turns the pending save into the
+/// matching save action, `n` or Esc drops it. Other keys keep the prompt open.
+fn confirm_save_key(app: &mut App, key: KeyEvent) -> AppAction {
+    match key.code {
+        KeyCode::Char('y' | 'Y') => match app.pending_save.take() {
+            Some(SaveTarget::BitcoinConfig) => AppAction::SaveBitcoinConfig,
+            Some(SaveTarget::P2PoolConfig) => AppAction::SaveP2PoolConfig,
+            None => AppAction::None,
+        },
+        KeyCode::Char('n' | 'N') | KeyCode::Esc => {
+            app.pending_save = None;
+            AppAction::None
+        }
+        _ => AppAction::None,
+    }
+}
+
+/// Answers the reload prompt: `y` reloads the changed file and drops local
+/// edits, `n` or Esc keeps the edits. Other keys keep the prompt open.
+fn confirm_reload_key(app: &mut App, key: KeyEvent) -> AppAction {
+    match key.code {
+        KeyCode::Char('y' | 'Y') => app
+            .pending_reload
+            .take()
+            .map_or(AppAction::None, AppAction::ReloadConfig),
+        KeyCode::Char('n' | 'N') | KeyCode::Esc => {
+            app.pending_reload = None;
+            AppAction::None
+        }
+        _ => AppAction::None,
+    }
+}
+
+/// Which loaded config `path` (as reported by the watcher) belongs to.
+fn watched_target(app: &App, path: &std::path::Path) -> Option<SaveTarget> {
+    let is = |loaded: &Option<std::path::PathBuf>| {
+        loaded
+            .as_ref()
+            .and_then(|p| std::path::absolute(p).ok())
+            .is_some_and(|p| p == path)
+    };
+    if is(&app.bitcoin_conf_path) {
+        Some(SaveTarget::BitcoinConfig)
+    } else if is(&app.p2pool_conf_path) {
+        Some(SaveTarget::P2PoolConfig)
+    } else {
+        None
+    }
+}
+
+/// Whether the target's file on disk no longer matches what PDM holds,
+/// ignoring unsaved edits. Our own saves also fire watcher events; this is
+/// what tells them apart from edits made elsewhere. A file that can no
+/// longer be read counts as changed so the reload reports the failure.
+fn differs_from_disk(app: &App, target: SaveTarget) -> bool {
+    match target {
+        SaveTarget::BitcoinConfig => {
+            let Some(path) = &app.bitcoin_conf_path else {
+                return false;
+            };
+            let Ok(entries) = parse_bitcoin_config(path) else {
+                return true;
+            };
+            let fields =
+                |e: &BitcoinEntry| (e.section.clone(), e.key.clone(), e.value.clone(), e.enabled);
+            !entries
+                .iter()
+                .map(fields)
+                .eq(app.bitcoin_data.iter().map(fields))
+        }
+        SaveTarget::P2PoolConfig => {
+            let (Some(path), Some(cfg)) = (&app.p2pool_conf_path, &app.p2pool_config) else {
+                return false;
+            };
+            match load_p2pool_config(&path.to_string_lossy()) {
+                Ok(on_disk) => to_toml_string(&on_disk).ok() != to_toml_string(cfg).ok(),
+                Err(_) => true,
+            }
+        }
+    }
+}
+
+/// Entries of the target that differ from a fresh read of its file, with
+/// unsaved edits on the new side.
+///
+/// # Errors
+/// Returns an error if the file can no longer be read or parsed.
+fn diff_with_disk(app: &App, target: SaveTarget) -> Result<Vec<DiffLine>> {
+    match target {
+        SaveTarget::BitcoinConfig => {
+            let Some(path) = &app.bitcoin_conf_path else {
+                return Ok(Vec::new());
+            };
+            Ok(bitcoin_diff(
+                &parse_bitcoin_config(path)?,
+                &app.bitcoin_data,
+            ))
+        }
+        SaveTarget::P2PoolConfig => {
+            let Some(path) = &app.p2pool_conf_path else {
+                return Ok(Vec::new());
+            };
+            let on_disk = flatten_config(&load_p2pool_config(&path.to_string_lossy())?);
+            Ok(p2pool_diff(&on_disk, &app.p2pool_entries()))
+        }
+    }
+}
+
+/// Re-reads the target's file in place, keeping the current screen and, where
+/// it still fits, the selected row. Failures keep the loaded data and are
+/// shown in the error modal and on the target's view.
+fn reload_config(app: &mut App, target: SaveTarget) {
+    match target {
+        SaveTarget::BitcoinConfig => {
+            let Some(path) = app.bitcoin_conf_path.clone() else {
+                return;
+            };
+            let view = &mut app.bitcoin_config_view;
+            match parse_bitcoin_config(&path) {
+                Ok(entries) => {
+                    view.selected_index = view.selected_index.min(entries.len().saturating_sub(1));
+                    view.dirty = false;
+                    view.warning_message = None;
+                    app.bitcoin_data = entries;
+                    app.bitcoin_edits = EditHistory::default();
+                }
+                Err(e) => {
+                    let msg = format!("Reload failed: {e}");
+                    view.warning_message = Some(msg.clone());
+                    app.error = Some(msg);
+                }
+            }
+        }
+        SaveTarget::P2PoolConfig => {
+            let Some(path) = app.p2pool_conf_path.clone() else {
+                return;
+            };
+            match load_p2pool_config(&path.to_string_lossy()) {
+                Ok(cfg) => {
+                    app.p2pool_origins = read_p2pool_origins(&path, &cfg);
+                    app.p2pool_unknown_keys = read_p2pool_unknown_keys(&path, &cfg);
+                    app.p2pool_config = Some(cfg);
+                    app.p2pool_custom.clear();
+                    app.p2pool_edits = EditHistory::default();
+                    app.check_store_path();
+                    let len = app.p2pool_entries().len();
+                    let view = &mut app.p2pool_config_view;
+                    view.selected_index = view.selected_index.min(len.saturating_sub(1));
+                    view.revealed = None;
+                    view.dirty = false;
+                    view.warning_message = None;
+                }
+                Err(e) => {
+                    let msg = format!("Reload failed: {e}");
+                    app.p2pool_config_view.warning_message = Some(msg.clone());
+                    app.error = Some(msg);
+                }
+            }
+        }
+    }
+}
+
+/// Pre-populate app state from `app.settings`, skipping configs already
+/// loaded from the command line. Called once at startup after
 /// settings have been loaded into `app.settings = load_settings()`.
 fn bootstrap_from_settings(app: &mut App) {
     // Bitcoin config
-    if let Some(path) = &app.settings.bitcoin_conf_path {
+    if app.bitcoin_conf_path.is_none()
+        && let Some(path) = &app.settings.bitcoin_conf_path
+    {
         let entries = parse_bitcoin_config(path).unwrap_or_default();
         if entries.iter().any(|e| e.enabled && e.schema.is_some()) {
             app.bitcoin_conf_path = Some(path.clone());
@@ -205,12 +1001,17 @@ fn bootstrap_from_settings(app: &mut App) {
     }
 
     // P2Pool config — only set the path when the config is actually loadable
-    if let Some(path) = &app.settings.p2pool_conf_path.clone() {
+    if app.p2pool_conf_path.is_none()
+        && let Some(path) = &app.settings.p2pool_conf_path.clone()
+    {
         if let Some(p) = path.to_str() {
-            match P2PoolConfig::load(p) {
+            match load_p2pool_config(p) {
                 Ok(cfg) => {
                     app.p2pool_conf_path = Some(path.clone());
+                    app.p2pool_origins = read_p2pool_origins(path, &cfg);
+                    app.p2pool_unknown_keys = read_p2pool_unknown_keys(path, &cfg);
                     app.p2pool_config = Some(cfg);
+                    app.check_store_path();
                 }
                 Err(e) => {
                     eprintln!("pdm: failed to load p2pool config on startup: {e}");
@@ -224,61 +1025,127 @@ fn bootstrap_from_settings(app: &mut App) {
 // Logic Handler
 #[allow(clippy::too_many_lines)] // Central dispatch; splitting would obscure the flow
 fn handle_action(action: AppAction, app: &mut App) -> Result<ControlFlow<()>> {
+    // The finished parse replaces the entries, so edits now would be lost
+    if app.bitcoin_loading.is_some()
+        && matches!(
+            action,
+            AppAction::CommitEdit(..)
+                | AppAction::AddBitcoinEntry(..)
+                | AppAction::DuplicateBitcoinEntry(_)
+                | AppAction::GenerateRpcAuth(_)
+                | AppAction::ToggleBitcoinEntry(_)
+                | AppAction::Undo(SaveTarget::BitcoinConfig)
+                | AppAction::Redo(SaveTarget::BitcoinConfig)
+        )
+    {
+        app.bitcoin_config_view.warning_message =
+            Some("Wait for the bitcoin.conf to finish loading".to_string());
+        return Ok(ControlFlow::Continue(()));
+    }
+
     match action {
         AppAction::Quit => return Ok(ControlFlow::Break(())),
 
+        AppAction::RequestQuit => {
+            if app.current_screen != CurrentScreen::Exiting {
+                app.previous_screen = app.current_screen;
+                app.current_screen = CurrentScreen::Exiting;
+            }
+        }
+
+        AppAction::CancelQuit => {
+            if app.current_screen == CurrentScreen::Exiting {
+                app.current_screen = app.previous_screen;
+            }
+        }
+
         AppAction::ToggleMenu => app.toggle_menu(),
 
         AppAction::OpenExplorer(trigger) => {
-            if app.explorer.allow_dir_select {
-                app.explorer.allow_dir_select = false;
-                app.explorer.load_directory();
-            }
+            app.explorer.ensure_current_dir();
+            app.explorer.set_select_mode(SelectMode::File);
             app.explorer_trigger = Some(trigger);
-            app.current_screen = CurrentScreen::FileExplorer;
+            app.navigate_to(CurrentScreen::FileExplorer);
         }
 
         AppAction::OpenExplorerForSettings(field) => {
-            let dir_select = FIELDS
+            let mode = if FIELDS
                 .get(field)
-                .map_or(false, |f| matches!(f.1, FieldKind::DirectoryPicker));
-            if app.explorer.allow_dir_select != dir_select {
-                app.explorer.allow_dir_select = dir_select;
-                app.explorer.load_directory();
-            }
+                .is_some_and(|f| matches!(f.1, FieldKind::DirectoryPicker))
+            {
+                SelectMode::Directory
+            } else {
+                SelectMode::File
+            };
+            app.explorer.ensure_current_dir();
+            app.explorer.set_select_mode(mode);
             app.explorer_trigger = Some(ExplorerTrigger::Settings(field));
-            app.current_screen = CurrentScreen::FileExplorer;
+            app.navigate_to(CurrentScreen::FileExplorer);
         }
 
         AppAction::CloseModal => {
-            app.explorer.allow_dir_select = false;
+            app.explorer.select_mode = SelectMode::File;
             app.explorer_trigger = None;
             app.toggle_menu();
+            app.forget_explorer_entry();
+        }
+
+        AppAction::BookmarksChanged => {
+            if let Err(e) = save_bookmarks(&app.explorer.bookmarks) {
+                app.explorer.message = Some(format!("Failed to save bookmarks: {e}"));
+            }
         }
 
         AppAction::FileSelected(path) => {
             if let Some(trigger) = app.explorer_trigger.take() {
+                // Open the file as whatever it actually is, even if it was
+                // picked from the other config screen
+                let trigger = match (trigger, detect_config_kind(&path)) {
+                    (ExplorerTrigger::BitcoinConfig, ConfigKind::P2Pool) => {
+                        app.notice = Some(
+                            "This looks like a p2pool config; opened it in P2Pool Config."
+                                .to_string(),
+                        );
+                        ExplorerTrigger::P2PoolConfig
+                    }
+                    (ExplorerTrigger::P2PoolConfig, ConfigKind::Bitcoin) => {
+                        app.notice = Some(
+                            "This looks like a bitcoin.conf; opened it in Bitcoin Config."
+                                .to_string(),
+                        );
+                        ExplorerTrigger::BitcoinConfig
+                    }
+                    (trigger, _) => trigger,
+                };
                 match trigger {
                     ExplorerTrigger::P2PoolConfig => {
-                        match P2PoolConfig::load(path.to_str().unwrap_or_default()) {
+                        match load_p2pool_config(path.to_str().unwrap_or_default()) {
                             Ok(cfg) => {
                                 // Sanity check — a valid p2pool config must have
                                 // a stratum section with at least a hostname
 
                                 if cfg.stratum.hostname.is_empty() {
-                                    app.p2pool_config_view.warning_message = Some(
-                                        "Config loaded but appears invalid: stratum.hostname is empty. Select another file."
-                                            .to_string(),
-                                    );
+                                    let msg = "Config loaded but appears invalid: stratum.hostname is empty. Select another file.";
+                                    app.p2pool_config_view.warning_message = Some(msg.to_string());
+                                    app.error = Some(msg.to_string());
                                     app.p2pool_conf_path = None;
                                     app.p2pool_config = None;
+                                    app.p2pool_store_warnings.clear();
                                 } else {
                                     // Only set path + persist settings when config is actually valid
                                     app.p2pool_conf_path = Some(path.clone());
+                                    app.p2pool_origins = read_p2pool_origins(&path, &cfg);
+                                    app.p2pool_unknown_keys = read_p2pool_unknown_keys(&path, &cfg);
                                     app.p2pool_config = Some(cfg);
+                                    app.p2pool_custom.clear();
+                                    app.p2pool_edits = EditHistory::default();
+                                    app.check_store_path();
+                                    app.p2pool_config_view.dirty = false;
                                     app.p2pool_config_view.sidebar_focused = false;
                                     app.p2pool_config_view.warning_message = None;
                                     app.p2pool_config_view.selected_index = 0;
+                                    app.p2pool_config_view.revealed = None;
+                                    *app.p2pool_config_view.list_state.offset_mut() = 0;
                                     app.settings.p2pool_conf_path = Some(path.clone());
                                     app.settings_view.save_error = None;
                                     if let Err(e) = save_settings(&app.settings) {
@@ -288,56 +1155,24 @@ fn handle_action(action: AppAction, app: &mut App) -> Result<ControlFlow<()>> {
                                 }
                             }
                             Err(e) => {
-                                app.p2pool_config_view.warning_message = Some(format!(
-                                    "Failed to load P2Pool config: {}. Select another file.",
-                                    e
-                                ));
+                                let msg = format!(
+                                    "Failed to load P2Pool config: {e}. Select another file."
+                                );
+                                app.p2pool_config_view.warning_message = Some(msg.clone());
+                                app.error = Some(msg);
                                 app.p2pool_conf_path = None;
                                 app.p2pool_config = None;
+                                app.p2pool_store_warnings.clear();
                             }
                         }
                         app.current_screen = CurrentScreen::P2PoolConfig;
                     }
-                    ExplorerTrigger::BitcoinConfig => match parse_bitcoin_config(&path) {
-                        Ok(entries) => {
-                            const MIN_KNOWN_KEYS: usize = 1;
-                            let known_key_count = entries
-                                .iter()
-                                .filter(|e| e.enabled && e.schema.is_some())
-                                .count();
-
-                            if known_key_count >= MIN_KNOWN_KEYS {
-                                app.bitcoin_conf_path = Some(path.clone());
-                                app.bitcoin_data = entries;
-                                app.bitcoin_config_view.selected_index = 0;
-                                app.bitcoin_config_view.dirty = false;
-                                app.current_screen = CurrentScreen::BitcoinConfig;
-                                app.bitcoin_config_view.sidebar_focused = false;
-                                app.bitcoin_config_view.warning_message = None;
-                                app.settings.bitcoin_conf_path = Some(path.clone());
-                                app.settings_view.save_error = None;
-                                if let Err(e) = save_settings(&app.settings) {
-                                    let save_error = format!("Save failed: {e}");
-                                    app.settings_view.save_error = Some(save_error.clone());
-                                    app.bitcoin_config_view.warning_message = Some(save_error);
-                                }
-                            } else {
-                                app.bitcoin_config_view.warning_message = Some(
-                                    "File does not appear to be a Bitcoin config. Select another file."
-                                        .to_string(),
-                                );
-                                app.current_screen = CurrentScreen::BitcoinConfig;
-                            }
-                        }
-                        Err(e) => {
-                            app.bitcoin_config_view.warning_message = Some(format!(
-                                "Failed to read config: {e}. Check permissions and try again."
-                            ));
-                            app.current_screen = CurrentScreen::BitcoinConfig;
-                        }
-                    },
+                    ExplorerTrigger::BitcoinConfig => {
+                        app.bitcoin_loading = Some(BitcoinLoad::spawn(path));
+                        app.current_screen = CurrentScreen::BitcoinConfig;
+                    }
                     ExplorerTrigger::Settings(field) => {
-                        app.explorer.allow_dir_select = false;
+                        app.explorer.select_mode = SelectMode::File;
                         let mut should_save = true;
                         match field {
                             0 => match parse_bitcoin_config(&path) {
@@ -349,7 +1184,9 @@ fn handle_action(action: AppAction, app: &mut App) -> Result<ControlFlow<()>> {
                                     if known_key_count >= 1 {
                                         app.bitcoin_conf_path = Some(path.clone());
                                         app.bitcoin_data = entries;
+                                        app.bitcoin_edits = EditHistory::default();
                                         app.bitcoin_config_view.selected_index = 0;
+                                        *app.bitcoin_config_view.list_state.offset_mut() = 0;
                                         app.bitcoin_config_view.dirty = false;
                                         app.bitcoin_config_view.warning_message = None;
                                         app.settings.bitcoin_conf_path = Some(path.clone());
@@ -362,12 +1199,13 @@ fn handle_action(action: AppAction, app: &mut App) -> Result<ControlFlow<()>> {
                                     }
                                 }
                                 Err(e) => {
-                                    app.settings_view.save_error =
-                                        Some(format!("Failed to read config: {e}"));
+                                    let msg = format!("Failed to read config: {e}");
+                                    app.settings_view.save_error = Some(msg.clone());
+                                    app.error = Some(msg);
                                     should_save = false;
                                 }
                             },
-                            1 => match P2PoolConfig::load(path.to_str().unwrap_or_default()) {
+                            1 => match load_p2pool_config(path.to_str().unwrap_or_default()) {
                                 Ok(cfg) => {
                                     if cfg.stratum.hostname.is_empty() {
                                         app.settings_view.save_error = Some(
@@ -376,16 +1214,25 @@ fn handle_action(action: AppAction, app: &mut App) -> Result<ControlFlow<()>> {
                                         );
                                         should_save = false;
                                     } else {
+                                        app.p2pool_origins = read_p2pool_origins(&path, &cfg);
+                                        app.p2pool_unknown_keys =
+                                            read_p2pool_unknown_keys(&path, &cfg);
                                         app.p2pool_config = Some(cfg);
+                                        app.p2pool_custom.clear();
+                                        app.p2pool_edits = EditHistory::default();
+                                        app.check_store_path();
                                         app.settings.p2pool_conf_path = Some(path.clone());
                                         app.p2pool_config_view.warning_message = None;
                                         app.p2pool_config_view.selected_index = 0;
+                                        app.p2pool_config_view.revealed = None;
+                                        *app.p2pool_config_view.list_state.offset_mut() = 0;
                                         app.settings.p2pool_conf_path = Some(path.clone());
                                     }
                                 }
                                 Err(e) => {
-                                    app.settings_view.save_error =
-                                        Some(format!("Failed to load P2Pool config: {}", e));
+                                    let msg = format!("Failed to load P2Pool config: {e}");
+                                    app.settings_view.save_error = Some(msg.clone());
+                                    app.error = Some(msg);
                                     should_save = false;
                                 }
                             },
@@ -404,32 +1251,357 @@ fn handle_action(action: AppAction, app: &mut App) -> Result<ControlFlow<()>> {
                         app.settings_view.sidebar_focused = false;
                     }
                 }
+                app.sync_sidebar();
             }
+            app.forget_explorer_entry();
         }
 
-        AppAction::SaveBitcoinConfig => {
-            if let Some(path) = &app.bitcoin_conf_path {
-                save_bitcoin_config(path, &app.bitcoin_data)?;
-                app.bitcoin_config_view.save_message =
-                    Some("Configuration correctly saved".to_string());
-                app.bitcoin_config_view.dirty = false;
+        AppAction::RequestSave(target) => {
+            if app.save_path(target).is_some() {
+                app.pending_save = Some(target);
             }
         }
 
-        AppAction::Navigate(screen) => {
-            app.current_screen = screen;
+        AppAction::SaveValidated(target) => {
+            let blockers = save_blockers(app, target);
+            if !blockers.is_empty() {
+                app.error = Some(format!(
+                    "Not saved. Fix these errors first:\n\n{}",
+                    blockers.join("\n")
+                ));
+            } else if app.save_path(target).is_some() {
+                let save = match target {
+                    SaveTarget::BitcoinConfig => AppAction::SaveBitcoinConfig,
+                    SaveTarget::P2PoolConfig => AppAction::SaveP2PoolConfig,
+                };
+                return handle_action(save, app);
+            }
         }
 
-        AppAction::CommitEdit(index, value) => {
-            if index < app.bitcoin_data.len() {
-                app.bitcoin_data[index].value = value;
-                app.bitcoin_data[index].enabled = true;
-                app.bitcoin_config_view.dirty = true;
+        AppAction::ShowDiff(target) => match diff_with_disk(app, target) {
+            Ok(lines) if lines.is_empty() => {
+                if let Some(path) = app.save_path(target) {
+                    app.notice = Some(format!("No changes from {}", path.display()));
+                }
+            }
+            Ok(lines) => app.diff = Some(lines),
+            Err(e) => app.error = Some(format!("Cannot compare with the file on disk: {e}")),
+        },
+
+        AppAction::SaveBitcoinConfig => {
+            if let Some(path) = &app.bitcoin_conf_path {
+                let backup = if app.settings.skip_backups {
+                    Ok(())
+                } else {
+                    config_files(path, &app.bitcoin_data)
+                        .iter()
+                        .try_for_each(|file| backup_file(file).map(|_| ()))
+                };
+                match backup.and_then(|()| save_bitcoin_config(path, &app.bitcoin_data)) {
+                    Ok(()) => {
+                        app.bitcoin_config_view.save_message =
+                            Some("Configuration correctly saved".to_string());
+                        app.bitcoin_config_view.dirty = false;
+                        app.bitcoin_edits.mark_saved();
+                    }
+                    Err(e) => {
+                        let msg = format!("Save failed: {e}");
+                        app.bitcoin_config_view.warning_message = Some(msg.clone());
+                        app.error = Some(msg);
+                    }
+                }
             }
         }
 
-        AppAction::ClearSettingsField(field) => {
-            match field {
+        AppAction::ConfigChanged(target) => {
+            if differs_from_disk(app, target) {
+                return handle_action(AppAction::RequestReload(target), app);
+            }
+        }
+
+        AppAction::RequestReload(target) => {
+            let dirty = match target {
+                SaveTarget::BitcoinConfig => app.bitcoin_config_view.dirty,
+                SaveTarget::P2PoolConfig => app.p2pool_config_view.dirty,
+            };
+            if dirty {
+                app.pending_reload = Some(target);
+            } else {
+                reload_config(app, target);
+            }
+        }
+
+        AppAction::ReloadConfig(target) => reload_config(app, target),
+
+        AppAction::Undo(SaveTarget::BitcoinConfig) => {
+            if let Some(edit) = app.bitcoin_edits.undo() {
+                restore_bitcoin_entry(app, edit.index, edit.before);
+            }
+        }
+
+        AppAction::Redo(SaveTarget::BitcoinConfig) => {
+            if let Some(edit) = app.bitcoin_edits.redo() {
+                restore_bitcoin_entry(app, edit.index, edit.after);
+            }
+        }
+
+        AppAction::Undo(SaveTarget::P2PoolConfig) => {
+            if let Some(edit) = app.p2pool_edits.undo() {
+                restore_p2pool_state(app, edit.index, edit.before);
+            }
+        }
+
+        AppAction::Redo(SaveTarget::P2PoolConfig) => {
+            if let Some(edit) = app.p2pool_edits.redo() {
+                restore_p2pool_state(app, edit.index, edit.after);
+            }
+        }
+
+        AppAction::CopyValue(target, value) => {
+            let clipboard = match app.clipboard.take() {
+                Some(clipboard) => Ok(clipboard),
+                None => arboard::Clipboard::new(),
+            };
+            let copied = clipboard.and_then(|mut clipboard| {
+                let result = clipboard.set_text(value);
+                app.clipboard = Some(clipboard);
+                result
+            });
+            match copied {
+                Ok(()) => {
+                    let message = Some("Copied to clipboard".to_string());
+                    match target {
+                        SaveTarget::BitcoinConfig => app.bitcoin_config_view.save_message = message,
+                        SaveTarget::P2PoolConfig => app.p2pool_config_view.save_message = message,
+                    }
+                }
+                Err(e) => app.error = Some(format!("Clipboard unavailable: {e}")),
+            }
+        }
+
+        AppAction::Navigate(screen) => app.navigate_to(screen),
+
+        AppAction::GoBack => app.go_back(),
+
+        AppAction::CommitEdit(index, value) => {
+            if let Some(entry) = app.bitcoin_data.get_mut(index) {
+                match validate_bitcoin_value(entry, &value) {
+                    Ok(()) => {
+                        let before = (std::mem::replace(&mut entry.value, value), entry.enabled);
+                        entry.enabled = true;
+                        entry.validation_error = None;
+                        let after = (entry.value.clone(), true);
+                        if before != after {
+                            app.bitcoin_edits.record(Edit {
+                                index,
+                                before,
+                                after,
+                            });
+                        }
+                        // A path here was most likely meant as a password file
+                        let file_hint = (entry.key == "rpcpassword"
+                            && std::path::Path::new(entry.value.trim()).is_file())
+                        .then(|| {
+                            format!(
+                                "rpcpassword looks like a file; add {PASSWORD_FILE_KEY}={} \
+                                 with n to read the password from it on save",
+                                entry.value.trim()
+                            )
+                        });
+                        flag_bitcoin_conflicts(&mut app.bitcoin_data);
+                        app.bitcoin_config_view.dirty = true;
+                        app.bitcoin_config_view.warning_message = file_hint;
+                    }
+                    Err(e) => {
+                        app.bitcoin_config_view.warning_message =
+                            Some(format!("{}: {e}", entry.key));
+                    }
+                }
+            }
+        }
+
+        AppAction::GenerateRpcAuth(user) => match generate_rpcauth(&user, None) {
+            Ok((line, password)) => {
+                let value = line.trim_start_matches("rpcauth=").to_string();
+                let prefix = format!("{user}:");
+                // Replace this user's credentials, or fill the unset schema
+                // entry; other users' lines, even disabled ones, stay as they are
+                let existing = app.bitcoin_data.iter().position(|e| {
+                    e.key == "rpcauth"
+                        && (e.value.starts_with(&prefix) || (!e.enabled && e.value.is_empty()))
+                });
+                let index = if let Some(index) = existing {
+                    let entry = &mut app.bitcoin_data[index];
+                    let before = (std::mem::replace(&mut entry.value, value), entry.enabled);
+                    entry.enabled = true;
+                    entry.validation_error = None;
+                    let after = (entry.value.clone(), true);
+                    app.bitcoin_edits.record(Edit {
+                        index,
+                        before,
+                        after,
+                    });
+                    index
+                } else {
+                    let schema = app
+                        .bitcoin_data
+                        .iter()
+                        .find(|e| e.key == "rpcauth")
+                        .and_then(|e| e.schema.clone());
+                    app.bitcoin_data.push(BitcoinEntry {
+                        schema,
+                        ..BitcoinEntry::new("rpcauth", &value)
+                    });
+                    app.bitcoin_edits.mark_unsaved();
+                    app.bitcoin_data.len() - 1
+                };
+                app.bitcoin_config_view.filter.clear();
+                app.bitcoin_config_view.selected_index = index;
+                app.bitcoin_config_view.dirty = true;
+                app.notice = Some(format!(
+                    "Added rpcauth for {user}.\n\nPassword (shown only once): {password}\n\n\
+                     Save the config and restart bitcoind to use it."
+                ));
+            }
+            Err(e) => app.error = Some(format!("Failed to generate rpcauth: {e}")),
+        },
+
+        AppAction::AddBitcoinEntry(key, value) => {
+            let entry = BitcoinEntry::new(&key, &value);
+            let result = validate_new_key(&entry.key)
+                .and_then(|()| {
+                    if app
+                        .bitcoin_data
+                        .iter()
+                        .any(|e| e.key == entry.key && e.section.is_none())
+                    {
+                        Err(format!("{} already exists; edit it instead", entry.key))
+                    } else {
+                        Ok(())
+                    }
+                })
+                .and_then(|()| {
+                    validate_bitcoin_value(&entry, &entry.value)
+                        .map_err(|e| format!("{}: {e}", entry.key))
+                });
+            match result {
+                Ok(()) => {
+                    app.bitcoin_data.push(entry);
+                    app.bitcoin_config_view.filter.clear();
+                    app.bitcoin_config_view.selected_index = app.bitcoin_data.len() - 1;
+                    app.bitcoin_config_view.warning_message = None;
+                    app.bitcoin_config_view.dirty = true;
+                    app.bitcoin_edits.mark_unsaved();
+                }
+                Err(e) => app.bitcoin_config_view.warning_message = Some(e),
+            }
+        }
+
+        AppAction::DuplicateBitcoinEntry(index) => {
+            let Some(original) = app.bitcoin_data.get(index) else {
+                return Ok(ControlFlow::Continue(()));
+            };
+            let mut copy = original.clone();
+            copy.enabled = true;
+            // bitcoin.conf repeats a key for each value, so the copy keeps the
+            // whole value; commas may belong to it, as in `rpcwhitelist`
+            copy.validation_error = validate_bitcoin_value(&copy, &copy.value).err();
+
+            app.bitcoin_data.insert(index + 1, copy);
+            app.bitcoin_edits.shift_from(index + 1);
+            app.bitcoin_edits.mark_unsaved();
+            flag_bitcoin_conflicts(&mut app.bitcoin_data);
+
+            let view = &mut app.bitcoin_config_view;
+            view.filter.clear();
+            let value = app.bitcoin_data[index + 1].value.clone();
+            view.start_edit(index + 1, value, &app.bitcoin_data);
+            view.warning_message = None;
+            view.dirty = true;
+        }
+
+        AppAction::AddP2PoolEntry(section, key, value) => {
+            let entries = app.p2pool_entries();
+            let result = validate_new_key(&section)
+                .and_then(|()| validate_new_key(&key))
+                .and_then(|()| {
+                    if section.contains('.') || key.contains('.') {
+                        Err("section and key cannot contain '.'".to_string())
+                    } else if entries.is_empty() {
+                        Err("Load a p2pool config first".to_string())
+                    } else if entries
+                        .iter()
+                        .any(|e| e.section.to_string() == section && e.key == key)
+                    {
+                        Err(format!("{section}.{key} already exists; edit it instead"))
+                    } else {
+                        Ok(())
+                    }
+                });
+            match result {
+                Ok(()) => {
+                    let before = p2pool_state(app);
+                    app.p2pool_custom
+                        .push(P2PoolConfigEntry::custom(&section, &key, &value));
+                    if let Some(before) = before {
+                        record_p2pool_edit(app, entries.len(), before);
+                    }
+                    app.p2pool_config_view.filter.clear();
+                    app.p2pool_config_view.select(entries.len());
+                    app.p2pool_config_view.warning_message = None;
+                    app.p2pool_config_view.dirty = true;
+                }
+                Err(e) => app.p2pool_config_view.warning_message = Some(e),
+            }
+        }
+
+        AppAction::ToggleBitcoinEntry(index) => {
+            let before = app
+                .bitcoin_data
+                .get(index)
+                .map(|e| (e.value.clone(), e.enabled));
+            let mut prompt = false;
+            if let Some(entry) = app.bitcoin_data.get_mut(index) {
+                if entry.enabled {
+                    entry.enabled = false;
+                    app.bitcoin_config_view.dirty = true;
+                } else if !entry.value.is_empty() {
+                    entry.enabled = true;
+                    app.bitcoin_config_view.dirty = true;
+                } else if let Some(default) = entry
+                    .schema
+                    .as_ref()
+                    .map(|s| s.default.clone())
+                    .filter(|d| !d.is_empty())
+                {
+                    entry.value = default;
+                    entry.enabled = true;
+                    entry.validation_error = None;
+                    app.bitcoin_config_view.dirty = true;
+                } else {
+                    // Nothing to enable yet: prompt for a value, committing enables it
+                    prompt = true;
+                }
+            }
+            if prompt {
+                app.bitcoin_config_view
+                    .start_edit(index, String::new(), &app.bitcoin_data);
+            }
+            if let Some((before, entry)) = before.zip(app.bitcoin_data.get(index)) {
+                let after = (entry.value.clone(), entry.enabled);
+                if before != after {
+                    app.bitcoin_edits.record(Edit {
+                        index,
+                        before,
+                        after,
+                    });
+                }
+            }
+            flag_bitcoin_conflicts(&mut app.bitcoin_data);
+        }
+
+        AppAction::ClearSettingsField(field) => {
+            match field {
                 0 => {
                     app.settings.bitcoin_conf_path = None;
                     app.bitcoin_conf_path = None;
@@ -439,10 +1611,14 @@ fn handle_action(action: AppAction, app: &mut App) -> Result<ControlFlow<()>> {
                     app.settings.p2pool_conf_path = None;
                     app.p2pool_conf_path = None;
                     app.p2pool_config = None;
+                    app.p2pool_custom.clear();
+                    app.p2pool_edits = EditHistory::default();
+                    app.p2pool_store_warnings.clear();
                 }
                 2 => app.settings.ln_conf_path = None,
                 3 => app.settings.shares_market_conf_path = None,
                 4 => app.settings.settings_dir_override = None,
+                5 => app.settings.skip_backups = false,
                 _ => {}
             }
             app.settings_view.save_error = None;
@@ -450,31 +1626,71 @@ fn handle_action(action: AppAction, app: &mut App) -> Result<ControlFlow<()>> {
                 app.settings_view.save_error = Some(format!("Save failed: {e}"));
             }
         }
+        AppAction::ToggleSettingsField(field) => {
+            if field == 5 {
+                app.settings.skip_backups = !app.settings.skip_backups;
+            }
+            app.settings_view.save_error = None;
+            if let Err(e) = save_settings(&app.settings) {
+                app.settings_view.save_error = Some(format!("Save failed: {e}"));
+            }
+        }
         AppAction::CommitP2PoolEdit(index, value) => {
-            if let Some(cfg) = app.p2pool_config.as_mut() {
+            let before = p2pool_state(app);
+            let base = app
+                .p2pool_config
+                .as_ref()
+                .map_or(0, |cfg| flatten_config(cfg).len());
+            if let Some(entry) = index
+                .checked_sub(base)
+                .and_then(|i| app.p2pool_custom.get_mut(i))
+            {
+                // Custom keys have no typed field to check against
+                entry.value = value;
+                app.p2pool_config_view.warning_message = None;
+                app.p2pool_config_view.dirty = true;
+            } else if let Some(cfg) = app.p2pool_config.as_mut() {
                 match apply_p2pool_edit(cfg, index, &value) {
                     Ok(()) => {
                         app.p2pool_config_view.warning_message = None;
+                        app.p2pool_config_view.dirty = true;
+                        app.check_store_path();
                     }
                     Err(e) => {
                         app.p2pool_config_view.warning_message = Some(e);
                     }
                 }
             }
+            if let Some(before) = before {
+                record_p2pool_edit(app, index, before);
+            }
         }
 
         AppAction::SaveP2PoolConfig => {
             if let (Some(path), Some(cfg)) =
                 (app.p2pool_conf_path.clone(), app.p2pool_config.as_ref())
             {
-                match save_p2pool_config(&path, cfg) {
+                // A JSON or YAML file is refused before it gets a backup
+                let backup = check_p2pool_save_format(&path).and_then(|()| {
+                    if app.settings.skip_backups {
+                        Ok(None)
+                    } else {
+                        backup_file(&path)
+                    }
+                });
+                match backup.and_then(|_| save_p2pool_config(&path, cfg, &app.p2pool_custom)) {
                     Ok(()) => {
                         app.p2pool_config_view.save_message =
                             Some("Configuration correctly saved".to_string());
+                        app.p2pool_config_view.dirty = false;
+                        app.p2pool_edits.mark_saved();
+                        app.p2pool_origins = read_p2pool_origins(&path, cfg);
+                        app.p2pool_unknown_keys = read_p2pool_unknown_keys(&path, cfg);
                     }
                     Err(e) => {
-                        app.p2pool_config_view.warning_message =
-                            Some(format!("Save failed: {}", e));
+                        let msg = format!("Save failed: {e}");
+                        app.p2pool_config_view.warning_message = Some(msg.clone());
+                        app.error = Some(msg);
                     }
                 }
             }
@@ -486,93 +1702,125 @@ fn handle_action(action: AppAction, app: &mut App) -> Result<ControlFlow<()>> {
     Ok(ControlFlow::Continue(()))
 }
 
-/// Matches the TOML type of an existing item and parses the new string
-/// value into that same type. This prevents numeric/bool fields from
-/// being written back as quoted strings (e.g. port = "3333").
-fn typed_toml_item_like(existing: &toml_edit::Item, new_value: &str) -> Result<toml_edit::Item> {
-    if existing.as_integer().is_some() {
-        let parsed = new_value
-            .parse::<i64>()
-            .map_err(|e| anyhow::anyhow!("Expected integer, got '{}': {}", new_value, e))?;
-        Ok(toml_edit::value(parsed))
-    } else if existing.as_float().is_some() {
-        let parsed = new_value
-            .parse::<f64>()
-            .map_err(|e| anyhow::anyhow!("Expected float, got '{}': {}", new_value, e))?;
-        Ok(toml_edit::value(parsed))
-    } else if existing.as_bool().is_some() {
-        let parsed = new_value.parse::<bool>().map_err(|e| {
-            anyhow::anyhow!("Expected bool (true/false), got '{}': {}", new_value, e)
-        })?;
-        Ok(toml_edit::value(parsed))
-    } else if existing.as_str().is_some() {
-        Ok(toml_edit::value(new_value.to_owned()))
-    } else if existing.as_array().is_some() {
-        let values: Vec<String> = new_value
-            .split(',')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .map(ToOwned::to_owned)
-            .collect();
-
-        let mut new_arr = toml_edit::Array::default();
-        for value in values {
-            new_arr.push(value);
-        }
-        Ok(toml_edit::Item::Value(toml_edit::Value::Array(new_arr)))
-    } else {
-        Err(anyhow::anyhow!(
-            "Unsupported TOML value type for key: {}",
-            existing
-        ))
-    }
+/// Puts a bitcoin entry back to a `(value, enabled)` state from the edit
+/// history, revalidates it and selects it so the change is visible.
+fn restore_bitcoin_entry(app: &mut App, index: usize, (value, enabled): (String, bool)) {
+    let Some(entry) = app.bitcoin_data.get_mut(index) else {
+        return;
+    };
+    entry.validation_error = validate_bitcoin_value(entry, &value).err();
+    entry.value = value;
+    entry.enabled = enabled;
+    flag_bitcoin_conflicts(&mut app.bitcoin_data);
+    let view = &mut app.bitcoin_config_view;
+    view.selected_index = index;
+    view.warning_message = None;
+    view.dirty = !app.bitcoin_edits.is_saved();
 }
 
-/// Serialize the live `P2PoolConfig` back to TOML and write it to disk.
-/// Saves P2Pool config by patching the original TOML file in-place.
-/// Uses toml_edit so comments and formatting are preserved.
-fn save_p2pool_config(path: &std::path::Path, cfg: &P2PoolConfig) -> Result<()> {
-    use pdm::p2poolv2_config::flatten_config;
-    use toml_edit::DocumentMut;
-
-    // Read the original file so we preserve comments/ordering
-    let original = std::fs::read_to_string(path)
-        .map_err(|e| anyhow::anyhow!("Failed to read P2Pool config: {}", e))?;
-
-    let mut doc = original
-        .parse::<DocumentMut>()
-        .map_err(|e| anyhow::anyhow!("Failed to parse P2Pool config TOML: {}", e))?;
+/// The p2pool config and custom keys as they are now, if one is loaded.
+fn p2pool_state(app: &App) -> Option<(P2PoolConfig, Vec<P2PoolConfigEntry>)> {
+    app.p2pool_config
+        .clone()
+        .map(|cfg| (cfg, app.p2pool_custom.clone()))
+}
 
-    // Walk every flattened entry and patch the matching TOML key
-    for entry in flatten_config(cfg) {
-        let section = entry.section.to_string();
-        let key = entry.key.as_str();
+/// Records the change from `before` to the current p2pool state as an edit
+/// of the entry at `index`, unless every entry still reads the same.
+fn record_p2pool_edit(app: &mut App, index: usize, before: (P2PoolConfig, Vec<P2PoolConfigEntry>)) {
+    let Some(after) = p2pool_state(app) else {
+        return;
+    };
+    let values = |(cfg, custom): &(P2PoolConfig, Vec<P2PoolConfigEntry>)| {
+        flatten_config(cfg)
+            .into_iter()
+            .chain(custom.iter().cloned())
+            .map(|e| (e.field(), e.value, e.enabled))
+            .collect::<Vec<_>>()
+    };
+    if values(&before) != values(&after) {
+        app.p2pool_edits.record(P2PoolEdit {
+            index,
+            before,
+            after,
+        });
+    }
+}
 
-        // Skip optional fields that are unset — leave them absent in the file
-        if !entry.enabled {
-            continue;
-        }
+/// Puts the p2pool config and custom keys back to a state from the edit
+/// history and selects the entry the edit changed.
+fn restore_p2pool_state(
+    app: &mut App,
+    index: usize,
+    (cfg, custom): (P2PoolConfig, Vec<P2PoolConfigEntry>),
+) {
+    app.p2pool_config = Some(cfg);
+    app.p2pool_custom = custom;
+    app.check_store_path();
+    let view = &mut app.p2pool_config_view;
+    view.select(index);
+    view.warning_message = None;
+    view.dirty = !app.p2pool_edits.is_saved();
+}
 
-        if let Some(table) = doc.get_mut(&section).and_then(|v| v.as_table_mut()) {
-            // Only update keys that already exist in the file to avoid
-            // injecting fields the user intentionally omitted
-            if let Some(existing) = table.get(key) {
-                match typed_toml_item_like(existing, &entry.value) {
-                    Ok(updated) => table[key] = updated,
-                    Err(e) => {
-                        // Soft error — skip this field and continue
-                        // saving the rest rather than aborting entirely
-                        eprintln!("Warning: skipping {}.{}: {}", section, key, e);
-                    }
+/// Applies the result of parsing the bitcoin.conf at `path` picked in the
+/// explorer: a file with at least one known option becomes the loaded
+/// config, anything else is reported in the error modal.
+fn finish_bitcoin_load(app: &mut App, path: std::path::PathBuf, result: Result<Vec<BitcoinEntry>>) {
+    match result {
+        Ok(entries) => {
+            const MIN_KNOWN_KEYS: usize = 1;
+            let known_key_count = entries
+                .iter()
+                .filter(|e| e.enabled && e.schema.is_some())
+                .count();
+
+            if known_key_count >= MIN_KNOWN_KEYS {
+                app.bitcoin_conf_path = Some(path.clone());
+                app.bitcoin_data = entries;
+                app.bitcoin_edits = EditHistory::default();
+                app.bitcoin_config_view.selected_index = 0;
+                *app.bitcoin_config_view.list_state.offset_mut() = 0;
+                app.bitcoin_config_view.dirty = false;
+                app.current_screen = CurrentScreen::BitcoinConfig;
+                app.bitcoin_config_view.sidebar_focused = false;
+                app.bitcoin_config_view.warning_message = None;
+                app.settings.bitcoin_conf_path = Some(path.clone());
+                app.settings_view.save_error = None;
+                if let Err(e) = save_settings(&app.settings) {
+                    let save_error = format!("Save failed: {e}");
+                    app.settings_view.save_error = Some(save_error.clone());
+                    app.bitcoin_config_view.warning_message = Some(save_error);
                 }
+            } else {
+                let msg = "File does not appear to be a Bitcoin config. Select another file.";
+                app.bitcoin_config_view.warning_message = Some(msg.to_string());
+                app.error = Some(msg.to_string());
+                app.current_screen = CurrentScreen::BitcoinConfig;
             }
         }
+        Err(e) => {
+            let msg = format!("Failed to read config: {e}. Check permissions and try again.");
+            app.bitcoin_config_view.warning_message = Some(msg.clone());
+            app.error = Some(msg);
+            app.current_screen = CurrentScreen::BitcoinConfig;
+        }
     }
+}
 
-    std::fs::write(path, doc.to_string())
-        .map_err(|e| anyhow::anyhow!("Failed to write P2Pool config: {}", e))?;
-
-    Ok(())
+/// Hands a finished background parse of bitcoin.conf to
+/// [`finish_bitcoin_load`]; does nothing while it is still running.
+fn poll_bitcoin_load(app: &mut App) {
+    let Some(result) = app
+        .bitcoin_loading
+        .as_ref()
+        .and_then(BitcoinLoad::try_finish)
+    else {
+        return;
+    };
+    if let Some(load) = app.bitcoin_loading.take() {
+        finish_bitcoin_load(app, load.path, result);
+    }
 }
 
 #[cfg(test)]
@@ -591,9 +1839,13 @@ mod tests {
         unsafe { std::env::set_var("PDM_CONFIG_DIR", dir.path()) };
     }
 
-    /// Run an action for its side effects, discarding the ControlFlow return.
+    /// Handles `action`, waiting for any bitcoin.conf it started parsing.
     fn run(action: AppAction, app: &mut App) {
         let _ = handle_action(action, app).unwrap();
+        if let Some(load) = app.bitcoin_loading.take() {
+            let path = load.path.clone();
+            finish_bitcoin_load(app, path, load.wait());
+        }
     }
 
     /// Write a  p2pool TOML to `path`.
@@ -759,6 +2011,50 @@ port = 46884
         terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
     }
 
+    #[test]
+    #[serial]
+    fn open_key_from_p2pool_config_loads_the_picked_file() {
+        let dir = tempfile::tempdir().unwrap();
+        redirect_saves_to(&dir);
+        let path = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&path);
+
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::P2PoolConfig;
+        let action = open_file_action(&mut app).unwrap();
+        assert!(matches!(
+            action,
+            AppAction::OpenExplorer(ExplorerTrigger::P2PoolConfig)
+        ));
+        run(action, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::FileExplorer);
+        assert_eq!(app.explorer_trigger, Some(ExplorerTrigger::P2PoolConfig));
+
+        run(AppAction::FileSelected(path.clone()), &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::P2PoolConfig);
+        assert_eq!(app.p2pool_conf_path, Some(path));
+        assert!(!app.p2pool_entries().is_empty());
+        assert!(app.explorer_trigger.is_none());
+    }
+
+    #[test]
+    fn open_key_refuses_to_replace_unsaved_edits() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::BitcoinConfig;
+        assert!(matches!(
+            open_file_action(&mut app),
+            Some(AppAction::OpenExplorer(ExplorerTrigger::BitcoinConfig))
+        ));
+
+        app.bitcoin_config_view.dirty = true;
+        assert!(matches!(open_file_action(&mut app), Some(AppAction::None)));
+        assert!(app.notice.as_deref().unwrap().contains("Save or reload"));
+
+        // Screens without a config file leave `o` alone
+        app.current_screen = CurrentScreen::Home;
+        assert!(open_file_action(&mut app).is_none());
+    }
+
     #[test]
     #[serial]
     fn test_file_explorer_wrap_and_select_sets_config() {
@@ -795,13 +2091,142 @@ port = 46884
             .explorer
             .handle_input(KeyEvent::new(KeyCode::Enter, KeyModifiers::empty()));
 
-        let _ = handle_action(action, &mut app).unwrap();
+        run(action, &mut app);
 
         assert_eq!(app.bitcoin_conf_path, Some(file_path));
+        assert_eq!(app.bitcoin_data[index_of(&app, "rpcuser")].value, "test");
 
         terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
     }
 
+    #[test]
+    fn file_selected_unparsable_bitcoin_config_shows_error_modal() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut app = App::new();
+        app.explorer_trigger = Some(ExplorerTrigger::BitcoinConfig);
+        // A directory yields no known options, so it isn't taken for a Bitcoin config
+        run(AppAction::FileSelected(dir.path().to_path_buf()), &mut app);
+
+        assert!(
+            app.error
+                .as_deref()
+                .unwrap()
+                .contains("does not appear to be a Bitcoin config")
+        );
+        assert!(app.bitcoin_conf_path.is_none());
+        assert!(app.bitcoin_data.is_empty());
+        assert_eq!(app.current_screen, CurrentScreen::BitcoinConfig);
+    }
+
+    /// Polls like the event loop does until the bitcoin.conf parse lands.
+    fn poll_until_loaded(app: &mut App) {
+        while app.bitcoin_loading.is_some() {
+            poll_bitcoin_load(app);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn bitcoin_config_shows_loading_until_the_parse_is_delivered() {
+        let dir = tempfile::tempdir().unwrap();
+        redirect_saves_to(&dir);
+        let path = dir.path().join("bitcoin.conf");
+        std::fs::write(&path, "server=1\n").unwrap();
+        let mut app = App::new();
+        run(
+            AppAction::OpenExplorer(ExplorerTrigger::BitcoinConfig),
+            &mut app,
+        );
+
+        let _ = handle_action(AppAction::FileSelected(path.clone()), &mut app).unwrap();
+        assert_eq!(app.current_screen, CurrentScreen::BitcoinConfig);
+        assert!(app.bitcoin_conf_path.is_none());
+        assert!(render_to_string(&mut app).contains("Loading"));
+
+        poll_until_loaded(&mut app);
+        assert_eq!(app.bitcoin_conf_path, Some(path));
+        assert!(app.error.is_none());
+        let screen = render_to_string(&mut app);
+        assert!(!screen.contains("Loading"));
+        assert!(screen.contains("Bitcoin Configuration"));
+
+        // A file that isn't a bitcoin.conf ends in the error modal instead
+        let other = dir.path().join("notes.txt");
+        std::fs::write(&other, "hello\n").unwrap();
+        run(
+            AppAction::OpenExplorer(ExplorerTrigger::BitcoinConfig),
+            &mut app,
+        );
+        let _ = handle_action(AppAction::FileSelected(other), &mut app).unwrap();
+        poll_until_loaded(&mut app);
+        assert!(
+            app.error
+                .as_deref()
+                .is_some_and(|e| e.starts_with("File does not appear"))
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn bitcoin_edits_wait_for_a_load_in_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        redirect_saves_to(&dir);
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &dir.path().join("old.conf"), "server=1\n");
+        let next = dir.path().join("bitcoin.conf");
+        std::fs::write(&next, "txindex=1\n").unwrap();
+        run(
+            AppAction::OpenExplorer(ExplorerTrigger::BitcoinConfig),
+            &mut app,
+        );
+        let _ = handle_action(AppAction::FileSelected(next.clone()), &mut app).unwrap();
+
+        let server = index_of(&app, "server");
+        let _ = handle_action(AppAction::ToggleBitcoinEntry(server), &mut app).unwrap();
+        assert!(app.bitcoin_data[server].enabled);
+        assert!(!app.bitcoin_config_view.dirty);
+        assert_eq!(
+            app.bitcoin_config_view.warning_message.as_deref(),
+            Some("Wait for the bitcoin.conf to finish loading")
+        );
+
+        poll_until_loaded(&mut app);
+        assert_eq!(app.bitcoin_conf_path, Some(next));
+        assert!(app.bitcoin_edits.is_saved());
+    }
+
+    #[test]
+    fn page_size_uses_viewport_and_item_height() {
+        let mut app = App::new();
+        // Before the first draw the viewport is unknown; still move by one
+        assert_eq!(page_size(&app, 1), 1);
+
+        app.viewport_height = 20;
+        assert_eq!(page_size(&app, 1), 20);
+        assert_eq!(page_size(&app, 2), 10);
+    }
+
+    #[test]
+    fn expanded_entries_count_at_their_drawn_height() {
+        // The second entry is expanded over five rows
+        let heights = [2, 5, 2, 2];
+        assert_eq!(entry_at_row(&heights, 0, 1), Some(0));
+        assert_eq!(entry_at_row(&heights, 0, 2), Some(1));
+        assert_eq!(entry_at_row(&heights, 0, 6), Some(1));
+        assert_eq!(entry_at_row(&heights, 0, 7), Some(2));
+        assert_eq!(entry_at_row(&heights, 1, 5), Some(1));
+        assert_eq!(entry_at_row(&heights, 0, 11), None);
+
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::BitcoinConfig;
+        app.viewport_height = 8;
+        assert_eq!(config_page_size(&app), 4);
+        app.bitcoin_config_view.row_heights = heights.to_vec();
+        assert_eq!(config_page_size(&app), 2);
+    }
+
     #[test]
     fn app_action_open_explorer_sets_state() {
         let mut app = App::new();
@@ -842,78 +2267,1293 @@ port = 46884
     }
 
     #[test]
-    fn commit_edit_updates_entry_value_and_enables_it() {
-        use pdm::bitcoin_config::ConfigEntry;
+    fn flag_value_reads_the_following_path() {
+        let args = |list: &[&str]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
 
-        let mut app = App::new();
-        app.bitcoin_data = vec![
-            ConfigEntry {
-                key: "rpcuser".to_string(),
-                value: "old".to_string(),
-                enabled: false,
-                schema: None,
-                section: None,
-            },
-            ConfigEntry {
-                key: "server".to_string(),
-                value: "0".to_string(),
-                enabled: true,
-                schema: None,
-                section: None,
-            },
-        ];
+        assert!(flag_value(&args(&[]), "--json").unwrap().is_none());
+        assert_eq!(
+            flag_value(&args(&["--json", "bitcoin.conf"]), "--json").unwrap(),
+            Some(std::path::PathBuf::from("bitcoin.conf"))
+        );
+        assert!(flag_value(&args(&["--check"]), "--check").is_err());
+    }
 
-        run(AppAction::CommitEdit(0, "alice".to_string()), &mut app);
+    #[test]
+    fn init_config_refuses_to_overwrite_without_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let conf = dir.path().join("bitcoin.conf");
 
-        assert_eq!(app.bitcoin_data[0].value, "alice");
-        assert!(app.bitcoin_data[0].enabled);
-        // Other entries unchanged
-        assert_eq!(app.bitcoin_data[1].value, "0");
+        init_config(&conf, false).unwrap();
+        assert!(
+            std::fs::read_to_string(&conf)
+                .unwrap()
+                .contains("#txindex=0")
+        );
+
+        std::fs::write(&conf, "server=1\n").unwrap();
+        let err = init_config(&conf, false).unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        assert_eq!(std::fs::read_to_string(&conf).unwrap(), "server=1\n");
+
+        init_config(&conf, true).unwrap();
+        assert!(
+            std::fs::read_to_string(&conf)
+                .unwrap()
+                .contains("#txindex=0")
+        );
+
+        let toml = dir.path().join("p2pool.toml");
+        init_config(&toml, false).unwrap();
+        assert!(check_config(&toml, false).unwrap().is_empty());
     }
 
     #[test]
-    fn commit_edit_out_of_bounds_is_noop() {
-        let mut app = App::new();
-        // bitcoin_data is empty
-        let result = handle_action(AppAction::CommitEdit(5, "val".to_string()), &mut app);
-        assert!(result.is_ok());
+    fn check_config_reports_invalid_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let conf = dir.path().join("bitcoin.conf");
+
+        std::fs::write(&conf, "server=1\n[test]\nrpcport=0\n").unwrap();
+        assert_eq!(
+            check_config(&conf, false).unwrap(),
+            vec!["[test] rpcport: 0 is not a valid port (1-65535)"]
+        );
+
+        std::fs::write(&conf, "server=1\n").unwrap();
+        assert!(check_config(&conf, false).unwrap().is_empty());
+
+        // A p2pool file without a .toml name is still recognised
+        let p2pool = dir.path().join("p2pool.cfg");
+        write_valid_p2pool_toml(&p2pool);
+        assert!(check_config(&p2pool, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn check_config_checks_the_store_directory_only_when_asked() {
+        let dir = tempfile::tempdir().unwrap();
+        let p2pool = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&p2pool);
+        assert!(check_config(&p2pool, true).unwrap().is_empty());
+
+        let text = std::fs::read_to_string(&p2pool)
+            .unwrap()
+            .replace("./store.db", "./missing/store.db");
+        std::fs::write(&p2pool, text).unwrap();
+        assert!(check_config(&p2pool, false).unwrap().is_empty());
+        let problems = check_config(&p2pool, true).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("store.path: "), "{problems:?}");
+        assert!(problems[0].ends_with("does not exist"), "{problems:?}");
+    }
+
+    #[test]
+    fn export_json_picks_parser_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let conf = dir.path().join("bitcoin.conf");
+        std::fs::write(&conf, "server=1\n").unwrap();
+        let json = export_json(&conf, false).unwrap();
+        assert!(
+            json.as_array()
+                .unwrap()
+                .iter()
+                .any(|v| v["key"] == "server" && v["value"] == "1" && v["section"].is_null())
+        );
+
+        let toml = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&toml);
+        let json = export_json(&toml, false).unwrap();
+        assert!(
+            json.as_array()
+                .unwrap()
+                .iter()
+                .any(|v| v["section"] == "stratum" && v["key"] == "hostname")
+        );
+
+        assert!(export_json(&dir.path().join("missing.conf"), false).is_err());
+    }
+
+    #[test]
+    fn export_env_prints_p2pool_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&toml);
+
+        let env = export_env(&toml, false).unwrap();
+        assert!(env.lines().all(|l| l.starts_with("P2POOL_")), "{env}");
+        assert!(env.contains("P2POOL_NETWORK_LISTEN_ADDRESS=/ip4/127.0.0.1/tcp/6884\n"));
+        assert!(!env.contains("P2POOL_BITCOINRPC_PASSWORD"));
+        assert!(
+            export_env(&toml, true)
+                .unwrap()
+                .contains("P2POOL_BITCOINRPC_PASSWORD=")
+        );
+
+        let conf = dir.path().join("bitcoin.conf");
+        std::fs::write(&conf, "server=1\n").unwrap();
+        assert!(export_env(&conf, false).is_err());
+    }
+
+    #[test]
+    fn unknown_p2pool_keys_are_listed_above_the_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&path);
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(
+            &path,
+            text.replace("[network]\n", "[network]\ndial_peer = []\n"),
+        )
+        .unwrap();
+
+        let mut app = App::with_args(None, Some(path)).unwrap();
+
+        assert_eq!(
+            app.p2pool_unknown_keys,
+            ["unknown key network.dial_peer (did you mean dial_peers?)"]
+        );
+        let screen = render_to_string(&mut app);
+        assert!(screen.contains("Ignored by p2pool"));
+        assert!(screen.contains("network.dial_peer"));
+    }
+
+    #[test]
+    fn suspicious_network_limits_are_listed_above_the_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&path);
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(
+            &path,
+            text.replace(
+                "max_established_per_peer = 1\n",
+                "max_established_per_peer = 80\n",
+            ),
+        )
+        .unwrap();
+
+        let mut app = App::with_args(None, Some(path)).unwrap();
+
+        let screen = render_to_string(&mut app);
+        assert!(screen.contains("Suspicious network limits"));
+        assert!(screen.contains("max_established_per_peer (80)"));
+    }
+
+    /// Renders `app` on an 80x25 test terminal and returns the buffer text.
+    fn render_to_string(app: &mut App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(80, 25)).unwrap();
+        terminal.draw(|f| ui::ui(f, app)).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect()
+    }
+
+    fn press(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::empty())
+    }
+
+    #[test]
+    fn vim_j_and_k_move_sidebar_like_arrows() {
+        let mut arrows = App::new();
+        let mut vim = App::new();
+
+        for _ in 0..3 {
+            sidebar_nav(KeyCode::Down, &mut arrows);
+            sidebar_nav(vim_key(press('j'), vim.current_screen).code, &mut vim);
+            assert_eq!(vim.sidebar_index, arrows.sidebar_index);
+        }
+        sidebar_nav(KeyCode::Up, &mut arrows);
+        sidebar_nav(vim_key(press('k'), vim.current_screen).code, &mut vim);
+        assert_eq!(vim.sidebar_index, 2);
+        assert_eq!(arrows.sidebar_index, 2);
+    }
+
+    #[test]
+    fn vim_h_and_l_map_to_parent_and_enter_in_explorer() {
+        let explorer = CurrentScreen::FileExplorer;
+        assert_eq!(vim_key(press('h'), explorer).code, KeyCode::Backspace);
+        assert_eq!(vim_key(press('l'), explorer).code, KeyCode::Enter);
+        assert_eq!(
+            vim_key(press('h'), CurrentScreen::BitcoinStatus).code,
+            KeyCode::Left
+        );
+        assert_eq!(
+            vim_key(press('l'), CurrentScreen::BitcoinStatus).code,
+            KeyCode::Right
+        );
+        // Other letters and modified keys pass through untouched
+        assert_eq!(vim_key(press('q'), explorer).code, KeyCode::Char('q'));
+        let ctrl_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL);
+        assert_eq!(vim_key(ctrl_j, explorer), ctrl_j);
+    }
+
+    #[test]
+    fn generate_rpcauth_fills_entry_and_shows_password_once() {
+        use pdm::bitcoin_config::{get_default_schema, rpcauth_value};
+
+        let mut app = App::new();
+        app.bitcoin_data = get_default_schema()
+            .into_iter()
+            .filter(|s| s.key == "rpcauth")
+            .map(|s| BitcoinEntry {
+                schema: Some(s),
+                enabled: false,
+                ..BitcoinEntry::new(&s.key, &s.default)
+            })
+            .collect();
+
+        run(AppAction::GenerateRpcAuth("alice".to_string()), &mut app);
+
+        assert_eq!(app.bitcoin_data.len(), 1, "unset schema entry is reused");
+        let entry = &app.bitcoin_data[0];
+        assert!(entry.enabled);
+        assert!(app.bitcoin_config_view.dirty);
+        let notice = app.notice.clone().expect("password must be shown");
+        let password = notice
+            .split("Password (shown only once): ")
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .unwrap();
+        let salt = entry.value.split(['$', ':']).nth(1).unwrap();
+        assert_eq!(entry.value, rpcauth_value("alice", salt, password));
+
+        // Regenerating for alice replaces her line; a new user adds one
+        run(AppAction::GenerateRpcAuth("alice".to_string()), &mut app);
+        assert_eq!(app.bitcoin_data.len(), 1);
+        run(AppAction::GenerateRpcAuth("bob".to_string()), &mut app);
+        assert_eq!(app.bitcoin_data.len(), 2);
+        assert!(app.bitcoin_data[1].value.starts_with("bob:"));
+        assert_eq!(app.bitcoin_config_view.selected_index, 1);
+    }
+
+    #[test]
+    fn generate_rpcauth_keeps_other_users_disabled_lines_and_can_be_undone() {
+        let carol = "carol:0123$abcd";
+        let mut app = App::new();
+        app.bitcoin_data = vec![
+            BitcoinEntry {
+                enabled: false,
+                ..BitcoinEntry::new("rpcauth", carol)
+            },
+            BitcoinEntry::new("rpcauth", "alice:4567$ef01"),
+        ];
+
+        // Carol's commented-out line is not alice's to overwrite
+        run(AppAction::GenerateRpcAuth("alice".to_string()), &mut app);
+        assert_eq!(app.bitcoin_data.len(), 2);
+        assert_eq!(app.bitcoin_data[0].value, carol);
+        assert!(!app.bitcoin_data[0].enabled);
+        assert_ne!(app.bitcoin_data[1].value, "alice:4567$ef01");
+
+        // Replacing alice's line is an edit like any other
+        run(AppAction::Undo(SaveTarget::BitcoinConfig), &mut app);
+        assert_eq!(app.bitcoin_data[1].value, "alice:4567$ef01");
+    }
+
+    #[test]
+    fn add_bitcoin_entry_appends_enabled_schemaless_entry() {
+        let mut app = App::new();
+        app.bitcoin_data = vec![BitcoinEntry::new("server", "1")];
+
+        run(
+            AppAction::AddBitcoinEntry("myopt".to_string(), "1".to_string()),
+            &mut app,
+        );
+
+        let entry = app.bitcoin_data.last().unwrap();
+        assert_eq!((entry.key.as_str(), entry.value.as_str()), ("myopt", "1"));
+        assert!(entry.enabled);
+        assert!(entry.schema.is_none());
+        assert_eq!(app.bitcoin_config_view.selected_index, 1);
+        assert!(app.bitcoin_config_view.dirty);
+
+        // Duplicates and malformed keys are refused with a warning
+        run(
+            AppAction::AddBitcoinEntry("myopt".to_string(), "2".to_string()),
+            &mut app,
+        );
+        run(
+            AppAction::AddBitcoinEntry("".to_string(), "2".to_string()),
+            &mut app,
+        );
+        assert_eq!(app.bitcoin_data.len(), 2);
+        assert!(app.bitcoin_config_view.warning_message.is_some());
+    }
+
+    #[test]
+    fn go_back_pops_history_in_reverse_order() {
+        let mut app = App::new();
+
+        run(AppAction::Navigate(CurrentScreen::BitcoinConfig), &mut app);
+        run(
+            AppAction::OpenExplorer(ExplorerTrigger::BitcoinConfig),
+            &mut app,
+        );
+        assert_eq!(app.current_screen, CurrentScreen::FileExplorer);
+        assert_eq!(
+            app.history,
+            vec![CurrentScreen::Home, CurrentScreen::BitcoinConfig]
+        );
+
+        run(AppAction::GoBack, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::BitcoinConfig);
+        assert_eq!(app.sidebar_index, 1);
+
+        run(AppAction::GoBack, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Home);
+        assert_eq!(app.sidebar_index, 0);
+        assert!(app.history.is_empty());
+    }
+
+    #[test]
+    fn go_back_with_empty_history_stays_on_home() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::LNStatus;
+        app.sidebar_index = 6;
+
+        run(AppAction::GoBack, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Home);
+        assert_eq!(app.sidebar_index, 0);
+
+        run(AppAction::GoBack, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Home);
+    }
+
+    #[test]
+    fn cancelling_explorer_does_not_leave_stale_history() {
+        let mut app = App::new();
+        app.sidebar_index = 1;
+        app.toggle_menu();
+
+        run(
+            AppAction::OpenExplorer(ExplorerTrigger::BitcoinConfig),
+            &mut app,
+        );
+        // Explorer Esc still cancels the modal rather than going back
+        let action = app
+            .explorer
+            .handle_input(KeyEvent::new(KeyCode::Esc, KeyModifiers::empty()));
+        run(action, &mut app);
+
+        assert_eq!(app.current_screen, CurrentScreen::BitcoinConfig);
+        assert!(app.history.is_empty());
+    }
+
+    #[test]
+    fn sidebar_backspace_and_esc_go_back() {
+        let mut app = App::new();
+        assert!(matches!(
+            sidebar_nav(KeyCode::Backspace, &mut app),
+            AppAction::GoBack
+        ));
+        assert!(matches!(
+            sidebar_nav(KeyCode::Esc, &mut app),
+            AppAction::GoBack
+        ));
+    }
+
+    #[test]
+    fn request_quit_opens_exiting_and_remembers_screen() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::P2PoolConfig;
+
+        let flow = handle_action(AppAction::RequestQuit, &mut app).unwrap();
+
+        assert!(flow.is_continue());
+        assert_eq!(app.current_screen, CurrentScreen::Exiting);
+        assert_eq!(app.previous_screen, CurrentScreen::P2PoolConfig);
+
+        // A second request must not overwrite the remembered screen
+        run(AppAction::RequestQuit, &mut app);
+        assert_eq!(app.previous_screen, CurrentScreen::P2PoolConfig);
+    }
+
+    #[test]
+    fn exiting_y_quits() {
+        let mut app = App::new();
+        run(AppAction::RequestQuit, &mut app);
+
+        let flow = handle_action(AppAction::Quit, &mut app).unwrap();
+
+        assert!(flow.is_break());
+    }
+
+    #[test]
+    fn exiting_n_returns_to_previous_screen() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Settings;
+        run(AppAction::RequestQuit, &mut app);
+
+        let flow = handle_action(AppAction::CancelQuit, &mut app).unwrap();
+
+        assert!(flow.is_continue());
+        assert_eq!(app.current_screen, CurrentScreen::Settings);
+    }
+
+    #[test]
+    fn exiting_prompt_warns_only_with_unsaved_changes() {
+        let mut app = App::new();
+        run(AppAction::RequestQuit, &mut app);
+        let clean = render_to_string(&mut app);
+        assert!(clean.contains("Quit PDM? (y/n)"));
+        assert!(!clean.contains("unsaved"));
+
+        app.bitcoin_config_view.dirty = true;
+        let dirty = render_to_string(&mut app);
+        assert!(dirty.contains("Quit PDM? (y/n)"));
+        assert!(dirty.contains("unsaved"));
+    }
+
+    /// Loads `text` as the bitcoin config, the way selecting it would.
+    fn load_bitcoin_text(app: &mut App, path: &std::path::Path, text: &str) {
+        std::fs::write(path, text).unwrap();
+        app.bitcoin_data = parse_bitcoin_config(path).unwrap();
+        app.bitcoin_conf_path = Some(path.to_path_buf());
+    }
+
+    fn value_of<'a>(app: &'a App, key: &str) -> &'a str {
+        &app.bitcoin_data
+            .iter()
+            .find(|e| e.key == key && e.enabled)
+            .unwrap()
+            .value
+    }
+
+    fn index_of(app: &App, key: &str) -> usize {
+        app.bitcoin_data.iter().position(|e| e.key == key).unwrap()
+    }
+
+    #[test]
+    fn external_change_with_local_edits_prompts_for_reload() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "server=1\nrpcuser=alice\n");
+        app.bitcoin_config_view.dirty = true;
+
+        std::fs::write(&path, "server=1\nrpcuser=bob\n").unwrap();
+        run(
+            AppAction::ConfigChanged(SaveTarget::BitcoinConfig),
+            &mut app,
+        );
+
+        assert_eq!(app.pending_reload, Some(SaveTarget::BitcoinConfig));
+        assert_eq!(value_of(&app, "rpcuser"), "alice");
+        let output = render_to_string(&mut app);
+        assert!(output.contains("changed on disk"));
+        assert!(output.contains("Keep edits"));
+
+        let key = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::empty());
+        let action = confirm_reload_key(&mut app, key);
+        run(action, &mut app);
+
+        assert!(app.pending_reload.is_none());
+        assert_eq!(value_of(&app, "rpcuser"), "bob");
+        assert!(!app.bitcoin_config_view.dirty);
+    }
+
+    #[test]
+    fn external_change_without_local_edits_reloads_silently() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "server=1\nrpcuser=alice\n");
+
+        std::fs::write(&path, "server=1\nrpcuser=bob\n").unwrap();
+        run(
+            AppAction::ConfigChanged(SaveTarget::BitcoinConfig),
+            &mut app,
+        );
+
+        assert!(app.pending_reload.is_none());
+        assert_eq!(value_of(&app, "rpcuser"), "bob");
+    }
+
+    #[test]
+    fn reload_key_picks_up_edits_made_on_disk() {
+        use crossterm::event::{KeyEvent, KeyModifiers};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "server=1\nrpcuser=alice\n");
+        app.current_screen = CurrentScreen::BitcoinConfig;
+        app.bitcoin_config_view.sidebar_focused = false;
+
+        std::fs::write(&path, "server=1\nrpcuser=bob\n").unwrap();
+        let key = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::empty());
+        let action = app.bitcoin_config_view.handle_input(key, &app.bitcoin_data);
+        run(action, &mut app);
+
+        assert_eq!(value_of(&app, "rpcuser"), "bob");
+    }
+
+    #[test]
+    fn reload_with_unsaved_edits_asks_first_and_reports_parse_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&path);
+        let mut app = App::new();
+        app.p2pool_config = Some(load_p2pool_config(path.to_str().unwrap()).unwrap());
+        app.p2pool_conf_path = Some(path.clone());
+        app.p2pool_config_view.dirty = true;
+
+        std::fs::write(&path, "invalid === toml").unwrap();
+        run(AppAction::RequestReload(SaveTarget::P2PoolConfig), &mut app);
+        assert_eq!(app.pending_reload, Some(SaveTarget::P2PoolConfig));
+
+        run(AppAction::ReloadConfig(SaveTarget::P2PoolConfig), &mut app);
+        assert!(app.error.as_deref().unwrap().starts_with("Reload failed"));
+        // The loaded config survives a failed reload
+        assert!(app.p2pool_config.is_some());
+    }
+
+    #[test]
+    fn change_event_for_unchanged_contents_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "server=1\nrpcuser=alice\n");
+        app.bitcoin_config_view.dirty = true;
+
+        // What our own save looks like to the watcher
+        run(
+            AppAction::ConfigChanged(SaveTarget::BitcoinConfig),
+            &mut app,
+        );
+
+        assert!(app.pending_reload.is_none());
+        assert!(app.bitcoin_config_view.dirty);
+    }
+
+    #[test]
+    fn tick_redraws_without_changing_state() {
+        use crossterm::event::KeyEvent;
+
+        let mut app = App::new();
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let mut script = vec![
+            Some(Event::Key(KeyEvent::new(
+                KeyCode::Char('c'),
+                KeyModifiers::CONTROL,
+            ))),
+            None,
+            None,
+        ];
+        let mut polls = 0;
+
+        run_app(&mut terminal, &mut app, |_| {
+            polls += 1;
+            Ok(script.pop().flatten())
+        })
+        .unwrap();
+
+        // One draw precedes every poll, ticks included
+        assert_eq!(polls, 3);
+        assert_eq!(app.current_screen, CurrentScreen::Home);
+        assert_eq!(app.sidebar_index, 0);
+        assert!(render_to_string(&mut app).contains(" PDM "));
+    }
+
+    fn click(column: u16, row: u16) -> Event {
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::empty(),
+        })
+    }
+
+    /// Runs the app over `events` on a 100x30 terminal, then quits.
+    fn run_scripted(app: &mut App, events: Vec<Event>) {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let quit = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        let mut script = events.into_iter().chain([quit]);
+        run_app(&mut terminal, app, |_| Ok(script.next())).unwrap();
+    }
+
+    #[test]
+    fn clicking_a_sidebar_item_selects_it() {
+        let mut app = App::new();
+
+        // Border on row 0, so item 2 is on row 3
+        run_scripted(&mut app, vec![click(2, 3)]);
+
+        assert_eq!(app.sidebar_index, 2);
+        assert_eq!(app.current_screen, SIDEBAR_ITEMS[2].1);
+
+        // Clicks on the border or below the last item do nothing
+        run_scripted(&mut app, vec![click(0, 3), click(2, 28)]);
+        assert_eq!(app.sidebar_index, 2);
+    }
+
+    #[test]
+    fn clicking_and_scrolling_the_config_list_moves_the_selection() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "server=1\n");
+        app.current_screen = CurrentScreen::BitcoinConfig;
+        app.bitcoin_config_view.sidebar_focused = true;
+
+        // The list starts right of the sidebar; rows are two lines tall
+        run_scripted(&mut app, vec![click(30, 1 + 2 * 3)]);
+        assert_eq!(app.bitcoin_config_view.selected_index, 3);
+        assert!(!app.bitcoin_config_view.sidebar_focused);
+
+        let scroll = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            column: 30,
+            row: 5,
+            modifiers: KeyModifiers::empty(),
+        });
+        run_scripted(&mut app, vec![scroll]);
+        assert_eq!(app.bitcoin_config_view.selected_index, 4);
+    }
+
+    #[test]
+    fn clicking_another_p2pool_row_masks_the_revealed_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&file);
+        let mut app = App::new();
+        app.p2pool_config = Some(P2PoolConfig::load(file.to_str().unwrap()).unwrap());
+        app.p2pool_conf_path = Some(file);
+        app.current_screen = CurrentScreen::P2PoolConfig;
+        app.p2pool_config_view.revealed = Some(0);
+
+        select_list_row(&mut app, 1);
+
+        assert_eq!(app.p2pool_config_view.selected_index, 1);
+        assert_eq!(app.p2pool_config_view.revealed, None);
+    }
+
+    #[test]
+    fn q_is_typed_into_a_new_directory_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::FileExplorer;
+        app.explorer.current_dir = dir.path().to_path_buf();
+        app.explorer.load_directory();
+        app.explorer.new_dir_input = Some(String::new());
+
+        run_scripted(
+            &mut app,
+            vec![
+                Event::Key(press('q')),
+                Event::Key(KeyEvent::from(KeyCode::Enter)),
+            ],
+        );
+
+        assert_eq!(app.current_screen, CurrentScreen::FileExplorer);
+        assert!(dir.path().join("q").is_dir());
+    }
+
+    #[test]
+    fn q_is_typed_into_an_open_editor_instead_of_quitting() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "rpcuser=alice\n");
+        app.current_screen = CurrentScreen::BitcoinConfig;
+        app.bitcoin_config_view.sidebar_focused = false;
+        let index = index_of(&app, "rpcuser");
+        app.bitcoin_config_view
+            .start_edit(index, String::new(), &app.bitcoin_data);
+
+        run_scripted(&mut app, vec![Event::Key(press('q'))]);
+
+        assert_eq!(app.current_screen, CurrentScreen::BitcoinConfig);
+        assert!(app.bitcoin_config_view.editing);
+        assert_eq!(app.bitcoin_config_view.edit_input, "q");
+    }
+
+    #[test]
+    fn resize_recomputes_layout_and_keeps_the_selection_visible() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "server=1\n");
+        app.current_screen = CurrentScreen::BitcoinConfig;
+        app.bitcoin_config_view.sidebar_focused = false;
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+        assert_eq!(app.sidebar_area.height, 30);
+        let tall_viewport = app.viewport_height;
+        let tall_list = app.list_area;
+
+        // Select a row near the bottom of the tall viewport
+        let selected = usize::from(tall_viewport / 2) - 1;
+        app.bitcoin_config_view.selected_index = selected;
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+
+        terminal.backend_mut().resize(100, 12);
+        handle_resize(&mut terminal, &mut app, 100, 12).unwrap();
+
+        assert_eq!(app.sidebar_area.height, 12);
+        assert!(app.viewport_height < tall_viewport);
+        assert!(app.list_area.height < tall_list.height);
+        let state = &app.bitcoin_config_view.list_state;
+        let rows = page_size(&app, 2);
+        let row = state.selected().unwrap();
+        assert!(state.offset() <= row && row < state.offset() + rows);
+    }
+
+    #[test]
+    fn title_marks_unsaved_changes() {
+        let mut app = App::new();
+        let clean = render_to_string(&mut app);
+        assert!(clean.contains(" PDM "));
+        assert!(!clean.contains(" PDM * "));
+
+        app.p2pool_config_view.dirty = true;
+        assert!(render_to_string(&mut app).contains(" PDM * "));
+    }
+
+    #[test]
+    fn commit_edit_updates_entry_value_and_enables_it() {
+        use pdm::bitcoin_config::ConfigEntry;
+
+        let mut app = App::new();
+        app.bitcoin_data = vec![
+            ConfigEntry {
+                enabled: false,
+                ..ConfigEntry::new("rpcuser", "old")
+            },
+            ConfigEntry::new("server", "0"),
+        ];
+
+        run(AppAction::CommitEdit(0, "alice".to_string()), &mut app);
+
+        assert_eq!(app.bitcoin_data[0].value, "alice");
+        assert!(app.bitcoin_data[0].enabled);
+        // Other entries unchanged
+        assert_eq!(app.bitcoin_data[1].value, "0");
+    }
+
+    #[test]
+    fn commit_edit_out_of_bounds_is_noop() {
+        let mut app = App::new();
+        // bitcoin_data is empty
+        let result = handle_action(AppAction::CommitEdit(5, "val".to_string()), &mut app);
+        assert!(result.is_ok());
+    }
+
+    /// App with a single-entry bitcoin config loaded from a file on disk
+    /// holding `rpcuser=original`; the in-memory value has been edited.
+    fn app_with_edited_bitcoin_file(dir: &tempfile::TempDir) -> (App, std::path::PathBuf) {
+        use pdm::bitcoin_config::ConfigEntry;
+
+        let path = dir.path().join("bitcoin.conf");
+        std::fs::write(&path, "rpcuser=original\n").unwrap();
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::BitcoinConfig;
+        app.bitcoin_conf_path = Some(path.clone());
+        app.bitcoin_data = vec![ConfigEntry::new("rpcuser", "edited")];
+        (app, path)
+    }
+
+    #[test]
+    fn confirm_save_n_leaves_file_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut app, path) = app_with_edited_bitcoin_file(&dir);
+
+        run(AppAction::RequestSave(SaveTarget::BitcoinConfig), &mut app);
+        assert_eq!(app.pending_save, Some(SaveTarget::BitcoinConfig));
+
+        let action = confirm_save_key(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('n'), KeyModifiers::empty()),
+        );
+        run(action, &mut app);
+
+        assert!(app.pending_save.is_none());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "rpcuser=original\n"
+        );
+    }
+
+    #[test]
+    fn confirm_save_esc_cancels() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut app, path) = app_with_edited_bitcoin_file(&dir);
+
+        run(AppAction::RequestSave(SaveTarget::BitcoinConfig), &mut app);
+        let action = confirm_save_key(&mut app, KeyEvent::new(KeyCode::Esc, KeyModifiers::empty()));
+        run(action, &mut app);
+
+        assert!(app.pending_save.is_none());
+        assert_eq!(app.current_screen, CurrentScreen::BitcoinConfig);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "rpcuser=original\n"
+        );
+    }
+
+    #[test]
+    fn confirm_save_y_writes_new_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut app, path) = app_with_edited_bitcoin_file(&dir);
+
+        run(AppAction::RequestSave(SaveTarget::BitcoinConfig), &mut app);
+        let action = confirm_save_key(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('y'), KeyModifiers::empty()),
+        );
+        run(action, &mut app);
+
+        assert!(app.pending_save.is_none());
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("rpcuser=edited"));
+        assert_eq!(
+            app.bitcoin_config_view.save_message.as_deref(),
+            Some("Configuration correctly saved")
+        );
+    }
+
+    #[test]
+    fn confirm_save_renders_overwrite_prompt_with_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut app, _path) = app_with_edited_bitcoin_file(&dir);
+        run(AppAction::RequestSave(SaveTarget::BitcoinConfig), &mut app);
+
+        let mut terminal = Terminal::new(TestBackend::new(200, 30)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+        let output: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(output.contains("Overwrite "));
+        assert!(output.contains("bitcoin.conf? (y/n)"));
+        assert!(output.contains(".bak"));
+    }
+
+    #[test]
+    fn request_save_without_loaded_file_is_noop() {
+        let mut app = App::new();
+        run(AppAction::RequestSave(SaveTarget::P2PoolConfig), &mut app);
+        assert!(app.pending_save.is_none());
+    }
+
+    #[test]
+    fn save_bitcoin_config_writes_file_and_sets_message() {
+        use pdm::bitcoin_config::ConfigEntry;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+
+        let mut app = App::new();
+        app.bitcoin_conf_path = Some(path.clone());
+        app.bitcoin_data = vec![ConfigEntry::new("rpcuser", "testuser")];
+
+        run(AppAction::SaveBitcoinConfig, &mut app);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("rpcuser=testuser"));
+        assert_eq!(
+            app.bitcoin_config_view.save_message.as_deref(),
+            Some("Configuration correctly saved")
+        );
+    }
+
+    #[test]
+    fn rpc_password_is_read_from_a_file_on_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let secret = dir.path().join("rpc.secret");
+        std::fs::write(&secret, "s3cret\n").unwrap();
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "rpcuser=alice\n");
+        app.current_screen = CurrentScreen::BitcoinConfig;
+
+        // Setting rpcpassword to the path suggests the file entry instead
+        run(
+            AppAction::CommitEdit(index_of(&app, "rpcpassword"), secret.display().to_string()),
+            &mut app,
+        );
+        let hint = app.bitcoin_config_view.warning_message.clone().unwrap();
+        assert!(hint.contains("add rpcpassword@file="));
+
+        run(
+            AppAction::AddBitcoinEntry(PASSWORD_FILE_KEY.to_string(), secret.display().to_string()),
+            &mut app,
+        );
+        assert!(app.bitcoin_data.iter().any(|e| e.key == PASSWORD_FILE_KEY));
+        assert!(!render_to_string(&mut app).contains("s3cret"));
+
+        run(AppAction::SaveBitcoinConfig, &mut app);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("rpcpassword=s3cret\n"));
+        assert!(!content.contains(PASSWORD_FILE_KEY));
+
+        // A password file removed since it was added fails the save, which
+        // is reported instead of leaving the TUI
+        std::fs::remove_file(&secret).unwrap();
+        assert_eq!(
+            handle_action(AppAction::SaveBitcoinConfig, &mut app).unwrap(),
+            ControlFlow::Continue(())
+        );
+        assert!(
+            app.error
+                .as_deref()
+                .unwrap()
+                .starts_with("Save failed: rpcpassword@file: cannot read")
+        );
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("rpcpassword=s3cret\n")
+        );
+
+        // A file that cannot be read is refused when the entry is added
+        let mut app = App::new();
+        run(
+            AppAction::AddBitcoinEntry(
+                PASSWORD_FILE_KEY.to_string(),
+                dir.path().join("missing").display().to_string(),
+            ),
+            &mut app,
+        );
+        assert!(app.bitcoin_data.is_empty());
+        assert!(
+            app.bitcoin_config_view
+                .warning_message
+                .as_deref()
+                .unwrap()
+                .starts_with("rpcpassword@file: cannot read")
+        );
+
+        // Typing a path leaves the file alone until the edit is committed
+        app.bitcoin_data = vec![BitcoinEntry::new(
+            PASSWORD_FILE_KEY,
+            &secret.display().to_string(),
+        )];
+        let missing = dir.path().join("missing").display().to_string();
+        app.bitcoin_config_view
+            .start_edit(0, missing.clone(), &app.bitcoin_data);
+        assert!(app.bitcoin_config_view.edit_error.is_none());
+        run(AppAction::CommitEdit(0, missing), &mut app);
+        assert_eq!(app.bitcoin_data[0].value, secret.display().to_string());
+        assert!(
+            app.bitcoin_config_view
+                .warning_message
+                .as_deref()
+                .unwrap()
+                .starts_with("rpcpassword@file: cannot read")
+        );
+    }
+
+    #[test]
+    fn duplicating_an_entry_opens_an_editable_copy_below_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(
+            &mut app,
+            &path,
+            "rpcuser=alice\nzmqpubrawtx=tcp://127.0.0.1:28332\n",
+        );
+        run(
+            AppAction::CommitEdit(
+                index_of(&app, "zmqpubrawtx"),
+                "tcp://127.0.0.1:28333".into(),
+            ),
+            &mut app,
+        );
+        let len = app.bitcoin_data.len();
+        let index = index_of(&app, "rpcuser");
+
+        run(AppAction::DuplicateBitcoinEntry(index), &mut app);
+        assert_eq!(app.bitcoin_data.len(), len + 1);
+        let copy = &app.bitcoin_data[index + 1];
+        assert_eq!(
+            (copy.key.as_str(), copy.value.as_str()),
+            ("rpcuser", "alice")
+        );
+        assert!(copy.enabled);
+        // Until the copy is changed it is flagged as a repeat
+        assert_eq!(
+            copy.validation_error.as_deref(),
+            Some(pdm::bitcoin_config::DUPLICATE_ENTRY_CONFLICT)
+        );
+        let view = &app.bitcoin_config_view;
+        assert!(view.editing);
+        assert_eq!(view.selected_index, index + 1);
+        assert_eq!(view.edit_input, "alice");
+
+        // Undo still finds the entry the insert moved down
+        app.bitcoin_config_view.editing = false;
+        run(AppAction::Undo(SaveTarget::BitcoinConfig), &mut app);
+        assert_eq!(value_of(&app, "zmqpubrawtx"), "tcp://127.0.0.1:28332");
+
+        run(AppAction::CommitEdit(index + 1, "bob".into()), &mut app);
+        assert!(app.bitcoin_data[index + 1].validation_error.is_none());
+        run(AppAction::SaveBitcoinConfig, &mut app);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.matches("rpcuser=").count(), 2);
+        assert!(content.contains("rpcuser=bob\n"));
+    }
+
+    #[test]
+    fn duplicating_a_comma_value_copies_it_whole() {
+        let mut app = App::new();
+        app.bitcoin_data = vec![BitcoinEntry::new(
+            "rpcwhitelist",
+            "alice:getblock,getblockcount",
+        )];
+
+        run(AppAction::DuplicateBitcoinEntry(0), &mut app);
+        assert_eq!(app.bitcoin_data[0].value, "alice:getblock,getblockcount");
+        assert_eq!(app.bitcoin_data[1].value, "alice:getblock,getblockcount");
+        assert_eq!(
+            app.bitcoin_config_view.edit_input,
+            "alice:getblock,getblockcount"
+        );
+    }
+
+    #[test]
+    fn save_bitcoin_config_noop_when_no_path() {
+        let mut app = App::new();
+        // No bitcoin_conf_path set
+        let result = handle_action(AppAction::SaveBitcoinConfig, &mut app);
+        assert!(result.is_ok());
+        assert!(app.bitcoin_config_view.save_message.is_none());
+    }
+
+    #[test]
+    fn save_bitcoin_config_failure_is_shown_without_quitting() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        std::fs::write(&path, "server=0\n").unwrap();
+        let mut permissions = std::fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&path, permissions).unwrap();
+        let mut app = App::new();
+        app.bitcoin_conf_path = Some(path.clone());
+        app.bitcoin_data = vec![BitcoinEntry::new("server", "1")];
+        app.bitcoin_config_view.dirty = true;
+
+        let flow = handle_action(AppAction::SaveBitcoinConfig, &mut app).unwrap();
+        assert_eq!(flow, ControlFlow::Continue(()));
+        assert!(app.error.as_deref().unwrap().contains("read-only"));
+        assert!(app.bitcoin_config_view.dirty);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "server=0\n");
+    }
+
+    #[test]
+    fn saving_backs_up_each_previous_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "rpcuser=first\n");
+
+        run(
+            AppAction::CommitEdit(index_of(&app, "rpcuser"), "second".into()),
+            &mut app,
+        );
+        run(AppAction::SaveBitcoinConfig, &mut app);
+        run(
+            AppAction::CommitEdit(index_of(&app, "rpcuser"), "third".into()),
+            &mut app,
+        );
+        run(AppAction::SaveBitcoinConfig, &mut app);
+
+        let read = |name: &str| std::fs::read_to_string(dir.path().join(name)).unwrap();
+        assert_eq!(read("bitcoin.conf.bak"), "rpcuser=first\n");
+        assert!(read("bitcoin.conf.bak.1").contains("rpcuser=second"));
+        assert!(read("bitcoin.conf").contains("rpcuser=third"));
+    }
+
+    #[test]
+    fn ctrl_s_saves_a_valid_config_with_a_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut app, path) = app_with_edited_bitcoin_file(&dir);
+        app.bitcoin_config_view.sidebar_focused = false;
+        app.bitcoin_config_view.dirty = true;
+
+        let ctrl_s = Event::Key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        run_scripted(&mut app, vec![ctrl_s]);
+
+        // No overwrite prompt; the file is written straight away
+        assert!(app.pending_save.is_none());
+        assert!(app.error.is_none());
+        assert!(!app.bitcoin_config_view.dirty);
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("rpcuser=edited")
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("bitcoin.conf.bak")).unwrap(),
+            "rpcuser=original\n"
+        );
+    }
+
+    #[test]
+    fn save_validated_refuses_invalid_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "rpcport=8332\n");
+        app.bitcoin_config_view.dirty = true;
+
+        // Changed in memory without going through the editor's checks
+        let i = index_of(&app, "rpcport");
+        app.bitcoin_data[i].value = "0".to_string();
+        run(
+            AppAction::SaveValidated(SaveTarget::BitcoinConfig),
+            &mut app,
+        );
+
+        let error = app.error.as_deref().unwrap();
+        assert!(error.starts_with("Not saved"), "{error}");
+        assert!(error.contains("rpcport: 0 is not a valid port"), "{error}");
+        assert!(app.bitcoin_config_view.dirty);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "rpcport=8332\n");
+        assert!(!dir.path().join("bitcoin.conf.bak").exists());
+    }
+
+    #[test]
+    fn save_validated_refuses_an_invalid_p2pool_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&path);
+        let original = std::fs::read_to_string(&path).unwrap();
+        let mut app = App::new();
+        app.p2pool_conf_path = Some(path.clone());
+        let mut cfg = load_p2pool_config(&path.to_string_lossy()).unwrap();
+        cfg.stratum.minimum_difficulty = cfg.stratum.start_difficulty + 1;
+        app.p2pool_config = Some(cfg);
+        app.p2pool_config_view.dirty = true;
+
+        run(AppAction::SaveValidated(SaveTarget::P2PoolConfig), &mut app);
+
+        assert!(app.error.as_deref().unwrap().starts_with("Not saved"));
+        assert!(app.p2pool_config_view.dirty);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn show_diff_lists_edits_against_the_file_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "rpcuser=first\n");
+
+        run(AppAction::ShowDiff(SaveTarget::BitcoinConfig), &mut app);
+        assert!(app.diff.is_none());
+        assert!(app.notice.as_deref().unwrap().starts_with("No changes"));
+        app.notice = None;
+
+        run(
+            AppAction::CommitEdit(index_of(&app, "rpcuser"), "second".into()),
+            &mut app,
+        );
+        run(
+            AppAction::AddBitcoinEntry("mycustom".into(), "x".into()),
+            &mut app,
+        );
+        run(AppAction::ShowDiff(SaveTarget::BitcoinConfig), &mut app);
+
+        let lines: Vec<String> = app
+            .diff
+            .as_ref()
+            .expect("edits must produce a diff")
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(lines, vec!["~ rpcuser = first → second", "+ mycustom = x"]);
+        let screen = render_to_string(&mut app);
+        assert!(screen.contains("Unsaved changes"));
+        assert!(screen.contains("+ mycustom = x"));
     }
 
     #[test]
-    fn save_bitcoin_config_writes_file_and_sets_message() {
-        use pdm::bitcoin_config::ConfigEntry;
-        use tempfile::tempdir;
-
-        let dir = tempdir().unwrap();
+    fn undo_and_redo_walk_the_edit_history() {
+        let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("bitcoin.conf");
+        let mut app = App::new();
+        load_bitcoin_text(&mut app, &path, "rpcuser=first\n");
+        let index = index_of(&app, "rpcuser");
 
+        run(AppAction::CommitEdit(index, "second".into()), &mut app);
+        run(AppAction::CommitEdit(index, "third".into()), &mut app);
+
+        run(AppAction::Undo(SaveTarget::BitcoinConfig), &mut app);
+        assert_eq!(value_of(&app, "rpcuser"), "second");
+        assert!(app.bitcoin_config_view.dirty);
+        run(AppAction::Undo(SaveTarget::BitcoinConfig), &mut app);
+        assert_eq!(value_of(&app, "rpcuser"), "first");
+        // Back at the loaded state
+        assert!(!app.bitcoin_config_view.dirty);
+        // Nothing left to undo
+        run(AppAction::Undo(SaveTarget::BitcoinConfig), &mut app);
+        assert_eq!(value_of(&app, "rpcuser"), "first");
+
+        run(AppAction::Redo(SaveTarget::BitcoinConfig), &mut app);
+        assert_eq!(value_of(&app, "rpcuser"), "second");
+        assert!(app.bitcoin_config_view.dirty);
+    }
+
+    #[test]
+    fn a_new_edit_after_undo_drops_the_redo_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
         let mut app = App::new();
-        app.bitcoin_conf_path = Some(path.clone());
-        app.bitcoin_data = vec![ConfigEntry {
-            key: "rpcuser".to_string(),
-            value: "testuser".to_string(),
-            enabled: true,
-            schema: None,
-            section: None,
-        }];
+        load_bitcoin_text(&mut app, &path, "rpcuser=first\n");
+        let index = index_of(&app, "rpcuser");
 
+        run(AppAction::CommitEdit(index, "second".into()), &mut app);
         run(AppAction::SaveBitcoinConfig, &mut app);
+        run(AppAction::Undo(SaveTarget::BitcoinConfig), &mut app);
+        run(AppAction::CommitEdit(index, "other".into()), &mut app);
+
+        run(AppAction::Redo(SaveTarget::BitcoinConfig), &mut app);
+        assert_eq!(value_of(&app, "rpcuser"), "other");
+        // The saved "second" can no longer be reached by undoing
+        run(AppAction::Undo(SaveTarget::BitcoinConfig), &mut app);
+        assert_eq!(value_of(&app, "rpcuser"), "first");
+        assert!(app.bitcoin_config_view.dirty);
+    }
 
-        let content = std::fs::read_to_string(&path).unwrap();
-        assert!(content.contains("rpcuser=testuser"));
-        assert_eq!(
-            app.bitcoin_config_view.save_message.as_deref(),
-            Some("Configuration correctly saved")
+    #[test]
+    fn u_and_ctrl_r_map_to_undo_and_redo() {
+        let mut view = pdm::components::bitcoin_config_view::BitcoinConfigView::new();
+        view.sidebar_focused = false;
+        let undo = view.handle_input(
+            KeyEvent::new(KeyCode::Char('u'), KeyModifiers::empty()),
+            &[],
+        );
+        let redo = view.handle_input(
+            KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+            &[],
         );
+        assert!(matches!(undo, AppAction::Undo(SaveTarget::BitcoinConfig)));
+        assert!(matches!(redo, AppAction::Redo(SaveTarget::BitcoinConfig)));
     }
 
     #[test]
-    fn save_bitcoin_config_noop_when_no_path() {
+    fn skip_backups_setting_saves_without_a_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
         let mut app = App::new();
-        // No bitcoin_conf_path set
-        let result = handle_action(AppAction::SaveBitcoinConfig, &mut app);
-        assert!(result.is_ok());
-        assert!(app.bitcoin_config_view.save_message.is_none());
+        app.settings.skip_backups = true;
+        load_bitcoin_text(&mut app, &path, "rpcuser=first\n");
+
+        run(AppAction::SaveBitcoinConfig, &mut app);
+
+        assert!(!dir.path().join("bitcoin.conf.bak").exists());
     }
 
     #[test]
@@ -1039,13 +3679,7 @@ port = 46884
         use pdm::bitcoin_config::ConfigEntry;
 
         let mut app = App::new();
-        app.bitcoin_data = vec![ConfigEntry {
-            key: "rpcuser".to_string(),
-            value: "old".to_string(),
-            enabled: true,
-            schema: None,
-            section: None,
-        }];
+        app.bitcoin_data = vec![ConfigEntry::new("rpcuser", "old")];
 
         run(AppAction::CommitEdit(0, "new".to_string()), &mut app);
 
@@ -1064,13 +3698,7 @@ port = 46884
         let mut app = App::new();
         app.bitcoin_conf_path = Some(path.clone());
         app.bitcoin_config_view.dirty = true;
-        app.bitcoin_data = vec![ConfigEntry {
-            key: "rpcuser".to_string(),
-            value: "testuser".to_string(),
-            enabled: true,
-            schema: None,
-            section: None,
-        }];
+        app.bitcoin_data = vec![ConfigEntry::new("rpcuser", "testuser")];
 
         run(AppAction::SaveBitcoinConfig, &mut app);
 
@@ -1342,15 +3970,15 @@ port = 46884
         );
         assert_eq!(app.current_screen, CurrentScreen::Settings);
         assert!(!app.settings_view.sidebar_focused);
-        // allow_dir_select must be reset after selection
-        assert!(!app.explorer.allow_dir_select);
+        // select_mode must be reset after selection
+        assert_eq!(app.explorer.select_mode, SelectMode::File);
     }
 
     #[test]
     fn open_explorer_for_settings_field4_enables_dir_select() {
         let mut app = App::new();
         run(AppAction::OpenExplorerForSettings(4), &mut app);
-        assert!(app.explorer.allow_dir_select);
+        assert_eq!(app.explorer.select_mode, SelectMode::Directory);
         assert_eq!(app.current_screen, CurrentScreen::FileExplorer);
     }
 
@@ -1358,25 +3986,52 @@ port = 46884
     fn open_explorer_for_settings_non_dir_field_disables_dir_select() {
         let mut app = App::new();
         // First enable dir select, then open a file-picker field — must reset.
-        app.explorer.allow_dir_select = true;
+        app.explorer.select_mode = SelectMode::Directory;
         run(AppAction::OpenExplorerForSettings(0), &mut app);
-        assert!(!app.explorer.allow_dir_select);
+        assert_eq!(app.explorer.select_mode, SelectMode::File);
     }
 
     #[test]
-    fn close_modal_resets_allow_dir_select() {
+    fn close_modal_resets_select_mode() {
         let mut app = App::new();
-        app.explorer.allow_dir_select = true;
+        app.explorer.select_mode = SelectMode::Directory;
         app.explorer_trigger = Some(ExplorerTrigger::Settings(4));
         app.current_screen = CurrentScreen::FileExplorer;
         app.sidebar_index = MAX_SIDEBAR_INDEX;
 
         run(AppAction::CloseModal, &mut app);
 
-        assert!(!app.explorer.allow_dir_select);
+        assert_eq!(app.explorer.select_mode, SelectMode::File);
         assert!(app.explorer_trigger.is_none());
     }
 
+    #[test]
+    fn reopening_the_explorer_resumes_the_last_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let visited = dir.path().join("configs");
+        std::fs::create_dir(&visited).unwrap();
+        let mut app = App::new();
+
+        run(
+            AppAction::OpenExplorer(ExplorerTrigger::BitcoinConfig),
+            &mut app,
+        );
+        app.explorer.current_dir = visited.clone();
+        app.explorer.load_directory();
+        run(AppAction::CloseModal, &mut app);
+        run(
+            AppAction::OpenExplorer(ExplorerTrigger::P2PoolConfig),
+            &mut app,
+        );
+        assert_eq!(app.explorer.current_dir, visited);
+
+        // A directory deleted in between falls back to the working directory
+        run(AppAction::CloseModal, &mut app);
+        std::fs::remove_dir(&visited).unwrap();
+        run(AppAction::OpenExplorerForSettings(0), &mut app);
+        assert_eq!(app.explorer.current_dir, std::env::current_dir().unwrap());
+    }
+
     // Fix 16: CloseModal clears the ExplorerTrigger when triggered from Settings
     #[test]
     fn close_modal_clears_settings_explorer_trigger() {
@@ -1458,6 +4113,27 @@ port = 46884
         assert!(app.settings_view.save_error.is_none());
     }
 
+    #[test]
+    #[serial]
+    fn toggle_settings_field_flips_skip_backups_and_saves() {
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        redirect_saves_to(&dir);
+
+        let mut app = App::new();
+        run(AppAction::ToggleSettingsField(5), &mut app);
+        assert!(app.settings.skip_backups);
+        assert!(load_settings().skip_backups);
+
+        run(AppAction::ToggleSettingsField(5), &mut app);
+        assert!(!app.settings.skip_backups);
+
+        app.settings.skip_backups = true;
+        run(AppAction::ClearSettingsField(5), &mut app);
+        assert!(!app.settings.skip_backups);
+    }
+
     #[test]
     fn commit_p2pool_edit_success_clears_warning() {
         use std::path::PathBuf;
@@ -1482,6 +4158,255 @@ port = 46884
         assert!(app.p2pool_config_view.warning_message.is_none());
     }
 
+    fn stratum_port_index(cfg: &P2PoolConfig) -> usize {
+        use pdm::p2poolv2_config::{ConfigSection, flatten_config};
+
+        flatten_config(cfg)
+            .iter()
+            .position(|e| e.section == ConfigSection::Stratum && e.key == "port")
+            .unwrap()
+    }
+
+    #[test]
+    fn p2pool_editor_flags_an_invalid_address_until_it_is_fixed() {
+        use pdm::p2poolv2_config::{ConfigSection, flatten_config};
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&file);
+        let mut app = App::new();
+        let cfg = P2PoolConfig::load(file.to_str().unwrap()).unwrap();
+        let idx = flatten_config(&cfg)
+            .iter()
+            .position(|e| e.section == ConfigSection::Stratum && e.key == "solo_address")
+            .unwrap();
+        app.p2pool_config = Some(cfg);
+        app.p2pool_conf_path = Some(file);
+        app.current_screen = CurrentScreen::P2PoolConfig;
+        app.p2pool_config_view.sidebar_focused = false;
+        app.p2pool_config_view.selected_index = idx;
+        let press = |code| Event::Key(KeyEvent::new(code, KeyModifiers::empty()));
+
+        // A stray character breaks the checksum; Enter keeps the editor open
+        run_scripted(
+            &mut app,
+            vec![press(KeyCode::Enter), press(KeyCode::Char('x'))],
+        );
+        assert!(app.p2pool_config_view.edit_error.is_some());
+        run_scripted(&mut app, vec![press(KeyCode::Enter)]);
+        assert!(app.p2pool_config_view.editing);
+        assert!(!app.p2pool_config_view.dirty);
+
+        run_scripted(&mut app, vec![press(KeyCode::Backspace)]);
+        assert!(app.p2pool_config_view.edit_error.is_none());
+        run_scripted(&mut app, vec![press(KeyCode::Enter)]);
+        assert!(!app.p2pool_config_view.editing);
+        assert!(app.p2pool_config_view.dirty);
+    }
+
+    #[test]
+    fn commit_p2pool_edit_stratum_port_updates_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&file);
+
+        let mut app = App::new();
+        let cfg = P2PoolConfig::load(file.to_str().unwrap()).unwrap();
+        let idx = stratum_port_index(&cfg);
+        app.p2pool_config = Some(cfg);
+
+        run(
+            AppAction::CommitP2PoolEdit(idx, "4444".to_string()),
+            &mut app,
+        );
+
+        assert_eq!(app.p2pool_config.as_ref().unwrap().stratum.port, 4444);
+        assert!(app.p2pool_config_view.dirty);
+        assert!(app.p2pool_config_view.warning_message.is_none());
+    }
+
+    #[test]
+    fn undo_and_redo_walk_p2pool_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&file);
+
+        let mut app = App::new();
+        let cfg = P2PoolConfig::load(file.to_str().unwrap()).unwrap();
+        let idx = stratum_port_index(&cfg);
+        let port = cfg.stratum.port;
+        app.p2pool_config = Some(cfg);
+        let base = app.p2pool_entries().len();
+
+        run(AppAction::CommitP2PoolEdit(idx, "4444".into()), &mut app);
+        run(
+            AppAction::AddP2PoolEntry("stratum".into(), "myopt".into(), "1".into()),
+            &mut app,
+        );
+
+        run(AppAction::Undo(SaveTarget::P2PoolConfig), &mut app);
+        assert_eq!(app.p2pool_entries().len(), base);
+        run(AppAction::Undo(SaveTarget::P2PoolConfig), &mut app);
+        assert_eq!(app.p2pool_config.as_ref().unwrap().stratum.port, port);
+        assert_eq!(app.p2pool_config_view.selected_index, idx);
+        // Back at the loaded state
+        assert!(!app.p2pool_config_view.dirty);
+
+        run(AppAction::Redo(SaveTarget::P2PoolConfig), &mut app);
+        assert_eq!(app.p2pool_config.as_ref().unwrap().stratum.port, 4444);
+        assert!(app.p2pool_config_view.dirty);
+    }
+
+    #[test]
+    fn editing_store_path_rechecks_its_directory() {
+        use pdm::p2poolv2_config::ConfigSection;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&file);
+
+        let mut app = App::new();
+        app.p2pool_config = Some(P2PoolConfig::load(file.to_str().unwrap()).unwrap());
+        let idx = app
+            .p2pool_entries()
+            .iter()
+            .position(|e| e.section == ConfigSection::Store && e.key == "path")
+            .unwrap();
+        let store_warnings = |app: &App| {
+            app.p2pool_warnings()
+                .into_iter()
+                .filter(|w| w.field == "store.path")
+                .count()
+        };
+
+        let missing = dir.path().join("missing/store.db");
+        run(
+            AppAction::CommitP2PoolEdit(idx, missing.display().to_string()),
+            &mut app,
+        );
+        assert_eq!(store_warnings(&app), 1);
+
+        let present = dir.path().join("store.db");
+        run(
+            AppAction::CommitP2PoolEdit(idx, present.display().to_string()),
+            &mut app,
+        );
+        assert_eq!(store_warnings(&app), 0);
+    }
+
+    #[test]
+    fn commit_p2pool_edit_rejects_out_of_range_port() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&file);
+
+        let mut app = App::new();
+        let cfg = P2PoolConfig::load(file.to_str().unwrap()).unwrap();
+        let original = cfg.stratum.port;
+        let idx = stratum_port_index(&cfg);
+        app.p2pool_config = Some(cfg);
+
+        run(
+            AppAction::CommitP2PoolEdit(idx, "70000".to_string()),
+            &mut app,
+        );
+
+        assert_eq!(app.p2pool_config.as_ref().unwrap().stratum.port, original);
+        assert!(!app.p2pool_config_view.dirty);
+        assert!(app.p2pool_config_view.warning_message.is_some());
+    }
+
+    fn toggle_entry(value: &str, enabled: bool, default: &str) -> pdm::bitcoin_config::ConfigEntry {
+        use pdm::bitcoin_config::{ConfigCategory, ConfigEntry, ConfigSchema, ConfigType};
+
+        ConfigEntry {
+            enabled,
+            schema: Some(ConfigSchema::new(
+                "dbcache",
+                default,
+                ConfigType::Int,
+                ConfigCategory::Core,
+                "DB cache size",
+            )),
+            ..ConfigEntry::new("dbcache", value)
+        }
+    }
+
+    #[test]
+    fn toggle_bitcoin_entry_flips_enabled_and_display() {
+        use pdm::components::bitcoin_config_view::entry_display;
+
+        let mut app = App::new();
+        app.bitcoin_data = vec![toggle_entry("1000", true, "450")];
+        assert_eq!(entry_display(&app.bitcoin_data[0], &app.theme).0, "1000");
+
+        run(AppAction::ToggleBitcoinEntry(0), &mut app);
+        assert!(!app.bitcoin_data[0].enabled);
+        assert!(app.bitcoin_config_view.dirty);
+        assert_eq!(
+            entry_display(&app.bitcoin_data[0], &app.theme).0,
+            "(default: 450)"
+        );
+
+        run(AppAction::ToggleBitcoinEntry(0), &mut app);
+        assert!(app.bitcoin_data[0].enabled);
+        assert_eq!(entry_display(&app.bitcoin_data[0], &app.theme).0, "1000");
+    }
+
+    #[test]
+    fn toggle_bitcoin_entry_with_empty_value_uses_default() {
+        let mut app = App::new();
+        app.bitcoin_data = vec![toggle_entry("", false, "450")];
+
+        run(AppAction::ToggleBitcoinEntry(0), &mut app);
+
+        assert!(app.bitcoin_data[0].enabled);
+        assert_eq!(app.bitcoin_data[0].value, "450");
+    }
+
+    #[test]
+    fn toggle_bitcoin_entry_without_value_or_default_prompts() {
+        let mut app = App::new();
+        app.bitcoin_data = vec![toggle_entry("", false, "")];
+
+        run(AppAction::ToggleBitcoinEntry(0), &mut app);
+
+        assert!(!app.bitcoin_data[0].enabled);
+        assert!(app.bitcoin_config_view.editing);
+        assert!(!app.bitcoin_config_view.dirty);
+        // The empty prompt is checked like any edit, so Enter can't commit it
+        assert_eq!(
+            app.bitcoin_config_view.edit_error.as_deref(),
+            Some("must be an integer")
+        );
+    }
+
+    #[test]
+    fn commit_edit_rejects_invalid_bitcoin_port() {
+        use pdm::bitcoin_config::{ConfigCategory, ConfigEntry, ConfigSchema, ConfigType};
+
+        let mut app = App::new();
+        app.bitcoin_data = vec![ConfigEntry {
+            schema: Some(ConfigSchema::new(
+                "rpcport",
+                "8332",
+                ConfigType::Int,
+                ConfigCategory::RPC,
+                "RPC port",
+            )),
+            ..ConfigEntry::new("rpcport", "8332")
+        }];
+
+        run(AppAction::CommitEdit(0, "99999".to_string()), &mut app);
+
+        assert_eq!(app.bitcoin_data[0].value, "8332");
+        assert!(!app.bitcoin_config_view.dirty);
+        assert_eq!(
+            app.bitcoin_config_view.warning_message.as_deref(),
+            Some("rpcport: 99999 is not a valid port (1-65535)")
+        );
+    }
+
     #[test]
     fn commit_p2pool_edit_failure_sets_warning() {
         let dir = tempfile::tempdir().unwrap();
@@ -1540,51 +4465,32 @@ port = 46884
     }
 
     #[test]
-    fn typed_toml_item_like_integer_success() {
-        let existing = toml_edit::value(3333);
-        let updated = typed_toml_item_like(&existing, "4444").unwrap();
-        assert_eq!(updated.as_integer(), Some(4444));
-    }
-
-    #[test]
-    fn typed_toml_item_like_float_success() {
-        let existing = toml_edit::value(1.5);
-        let updated = typed_toml_item_like(&existing, "2.5").unwrap();
-        assert_eq!(updated.as_float(), Some(2.5));
-    }
-
-    #[test]
-    fn typed_toml_item_like_bool_success() {
-        let existing = toml_edit::value(true);
-        let updated = typed_toml_item_like(&existing, "false").unwrap();
-        assert_eq!(updated.as_bool(), Some(false));
-    }
-
-    #[test]
-    fn typed_toml_item_like_string_success() {
-        let existing = toml_edit::value("old");
-        let updated = typed_toml_item_like(&existing, "new").unwrap();
-        assert_eq!(updated.as_str(), Some("new"));
-    }
+    fn saving_a_json_p2pool_config_is_refused_before_the_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&toml);
+        let json = dir.path().join("p2pool.json");
+        std::fs::write(&json, "{}").unwrap();
 
-    #[test]
-    fn typed_toml_item_like_invalid_parse_fails() {
-        let existing = toml_edit::value(true);
-        assert!(typed_toml_item_like(&existing, "not_bool").is_err());
+        let mut app = App::new();
+        app.p2pool_config = Some(P2PoolConfig::load(toml.to_str().unwrap()).unwrap());
+        app.p2pool_conf_path = Some(json.clone());
+        run(AppAction::SaveP2PoolConfig, &mut app);
 
-        let existing = toml_edit::value(1);
-        assert!(typed_toml_item_like(&existing, "abc").is_err());
+        assert!(app.error.as_deref().unwrap().contains("is not TOML"));
+        assert!(!pdm::util::backup_path(&json).exists());
+        assert_eq!(std::fs::read_to_string(&json).unwrap(), "{}");
     }
 
     #[test]
-    fn save_p2pool_config_missing_file_fails() {
+    fn save_p2pool_config_missing_directory_fails() {
         let dir = tempfile::tempdir().unwrap();
         let valid = dir.path().join("valid.toml");
         write_valid_p2pool_toml(&valid);
         let cfg = P2PoolConfig::load(valid.to_str().unwrap()).unwrap();
 
-        let missing = dir.path().join("missing.toml");
-        let result = save_p2pool_config(&missing, &cfg);
+        let missing = dir.path().join("missing/p2pool.toml");
+        let result = save_p2pool_config(&missing, &cfg, &[]);
         assert!(result.is_err());
     }
 
@@ -1598,10 +4504,35 @@ port = 46884
         write_valid_p2pool_toml(&valid);
         let cfg = P2PoolConfig::load(valid.to_str().unwrap()).unwrap();
 
-        let result = save_p2pool_config(&file, &cfg);
+        let result = save_p2pool_config(&file, &cfg, &[]);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn add_p2pool_entry_is_saved_to_its_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("p2pool.toml");
+        write_valid_p2pool_toml(&file);
+
+        let mut app = App::new();
+        app.p2pool_config = Some(P2PoolConfig::load(file.to_str().unwrap()).unwrap());
+        app.p2pool_conf_path = Some(file.clone());
+        let base = app.p2pool_entries().len();
+
+        run(
+            AppAction::AddP2PoolEntry("stratum".to_string(), "myopt".to_string(), "1".to_string()),
+            &mut app,
+        );
+        assert_eq!(app.p2pool_entries().len(), base + 1);
+        assert_eq!(app.p2pool_config_view.selected_index, base);
+
+        run(AppAction::CommitP2PoolEdit(base, "2".to_string()), &mut app);
+        run(AppAction::SaveP2PoolConfig, &mut app);
+
+        let doc: toml_edit::DocumentMut = std::fs::read_to_string(&file).unwrap().parse().unwrap();
+        assert_eq!(doc["stratum"]["myopt"].as_integer(), Some(2));
+    }
+
     #[test]
     fn file_selected_p2pool_invalid_hostname_sets_warning() {
         let dir = tempfile::tempdir().unwrap();
@@ -1618,6 +4549,68 @@ port = 46884
         assert!(app.p2pool_config.is_none());
     }
 
+    #[test]
+    fn file_selected_invalid_p2pool_shows_error_modal() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("bad.toml");
+        std::fs::write(&file, "invalid === toml").unwrap();
+
+        let mut app = App::new();
+        app.explorer_trigger = Some(ExplorerTrigger::P2PoolConfig);
+        run(AppAction::FileSelected(file), &mut app);
+
+        let error = app
+            .error
+            .clone()
+            .expect("load failure must populate app.error");
+        assert!(error.starts_with("Failed to load P2Pool config"));
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| ui::ui(f, &mut app)).unwrap();
+        let output: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(output.contains(" Error "));
+        assert!(output.contains("Failed to load P2Pool"));
+        assert!(output.contains("Press any key to dismiss"));
+    }
+
+    #[test]
+    #[serial]
+    fn file_selected_routes_by_detected_config_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        redirect_saves_to(&dir);
+        let toml = dir.path().join("pool.conf");
+        write_valid_p2pool_toml(&toml);
+
+        let mut app = App::new();
+        app.explorer_trigger = Some(ExplorerTrigger::BitcoinConfig);
+        run(AppAction::FileSelected(toml.clone()), &mut app);
+
+        assert_eq!(app.current_screen, CurrentScreen::P2PoolConfig);
+        assert_eq!(app.p2pool_conf_path, Some(toml));
+        assert!(app.bitcoin_conf_path.is_none());
+        assert!(app.notice.as_deref().unwrap().contains("p2pool config"));
+
+        let conf = dir.path().join("bitcoin.toml");
+        std::fs::write(&conf, "server=1\n").unwrap();
+        app.notice = None;
+        app.explorer_trigger = Some(ExplorerTrigger::P2PoolConfig);
+        run(AppAction::FileSelected(conf.clone()), &mut app);
+
+        assert_eq!(app.current_screen, CurrentScreen::BitcoinConfig);
+        assert_eq!(app.bitcoin_conf_path, Some(conf));
+        assert!(app.notice.as_deref().unwrap().contains("bitcoin.conf"));
+        assert_eq!(
+            SIDEBAR_ITEMS[app.sidebar_index].1,
+            CurrentScreen::BitcoinConfig
+        );
+    }
+
     #[test]
     fn file_selected_p2pool_parse_failure_sets_warning() {
         let dir = tempfile::tempdir().unwrap();
@@ -2,9 +2,12 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::error::ConfigError;
+use crate::warning::Warning;
 use bitcoin::Network;
 use p2poolv2_config::Config;
 use std::fmt;
+use std::path::Path;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ConfigSection {
@@ -14,6 +17,8 @@ pub enum ConfigSection {
     Store,
     Logging,
     Api,
+    /// A table the typed `Config` doesn't model, added by the user.
+    Custom(String),
 }
 
 impl fmt::Display for ConfigSection {
@@ -25,6 +30,7 @@ impl fmt::Display for ConfigSection {
             ConfigSection::Store => write!(f, "store"),
             ConfigSection::Logging => write!(f, "logging"),
             ConfigSection::Api => write!(f, "api"),
+            ConfigSection::Custom(name) => write!(f, "{name}"),
         }
     }
 }
@@ -43,6 +49,18 @@ pub struct P2PoolFieldSchema {
     pub sensitive: bool,
 }
 
+/// Where a flattened value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Origin {
+    /// Set in the TOML file.
+    #[default]
+    File,
+    /// Overridden by a `P2POOL_<SECTION>_<KEY>` environment variable.
+    Env,
+    /// Absent from both, so the loader's default applies.
+    Default,
+}
+
 /// A single editable TUI row — the view layer equivalent of
 /// `ConfigEntry` in bitcoin_config.rs.
 /// The external `p2poolv2_config` crate has no concept of this;
@@ -54,10 +72,22 @@ pub struct P2PoolConfigEntry {
     pub value: String,
     pub enabled: bool,
     pub schema: P2PoolFieldSchema,
+    pub origin: Origin,
 }
 
 impl P2PoolConfigEntry {
-    fn required(
+    /// True for an unset optional field or a value equal to its declared
+    /// default, i.e. anything the user has not actually customized.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        !self.enabled
+            || matches!(
+                &self.schema.kind,
+                FieldKind::Optional { default: Some(d) } if *d == self.value
+            )
+    }
+
+    pub(crate) fn required(
         section: ConfigSection,
         key: &str,
         value: String,
@@ -75,6 +105,7 @@ impl P2PoolConfigEntry {
                 type_hint: type_hint.to_string(),
                 sensitive: false,
             },
+            origin: Origin::File,
         }
     }
 
@@ -100,6 +131,35 @@ impl P2PoolConfigEntry {
                 type_hint: type_hint.to_string(),
                 sensitive: false,
             },
+            origin: Origin::File,
+        }
+    }
+
+    /// A user-added `section.key` the typed `Config` doesn't know about.
+    /// It is kept alongside the flattened entries and only written on save.
+    #[must_use]
+    pub fn custom(section: &str, key: &str, value: &str) -> Self {
+        let section = match section {
+            "stratum" => ConfigSection::Stratum,
+            "bitcoinrpc" => ConfigSection::BitcoinRpc,
+            "network" => ConfigSection::Network,
+            "store" => ConfigSection::Store,
+            "logging" => ConfigSection::Logging,
+            "api" => ConfigSection::Api,
+            other => ConfigSection::Custom(other.to_string()),
+        };
+        Self {
+            section,
+            key: key.to_string(),
+            value: value.to_string(),
+            enabled: true,
+            schema: P2PoolFieldSchema {
+                description: "Custom option".to_string(),
+                kind: FieldKind::Optional { default: None },
+                type_hint: "custom".to_string(),
+                sensitive: false,
+            },
+            origin: Origin::File,
         }
     }
 
@@ -107,6 +167,426 @@ impl P2PoolConfigEntry {
         self.schema.sensitive = true;
         self
     }
+
+    /// `section.key`, the name warnings and diffs refer to the entry by.
+    #[must_use]
+    pub fn field(&self) -> String {
+        format!("{}.{}", self.section, self.key)
+    }
+
+    /// Environment variable the loader reads to override this entry.
+    ///
+    /// The loader splits names on every `_`, so a key that itself contains
+    /// one, such as `solo_address`, gets a name it reads back as a different
+    /// path (`stratum.solo.address`).
+    #[must_use]
+    pub fn env_var(&self) -> String {
+        format!(
+            "P2POOL_{}_{}",
+            self.section.to_string().to_uppercase(),
+            self.key.to_uppercase()
+        )
+    }
+}
+
+/// Starter p2pool config returned by [`default_config_string`]: every
+/// section, required keys at working signet defaults except an empty
+/// `bootstrap_address`, and optional keys commented out.
+const DEFAULT_CONFIG: &str = r#"# p2pool config generated by PDM
+# Required keys hold signet defaults, except bootstrap_address, which must
+# be set; optional keys are commented out.
+
+[stratum]
+# Stratum server hostname
+hostname = "0.0.0.0"
+# Stratum server port
+port = 3333
+# Initial difficulty assigned to new miners
+start_difficulty = 10000
+# Minimum allowed difficulty
+minimum_difficulty = 100
+# Maximum allowed difficulty (unset = unlimited)
+#maximum_difficulty = 100000
+# Bitcoin address for solo mining payouts
+#solo_address = ""
+# ZMQ address for new block notifications
+zmqpubhashblock = "tcp://127.0.0.1:28332"
+# Bitcoin address for first jobs before any share exists; set your own
+bootstrap_address = ""
+# Developer donation address (must pair with donation)
+#donation_address = ""
+# Developer donation in basis points (100 = 1%)
+#donation = 0
+# Pool fee address (must pair with fee)
+#fee_address = ""
+# Pool fee in basis points (100 = 1%)
+#fee = 0
+# Bitcoin network: main, test, testnet4, signet or regtest
+network = "signet"
+# Version rolling mask (hex)
+version_mask = "1fffe000"
+# Multiplier applied to share difficulty
+difficulty_multiplier = 1.0
+# Accept shares regardless of difficulty
+#ignore_difficulty = false
+# Text added to coinbase transactions
+#pool_signature = ""
+
+[bitcoinrpc]
+# bitcoind RPC URL
+url = "http://127.0.0.1:38332"
+username = "rpcuser"
+password = "rpcpassword"
+
+[network]
+# libp2p multiaddr to listen on
+listen_address = "/ip4/0.0.0.0/tcp/6884"
+# Multiaddrs of peers to dial at startup
+dial_peers = []
+max_pending_incoming = 10
+max_pending_outgoing = 10
+max_established_incoming = 50
+max_established_outgoing = 50
+max_established_per_peer = 1
+max_workbase_per_second = 10
+max_userworkbase_per_second = 10
+max_miningshare_per_second = 100
+max_inventory_per_second = 100
+max_transaction_per_second = 100
+max_requests_per_second = 100
+dial_timeout_secs = 30
+
+[store]
+# Share chain database directory
+path = "./store.db"
+background_task_frequency_hours = 24
+pplns_ttl_days = 7
+
+[logging]
+# Log file; unset logs to the console only
+#file = "./logs/p2pool.log"
+level = "info"
+stats_dir = "./logs/stats"
+console = true
+
+[api]
+hostname = "127.0.0.1"
+port = 46884
+#auth_user = ""
+#auth_token = ""
+#auth_password = ""
+"#;
+
+/// Returns a starter p2pool TOML covering every section, ready to load.
+///
+/// The bootstrap address is left empty and the RPC credentials are
+/// examples; both must be set before mining.
+#[must_use]
+pub fn default_config_string() -> String {
+    DEFAULT_CONFIG.to_string()
+}
+
+/// Exports entries as a JSON array for scripting, one object per entry:
+/// `{section, key, value, enabled, is_default}`. Sensitive values are
+/// replaced with `*****` so the output is safe to log, unless
+/// `include_secrets` is set. `is_default` is true for unset optional fields
+/// and for values equal to their declared default.
+#[must_use]
+pub fn config_to_json(entries: &[P2PoolConfigEntry], include_secrets: bool) -> serde_json::Value {
+    entries
+        .iter()
+        .map(|entry| {
+            let is_default = entry.is_default();
+            let value = if entry.schema.sensitive && !include_secrets {
+                "*****"
+            } else {
+                entry.value.as_str()
+            };
+            serde_json::json!({
+                "section": entry.section.to_string(),
+                "key": entry.key,
+                "value": value,
+                "enabled": entry.enabled,
+                "is_default": is_default,
+            })
+        })
+        .collect()
+}
+
+/// Groups `entries` by section name, sections in the order they first
+/// appear and entries in their original order within each. A section that
+/// comes back later, as custom keys do, joins its first group.
+#[must_use]
+pub fn group_by_section<'a>(
+    entries: impl IntoIterator<Item = &'a P2PoolConfigEntry>,
+) -> Vec<(String, Vec<&'a P2PoolConfigEntry>)> {
+    let mut groups: Vec<(String, Vec<&'a P2PoolConfigEntry>)> = Vec::new();
+    for entry in entries {
+        let section = entry.section.to_string();
+        match groups.iter_mut().find(|(name, _)| *name == section) {
+            Some((_, group)) => group.push(entry),
+            None => groups.push((section, vec![entry])),
+        }
+    }
+    groups
+}
+
+/// Exports entries as `(name, value)` environment variables, named by
+/// [`P2PoolConfigEntry::env_var`] after the loader's `P2POOL` prefix and `_`
+/// separator. Lists such as `dial_peers` stay comma-separated. Unset optional
+/// fields and empty lists are left out, and so are sensitive values unless
+/// `include_secrets` is set.
+#[must_use]
+pub fn p2pool_to_env(
+    entries: &[P2PoolConfigEntry],
+    include_secrets: bool,
+) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .filter(|e| e.enabled && (include_secrets || !e.schema.sensitive))
+        .filter(|e| !(e.schema.type_hint == "CSV" && e.value.is_empty()))
+        .map(|e| (e.env_var(), e.value.clone()))
+        .collect()
+}
+
+/// Works out the [`Origin`] of each entry of `flatten_config(cfg)`, in the
+/// same order, given the text of the file `cfg` was loaded from and
+/// `env_set`, which says whether the named variable was set at load time.
+///
+/// The loader merges `P2POOL_*` variables over the file, so a set variable
+/// wins; otherwise a key present in `file_toml` came from the file. Text that
+/// does not parse is treated as an empty file.
+#[must_use]
+pub fn entry_origins(cfg: &Config, file_toml: &str, env_set: impl Fn(&str) -> bool) -> Vec<Origin> {
+    let doc = file_toml
+        .parse::<toml_edit::DocumentMut>()
+        .unwrap_or_default();
+    flatten_config(cfg)
+        .iter()
+        .map(|entry| {
+            let in_file = doc
+                .get(&entry.section.to_string())
+                .and_then(|table| table.get(&entry.key))
+                .is_some();
+            if env_set(&entry.env_var()) {
+                Origin::Env
+            } else if in_file {
+                Origin::File
+            } else {
+                Origin::Default
+            }
+        })
+        .collect()
+}
+
+/// [`entry_origins`] for the file at `path` and the process environment; an
+/// unreadable file counts as empty.
+#[must_use]
+pub fn read_origins(path: &Path, cfg: &Config) -> Vec<Origin> {
+    entry_origins(cfg, &read_as_toml(path), |name| {
+        std::env::var_os(name).is_some()
+    })
+}
+
+/// The file at `path` as TOML text, converting JSON and YAML; empty when it
+/// cannot be read or converted.
+fn read_as_toml(path: &Path) -> String {
+    let text = std::fs::read_to_string(path).unwrap_or_default();
+    match foreign_format(path) {
+        Some(format) => to_toml_text(&text, format).unwrap_or_default(),
+        None => text,
+    }
+}
+
+/// Lists the sections and keys of `file_toml` that `cfg`'s schema does not
+/// have, such as a misspelled `[stratrum]` or `stratum.prot`, which the
+/// loader would otherwise drop without a word. Each message suggests the
+/// closest known name when one is a typo away. Text that does not parse
+/// yields no warnings; the loader reports that itself.
+#[must_use]
+pub fn unknown_keys(cfg: &Config, file_toml: &str) -> Vec<String> {
+    let Ok(doc) = file_toml.parse::<toml_edit::DocumentMut>() else {
+        return Vec::new();
+    };
+    let known = known_keys(cfg);
+    let hint = |name: &str, known: &[&str]| {
+        known
+            .iter()
+            .map(|k| (edit_distance(name, k), *k))
+            .filter(|(d, _)| *d <= 2)
+            .min()
+            .map(|(_, k)| k.to_string())
+    };
+
+    let mut warnings = Vec::new();
+    for (section, item) in doc.iter() {
+        if !known.iter().any(|(s, _)| s == section) {
+            let mut sections: Vec<&str> = known.iter().map(|(s, _)| s.as_str()).collect();
+            sections.dedup();
+            warnings.push(match hint(section, &sections) {
+                Some(k) => format!("unknown section [{section}] (did you mean [{k}]?)"),
+                None => format!("unknown section [{section}]"),
+            });
+            continue;
+        }
+        let Some(table) = item.as_table_like() else {
+            continue;
+        };
+        let keys: Vec<&str> = known
+            .iter()
+            .filter(|(s, _)| s == section)
+            .map(|(_, k)| k.as_str())
+            .collect();
+        for (key, _) in table.iter() {
+            if keys.contains(&key) {
+                continue;
+            }
+            warnings.push(match hint(key, &keys) {
+                Some(k) => format!("unknown key {section}.{key} (did you mean {k}?)"),
+                None => format!("unknown key {section}.{key}"),
+            });
+        }
+    }
+    warnings
+}
+
+/// `(section, key)` pairs the loader reads: every key of [`DEFAULT_CONFIG`],
+/// commented-out optional ones included, plus the fields [`flatten_config`]
+/// shows for `cfg`.
+fn known_keys(cfg: &Config) -> Vec<(String, String)> {
+    let mut known = Vec::new();
+    let mut section = "";
+    for line in DEFAULT_CONFIG.lines() {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name;
+        } else if let Some((key, _)) = line.trim_start_matches('#').split_once('=') {
+            let key = key.trim();
+            if !key.is_empty() && key.chars().all(|c| c.is_ascii_lowercase() || c == '_') {
+                known.push((section.to_string(), key.to_string()));
+            }
+        }
+    }
+    for entry in flatten_config(cfg) {
+        let pair = (entry.section.to_string(), entry.key);
+        if !known.contains(&pair) {
+            known.push(pair);
+        }
+    }
+    known
+}
+
+/// Flags `[network]` limits that load fine but make little sense together:
+/// a per-peer connection limit above `max_established_incoming`, no
+/// established connections allowed in either direction, or a zero
+/// `rate_limit_window_secs` (set as a custom entry), which rate limits are
+/// divided by. Unlike [`validate_config`] these never block loading or
+/// saving; values that do not parse are left to the editor's own checks.
+#[must_use]
+pub fn network_warnings(entries: &[P2PoolConfigEntry]) -> Vec<String> {
+    network_field_warnings(entries)
+        .into_iter()
+        .map(|w| w.message)
+        .collect()
+}
+
+/// [`network_warnings`], each tagged with the field it is shown on.
+#[must_use]
+pub fn network_field_warnings(entries: &[P2PoolConfigEntry]) -> Vec<Warning> {
+    let value = |key: &str| {
+        entries
+            .iter()
+            .find(|e| e.enabled && e.section == ConfigSection::Network && e.key == key)
+            .map(|e| e.value.trim())
+    };
+
+    let mut warnings = Vec::new();
+    if value("rate_limit_window_secs").and_then(|v| parse_duration_secs(v).ok()) == Some(0) {
+        warnings.push(Warning {
+            field: "network.rate_limit_window_secs",
+            message: "network.rate_limit_window_secs is 0; rate limits are divided by this window"
+                .to_string(),
+        });
+    }
+    let count = |key: &str| value(key).and_then(|v| v.parse::<u64>().ok());
+    if let (Some(per_peer), Some(incoming)) = (
+        count("max_established_per_peer"),
+        count("max_established_incoming"),
+    ) && per_peer > incoming
+    {
+        warnings.push(Warning {
+            field: "network.max_established_per_peer",
+            message: format!(
+                "network.max_established_per_peer ({per_peer}) exceeds \
+                 max_established_incoming ({incoming})"
+            ),
+        });
+    }
+    if count("max_established_incoming") == Some(0) && count("max_established_outgoing") == Some(0)
+    {
+        warnings.push(Warning {
+            field: "network.max_established_incoming",
+            message: "network.max_established_incoming and max_established_outgoing are both 0; \
+                      no peer can connect"
+                .to_string(),
+        });
+    }
+    warnings
+}
+
+/// Flags a `store.path` whose parent directory is missing, not a directory
+/// or not writable, since p2pool then fails at startup when it opens the
+/// store. A relative path is taken from `working_dir`, the directory p2pool
+/// is started in. This touches the filesystem, so callers run it when the
+/// config is loaded or edited rather than on every frame.
+#[must_use]
+pub fn store_path_warnings(entries: &[P2PoolConfigEntry], working_dir: &Path) -> Vec<Warning> {
+    let Some(store) = entries
+        .iter()
+        .find(|e| e.enabled && e.section == ConfigSection::Store && e.key == "path")
+        .map(|e| Path::new(e.value.trim()))
+        .filter(|p| !p.as_os_str().is_empty())
+    else {
+        return Vec::new();
+    };
+    let store = working_dir.join(store);
+    let parent = match store.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let message = match std::fs::metadata(parent) {
+        Err(_) => format!("{} does not exist", parent.display()),
+        Ok(meta) if !meta.is_dir() => format!("{} is not a directory", parent.display()),
+        Ok(_) if !crate::util::dir_is_writable(parent) => {
+            format!("{} is not writable", parent.display())
+        }
+        Ok(_) => return Vec::new(),
+    };
+    vec![Warning {
+        field: "store.path",
+        message,
+    }]
+}
+
+/// [`unknown_keys`] for the file at `path`.
+#[must_use]
+pub fn read_unknown_keys(path: &Path, cfg: &Config) -> Vec<String> {
+    unknown_keys(cfg, &read_as_toml(path))
+}
+
+/// Levenshtein distance between `a` and `b`, counted in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
 }
 
 /// Flattens the nested `p2poolv2_config::Config` into a flat
@@ -209,7 +689,7 @@ pub fn flatten_config(cfg: &Config) -> Vec<P2PoolConfigEntry> {
     e.push(P2PoolConfigEntry::required(
         ConfigSection::Stratum,
         "network",
-        cfg.stratum.network.to_string(),
+        cfg.stratum.network.to_core_arg().to_string(),
         "Bitcoin network: main / testnet4 / signet / regtest",
         "Network",
     ));
@@ -277,14 +757,14 @@ pub fn flatten_config(cfg: &Config) -> Vec<P2PoolConfigEntry> {
         ConfigSection::Network,
         "listen_address",
         n.listen_address.clone(),
-        "P2P listen address (host:port)",
+        "P2P listen multiaddr (/ip4/0.0.0.0/tcp/6884)",
         "String",
     ));
     e.push(P2PoolConfigEntry::required(
         ConfigSection::Network,
         "dial_peers",
         n.dial_peers.join(","),
-        "Bootstrap peers, comma-separated (host:port,...)",
+        "Bootstrap peer multiaddrs, comma-separated",
         "CSV",
     ));
     e.push(P2PoolConfigEntry::required(
@@ -312,7 +792,7 @@ pub fn flatten_config(cfg: &Config) -> Vec<P2PoolConfigEntry> {
         ConfigSection::Network,
         "dial_timeout_secs",
         n.dial_timeout_secs.to_string(),
-        "Timeout in seconds for outbound dial attempts",
+        "Timeout for outbound dial attempts, in seconds or as 30s, 5m, 1h",
         "u64",
     ));
     e.push(P2PoolConfigEntry::required(
@@ -430,6 +910,595 @@ pub fn flatten_config(cfg: &Config) -> Vec<P2PoolConfigEntry> {
     e
 }
 
+/// Parses `value` as a bitcoin address valid on `network`.
+fn validate_address(value: &str, network: Network) -> Result<(), String> {
+    value
+        .parse::<bitcoin::Address<bitcoin::address::NetworkUnchecked>>()
+        .map_err(|_| format!("{value} is not a valid bitcoin address"))?
+        .require_network(network)
+        .map(|_| ())
+        .map_err(|_| format!("{value} is not an address for {network}"))
+}
+
+/// Parses `value` as a TCP port, rejecting 0 and anything above 65535.
+fn parse_port(value: &str) -> Result<u16, String> {
+    match value.parse::<u16>() {
+        Ok(port) if port > 0 => Ok(port),
+        _ => Err(format!("{value} is not a valid port (1-65535)")),
+    }
+}
+
+/// Checks the `port` keys of `[stratum]` and `[api]` in raw TOML text.
+///
+/// The typed config stores ports as `u16`, so an out-of-range value would
+/// otherwise surface as an opaque deserialize error, and 0 would be accepted.
+///
+/// # Errors
+/// Returns [`ConfigError::InvalidPort`] naming the offending key when a port
+/// is not an integer in `1..=65535`, or [`ConfigError::Toml`] for bad TOML.
+pub fn validate_ports(toml: &str) -> Result<(), ConfigError> {
+    let doc = parse_document(toml)?;
+    for section in ["stratum", "api"] {
+        let Some(item) = doc.get(section).and_then(|t| t.get("port")) else {
+            continue;
+        };
+        let valid = item
+            .as_integer()
+            .is_some_and(|port| (1..=i64::from(u16::MAX)).contains(&port));
+        if !valid {
+            return Err(ConfigError::InvalidPort {
+                field: format!("{section}.port"),
+                value: item.to_string().trim().to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks relations between loaded fields that `Config::load` leaves alone.
+///
+/// `solo_address` must belong to `stratum.network`, like the other payout
+/// addresses. `donation` and `fee` are basis points: each, and their sum,
+/// must be at most 10000 (100%), and a non-zero value needs its address.
+/// Difficulties must be ordered minimum ≤ start ≤ maximum. The stratum,
+/// api and zmq endpoints must not share a port on the same host.
+/// `zmqpubhashblock` must be a `tcp://` URL and `bitcoinrpc.url` an
+/// `http(s)://` one, both with a port. `network.listen_address` and every
+/// `dial_peers` entry must be multiaddrs. `api.auth_user` and
+/// `api.auth_token` must be set together or not at all.
+///
+/// # Errors
+/// Returns a [`ConfigError`] naming the first invalid field.
+pub fn validate_config(cfg: &Config) -> Result<(), ConfigError> {
+    let s = &cfg.stratum;
+    if let Some(address) = &s.solo_address {
+        validate_address(address, s.network).map_err(|reason| ConfigError::InvalidAddress {
+            field: "solo_address",
+            value: address.clone(),
+            reason,
+        })?;
+    }
+    for (name, bps, address) in [
+        ("donation", s.donation, &s.donation_address),
+        ("fee", s.fee, &s.fee_address),
+    ] {
+        let value = bps.unwrap_or_default();
+        if value > MAX_BASIS_POINTS {
+            return Err(ConfigError::BasisPointsOutOfRange { field: name, value });
+        }
+        if value > 0 && address.is_none() {
+            return Err(ConfigError::MissingPayoutAddress { field: name, value });
+        }
+    }
+    check_payout_total(s.donation, s.fee)?;
+    check_difficulties(
+        s.minimum_difficulty,
+        s.start_difficulty,
+        s.maximum_difficulty,
+    )?;
+    let endpoint = |field| move |reason| ConfigError::InvalidEndpoint { field, reason };
+    let zmq = zmq_endpoint(cfg).map_err(endpoint("zmqpubhashblock"))?;
+    rpc_endpoint(cfg).map_err(endpoint("bitcoinrpc.url"))?;
+    check_port_conflicts(cfg, &zmq)?;
+
+    let n = &cfg.network;
+    validate_multiaddr(&n.listen_address).map_err(endpoint("listen_address"))?;
+    for peer in &n.dial_peers {
+        validate_multiaddr(peer).map_err(endpoint("dial_peers entry"))?;
+    }
+    check_api_auth(cfg.api.auth_user.as_deref(), cfg.api.auth_token.as_deref())
+}
+
+/// Rejects an API with only one of `auth_user` and `auth_token`, which
+/// would leave it half-authenticated.
+fn check_api_auth(user: Option<&str>, token: Option<&str>) -> Result<(), ConfigError> {
+    if user.is_some() != token.is_some() {
+        return Err(ConfigError::IncompleteApiAuth);
+    }
+    Ok(())
+}
+
+/// Rejects two of the stratum, api and zmq endpoints sharing a port on
+/// overlapping hosts. A wildcard host (`0.0.0.0`, `::`) overlaps every host.
+fn check_port_conflicts(cfg: &Config, zmq: &Endpoint) -> Result<(), ConfigError> {
+    let endpoints = [
+        ("stratum", cfg.stratum.hostname.as_str(), cfg.stratum.port),
+        ("api", cfg.api.hostname.as_str(), cfg.api.port),
+        ("zmqpubhashblock", zmq.host.as_str(), zmq.port),
+    ];
+    let wildcard = |host: &str| matches!(host, "0.0.0.0" | "::" | "[::]");
+    for (i, (name, host, port)) in endpoints.iter().enumerate() {
+        for (other, other_host, other_port) in &endpoints[i + 1..] {
+            let same_host = host == other_host || wildcard(*host) || wildcard(*other_host);
+            if port == other_port && same_host {
+                return Err(ConfigError::PortConflict {
+                    first: name,
+                    second: other,
+                    port: *port,
+                    host: (*host).to_string(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Requires `minimum_difficulty <= start_difficulty <= maximum_difficulty`,
+/// the maximum being optional. Equal values are allowed.
+fn check_difficulties(minimum: u64, start: u64, maximum: Option<u64>) -> Result<(), ConfigError> {
+    if start < minimum {
+        return Err(ConfigError::DifficultyOrder {
+            field: "start_difficulty",
+            value: start,
+            bound_field: "minimum_difficulty",
+            bound: minimum,
+        });
+    }
+    if let Some(maximum) = maximum.filter(|&maximum| maximum < start) {
+        return Err(ConfigError::DifficultyOrder {
+            field: "maximum_difficulty",
+            value: maximum,
+            bound_field: "start_difficulty",
+            bound: start,
+        });
+    }
+    Ok(())
+}
+
+/// Upper bound for `donation` and `fee`, in basis points: 100%.
+pub(crate) const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// Rejects a donation and fee that together give away more than 100%.
+fn check_payout_total(donation: Option<u16>, fee: Option<u16>) -> Result<(), ConfigError> {
+    let total = u32::from(donation.unwrap_or_default()) + u32::from(fee.unwrap_or_default());
+    if total > u32::from(MAX_BASIS_POINTS) {
+        return Err(ConfigError::PayoutTooHigh { total });
+    }
+    Ok(())
+}
+
+/// A `scheme://host:port` endpoint parsed from a config URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub scheme: String,
+    pub host: String,
+    pub port: u16,
+}
+
+impl Endpoint {
+    /// Parses `url` as `scheme://host:port[/path]`, where the scheme must be
+    /// one of `schemes`. IPv6 hosts are written in brackets and returned
+    /// without them.
+    ///
+    /// # Errors
+    /// Returns a message naming the allowed schemes when the scheme is
+    /// missing or different, or when the host or port is invalid.
+    pub fn parse(url: &str, schemes: &[&str]) -> Result<Self, String> {
+        let allowed = schemes
+            .iter()
+            .map(|s| format!("{s}://"))
+            .collect::<Vec<_>>()
+            .join(" or ");
+        let (scheme, rest) = url
+            .split_once("://")
+            .filter(|(scheme, _)| schemes.contains(scheme))
+            .ok_or_else(|| format!("{url} must start with {allowed}"))?;
+        let authority = rest.split('/').next().unwrap_or_default();
+        let (host, port) = authority
+            .rsplit_once(':')
+            .ok_or_else(|| format!("{url} is missing a port"))?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        if host.is_empty() {
+            return Err(format!("{url} is missing a host"));
+        }
+        let port = parse_port(port).map_err(|e| format!("{url}: {e}"))?;
+        Ok(Self {
+            scheme: scheme.to_string(),
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+/// The ZMQ block notification endpoint bitcoind publishes on.
+///
+/// # Errors
+/// Returns an error unless `stratum.zmqpubhashblock` is a `tcp://host:port`
+/// URL.
+pub fn zmq_endpoint(cfg: &Config) -> Result<Endpoint, String> {
+    Endpoint::parse(&cfg.stratum.zmqpubhashblock, &["tcp"])
+}
+
+/// The bitcoind RPC endpoint.
+///
+/// # Errors
+/// Returns an error unless `bitcoinrpc.url` is an `http(s)://host:port` URL.
+pub fn rpc_endpoint(cfg: &Config) -> Result<Endpoint, String> {
+    Endpoint::parse(&cfg.bitcoinrpc.url, &["http", "https"])
+}
+
+/// Checks that `value` is a libp2p multiaddr such as `/ip4/1.2.3.4/tcp/6884`.
+///
+/// Covers the protocols a p2pool node listens or dials on: `ip4`, `ip6`,
+/// the `dns` family, `tcp`/`udp`, `quic`/`quic-v1`, `ws`/`wss` and `p2p`.
+fn validate_multiaddr(value: &str) -> Result<(), String> {
+    let err = || format!("{value} is not a valid multiaddr");
+    let mut parts = value.strip_prefix('/').ok_or_else(err)?.split('/');
+    while let Some(protocol) = parts.next() {
+        let valid = match protocol {
+            "ip4" => parts
+                .next()
+                .is_some_and(|v| v.parse::<std::net::Ipv4Addr>().is_ok()),
+            "ip6" => parts
+                .next()
+                .is_some_and(|v| v.parse::<std::net::Ipv6Addr>().is_ok()),
+            "dns" | "dns4" | "dns6" | "dnsaddr" => parts.next().is_some_and(|v| !v.is_empty()),
+            "tcp" | "udp" => parts.next().is_some_and(|v| v.parse::<u16>().is_ok()),
+            // Peer ids are base58, which leaves out 0, O, I and l
+            "p2p" => parts.next().is_some_and(|v| {
+                !v.is_empty()
+                    && v.chars()
+                        .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+            }),
+            "quic" | "quic-v1" | "ws" | "wss" => true,
+            _ => false,
+        };
+        if !valid {
+            return Err(err());
+        }
+    }
+    Ok(())
+}
+
+/// Parses an optional basis-point value; empty clears it.
+fn parse_basis_points(value: &str) -> Result<Option<u16>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    match value.parse::<u16>() {
+        Ok(bps) if bps <= MAX_BASIS_POINTS => Ok(Some(bps)),
+        _ => Err(format!("must be 0–{MAX_BASIS_POINTS} basis points")),
+    }
+}
+
+/// Loads the p2pool config at `path`, checking its ports before
+/// deserializing and its fields with [`validate_config`] after.
+///
+/// The format follows the extension: `.json`, `.yaml` and `.yml` files are
+/// read as JSON or YAML with the same layout as the TOML, anything else as
+/// TOML. `P2POOL_*` variables override the file in every format. Timeout
+/// and window fields may be written as durations such as `"5m"` (see
+/// [`parse_duration_secs`]).
+///
+/// # Errors
+/// Returns a [`ConfigError`] if the file cannot be read, a port is out of
+/// range, the network is unknown, the config does not deserialize, or a
+/// field fails validation.
+pub fn load_config(path: &str) -> Result<Config, ConfigError> {
+    let text = std::fs::read_to_string(path)?;
+    let Some(format) = foreign_format(Path::new(path)) else {
+        validate_ports(&text)?;
+        validate_network_name(&text)?;
+        let normalized = normalize_network(&normalize_durations(&text)?)?;
+        let cfg = build_config(&normalized).map_err(|e| locate_error(&text, e.to_string()))?;
+        validate_config(&cfg)?;
+        return Ok(cfg);
+    };
+
+    // Checked as TOML so the port and network checks apply unchanged; line
+    // numbers would point into the conversion, so errors are not located
+    let text = normalize_durations(&to_toml_text(&text, format)?)?;
+    validate_ports(&text)?;
+    validate_network_name(&text)?;
+    let text = normalize_network(&text)?;
+    let cfg = build_config(&text).map_err(|e| ConfigError::Deserialize(e.to_string()))?;
+    validate_config(&cfg)?;
+    Ok(cfg)
+}
+
+/// Deserializes TOML `text` the way `Config::load` reads a file, with
+/// `P2POOL_*` variables on top.
+fn build_config(text: &str) -> Result<Config, config::ConfigError> {
+    config::Config::builder()
+        .add_source(config::File::from_str(text, config::FileFormat::Toml))
+        .add_source(config::Environment::with_prefix("P2POOL").separator("_"))
+        .build()
+        .and_then(config::Config::try_deserialize::<Config>)
+}
+
+/// Timeout and window fields, as `(section, key)`, that accept a duration
+/// such as `"5m"` in place of a number of seconds.
+const DURATION_FIELDS: [(&str, &str); 3] = [
+    ("network", "dial_timeout_secs"),
+    ("network", "peer_inactivity_timeout_secs"),
+    ("network", "rate_limit_window_secs"),
+];
+
+/// Parses a duration in seconds: a bare integer, or one followed by `s`,
+/// `m`, `h` or `d`, such as `"90"`, `"30s"`, `"5m"` or `"1h"`.
+///
+/// # Errors
+/// Returns a reason naming the accepted suffixes when `value` has another
+/// suffix, no number, or overflows `u64`.
+pub fn parse_duration_secs(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(split);
+    let unit = match suffix.trim() {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        other => {
+            return Err(format!(
+                "unknown duration suffix '{other}' in '{value}'; use s, m, h or d"
+            ));
+        }
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("'{value}' is not a duration such as 30s, 5m or 1h"))?;
+    number
+        .checked_mul(unit)
+        .ok_or_else(|| format!("'{value}' is too long a duration"))
+}
+
+/// Serde helper for duration fields: accepts a number of seconds or a
+/// string understood by [`parse_duration_secs`], and yields seconds.
+///
+/// # Errors
+/// Fails with the [`parse_duration_secs`] reason for a malformed string, or
+/// when the value is neither an unsigned integer nor a string.
+pub fn deserialize_duration_secs<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Secs(u64),
+        Text(String),
+    }
+
+    match <Raw as serde::Deserialize>::deserialize(deserializer)? {
+        Raw::Secs(secs) => Ok(secs),
+        Raw::Text(text) => parse_duration_secs(&text).map_err(serde::de::Error::custom),
+    }
+}
+
+/// Rewrites duration strings in the [`DURATION_FIELDS`] of TOML `text` as
+/// plain seconds, which is what the typed `Config` reads. Returns `text`
+/// unchanged when there are none.
+///
+/// # Errors
+/// Returns [`ConfigError::InvalidValue`] pointing at a malformed duration,
+/// or [`ConfigError::Toml`] for bad TOML.
+fn normalize_durations(text: &str) -> Result<String, ConfigError> {
+    let mut doc = parse_document(text)?;
+    let mut changed = false;
+    for (section, key) in DURATION_FIELDS {
+        let Some(item) = doc.get_mut(section).and_then(|t| t.get_mut(key)) else {
+            continue;
+        };
+        let Some(raw) = item.as_str() else {
+            continue;
+        };
+        let secs = duration_toml_secs(raw).map_err(|message| {
+            let key = format!("{section}.{key}");
+            match value_position(text, &key) {
+                Some((line, column)) => ConfigError::InvalidValue {
+                    key,
+                    line,
+                    column,
+                    message,
+                },
+                None => ConfigError::Deserialize(format!("{key}: {message}")),
+            }
+        })?;
+        *item = toml_edit::value(secs);
+        changed = true;
+    }
+    Ok(if changed {
+        doc.to_string()
+    } else {
+        text.to_string()
+    })
+}
+
+/// Seconds for a duration string, as the integer TOML the p2pool daemon
+/// reads.
+fn duration_toml_secs(raw: &str) -> Result<i64, String> {
+    let secs = parse_duration_secs(raw)?;
+    i64::try_from(secs).map_err(|_| format!("'{raw}' is too long"))
+}
+
+/// Rewrites duration strings left in the [`DURATION_FIELDS`] of `doc`,
+/// whether kept from the file or typed into a custom entry, as integer
+/// seconds: the p2pool daemon reads these fields as plain numbers.
+///
+/// # Errors
+/// Returns the field and the [`parse_duration_secs`] reason for a
+/// malformed duration.
+fn write_durations_as_secs(doc: &mut toml_edit::DocumentMut) -> anyhow::Result<()> {
+    for (section, key) in DURATION_FIELDS {
+        let Some(value) = doc
+            .get_mut(section)
+            .and_then(|t| t.get_mut(key))
+            .and_then(toml_edit::Item::as_value_mut)
+        else {
+            continue;
+        };
+        let Some(raw) = value.as_str() else {
+            continue;
+        };
+        let secs = duration_toml_secs(raw).map_err(|e| anyhow::anyhow!("{section}.{key}: {e}"))?;
+        let decor = value.decor().clone();
+        *value = toml_edit::Value::from(secs);
+        *value.decor_mut() = decor;
+    }
+    Ok(())
+}
+
+/// The `config` crate format of a p2pool config written in something other
+/// than TOML, judged by the extension of `path`.
+fn foreign_format(path: &Path) -> Option<config::FileFormat> {
+    match path.extension()?.to_str()? {
+        "json" => Some(config::FileFormat::Json),
+        "yaml" | "yml" => Some(config::FileFormat::Yaml),
+        _ => None,
+    }
+}
+
+/// Re-renders a JSON or YAML config as TOML text.
+fn to_toml_text(text: &str, format: config::FileFormat) -> Result<String, ConfigError> {
+    let table = config::Config::builder()
+        .add_source(config::File::from_str(text, format))
+        .build()
+        .and_then(config::Config::try_deserialize::<toml::Table>)
+        .map_err(|e| ConfigError::Toml(e.to_string()))?;
+    toml::to_string(&table).map_err(|e| ConfigError::Toml(e.to_string()))
+}
+
+/// Points a deserialize error at the value it is about.
+///
+/// The `config` crate names the key (``for key `stratum.port` ``) but not
+/// where it sits in the file, so the key is looked up in a span-preserving
+/// re-parse of `text`. Falls back to [`ConfigError::Deserialize`] with the
+/// original message when the key is missing or not in the file.
+fn locate_error(text: &str, message: String) -> ConfigError {
+    let located = message
+        .split_once("for key `")
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .and_then(|(key, _)| Some((key.to_string(), value_position(text, key)?)));
+    match located {
+        Some((key, (line, column))) => ConfigError::InvalidValue {
+            key,
+            line,
+            column,
+            message,
+        },
+        None => ConfigError::Deserialize(message),
+    }
+}
+
+/// 1-based line and column of the value at dotted `key` in TOML `text`,
+/// such as `stratum.port` or `network.dial_peers[1]`.
+fn value_position(text: &str, key: &str) -> Option<(usize, usize)> {
+    let doc = toml_edit::ImDocument::parse(text).ok()?;
+    let mut item = doc.as_item();
+    for part in key.split('.') {
+        // Array elements show up as `dial_peers[1]`
+        let (name, index) = match part.split_once('[') {
+            Some((name, index)) => (name, index.trim_end_matches(']').parse::<usize>().ok()),
+            None => (part, None),
+        };
+        item = item.get(name)?;
+        if let Some(index) = index {
+            item = item.get(index)?;
+        }
+    }
+    let start = item.span()?.start;
+    let before = &text[..start];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    Some((line, column))
+}
+
+fn parse_document(toml: &str) -> Result<toml_edit::DocumentMut, ConfigError> {
+    toml.parse()
+        .map_err(|e: toml_edit::TomlError| ConfigError::Toml(e.to_string()))
+}
+
+/// Network names accepted by [`parse_network`], as listed in its errors.
+pub(crate) const NETWORK_NAMES: &str =
+    "main, test, testnet4, signet, regtest (or mainnet, bitcoin, testnet)";
+
+/// Parses a network name, accepting common aliases on top of the core
+/// `-chain` arguments: `mainnet` and `bitcoin` for `main`, `testnet` for
+/// `test`. Matching is case-insensitive.
+///
+/// # Errors
+/// Returns the accepted names when `value` is not one of them.
+pub fn parse_network(value: &str) -> Result<Network, String> {
+    let name = value.trim().to_ascii_lowercase();
+    let core_arg = match name.as_str() {
+        "mainnet" | "bitcoin" => "main",
+        "testnet" => "test",
+        other => other,
+    };
+    Network::from_core_arg(core_arg)
+        .map_err(|_| ConfigError::UnknownNetwork(value.to_string()).to_string())
+}
+
+/// Checks `stratum.network` in raw TOML text so an unknown name is reported
+/// with the accepted list instead of a bare deserialize error.
+///
+/// # Errors
+/// Returns [`ConfigError::UnknownNetwork`] for an unknown name, or
+/// [`ConfigError::Toml`] for bad TOML.
+pub fn validate_network_name(toml: &str) -> Result<(), ConfigError> {
+    let doc = parse_document(toml)?;
+    if let Some(name) = doc
+        .get("stratum")
+        .and_then(|t| t.get("network"))
+        .and_then(|v| v.as_str())
+    {
+        parse_network(name).map_err(|_| ConfigError::UnknownNetwork(name.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Rewrites an aliased `stratum.network` in TOML `text`, such as `mainnet`
+/// or `Testnet`, as the core argument the typed `Config` reads. Returns
+/// `text` unchanged when the name is already one, or is missing; run
+/// [`validate_network_name`] first to report an unknown name.
+///
+/// # Errors
+/// Returns [`ConfigError::Toml`] for bad TOML.
+fn normalize_network(text: &str) -> Result<String, ConfigError> {
+    let mut doc = parse_document(text)?;
+    let Some(value) = doc
+        .get_mut("stratum")
+        .and_then(|t| t.get_mut("network"))
+        .and_then(toml_edit::Item::as_value_mut)
+    else {
+        return Ok(text.to_string());
+    };
+    let Some(network) = value.as_str().and_then(|name| parse_network(name).ok()) else {
+        return Ok(text.to_string());
+    };
+    let core_arg = network.to_core_arg();
+    if value.as_str() == Some(core_arg) {
+        return Ok(text.to_string());
+    }
+    let decor = value.decor().clone();
+    *value = toml_edit::Value::from(core_arg);
+    *value.decor_mut() = decor;
+    Ok(doc.to_string())
+}
+
 /// Inner dispatch for config edits.
 ///
 /// Matches a flattened `(section, key)` pair to the corresponding nested field inside `Config` and applies the parsed update.
@@ -447,65 +1516,81 @@ pub fn dispatch_edit(
             cfg.stratum.hostname = new_value.to_string();
         }
         (ConfigSection::Stratum, "port") => {
-            cfg.stratum.port = new_value.parse().map_err(|_| "port must be 0–65535")?;
+            cfg.stratum.port = parse_port(new_value)?;
         }
         (ConfigSection::Stratum, "start_difficulty") => {
-            cfg.stratum.start_difficulty = new_value.parse().map_err(|_| "must be u64")?;
+            let start = new_value.parse().map_err(|_| "must be u64")?;
+            let s = &cfg.stratum;
+            check_difficulties(s.minimum_difficulty, start, s.maximum_difficulty)
+                .map_err(|e| e.to_string())?;
+            cfg.stratum.start_difficulty = start;
         }
         (ConfigSection::Stratum, "minimum_difficulty") => {
-            cfg.stratum.minimum_difficulty = new_value.parse().map_err(|_| "must be u64")?;
+            let minimum = new_value.parse().map_err(|_| "must be u64")?;
+            let s = &cfg.stratum;
+            check_difficulties(minimum, s.start_difficulty, s.maximum_difficulty)
+                .map_err(|e| e.to_string())?;
+            cfg.stratum.minimum_difficulty = minimum;
         }
         (ConfigSection::Stratum, "maximum_difficulty") => {
-            cfg.stratum.maximum_difficulty = if new_value.is_empty() {
+            let maximum = if new_value.is_empty() {
                 None
             } else {
                 Some(new_value.parse().map_err(|_| "must be u64")?)
             };
+            let s = &cfg.stratum;
+            check_difficulties(s.minimum_difficulty, s.start_difficulty, maximum)
+                .map_err(|e| e.to_string())?;
+            cfg.stratum.maximum_difficulty = maximum;
         }
         (ConfigSection::Stratum, "solo_address") => {
             cfg.stratum.solo_address = if new_value.is_empty() {
                 None
             } else {
+                validate_address(new_value, cfg.stratum.network)?;
                 Some(new_value.to_string())
             };
         }
         (ConfigSection::Stratum, "zmqpubhashblock") => {
+            Endpoint::parse(new_value, &["tcp"])?;
             cfg.stratum.zmqpubhashblock = new_value.to_string();
         }
         (ConfigSection::Stratum, "bootstrap_address") => {
+            validate_address(new_value, cfg.stratum.network)?;
             cfg.stratum.bootstrap_address = new_value.to_string();
         }
         (ConfigSection::Stratum, "donation_address") => {
-            cfg.stratum.donation_address = if new_value.is_empty() {
-                None
+            if new_value.trim().is_empty() {
+                // Without an address the donation has nowhere to go, so it goes too
+                cfg.stratum.donation_address = None;
+                cfg.stratum.donation = None;
             } else {
-                Some(new_value.to_string())
-            };
+                validate_address(new_value, cfg.stratum.network)?;
+                cfg.stratum.donation_address = Some(new_value.to_string());
+            }
         }
         (ConfigSection::Stratum, "donation") => {
-            cfg.stratum.donation = if new_value.is_empty() {
-                None
-            } else {
-                Some(new_value.parse().map_err(|_| "must be u16")?)
-            };
+            let donation = parse_basis_points(new_value)?;
+            check_payout_total(donation, cfg.stratum.fee).map_err(|e| e.to_string())?;
+            cfg.stratum.donation = donation;
         }
         (ConfigSection::Stratum, "fee_address") => {
-            cfg.stratum.fee_address = if new_value.is_empty() {
-                None
+            if new_value.trim().is_empty() {
+                // Without an address the fee has nowhere to go, so it goes too
+                cfg.stratum.fee_address = None;
+                cfg.stratum.fee = None;
             } else {
-                Some(new_value.to_string())
-            };
+                validate_address(new_value, cfg.stratum.network)?;
+                cfg.stratum.fee_address = Some(new_value.to_string());
+            }
         }
         (ConfigSection::Stratum, "fee") => {
-            cfg.stratum.fee = if new_value.is_empty() {
-                None
-            } else {
-                Some(new_value.parse().map_err(|_| "must be u16")?)
-            };
+            let fee = parse_basis_points(new_value)?;
+            check_payout_total(cfg.stratum.donation, fee).map_err(|e| e.to_string())?;
+            cfg.stratum.fee = fee;
         }
         (ConfigSection::Stratum, "network") => {
-            cfg.stratum.network = Network::from_core_arg(new_value)
-                .map_err(|_| "must be main / testnet4 / signet / regtest")?;
+            cfg.stratum.network = parse_network(new_value)?;
         }
         (ConfigSection::Stratum, "version_mask") => {
             cfg.stratum.version_mask = i32::from_str_radix(new_value.trim_start_matches("0x"), 16)
@@ -531,6 +1616,7 @@ pub fn dispatch_edit(
 
         // BitcoinRPC
         (ConfigSection::BitcoinRpc, "url") => {
+            Endpoint::parse(new_value, &["http", "https"])?;
             cfg.bitcoinrpc.url = new_value.to_string();
         }
         (ConfigSection::BitcoinRpc, "username") => {
@@ -542,14 +1628,19 @@ pub fn dispatch_edit(
 
         // Network
         (ConfigSection::Network, "listen_address") => {
+            validate_multiaddr(new_value)?;
             cfg.network.listen_address = new_value.to_string();
         }
         (ConfigSection::Network, "dial_peers") => {
-            cfg.network.dial_peers = if new_value.is_empty() {
+            let peers: Vec<String> = if new_value.is_empty() {
                 vec![]
             } else {
                 new_value.split(',').map(|s| s.trim().to_string()).collect()
             };
+            for peer in &peers {
+                validate_multiaddr(peer)?;
+            }
+            cfg.network.dial_peers = peers;
         }
         (ConfigSection::Network, "max_established_incoming") => {
             cfg.network.max_established_incoming = new_value.parse().map_err(|_| "must be u32")?;
@@ -561,7 +1652,7 @@ pub fn dispatch_edit(
             cfg.network.max_established_per_peer = new_value.parse().map_err(|_| "must be u32")?;
         }
         (ConfigSection::Network, "dial_timeout_secs") => {
-            cfg.network.dial_timeout_secs = new_value.parse().map_err(|_| "must be u64")?;
+            cfg.network.dial_timeout_secs = parse_duration_secs(new_value)?;
         }
         (ConfigSection::Network, "max_requests_per_second") => {
             cfg.network.max_requests_per_second = new_value.parse().map_err(|_| "must be u64")?;
@@ -606,7 +1697,7 @@ pub fn dispatch_edit(
             cfg.api.hostname = new_value.to_string();
         }
         (ConfigSection::Api, "port") => {
-            cfg.api.port = new_value.parse().map_err(|_| "port must be 0–65535")?;
+            cfg.api.port = parse_port(new_value)?;
         }
         (ConfigSection::Api, "auth_user") => {
             cfg.api.auth_user = if new_value.is_empty() {
@@ -646,18 +1737,194 @@ pub fn apply_edit(cfg: &mut Config, index: usize, new_value: &str) -> Result<(),
     dispatch_edit(cfg, entry, new_value)
 }
 
+/// Converts a flattened value into a TOML value of the type named by its
+/// schema `type_hint`.
+fn typed_toml_value(entry: &P2PoolConfigEntry) -> Result<toml_edit::Item, String> {
+    let err = |ty: &str| format!("{}.{} must be {ty}", entry.section, entry.key);
+    let value = entry.value.as_str();
+    let item = match entry.schema.type_hint.as_str() {
+        "u16" | "u32" | "u64" => {
+            toml_edit::value(value.parse::<i64>().map_err(|_| err("an integer"))?)
+        }
+        "f64" => toml_edit::value(value.parse::<f64>().map_err(|_| err("a number"))?),
+        "bool" => toml_edit::value(value.parse::<bool>().map_err(|_| err("true or false"))?),
+        "CSV" => {
+            let mut array = toml_edit::Array::new();
+            for peer in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                array.push(peer);
+            }
+            toml_edit::value(array)
+        }
+        "custom" => infer_toml_value(value),
+        _ => toml_edit::value(value),
+    };
+    Ok(item)
+}
+
+/// Picks the TOML type a user typed for a custom entry: integers, floats
+/// and booleans keep their type, anything else is written as a string.
+fn infer_toml_value(value: &str) -> toml_edit::Item {
+    if let Ok(n) = value.parse::<i64>() {
+        toml_edit::value(n)
+    } else if let Ok(f) = value.parse::<f64>() {
+        toml_edit::value(f)
+    } else if let Ok(b) = value.parse::<bool>() {
+        toml_edit::value(b)
+    } else {
+        toml_edit::value(value)
+    }
+}
+
+/// Inserts or replaces user-added `custom` entries in `doc`, creating their
+/// tables when missing.
+///
+/// # Errors
+/// Returns an error if an existing `[section]` is not a table.
+pub fn insert_custom_entries(
+    doc: &mut toml_edit::DocumentMut,
+    custom: &[P2PoolConfigEntry],
+) -> anyhow::Result<()> {
+    // A section holding only disabled keys is not created
+    let enabled: Vec<P2PoolConfigEntry> = custom.iter().filter(|e| e.enabled).cloned().collect();
+    for (section, entries) in group_by_section(&enabled) {
+        let table = section_table(doc, &section)?;
+        for entry in entries {
+            table.insert(&entry.key, infer_toml_value(&entry.value));
+        }
+    }
+    Ok(())
+}
+
+/// The `[section]` table of `doc`, added empty if the file lacks it.
+fn section_table<'a>(
+    doc: &'a mut toml_edit::DocumentMut,
+    section: &str,
+) -> anyhow::Result<&'a mut toml_edit::Table> {
+    use toml_edit::{Item, Table};
+
+    if !doc.contains_key(section) {
+        doc.insert(section, Item::Table(Table::new()));
+    }
+    doc[section]
+        .as_table_mut()
+        .ok_or_else(|| anyhow::anyhow!("[{section}] is not a table"))
+}
+
+/// Serializes `cfg` to TOML and writes it to `path`.
+///
+/// Values come from `flatten_config`, which holds the real secrets; masking
+/// only happens in the view. If `path` already exists its document is used
+/// as the base, so comments, ordering and keys this model does not track
+/// survive. Unset optional fields are removed rather than written empty.
+/// The file is replaced atomically.
+///
+/// # Errors
+/// Returns an error if the existing file is not valid TOML, a value cannot be
+/// converted to its TOML type, or the file cannot be written.
+pub fn write_config(path: &Path, cfg: &Config) -> anyhow::Result<()> {
+    write_config_with(path, cfg, &[])
+}
+
+/// Like [`write_config`], additionally writing user-added `custom` entries
+/// (see [`P2PoolConfigEntry::custom`]) under their sections.
+///
+/// # Errors
+/// Same as [`write_config`].
+pub fn write_config_with(
+    path: &Path,
+    cfg: &Config,
+    custom: &[P2PoolConfigEntry],
+) -> anyhow::Result<()> {
+    use toml_edit::DocumentMut;
+
+    check_save_format(path)?;
+    let mut doc = if path.exists() {
+        std::fs::read_to_string(path)?
+            .parse::<DocumentMut>()
+            .map_err(|e| anyhow::anyhow!("Failed to parse P2Pool config TOML: {e}"))?
+    } else {
+        DocumentMut::new()
+    };
+    merge_into_document(&mut doc, cfg, custom)?;
+
+    crate::util::atomic_write(path, &doc.to_string())
+}
+
+/// Refuses a `path` that is not TOML. JSON and YAML configs load, but PDM
+/// only writes TOML, so a save checks this before anything, such as a
+/// backup, touches the file.
+///
+/// # Errors
+/// Returns an error naming `path` when its extension is JSON or YAML.
+pub fn check_save_format(path: &Path) -> anyhow::Result<()> {
+    if foreign_format(path).is_some() {
+        anyhow::bail!(
+            "{} is not TOML; PDM only writes p2pool configs as TOML",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Serializes `cfg` to a standalone TOML document.
+///
+/// Uses the same typed values as [`write_config`]: the network is written
+/// as its core argument (`main`, `signet`, ...), `version_mask` as a hex
+/// string and unset optional fields are omitted. Loading the result gives
+/// back an equivalent config.
+///
+/// # Errors
+/// Returns an error if a value cannot be converted to its TOML type.
+pub fn to_toml_string(cfg: &Config) -> anyhow::Result<String> {
+    let mut doc = toml_edit::DocumentMut::new();
+    merge_into_document(&mut doc, cfg, &[])?;
+    Ok(doc.to_string())
+}
+
+/// Writes every field of `cfg`, then the `custom` entries, into `doc`, with
+/// durations as integer seconds.
+fn merge_into_document(
+    doc: &mut toml_edit::DocumentMut,
+    cfg: &Config,
+    custom: &[P2PoolConfigEntry],
+) -> anyhow::Result<()> {
+    use toml_edit::Item;
+
+    let entries = flatten_config(cfg);
+    for (section, entries) in group_by_section(&entries) {
+        let table = section_table(doc, &section)?;
+        for entry in entries {
+            if !entry.enabled {
+                table.remove(&entry.key);
+                continue;
+            }
+            let item = typed_toml_value(entry).map_err(anyhow::Error::msg)?;
+            match table.get_mut(&entry.key).and_then(Item::as_value_mut) {
+                // Keep the existing value's decor (inline comments, spacing)
+                Some(existing) => {
+                    let decor = existing.decor().clone();
+                    if let Ok(mut value) = item.into_value() {
+                        *value.decor_mut() = decor;
+                        *existing = value;
+                    }
+                }
+                None => {
+                    table.insert(&entry.key, item);
+                }
+            }
+        }
+    }
+    insert_custom_entries(doc, custom)?;
+    write_durations_as_secs(doc)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use p2poolv2_config::Config;
     use tempfile::tempdir;
 
-    fn make_config() -> Config {
-        let dir = tempdir().unwrap();
-        let path = dir.path().join("config.toml");
-        std::fs::write(
-            &path,
-            r#"
+    const TEST_CONFIG: &str = r#"
 [stratum]
 hostname = "127.0.0.1"
 port = 3333
@@ -677,7 +1944,7 @@ username = "rpcuser"
 password = "rpcpassword"
 
 [network]
-listen_address = "0.0.0.0:8333"
+listen_address = "/ip4/0.0.0.0/tcp/6884"
 dial_peers = []
 max_pending_incoming = 10
 max_pending_outgoing = 10
@@ -705,9 +1972,12 @@ console = true
 [api]
 hostname = "127.0.0.1"
 port = 3030
-        "#,
-        )
-        .unwrap();
+        "#;
+
+    fn make_config() -> Config {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, TEST_CONFIG).unwrap();
 
         // keep dir alive until Config is loaded
         let cfg = Config::load(path.to_str().unwrap()).expect("inline test config must parse");
@@ -716,6 +1986,143 @@ port = 3030
         cfg
     }
 
+    /// Writes `toml` to `path` and returns the error loading it fails with.
+    fn load_err(path: &std::path::Path, toml: &str) -> ConfigError {
+        std::fs::write(path, toml).unwrap();
+        load_config(path.to_str().unwrap()).expect_err("config must be rejected")
+    }
+
+    /// 1-based number of the first line of `text` starting with `prefix`.
+    fn line_of(text: &str, prefix: &str) -> usize {
+        text.lines().position(|l| l.starts_with(prefix)).unwrap() + 1
+    }
+
+    fn entry_tuples(cfg: &Config) -> Vec<(String, String, String, bool)> {
+        flatten_config(cfg)
+            .into_iter()
+            .map(|e| (e.section.to_string(), e.key, e.value, e.enabled))
+            .collect()
+    }
+
+    #[test]
+    fn load_config_reads_json_and_yaml_like_toml() {
+        let dir = tempdir().unwrap();
+        let toml_path = dir.path().join("config.toml");
+        std::fs::write(&toml_path, TEST_CONFIG).unwrap();
+        let expected = entry_tuples(&load_config(toml_path.to_str().unwrap()).unwrap());
+
+        let table: toml::Table = toml::from_str(TEST_CONFIG).unwrap();
+        let json = serde_json::to_string_pretty(&table).unwrap();
+        // JSON is also valid YAML
+        for name in ["config.json", "config.yaml", "config.yml"] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, &json).unwrap();
+            let cfg = load_config(path.to_str().unwrap()).unwrap();
+            assert_eq!(entry_tuples(&cfg), expected, "{name}");
+        }
+
+        let yaml = dir.path().join("ports.yaml");
+        let err = load_err(&yaml, &json.replace("3333", "70000"));
+        assert!(matches!(err, ConfigError::InvalidPort { .. }), "{err}");
+        assert!(write_config(&yaml, &make_config()).is_err());
+    }
+
+    #[test]
+    fn write_config_roundtrip_preserves_entries() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+        std::fs::write(&path, TEST_CONFIG).unwrap();
+
+        let mut cfg = Config::load(path.to_str().unwrap()).unwrap();
+        cfg.api.auth_token = Some("salt$hmac".to_string());
+        write_config(&path, &cfg).unwrap();
+
+        let reparsed = Config::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(entry_tuples(&cfg), entry_tuples(&reparsed));
+    }
+
+    #[test]
+    fn write_config_writes_real_secrets_and_omits_unset_optionals() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+        std::fs::write(&path, TEST_CONFIG).unwrap();
+
+        let mut cfg = Config::load(path.to_str().unwrap()).unwrap();
+        cfg.stratum.pool_signature = None;
+        write_config(&path, &cfg).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("password = \"rpcpassword\""));
+        assert!(!written.contains("*****"));
+        assert!(!written.contains("pool_signature"));
+        for section in [
+            "stratum",
+            "bitcoinrpc",
+            "network",
+            "store",
+            "logging",
+            "api",
+        ] {
+            assert!(
+                written.contains(&format!("[{section}]")),
+                "missing [{section}]"
+            );
+        }
+    }
+
+    #[test]
+    fn write_config_keeps_untracked_keys_and_comments() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+        std::fs::write(
+            &path,
+            "# pool settings\n[stratum]\nport = 3333 # default\n\n[network]\nmax_pending_incoming = 10\n",
+        )
+        .unwrap();
+
+        let mut cfg = make_config();
+        cfg.stratum.port = 4444;
+        write_config(&path, &cfg).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# pool settings"));
+        assert!(written.contains("port = 4444 # default"));
+        assert!(written.contains("max_pending_incoming = 10"));
+    }
+
+    #[test]
+    fn write_config_with_adds_custom_entries_in_known_and_new_sections() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+        std::fs::write(&path, TEST_CONFIG).unwrap();
+        let cfg = Config::load(path.to_str().unwrap()).unwrap();
+
+        let custom = [
+            P2PoolConfigEntry::custom("stratum", "extra_threads", "4"),
+            P2PoolConfigEntry::custom("metrics", "enabled", "true"),
+            P2PoolConfigEntry::custom("metrics", "label", "pool-1"),
+        ];
+        assert_eq!(custom[0].section, ConfigSection::Stratum);
+        assert_eq!(
+            custom[1].section,
+            ConfigSection::Custom("metrics".to_string())
+        );
+        write_config_with(&path, &cfg, &custom).unwrap();
+
+        let doc = std::fs::read_to_string(&path)
+            .unwrap()
+            .parse::<toml_edit::DocumentMut>()
+            .unwrap();
+        assert_eq!(doc["stratum"]["extra_threads"].as_integer(), Some(4));
+        assert_eq!(doc["metrics"]["enabled"].as_bool(), Some(true));
+        assert_eq!(doc["metrics"]["label"].as_str(), Some("pool-1"));
+        // Tracked values are untouched
+        assert_eq!(
+            doc["stratum"]["port"].as_integer(),
+            Some(i64::from(cfg.stratum.port))
+        );
+    }
+
     #[test]
     fn flatten_produces_entries_for_all_sections() {
         let cfg = make_config();
@@ -732,6 +2139,126 @@ port = 3030
         assert!(entries.iter().any(|e| e.section == ConfigSection::Api));
     }
 
+    #[test]
+    fn unknown_keys_flags_typos_with_a_suggestion() {
+        let cfg = make_config();
+        assert_eq!(unknown_keys(&cfg, TEST_CONFIG), Vec::<String>::new());
+
+        let typo = TEST_CONFIG
+            .replace("port = 3333", "prot = 3333")
+            .replace("[logging]", "[loging]");
+        assert_eq!(
+            unknown_keys(&cfg, &typo),
+            [
+                "unknown key stratum.prot (did you mean port?)",
+                "unknown section [loging] (did you mean [logging]?)",
+            ]
+        );
+
+        let custom = format!("{TEST_CONFIG}\n[metrics]\nenabled = true\n");
+        assert_eq!(unknown_keys(&cfg, &custom), ["unknown section [metrics]"]);
+    }
+
+    #[test]
+    fn network_warnings_flag_zero_window_and_large_per_peer_limit() {
+        let mut entries = flatten_config(&make_config());
+        assert_eq!(network_warnings(&entries), Vec::<String>::new());
+
+        entries.push(P2PoolConfigEntry::custom(
+            "network",
+            "rate_limit_window_secs",
+            "0s",
+        ));
+        let per_peer = entries
+            .iter_mut()
+            .find(|e| e.key == "max_established_per_peer")
+            .unwrap();
+        per_peer.value = "500".to_string();
+
+        assert_eq!(
+            network_warnings(&entries),
+            [
+                "network.rate_limit_window_secs is 0; rate limits are divided by this window",
+                "network.max_established_per_peer (500) exceeds max_established_incoming (50)",
+            ]
+        );
+    }
+
+    #[test]
+    fn store_path_warnings_accept_an_existing_parent() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("data")).unwrap();
+        let mut entries = flatten_config(&make_config());
+        let store = entries
+            .iter_mut()
+            .find(|e| e.section == ConfigSection::Store && e.key == "path")
+            .unwrap();
+
+        // Relative to the working directory, and absolute
+        for path in [
+            "./data/store.db".to_string(),
+            "store.db".to_string(),
+            dir.path().join("data/store.db").display().to_string(),
+        ] {
+            store.value.clone_from(&path);
+            assert!(
+                store_path_warnings(&entries, dir.path()).is_empty(),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn store_path_warnings_flag_a_missing_parent() {
+        let dir = tempdir().unwrap();
+        let mut entries = flatten_config(&make_config());
+        let store = entries
+            .iter_mut()
+            .find(|e| e.section == ConfigSection::Store && e.key == "path")
+            .unwrap();
+        store.value = "./missing/store.db".to_string();
+
+        let warnings = store_path_warnings(&entries, dir.path());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "store.path");
+        assert_eq!(
+            warnings[0].message,
+            format!("{} does not exist", dir.path().join("./missing").display())
+        );
+
+        // A file where the directory should be
+        std::fs::write(dir.path().join("missing"), "").unwrap();
+        assert!(
+            store_path_warnings(&entries, dir.path())[0]
+                .message
+                .ends_with("is not a directory")
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn store_path_warnings_flag_a_directory_that_cannot_be_written() {
+        let mut entries = flatten_config(&make_config());
+        let store = entries
+            .iter_mut()
+            .find(|e| e.section == ConfigSection::Store && e.key == "path")
+            .unwrap();
+        store.value = "store.db".to_string();
+
+        // Not even root can create files in /proc, whatever its mode says
+        let warnings = store_path_warnings(&entries, Path::new("/proc"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "/proc is not writable");
+    }
+
+    #[test]
+    fn edit_distance_counts_single_char_edits() {
+        assert_eq!(edit_distance("stratrum", "stratum"), 1);
+        assert_eq!(edit_distance("prot", "port"), 2);
+        assert_eq!(edit_distance("", "api"), 3);
+        assert_eq!(edit_distance("api", "api"), 0);
+    }
+
     #[test]
     fn sensitive_fields_are_marked() {
         let cfg = make_config();
@@ -741,6 +2268,87 @@ port = 3030
             .find(|e| e.section == ConfigSection::BitcoinRpc && e.key == "password")
             .expect("password entry must exist");
         assert!(password.schema.sensitive);
+        // Masking is up to the view; the entry keeps the real value
+        assert_eq!(password.value, cfg.bitcoinrpc.password);
+    }
+
+    #[test]
+    fn p2pool_to_env_names_variables_after_section_and_key() {
+        let mut entries = flatten_config(&make_config());
+        let vars = p2pool_to_env(&entries, false);
+        let get = |vars: &[(String, String)], name: &str| {
+            vars.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone())
+        };
+
+        assert_eq!(get(&vars, "P2POOL_STRATUM_PORT").as_deref(), Some("3333"));
+        assert_eq!(
+            get(&vars, "P2POOL_BITCOINRPC_URL").as_deref(),
+            Some("http://127.0.0.1:38332")
+        );
+        assert_eq!(get(&vars, "P2POOL_LOGGING_LEVEL").as_deref(), Some("info"));
+        // Secrets and empty lists are left out
+        assert_eq!(get(&vars, "P2POOL_BITCOINRPC_PASSWORD"), None);
+        assert_eq!(get(&vars, "P2POOL_NETWORK_DIAL_PEERS"), None);
+
+        let peers = entries.iter_mut().find(|e| e.key == "dial_peers").unwrap();
+        peers.value = "/ip4/10.0.0.2/tcp/6884,/ip4/10.0.0.3/tcp/6884".to_string();
+        let vars = p2pool_to_env(&entries, true);
+        assert_eq!(
+            get(&vars, "P2POOL_NETWORK_DIAL_PEERS").as_deref(),
+            Some("/ip4/10.0.0.2/tcp/6884,/ip4/10.0.0.3/tcp/6884")
+        );
+        assert_eq!(
+            get(&vars, "P2POOL_BITCOINRPC_PASSWORD").as_deref(),
+            Some("rpcpassword")
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn p2pool_to_env_port_loads_back() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, TEST_CONFIG).unwrap();
+
+        let mut entries = flatten_config(&make_config());
+        let port = entries.iter_mut().find(|e| e.key == "port").unwrap();
+        port.value = "4444".to_string();
+        let vars = p2pool_to_env(&entries, false);
+        let (name, value) = vars
+            .iter()
+            .find(|(name, _)| name == "P2POOL_STRATUM_PORT")
+            .unwrap();
+
+        // SAFETY: serialised with the other tests that touch the environment.
+        unsafe { std::env::set_var(name, value) };
+        let loaded = load_config(path.to_str().unwrap());
+        unsafe { std::env::remove_var(name) };
+
+        assert_eq!(loaded.unwrap().stratum.port, 4444);
+    }
+
+    #[test]
+    fn to_toml_string_roundtrips_through_load() {
+        let mut cfg = make_config();
+        cfg.stratum.pool_signature = None;
+        let toml = to_toml_string(&cfg).unwrap();
+
+        assert!(toml.contains("network = \"signet\""));
+        assert!(toml.contains("version_mask = \"1fffe000\""));
+        assert!(!toml.contains("pool_signature"));
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, &toml).unwrap();
+        let reloaded = Config::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(entry_tuples(&reloaded), entry_tuples(&cfg));
+    }
+
+    #[test]
+    fn to_toml_string_writes_mainnet_as_core_arg() {
+        let mut cfg = make_config();
+        cfg.stratum.network = Network::Bitcoin;
+        assert!(to_toml_string(&cfg).unwrap().contains("network = \"main\""));
     }
 
     #[test]
@@ -764,6 +2372,47 @@ port = 3030
         assert!(apply_edit(&mut cfg, idx, "notanumber").is_err());
     }
 
+    #[test]
+    fn apply_edit_rejects_out_of_range_ports() {
+        let mut cfg = make_config();
+        let entries = flatten_config(&cfg);
+        for section in [ConfigSection::Stratum, ConfigSection::Api] {
+            let idx = entries
+                .iter()
+                .position(|e| e.section == section && e.key == "port")
+                .unwrap();
+            for bad in ["0", "70000"] {
+                assert_eq!(
+                    apply_edit(&mut cfg, idx, bad),
+                    Err(format!("{bad} is not a valid port (1-65535)"))
+                );
+            }
+        }
+        assert_eq!(cfg.stratum.port, make_config().stratum.port);
+    }
+
+    #[test]
+    fn load_config_rejects_zero_and_overflowing_ports() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        for (from, to, bad_field, bad_value) in [
+            ("port = 3333", "port = 0", "stratum.port", "0"),
+            ("port = 3333", "port = 70000", "stratum.port", "70000"),
+            ("port = 3030", "port = 0", "api.port", "0"),
+        ] {
+            let err = load_err(&path, &TEST_CONFIG.replace(from, to));
+            assert!(
+                matches!(&err, ConfigError::InvalidPort { field, value }
+                    if field == bad_field && value == bad_value),
+                "{err}"
+            );
+        }
+
+        std::fs::write(&path, TEST_CONFIG).unwrap();
+        assert!(load_config(path.to_str().unwrap()).is_ok());
+    }
+
     #[test]
     fn apply_edit_optional_empty_clears_field() {
         let mut cfg = make_config();
@@ -783,8 +2432,642 @@ port = 3030
             .iter()
             .position(|e| e.section == ConfigSection::Network && e.key == "dial_peers")
             .unwrap();
-        apply_edit(&mut cfg, idx, "a:1,b:2").unwrap();
-        assert_eq!(cfg.network.dial_peers, vec!["a:1", "b:2"]);
+        apply_edit(
+            &mut cfg,
+            idx,
+            "/ip4/10.0.0.1/tcp/6884,/dns/b.example/tcp/6884",
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.network.dial_peers,
+            vec!["/ip4/10.0.0.1/tcp/6884", "/dns/b.example/tcp/6884"]
+        );
+        assert!(apply_edit(&mut cfg, idx, "a:1,b:2").is_err());
+    }
+
+    #[test]
+    fn apply_edit_validates_addresses_against_network() {
+        let mut cfg = make_config();
+        let idx = flatten_config(&cfg)
+            .iter()
+            .position(|e| e.section == ConfigSection::Stratum && e.key == "bootstrap_address")
+            .unwrap();
+
+        assert!(apply_edit(&mut cfg, idx, "not-an-address").is_err());
+        // Mainnet address on a signet config
+        assert!(apply_edit(&mut cfg, idx, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq").is_err());
+        assert!(apply_edit(&mut cfg, idx, "tb1qyazxde6558qj6z3d9np5e6msmrspwpf6k0qggk").is_ok());
+    }
+
+    #[test]
+    fn wrong_network_solo_address_is_rejected() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let with_solo = |address: &str| {
+            TEST_CONFIG.replace(
+                "network = \"signet\"",
+                &format!("solo_address = \"{address}\"\nnetwork = \"signet\""),
+            )
+        };
+
+        // Mainnet address on a signet config
+        let mainnet = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        let err = load_err(&path, &with_solo(mainnet));
+        assert!(
+            matches!(&err, ConfigError::InvalidAddress { field: "solo_address", value, .. }
+                if value == mainnet),
+            "{err}"
+        );
+
+        std::fs::write(
+            &path,
+            with_solo("tb1qyazxde6558qj6z3d9np5e6msmrspwpf6k0qggk"),
+        )
+        .unwrap();
+        assert!(load_config(path.to_str().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn donation_and_fee_are_capped_at_100_percent() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let address = "tb1qyazxde6558qj6z3d9np5e6msmrspwpf6k0qggk";
+        let with_payouts = |extra: &str| {
+            TEST_CONFIG.replace(
+                "network = \"signet\"",
+                &format!("{extra}\nnetwork = \"signet\""),
+            )
+        };
+
+        let err = load_err(
+            &path,
+            &with_payouts(&format!(
+                "donation = 50000\ndonation_address = \"{address}\""
+            )),
+        );
+        assert!(
+            matches!(
+                err,
+                ConfigError::BasisPointsOutOfRange {
+                    field: "donation",
+                    value: 50000
+                }
+            ),
+            "{err}"
+        );
+
+        let err = load_err(
+            &path,
+            &with_payouts(&format!(
+                "donation = 6000\ndonation_address = \"{address}\"\n\
+                 fee = 5000\nfee_address = \"{address}\""
+            )),
+        );
+        assert!(
+            matches!(err, ConfigError::PayoutTooHigh { total: 11000 }),
+            "{err}"
+        );
+
+        let err = load_err(&path, &with_payouts("fee = 100"));
+        assert!(
+            matches!(
+                err,
+                ConfigError::MissingPayoutAddress {
+                    field: "fee",
+                    value: 100
+                }
+            ),
+            "{err}"
+        );
+
+        // Zero means no donation and needs no address
+        std::fs::write(&path, with_payouts("donation = 0")).unwrap();
+        assert!(load_config(path.to_str().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn apply_edit_rejects_payouts_above_100_percent() {
+        let mut cfg = make_config();
+        let entries = flatten_config(&cfg);
+        let idx = |key: &str| {
+            entries
+                .iter()
+                .position(|e| e.section == ConfigSection::Stratum && e.key == key)
+                .unwrap()
+        };
+
+        assert!(apply_edit(&mut cfg, idx("donation"), "10001").is_err());
+        apply_edit(&mut cfg, idx("fee"), "").unwrap();
+        apply_edit(&mut cfg, idx("donation"), "6000").unwrap();
+        assert!(apply_edit(&mut cfg, idx("fee"), "5000").is_err());
+        assert_eq!(cfg.stratum.fee, None);
+        apply_edit(&mut cfg, idx("fee"), "4000").unwrap();
+        assert_eq!(cfg.stratum.fee, Some(4000));
+    }
+
+    #[test]
+    fn clearing_a_payout_address_removes_the_payout() {
+        let mut cfg = make_config();
+        let address = "tb1qyazxde6558qj6z3d9np5e6msmrspwpf6k0qggk";
+        cfg.stratum.donation_address = Some(address.to_string());
+        cfg.stratum.donation = Some(100);
+        cfg.stratum.fee_address = Some(address.to_string());
+        cfg.stratum.fee = Some(200);
+        let entries = flatten_config(&cfg);
+        let idx = |key: &str| {
+            entries
+                .iter()
+                .position(|e| e.section == ConfigSection::Stratum && e.key == key)
+                .unwrap()
+        };
+
+        apply_edit(&mut cfg, idx("donation_address"), "").unwrap();
+        apply_edit(&mut cfg, idx("fee_address"), "  ").unwrap();
+        assert_eq!(cfg.stratum.donation_address, None);
+        assert_eq!(cfg.stratum.donation, None);
+        assert_eq!((cfg.stratum.fee_address, cfg.stratum.fee), (None, None));
+        assert!(validate_config(&cfg).is_ok());
+
+        // The cleared keys are left out of the saved file
+        let saved = to_toml_string(&cfg).unwrap();
+        assert!(!saved.contains("donation"), "{saved}");
+        assert!(!saved.contains("fee"), "{saved}");
+    }
+
+    #[test]
+    fn endpoints_expose_host_and_port() {
+        let cfg = make_config();
+        assert_eq!(
+            zmq_endpoint(&cfg),
+            Ok(Endpoint {
+                scheme: "tcp".to_string(),
+                host: "127.0.0.1".to_string(),
+                port: 28332,
+            })
+        );
+        let rpc = rpc_endpoint(&cfg).unwrap();
+        assert_eq!((rpc.host.as_str(), rpc.port), ("127.0.0.1", 38332));
+
+        let v6 = Endpoint::parse("https://[::1]:8332/wallet/x", &["http", "https"]).unwrap();
+        assert_eq!((v6.host.as_str(), v6.port), ("::1", 8332));
+    }
+
+    #[test]
+    fn load_config_rejects_bad_endpoint_schemes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let udp = TEST_CONFIG.replace("tcp://127.0.0.1:28332", "udp://127.0.0.1:28332");
+        let err = load_err(&path, &udp);
+        assert!(
+            matches!(&err, ConfigError::InvalidEndpoint { field: "zmqpubhashblock", reason }
+                if reason == "udp://127.0.0.1:28332 must start with tcp://"),
+            "{err}"
+        );
+
+        let no_scheme = TEST_CONFIG.replace("http://127.0.0.1:38332", "127.0.0.1:38332");
+        let err = load_err(&path, &no_scheme);
+        assert!(
+            matches!(&err, ConfigError::InvalidEndpoint { field: "bitcoinrpc.url", reason }
+                if reason == "127.0.0.1:38332 must start with http:// or https://"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn validate_multiaddr_accepts_common_forms() {
+        for ok in [
+            "/ip4/0.0.0.0/tcp/6884",
+            "/ip6/::1/udp/6884/quic-v1",
+            "/dns/seed.example.com/tcp/6884/p2p/12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN",
+        ] {
+            assert!(validate_multiaddr(ok).is_ok(), "{ok}");
+        }
+        for bad in [
+            "0.0.0.0:6884",
+            "/ip4/300.0.0.1/tcp/1",
+            "/ip4/1.2.3.4/tcp/",
+            "/foo/1",
+        ] {
+            assert_eq!(
+                validate_multiaddr(bad),
+                Err(format!("{bad} is not a valid multiaddr"))
+            );
+        }
+    }
+
+    #[test]
+    fn load_config_rejects_malformed_multiaddrs() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let bad_listen = TEST_CONFIG.replace("/ip4/0.0.0.0/tcp/6884", "/ip4/0.0.0.0/tcp");
+        let err = load_err(&path, &bad_listen);
+        assert!(
+            matches!(
+                err,
+                ConfigError::InvalidEndpoint {
+                    field: "listen_address",
+                    ..
+                }
+            ),
+            "{err}"
+        );
+
+        let bad_peer = TEST_CONFIG.replace(
+            "dial_peers = []",
+            "dial_peers = [\"/ip4/10.0.0.1/tcp/6884\", \"10.0.0.2:6884\"]",
+        );
+        let err = load_err(&path, &bad_peer);
+        assert!(
+            matches!(&err, ConfigError::InvalidEndpoint { field: "dial_peers entry", reason }
+                if reason == "10.0.0.2:6884 is not a valid multiaddr"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn entry_origins_reports_env_overrides() {
+        let cfg = make_config();
+        let entries = flatten_config(&cfg);
+        let port = entries
+            .iter()
+            .position(|e| e.section == ConfigSection::Stratum && e.key == "port")
+            .unwrap();
+        assert_eq!(entries[port].env_var(), "P2POOL_STRATUM_PORT");
+
+        let origins = entry_origins(&cfg, TEST_CONFIG, |name| name == "P2POOL_STRATUM_PORT");
+
+        assert_eq!(origins.len(), entries.len());
+        assert_eq!(origins[port], Origin::Env);
+        let hostname = entries.iter().position(|e| e.key == "hostname").unwrap();
+        assert_eq!(origins[hostname], Origin::File);
+        let solo = entries
+            .iter()
+            .position(|e| e.key == "solo_address")
+            .unwrap();
+        assert_eq!(origins[solo], Origin::Default);
+    }
+
+    #[test]
+    fn parse_duration_secs_accepts_suffixes_and_bare_seconds() {
+        assert_eq!(parse_duration_secs("2m"), Ok(120));
+        assert_eq!(parse_duration_secs("45"), Ok(45));
+        assert_eq!(parse_duration_secs("30s"), Ok(30));
+        assert_eq!(parse_duration_secs("1h"), Ok(3600));
+        assert_eq!(
+            parse_duration_secs("5x"),
+            Err("unknown duration suffix 'x' in '5x'; use s, m, h or d".to_string())
+        );
+        assert!(parse_duration_secs("m").is_err());
+    }
+
+    #[test]
+    fn deserialize_duration_secs_takes_numbers_or_strings() {
+        #[derive(serde::Deserialize)]
+        struct Timeout {
+            #[serde(deserialize_with = "deserialize_duration_secs")]
+            secs: u64,
+        }
+        let secs = |toml: &str| toml::from_str::<Timeout>(toml).map(|t| t.secs);
+
+        assert_eq!(secs("secs = \"2m\"").unwrap(), 120);
+        assert_eq!(secs("secs = 45").unwrap(), 45);
+        let err = secs("secs = \"5x\"").unwrap_err();
+        assert!(
+            err.to_string().contains("unknown duration suffix 'x'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn load_config_reads_duration_strings_as_seconds() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        std::fs::write(
+            &path,
+            TEST_CONFIG.replace("dial_timeout_secs = 30", "dial_timeout_secs = \"2m\""),
+        )
+        .unwrap();
+        let cfg = load_config(path.to_str().unwrap()).unwrap();
+        assert_eq!(cfg.network.dial_timeout_secs, 120);
+
+        let bad = TEST_CONFIG.replace("dial_timeout_secs = 30", "dial_timeout_secs = \"5x\"");
+        let err = load_err(&path, &bad);
+        assert!(
+            matches!(
+                &err,
+                ConfigError::InvalidValue { key, line, .. }
+                    if key == "network.dial_timeout_secs"
+                        && *line == line_of(&bad, "dial_timeout_secs")
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn durations_are_saved_as_integer_seconds() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            TEST_CONFIG.replace(
+                "dial_timeout_secs = 30",
+                "dial_timeout_secs = \"2m\"\npeer_inactivity_timeout_secs = \"1h\"",
+            ),
+        )
+        .unwrap();
+        let cfg = load_config(path.to_str().unwrap()).unwrap();
+
+        let custom = [P2PoolConfigEntry::custom(
+            "network",
+            "rate_limit_window_secs",
+            "5m",
+        )];
+        write_config_with(&path, &cfg, &custom).unwrap();
+        let doc = std::fs::read_to_string(&path)
+            .unwrap()
+            .parse::<toml_edit::DocumentMut>()
+            .unwrap();
+        assert_eq!(doc["network"]["dial_timeout_secs"].as_integer(), Some(120));
+        assert_eq!(
+            doc["network"]["peer_inactivity_timeout_secs"].as_integer(),
+            Some(3600)
+        );
+        assert_eq!(
+            doc["network"]["rate_limit_window_secs"].as_integer(),
+            Some(300)
+        );
+
+        let bad = [P2PoolConfigEntry::custom(
+            "network",
+            "rate_limit_window_secs",
+            "5x",
+        )];
+        let err = write_config_with(&path, &cfg, &bad).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("network.rate_limit_window_secs: unknown duration suffix 'x'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn load_config_rejects_misordered_difficulties() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let low_start = TEST_CONFIG.replace("start_difficulty = 1000", "start_difficulty = 50");
+        let err = load_err(&path, &low_start);
+        assert!(
+            matches!(
+                err,
+                ConfigError::DifficultyOrder {
+                    field: "start_difficulty",
+                    value: 50,
+                    bound_field: "minimum_difficulty",
+                    bound: 100,
+                }
+            ),
+            "{err}"
+        );
+
+        let low_max =
+            TEST_CONFIG.replace("maximum_difficulty = 100000", "maximum_difficulty = 500");
+        let err = load_err(&path, &low_max);
+        assert!(
+            matches!(
+                err,
+                ConfigError::DifficultyOrder {
+                    field: "maximum_difficulty",
+                    value: 500,
+                    bound_field: "start_difficulty",
+                    bound: 1000,
+                }
+            ),
+            "{err}"
+        );
+
+        // Equal values are fine
+        let equal = TEST_CONFIG
+            .replace("start_difficulty = 1000", "start_difficulty = 100")
+            .replace("maximum_difficulty = 100000", "maximum_difficulty = 100");
+        std::fs::write(&path, equal).unwrap();
+        assert!(load_config(path.to_str().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn apply_edit_keeps_difficulties_ordered() {
+        let mut cfg = make_config();
+        let entries = flatten_config(&cfg);
+        let idx = |key: &str| entries.iter().position(|e| e.key == key).unwrap();
+
+        assert!(apply_edit(&mut cfg, idx("start_difficulty"), "50").is_err());
+        assert!(apply_edit(&mut cfg, idx("maximum_difficulty"), "500").is_err());
+        assert!(apply_edit(&mut cfg, idx("minimum_difficulty"), "2000").is_err());
+        assert_eq!(cfg.stratum.start_difficulty, 1000);
+        apply_edit(&mut cfg, idx("maximum_difficulty"), "").unwrap();
+        apply_edit(&mut cfg, idx("start_difficulty"), "1000000").unwrap();
+    }
+
+    #[test]
+    fn parse_network_accepts_core_args_and_aliases() {
+        for (name, expected) in [
+            ("main", Network::Bitcoin),
+            ("mainnet", Network::Bitcoin),
+            ("bitcoin", Network::Bitcoin),
+            ("Mainnet", Network::Bitcoin),
+            ("test", Network::Testnet),
+            ("testnet", Network::Testnet),
+            ("signet", Network::Signet),
+            ("regtest", Network::Regtest),
+        ] {
+            assert_eq!(parse_network(name), Ok(expected), "{name}");
+        }
+        assert_eq!(parse_network("testnet4").unwrap().to_core_arg(), "testnet4");
+    }
+
+    #[test]
+    fn load_config_accepts_network_aliases() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        // The bootstrap address is a tb1 one, valid on both test networks
+        for (alias, expected) in [
+            ("testnet", Network::Testnet),
+            ("TESTNET", Network::Testnet),
+            ("Signet", Network::Signet),
+        ] {
+            std::fs::write(
+                &path,
+                TEST_CONFIG.replace("network = \"signet\"", &format!("network = \"{alias}\"")),
+            )
+            .unwrap();
+            let cfg = load_config(path.to_str().unwrap()).unwrap();
+            assert_eq!(cfg.stratum.network, expected, "{alias}");
+        }
+    }
+
+    #[test]
+    fn parse_network_lists_accepted_names_for_unknown_values() {
+        assert_eq!(
+            parse_network("litecoin"),
+            Err("unknown network 'litecoin'; expected one of \
+                 main, test, testnet4, signet, regtest (or mainnet, bitcoin, testnet)"
+                .to_string())
+        );
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let err = load_err(
+            &path,
+            &TEST_CONFIG.replace("network = \"signet\"", "network = \"litecoin\""),
+        );
+        assert!(
+            matches!(&err, ConfigError::UnknownNetwork(name) if name == "litecoin"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn locate_error_points_at_the_named_key() {
+        let message = "invalid type: string \"high\", expected u64 for key \
+                       `stratum.start_difficulty` in config.toml";
+        let err = locate_error(TEST_CONFIG, message.to_string());
+        let expected = line_of(TEST_CONFIG, "start_difficulty");
+        assert!(
+            matches!(&err, ConfigError::InvalidValue { key, line, column: 20, .. }
+                if key == "stratum.start_difficulty" && *line == expected),
+            "{err}"
+        );
+        assert!(err.to_string().ends_with(message));
+
+        let message = "invalid type for key `network.dial_peers[0]`";
+        let with_peer = TEST_CONFIG.replace("dial_peers = []", "dial_peers = [7]");
+        let err = locate_error(&with_peer, message.to_string());
+        assert!(
+            matches!(&err, ConfigError::InvalidValue { line, .. }
+                if *line == line_of(&with_peer, "dial_peers")),
+            "{err}"
+        );
+
+        // No key, or a key the file doesn't have: keep the message as is
+        for message in [
+            "missing field `hostname`",
+            "bad value for key `stratum.nope`",
+        ] {
+            let err = locate_error(TEST_CONFIG, message.to_string());
+            assert!(
+                matches!(&err, ConfigError::Deserialize(m) if m == message),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn load_config_reports_the_line_of_a_mistyped_value() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let mistyped =
+            TEST_CONFIG.replace("start_difficulty = 1000", "start_difficulty = \"high\"");
+
+        let err = load_err(&path, &mistyped);
+
+        let line = line_of(&mistyped, "start_difficulty");
+        assert!(
+            err.to_string().contains(&format!("at line {line}")),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn api_auth_needs_both_user_and_token() {
+        assert!(check_api_auth(None, None).is_ok());
+        assert!(check_api_auth(Some("admin"), Some("salt$hmac")).is_ok());
+        for (user, token) in [(Some("admin"), None), (None, Some("salt$hmac"))] {
+            let err = check_api_auth(user, token).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "api auth requires both auth_user and auth_token"
+            );
+        }
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let only_user = TEST_CONFIG.replace("port = 3030", "port = 3030\nauth_user = \"admin\"");
+        let err = load_err(&path, &only_user);
+        assert!(matches!(err, ConfigError::IncompleteApiAuth), "{err}");
+    }
+
+    #[test]
+    fn load_config_rejects_port_conflicts() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let err = load_err(&path, &TEST_CONFIG.replace("port = 3030", "port = 3333"));
+        assert!(
+            matches!(
+                &err,
+                ConfigError::PortConflict {
+                    first: "stratum",
+                    second: "api",
+                    port: 3333,
+                    host,
+                } if host == "127.0.0.1"
+            ),
+            "{err}"
+        );
+
+        let err = load_err(&path, &TEST_CONFIG.replace("port = 3030", "port = 28332"));
+        assert!(
+            matches!(
+                err,
+                ConfigError::PortConflict {
+                    first: "api",
+                    second: "zmqpubhashblock",
+                    port: 28332,
+                    ..
+                }
+            ),
+            "{err}"
+        );
+
+        // Different hosts may share a port
+        let split = TEST_CONFIG.replace("port = 3030", "port = 3333").replace(
+            "[api]\nhostname = \"127.0.0.1\"",
+            "[api]\nhostname = \"10.0.0.1\"",
+        );
+        std::fs::write(&path, split).unwrap();
+        assert!(load_config(path.to_str().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn config_to_json_masks_secrets_and_flags_defaults() {
+        let entries = flatten_config(&make_config());
+        let json = config_to_json(&entries, false);
+
+        let array = json.as_array().unwrap();
+        assert_eq!(array.len(), entries.len());
+        let find = |section: &str, key: &str| {
+            array
+                .iter()
+                .find(|v| v["section"] == section && v["key"] == key)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(
+            find("stratum", "port"),
+            serde_json::json!({
+                "section": "stratum",
+                "key": "port",
+                "value": "3333",
+                "enabled": true,
+                "is_default": false,
+            })
+        );
+        assert_eq!(find("bitcoinrpc", "password")["value"], "*****");
+        assert_eq!(find("stratum", "solo_address")["is_default"], true);
     }
 
     #[test]
@@ -850,10 +3133,13 @@ port = 3030
         apply_edit(
             &mut cfg,
             idx(ConfigSection::Network, "dial_peers"),
-            "a:1,b:2",
+            "/ip4/10.0.0.1/tcp/1,/ip4/10.0.0.2/tcp/2",
         )
         .unwrap();
-        assert_eq!(cfg.network.dial_peers, vec!["a:1", "b:2"]);
+        assert_eq!(
+            cfg.network.dial_peers,
+            vec!["/ip4/10.0.0.1/tcp/1", "/ip4/10.0.0.2/tcp/2"]
+        );
 
         // Enum parsing
         apply_edit(&mut cfg, idx(ConfigSection::Stratum, "network"), "signet").unwrap();
@@ -915,6 +3201,7 @@ port = 3030
                 type_hint: "String".to_string(),
                 sensitive: false,
             },
+            origin: Origin::File,
         };
         let result = dispatch_edit(&mut cfg, &fake_entry, "value");
         assert!(result.is_err());
@@ -936,4 +3223,70 @@ port = 3030
             "error message must mention index out of range"
         );
     }
+
+    #[test]
+    fn group_by_section_keeps_first_seen_order_for_the_example_config() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+        std::fs::write(&path, default_config_string()).unwrap();
+        let cfg = load_config(path.to_str().unwrap()).unwrap();
+        let mut entries = flatten_config(&cfg);
+        // Custom keys follow the typed ones, so [stratum] comes back late
+        entries.push(P2PoolConfigEntry::custom("metrics", "port", "9100"));
+        entries.push(P2PoolConfigEntry::custom("stratum", "motd", "hi"));
+
+        let groups = group_by_section(&entries);
+        let names: Vec<&str> = groups.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "stratum",
+                "bitcoinrpc",
+                "network",
+                "store",
+                "logging",
+                "api",
+                "metrics"
+            ]
+        );
+
+        for (name, group) in &groups {
+            let expected: Vec<&str> = entries
+                .iter()
+                .filter(|e| e.section.to_string() == *name)
+                .map(|e| e.key.as_str())
+                .collect();
+            let keys: Vec<&str> = group.iter().map(|e| e.key.as_str()).collect();
+            assert_eq!(keys, expected, "[{name}]");
+        }
+        assert_eq!(groups[0].1.last().unwrap().key, "motd");
+        assert_eq!(
+            groups.iter().map(|(_, g)| g.len()).sum::<usize>(),
+            entries.len()
+        );
+    }
+
+    #[test]
+    fn default_config_string_loads_and_lists_every_field() {
+        let template = default_config_string();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+        std::fs::write(&path, &template).unwrap();
+        let cfg = load_config(path.to_str().unwrap()).expect("template must load");
+        // No one's real address ships in the template
+        assert_eq!(cfg.stratum.bootstrap_address, "");
+
+        for entry in flatten_config(&cfg) {
+            let header = format!("[{}]\n", entry.section);
+            let rest = template.split(&header).nth(1).expect("section missing");
+            let body = format!("\n{}", rest.split("\n[").next().unwrap());
+            assert!(
+                body.contains(&format!("\n{} = ", entry.key))
+                    || body.contains(&format!("\n#{} = ", entry.key)),
+                "{}.{} missing",
+                entry.section,
+                entry.key
+            );
+        }
+    }
 }
@@ -2,6 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::theme::ThemeOverrides;
 use anyhow::Result;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
@@ -30,6 +31,10 @@ pub struct Settings {
     /// the default location always holds a copy so the override is found
     /// on the next launch.
     pub settings_dir_override: Option<PathBuf>,
+    /// When set, saving a config replaces it without first copying the old
+    /// file to `<path>.bak`.
+    #[serde(default)]
+    pub skip_backups: bool,
 }
 
 /// Returns the directory where `settings.toml` is stored.
@@ -111,6 +116,137 @@ pub fn save_settings(settings: &Settings) -> Result<()> {
     Ok(())
 }
 
+/// Where the last run left off, restored on the next launch. Unlike
+/// [`Settings`] nothing here is chosen by the user, so a missing or
+/// unreadable file just means starting fresh. Config paths live in
+/// [`Settings`] only, so the two files never disagree about them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionState {
+    /// Sidebar label of the screen that was open, e.g. `"P2Pool Config"`.
+    pub screen: Option<String>,
+    pub sidebar_index: usize,
+    /// Name of the built-in palette, `dark` or `light`.
+    pub theme: Option<String>,
+}
+
+/// Returns the path to the session state file.
+///
+/// # Errors
+/// Returns an error if [`config_dir`] fails.
+pub fn state_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("state.toml"))
+}
+
+/// Loads the session state. Returns `None` if the file does not exist or
+/// cannot be parsed, so the caller can keep its defaults.
+#[must_use]
+pub fn load_session_state() -> Option<SessionState> {
+    let content = std::fs::read_to_string(state_path().ok()?).ok()?;
+    toml::from_str(&content).ok()
+}
+
+/// Saves the session state, creating the config directory if needed.
+///
+/// # Errors
+/// Returns an error if the directory cannot be created or the file written.
+pub fn save_session_state(state: &SessionState) -> Result<()> {
+    let path = state_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::util::atomic_write(&path, &toml::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+/// Returns the path to the optional color overrides file.
+///
+/// # Errors
+/// Returns an error if [`config_dir`] fails.
+pub fn theme_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("theme.toml"))
+}
+
+/// Loads color overrides from `theme.toml`, with warnings for anything that
+/// was skipped. A missing file means no overrides and no warnings.
+#[must_use]
+pub fn load_theme_overrides() -> (ThemeOverrides, Vec<String>) {
+    let Ok(path) = theme_path() else {
+        return (ThemeOverrides::default(), Vec::new());
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(content) => ThemeOverrides::parse(&content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            (ThemeOverrides::default(), Vec::new())
+        }
+        Err(e) => (
+            ThemeOverrides::default(),
+            vec![format!("cannot read {}: {e}", path.display())],
+        ),
+    }
+}
+
+/// Returns the path to the explorer bookmarks file.
+///
+/// # Errors
+/// Returns an error if [`config_dir`] fails.
+pub fn bookmarks_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("bookmarks.json"))
+}
+
+/// Loads explorer bookmarks from disk. Returns `None` if the file does not
+/// exist or cannot be parsed, so the caller can keep its defaults.
+#[must_use]
+pub fn load_bookmarks() -> Option<Vec<PathBuf>> {
+    let path = bookmarks_path().ok()?;
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Returns the path to the file holding the explorer's last directory.
+///
+/// # Errors
+/// Returns an error if [`config_dir`] fails.
+pub fn last_dir_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("last_dir"))
+}
+
+/// Loads the directory the explorer was last left in. Returns `None` if it
+/// was never saved or no longer exists.
+#[must_use]
+pub fn load_last_dir() -> Option<PathBuf> {
+    let content = std::fs::read_to_string(last_dir_path().ok()?).ok()?;
+    let dir = PathBuf::from(content.trim_end_matches('\n'));
+    dir.is_dir().then_some(dir)
+}
+
+/// Saves the explorer's current directory, creating the config directory
+/// if needed.
+///
+/// # Errors
+/// Returns an error if the directory cannot be created or the file written.
+pub fn save_last_dir(dir: &std::path::Path) -> Result<()> {
+    let path = last_dir_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, format!("{}\n", dir.display()))?;
+    Ok(())
+}
+
+/// Saves explorer bookmarks to disk, creating the config directory if needed.
+///
+/// # Errors
+/// Returns an error if the directory cannot be created or the file written.
+pub fn save_bookmarks(bookmarks: &[PathBuf]) -> Result<()> {
+    let path = bookmarks_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(bookmarks)?)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,6 +342,68 @@ mod tests {
         unsafe { std::env::set_var("PDM_CONFIG_DIR", dir.path()) };
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn load_theme_overrides_reads_theme_toml() {
+        use crate::theme::Theme;
+        use ratatui::style::{Color, Style};
+
+        let dir = tempfile::tempdir().unwrap();
+        set_config_dir(&dir);
+        assert_eq!(load_theme_overrides(), (ThemeOverrides::default(), vec![]));
+
+        std::fs::write(
+            dir.path().join("theme.toml"),
+            "key = \"magenta\"\nerror = \"crimson\"\n",
+        )
+        .unwrap();
+        let (overrides, warnings) = load_theme_overrides();
+        assert_eq!(
+            warnings,
+            ["error: invalid color 'crimson'; keeping the default"]
+        );
+        let theme = overrides.apply(Theme::dark());
+        assert_eq!(theme.key, Style::default().fg(Color::Magenta));
+        assert_eq!(theme.error, Theme::dark().error);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn session_state_round_trips_through_state_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        set_config_dir(&dir);
+        assert_eq!(load_session_state(), None);
+
+        let state = SessionState {
+            screen: Some("P2Pool Config".to_string()),
+            sidebar_index: 3,
+            theme: Some("light".to_string()),
+        };
+        save_session_state(&state).unwrap();
+        assert!(dir.path().join("state.toml").exists());
+        assert_eq!(load_session_state(), Some(state));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn corrupt_session_state_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        set_config_dir(&dir);
+
+        std::fs::write(dir.path().join("state.toml"), "screen = [not toml").unwrap();
+        assert_eq!(load_session_state(), None);
+
+        // Fields missing from an older file fall back to their defaults
+        std::fs::write(dir.path().join("state.toml"), "sidebar_index = 2\n").unwrap();
+        assert_eq!(
+            load_session_state(),
+            Some(SessionState {
+                sidebar_index: 2,
+                ..SessionState::default()
+            })
+        );
+    }
+
     #[test]
     #[serial_test::serial]
     fn load_settings_returns_default_when_file_missing() {
@@ -363,4 +561,35 @@ mod tests {
             Some(PathBuf::from("/default/bitcoin.conf"))
         );
     }
+
+    #[test]
+    #[serial_test::serial]
+    fn last_dir_roundtrip_via_public_functions() {
+        let dir = tempfile::tempdir().unwrap();
+        set_config_dir(&dir);
+        assert!(load_last_dir().is_none());
+
+        let visited = dir.path().join("configs");
+        std::fs::create_dir(&visited).unwrap();
+        save_last_dir(&visited).unwrap();
+        assert_eq!(load_last_dir(), Some(visited.clone()));
+
+        // A directory removed since is not resumed
+        std::fs::remove_dir(&visited).unwrap();
+        assert!(load_last_dir().is_none());
+        unsafe { std::env::remove_var("PDM_CONFIG_DIR") };
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn bookmarks_roundtrip_via_public_functions() {
+        let dir = tempfile::tempdir().unwrap();
+        set_config_dir(&dir);
+        assert!(load_bookmarks().is_none());
+
+        let bookmarks = vec![PathBuf::from("/tmp/a"), PathBuf::from("/tmp/b")];
+        save_bookmarks(&bookmarks).unwrap();
+        assert!(dir.path().join("bookmarks.json").exists());
+        assert_eq!(load_bookmarks(), Some(bookmarks));
+    }
 }
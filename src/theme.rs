@@ -0,0 +1,275 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use ratatui::style::{Color, Modifier, Style};
+
+/// Named style roles used by the views instead of hardcoded colors.
+///
+/// The dark palette reproduces the original colors, so the default look is
+/// unchanged; `t` cycles to the light palette at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Selected row in the main sidebar.
+    pub sidebar_highlight: Style,
+    /// Selected row in a config list.
+    pub list_highlight: Style,
+    /// `[section]` prefix in the p2pool list.
+    pub section_label: Style,
+    /// `key = ` prefix of a config row.
+    pub key: Style,
+    /// Primary text such as the detail-panel description.
+    pub text: Style,
+    /// Descriptions and secondary labels.
+    pub description: Style,
+    /// Placeholder shown for unset entries.
+    pub default_value: Style,
+    /// Value of an entry that is set.
+    pub set_value: Style,
+    /// Edit buffers, dirty titles and cautions.
+    pub warning: Style,
+    pub error: Style,
+    pub success: Style,
+    /// Panel borders while focus is elsewhere.
+    pub dimmed: Style,
+    /// Background of the footer with messages and key hints.
+    pub status_bar: Style,
+}
+
+impl Theme {
+    #[must_use]
+    pub fn dark() -> Self {
+        Self {
+            name: "dark",
+            sidebar_highlight: Style::default().bg(Color::Gray).fg(Color::Black),
+            list_highlight: Style::default().bg(Color::DarkGray),
+            section_label: Style::default().fg(Color::Blue),
+            key: Style::default().fg(Color::Cyan),
+            text: Style::default().fg(Color::White),
+            description: Style::default().fg(Color::Gray),
+            default_value: Style::default().fg(Color::DarkGray),
+            set_value: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            warning: Style::default().fg(Color::Yellow),
+            error: Style::default().fg(Color::Red),
+            success: Style::default().fg(Color::Green),
+            dimmed: Style::default().fg(Color::DarkGray),
+            status_bar: Style::default().bg(Color::Black),
+        }
+    }
+
+    /// Palette for terminals with a light background.
+    #[must_use]
+    pub fn light() -> Self {
+        Self {
+            name: "light",
+            sidebar_highlight: Style::default().bg(Color::Blue).fg(Color::White),
+            list_highlight: Style::default().bg(Color::Gray),
+            section_label: Style::default().fg(Color::Magenta),
+            key: Style::default().fg(Color::Blue),
+            text: Style::default().fg(Color::Black),
+            description: Style::default().fg(Color::DarkGray),
+            default_value: Style::default().fg(Color::Gray),
+            set_value: Style::default()
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+            warning: Style::default().fg(Color::Rgb(176, 112, 0)),
+            error: Style::default().fg(Color::Red),
+            success: Style::default().fg(Color::Rgb(0, 128, 0)),
+            dimmed: Style::default().fg(Color::Gray),
+            status_bar: Style::default().bg(Color::White),
+        }
+    }
+
+    /// The style of the role named like its field, e.g. `list_highlight`.
+    fn role_mut(&mut self, role: &str) -> Option<&mut Style> {
+        Some(match role {
+            "sidebar_highlight" => &mut self.sidebar_highlight,
+            "list_highlight" => &mut self.list_highlight,
+            "section_label" => &mut self.section_label,
+            "key" => &mut self.key,
+            "text" => &mut self.text,
+            "description" => &mut self.description,
+            "default_value" => &mut self.default_value,
+            "set_value" => &mut self.set_value,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "success" => &mut self.success,
+            "dimmed" => &mut self.dimmed,
+            "status_bar" => &mut self.status_bar,
+            _ => return None,
+        })
+    }
+
+    /// The built-in palette called `name`, as stored in the session state.
+    #[must_use]
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            _ => None,
+        }
+    }
+
+    /// Returns the theme that follows `self` when cycling with `t`.
+    #[must_use]
+    pub fn next(&self) -> Self {
+        if self.name == "dark" {
+            Self::light()
+        } else {
+            Self::dark()
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Colors read from the user's `theme.toml`, laid over whichever built-in
+/// palette is active so `t` keeps them. Each key names a [`Theme`] role and
+/// takes either a color, which sets the foreground, or a `{ fg, bg }` table:
+///
+/// ```toml
+/// key = "lightblue"
+/// warning = "#f9e2af"
+/// list_highlight = { bg = "236" }
+/// ```
+///
+/// Colors are names such as `blue`, `#rrggbb` hex or a 256-color index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThemeOverrides {
+    /// `(role, fg, bg)` in file order; `None` keeps the palette's color.
+    roles: Vec<(String, Option<Color>, Option<Color>)>,
+}
+
+impl ThemeOverrides {
+    /// Reads overrides from the text of a `theme.toml`. Unknown roles and
+    /// colors that do not parse are skipped, keeping the palette's color,
+    /// and described in the returned warnings.
+    #[must_use]
+    pub fn parse(text: &str) -> (Self, Vec<String>) {
+        let mut overrides = Self::default();
+        let mut warnings = Vec::new();
+        let table = match text.parse::<toml::Table>() {
+            Ok(table) => table,
+            Err(e) => {
+                warnings.push(format!("not valid TOML: {}", e.message()));
+                return (overrides, warnings);
+            }
+        };
+
+        for (role, value) in &table {
+            if Theme::dark().role_mut(role).is_none() {
+                warnings.push(format!("unknown role '{role}'"));
+                continue;
+            }
+            let mut color = |value: Option<&toml::Value>| {
+                let value = value?;
+                let text = value
+                    .as_str()
+                    .map_or_else(|| value.to_string(), str::to_string);
+                match text.parse::<Color>() {
+                    Ok(color) => Some(color),
+                    Err(_) => {
+                        warnings.push(format!(
+                            "{role}: invalid color '{text}'; keeping the default"
+                        ));
+                        None
+                    }
+                }
+            };
+            let (fg, bg) = match value {
+                toml::Value::Table(colors) => (color(colors.get("fg")), color(colors.get("bg"))),
+                other => (color(Some(other)), None),
+            };
+            if fg.is_some() || bg.is_some() {
+                overrides.roles.push((role.clone(), fg, bg));
+            }
+        }
+        (overrides, warnings)
+    }
+
+    /// `theme` with the overridden colors replaced.
+    #[must_use]
+    pub fn apply(&self, mut theme: Theme) -> Theme {
+        for (role, fg, bg) in &self.roles {
+            if let Some(style) = theme.role_mut(role) {
+                if let Some(fg) = fg {
+                    *style = style.fg(*fg);
+                }
+                if let Some(bg) = bg {
+                    *style = style.bg(*bg);
+                }
+            }
+        }
+        theme
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_cycles_between_dark_and_light() {
+        let dark = Theme::default();
+        assert_eq!(dark.name, "dark");
+        assert_eq!(dark.next().name, "light");
+        assert_eq!(dark.next().next(), dark);
+    }
+
+    #[test]
+    fn theme_file_overrides_roles_on_any_palette() {
+        let text = "key = \"blue\"\nwarning = \"#1e1e2e\"\nlist_highlight = { bg = \"236\" }\n";
+        let (overrides, warnings) = ThemeOverrides::parse(text);
+        assert!(warnings.is_empty(), "{warnings:?}");
+
+        let theme = overrides.apply(Theme::dark());
+        assert_eq!(theme.key, Style::default().fg(Color::Blue));
+        assert_eq!(
+            theme.warning,
+            Style::default().fg(Color::Rgb(0x1e, 0x1e, 0x2e))
+        );
+        assert_eq!(
+            theme.list_highlight,
+            Style::default().bg(Color::Indexed(236))
+        );
+        // Roles the file leaves out keep the palette's colors
+        assert_eq!(theme.error, Theme::dark().error);
+
+        let light = overrides.apply(Theme::light());
+        assert_eq!(light.name, "light");
+        assert_eq!(light.key, Style::default().fg(Color::Blue));
+    }
+
+    #[test]
+    fn invalid_colors_and_roles_fall_back_with_a_warning() {
+        let text = "key = \"bluish\"\nerror = \"red\"\nborder = \"blue\"\n";
+        let (overrides, warnings) = ThemeOverrides::parse(text);
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(warnings.contains(&"unknown role 'border'".to_string()));
+        assert!(warnings.contains(&"key: invalid color 'bluish'; keeping the default".to_string()));
+
+        let theme = overrides.apply(Theme::dark());
+        assert_eq!(theme.key, Theme::dark().key);
+        assert_eq!(theme.error, Style::default().fg(Color::Red));
+
+        let (overrides, warnings) = ThemeOverrides::parse("key = ");
+        assert_eq!(overrides, ThemeOverrides::default());
+        assert!(warnings[0].starts_with("not valid TOML"));
+    }
+
+    #[test]
+    fn palettes_differ_in_every_value_role() {
+        let (dark, light) = (Theme::dark(), Theme::light());
+        assert_ne!(dark.sidebar_highlight, light.sidebar_highlight);
+        assert_ne!(dark.section_label, light.section_label);
+        assert_ne!(dark.default_value, light.default_value);
+        assert_ne!(dark.set_value, light.set_value);
+    }
+}
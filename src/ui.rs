@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::app;
-use crate::app::{App, CurrentScreen};
+use crate::app::{App, ConfigWarnings, CurrentScreen};
 use crate::components::{
     bitcoin_config_view::BitcoinConfigView, bitcoin_status_view::BitcoinStatusView,
     file_explorer::FileExplorer, home_view::HomeView, ln_config_view::LNConfigView,
@@ -11,11 +11,27 @@ use crate::components::{
     p2pool_status_view::P2PoolStatusView, settings_view::SettingsView,
     shares_market_view::SharesMarketView, status_bar::StatusBar,
 };
+use crate::diff::{DiffKind, DiffLine};
+use crate::util::backup_path;
 use ratatui::{
     prelude::*,
-    widgets::{Block, Borders, List, ListItem, ListState},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
 
+/// Below this terminal width the sidebar collapses to a strip of initials.
+const COMPACT_WIDTH: u16 = 60;
+
+/// Sidebar width when collapsed: two-letter initials inside the borders.
+const COMPACT_SIDEBAR_WIDTH: u16 = 4;
+
+/// Short sidebar label shown when collapsed: "Bitcoin Config" -> "BC".
+fn initials(label: &str) -> String {
+    label
+        .split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .collect()
+}
+
 pub fn ui(f: &mut Frame, app: &mut App) {
     let outer = Layout::default()
         .direction(Direction::Vertical)
@@ -28,26 +44,43 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     let main_row = outer[0];
     let status_bar_area = outer[1];
 
+    // Narrow terminals keep the sidebar navigable but give the room to content
+    let compact = f.area().width < COMPACT_WIDTH;
+    let sidebar_width = if compact { COMPACT_SIDEBAR_WIDTH } else { 25 };
+
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Length(25), // Sidebar
-            Constraint::Min(0),     // Main Content
+            Constraint::Length(sidebar_width), // Sidebar
+            Constraint::Min(0),                // Main Content
         ])
         .split(main_row);
 
     //  Sidebar
     let items: Vec<ListItem> = app::SIDEBAR_ITEMS
         .iter()
-        .map(|&(label, _)| ListItem::new(label))
+        .map(|&(label, _)| {
+            if compact {
+                ListItem::new(initials(label))
+            } else {
+                ListItem::new(label)
+            }
+        })
         .collect();
 
     // Highlight the active one
     let mut state = ListState::default();
     state.select(Some(app.sidebar_index));
 
+    // The quit prompt is drawn over the screen it was opened from
+    let screen = if app.current_screen == CurrentScreen::Exiting {
+        app.previous_screen
+    } else {
+        app.current_screen
+    };
+
     // Dim the sidebar when the user has moved focus into a content panel
-    let sidebar_focused = match app.current_screen {
+    let sidebar_focused = match screen {
         CurrentScreen::BitcoinConfig => app.bitcoin_config_view.sidebar_focused,
         CurrentScreen::Settings => app.settings_view.sidebar_focused,
         _ => true,
@@ -55,24 +88,46 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     let sidebar_border_style = if sidebar_focused {
         Style::default()
     } else {
-        Style::default().fg(Color::DarkGray)
+        app.theme.dimmed
     };
 
+    // `*` flags edits that have not been written to disk yet
+    let title = match (compact, app.has_unsaved_changes()) {
+        (false, true) => " PDM * ",
+        (false, false) => " PDM ",
+        (true, true) => "*",
+        (true, false) => "",
+    };
     let sidebar = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" PDM ")
+                .title(title)
                 .border_style(sidebar_border_style),
         )
-        .highlight_style(Style::default().bg(Color::Gray).fg(Color::Black));
+        .highlight_style(app.theme.sidebar_highlight);
 
     f.render_stateful_widget(sidebar, chunks[0], &mut state);
+    app.sidebar_area = chunks[0];
+
+    // Worked out once for the banners, the p2pool rows and the footer counts
+    let warnings = if matches!(
+        screen,
+        CurrentScreen::BitcoinConfig | CurrentScreen::P2PoolConfig
+    ) {
+        app.config_warnings()
+    } else {
+        ConfigWarnings::default()
+    };
 
     // Main Content
-    let main_area = chunks[1];
+    let main_area = config_mismatch_banner(f, app, screen, &warnings, chunks[1]);
+    let main_area = unknown_keys_banner(f, app, screen, main_area);
+    let main_area = network_limits_banner(f, app, screen, &warnings, main_area);
+    // Inner height of a bordered list in the content area
+    app.viewport_height = main_area.height.saturating_sub(2);
 
-    match app.current_screen {
+    match screen {
         CurrentScreen::Home => {
             HomeView::render(f, app, main_area);
         }
@@ -83,7 +138,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             BitcoinStatusView::render(f, app, main_area);
         }
         CurrentScreen::P2PoolConfig => {
-            P2PoolConfigView::render(f, app, main_area);
+            P2PoolConfigView::render(f, app, &warnings, main_area);
         }
         CurrentScreen::P2PoolStatus => {
             P2PoolStatusView::render(f, app, main_area);
@@ -103,15 +158,314 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         CurrentScreen::Settings => {
             SettingsView::render(f, app, main_area);
         }
+        // `previous_screen` is never `Exiting`
+        CurrentScreen::Exiting => {}
+    }
+
+    StatusBar::render(f, app, &warnings, status_bar_area);
+
+    if let Some(path) = app.pending_save.and_then(|target| app.save_path(target)) {
+        let message = if app.settings.skip_backups || !path.exists() {
+            format!(
+                "Overwrite {}? (y/n)\nNo .bak backup will be created; the file is replaced in place.",
+                path.display()
+            )
+        } else {
+            format!(
+                "Overwrite {}? (y/n)\nThe current file is backed up to {} first.",
+                path.display(),
+                backup_path(path).display()
+            )
+        };
+        render_modal(
+            f,
+            "Confirm save",
+            &message,
+            "y Overwrite · n/Esc Cancel",
+            app.theme.warning,
+            &app.theme,
+            f.area(),
+        );
+    }
+
+    if let Some(path) = app.pending_reload.and_then(|target| app.save_path(target)) {
+        let message = format!(
+            "{} changed on disk. Reload it? (y/n)\nYour unsaved edits will be lost.",
+            path.display()
+        );
+        render_modal(
+            f,
+            "File changed",
+            &message,
+            "y Reload · n/Esc Keep edits",
+            app.theme.warning,
+            &app.theme,
+            f.area(),
+        );
+    }
+
+    if app.current_screen == CurrentScreen::Exiting {
+        let message = if app.has_unsaved_changes() {
+            "Quit PDM? (y/n)\nYou have unsaved config changes; they will be lost."
+        } else {
+            "Quit PDM? (y/n)"
+        };
+        let border = if app.has_unsaved_changes() {
+            app.theme.warning
+        } else {
+            app.theme.key
+        };
+        render_modal(
+            f,
+            "Quit",
+            message,
+            "y Quit · n/Esc Cancel",
+            border,
+            &app.theme,
+            f.area(),
+        );
+    }
+
+    if let Some(message) = &app.notice {
+        render_modal(
+            f,
+            "Notice",
+            message,
+            "Press any key to dismiss",
+            app.theme.success,
+            &app.theme,
+            f.area(),
+        );
+    }
+
+    if let Some(lines) = &app.diff {
+        render_diff(f, lines, &app.theme, f.area());
+    }
+
+    if let Some(message) = &app.error {
+        render_modal(
+            f,
+            "Error",
+            message,
+            "Press any key to dismiss",
+            app.theme.error,
+            &app.theme,
+            f.area(),
+        );
+    }
+}
+
+/// With both configs loaded and one of them on screen, lists the ways the
+/// p2pool config disagrees with bitcoin.conf in a strip above the content.
+/// Returns the area left for the screen itself.
+fn config_mismatch_banner(
+    f: &mut Frame,
+    app: &App,
+    screen: CurrentScreen,
+    warnings: &ConfigWarnings,
+    area: Rect,
+) -> Rect {
+    let config_screen = matches!(
+        screen,
+        CurrentScreen::BitcoinConfig | CurrentScreen::P2PoolConfig
+    );
+    if !config_screen || app.bitcoin_conf_path.is_none() || app.p2pool_conf_path.is_none() {
+        return area;
+    }
+    warning_banner(f, app, " Config mismatch ", &warnings.mismatches, area)
+}
+
+/// On the p2pool screen, lists the file's sections and keys the loader
+/// ignores, typos included, in a strip above the content. Returns the area
+/// left for the screen itself.
+fn unknown_keys_banner(f: &mut Frame, app: &App, screen: CurrentScreen, area: Rect) -> Rect {
+    if screen != CurrentScreen::P2PoolConfig || app.p2pool_config.is_none() {
+        return area;
+    }
+    warning_banner(
+        f,
+        app,
+        " Ignored by p2pool ",
+        &app.p2pool_unknown_keys,
+        area,
+    )
+}
+
+/// On the p2pool screen, lists `[network]` limits that combine oddly (see
+/// [`network_warnings`](crate::p2poolv2_config::network_warnings)) in a
+/// strip above the content. Returns the area left for the screen itself.
+fn network_limits_banner(
+    f: &mut Frame,
+    app: &App,
+    screen: CurrentScreen,
+    warnings: &ConfigWarnings,
+    area: Rect,
+) -> Rect {
+    if screen != CurrentScreen::P2PoolConfig || app.p2pool_config.is_none() {
+        return area;
+    }
+    let messages: Vec<&str> = warnings
+        .network
+        .iter()
+        .map(|w| w.message.as_str())
+        .collect();
+    warning_banner(f, app, " Suspicious network limits ", &messages, area)
+}
+
+/// Draws one `⚠` line per warning in a bordered strip at the top of `area`
+/// and returns the rest; returns `area` untouched when there are none.
+fn warning_banner<W: std::fmt::Display>(
+    f: &mut Frame,
+    app: &App,
+    title: &str,
+    warnings: &[W],
+    area: Rect,
+) -> Rect {
+    if warnings.is_empty() {
+        return area;
+    }
+    let lines: Vec<Line> = warnings
+        .iter()
+        .map(|w| Line::from(format!("⚠ {w}")))
+        .collect();
+    // Borders plus one row per warning, leaving most of the area to content
+    let height = u16::try_from(lines.len() + 2)
+        .unwrap_or(u16::MAX)
+        .min(area.height / 3);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(height), Constraint::Min(0)])
+        .split(area);
+    f.render_widget(
+        Paragraph::new(lines).style(app.theme.warning).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(app.theme.warning),
+        ),
+        rows[0],
+    );
+    rows[1]
+}
+
+/// Returns a `width` x `height` rectangle centered in `area`, clamped to fit.
+#[must_use]
+pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Draws `message` in a centered box titled `title` over the whole frame,
+/// with `hint` on the last row. Long messages wrap; the box grows to fit
+/// them up to the frame height.
+fn render_modal(
+    f: &mut Frame,
+    title: &str,
+    message: &str,
+    hint: &str,
+    border: Style,
+    theme: &crate::theme::Theme,
+    area: Rect,
+) {
+    let width = (area.width.saturating_mul(3) / 5).max(30);
+    let text_width = usize::from(width.saturating_sub(4)).max(1);
+    // Approximate wrapped height: one row per `text_width` columns per line
+    let text_rows: usize = message
+        .lines()
+        .map(|line| line.chars().count().div_ceil(text_width).max(1))
+        .sum();
+    // Borders, padding row, the text and the hint
+    let height = u16::try_from(text_rows + 4).unwrap_or(u16::MAX);
+    let popup = centered_rect(width, height, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {title} "))
+        .border_style(border)
+        .title_style(border);
+    let inner = block.inner(popup).inner(Margin::new(1, 0));
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+    f.render_widget(
+        Paragraph::new(message)
+            .style(theme.text)
+            .wrap(Wrap { trim: true }),
+        rows[0],
+    );
+    f.render_widget(
+        Paragraph::new(hint)
+            .style(theme.description)
+            .alignment(Alignment::Center),
+        rows[1],
+    );
+}
+
+/// Draws the unsaved changes in a centered box like [`render_modal`], one
+/// `+`/`-`/`~` line per entry colored by kind. Lines past the box's height
+/// are summarised in the last visible row.
+fn render_diff(f: &mut Frame, lines: &[DiffLine], theme: &crate::theme::Theme, area: Rect) {
+    let width = (area.width.saturating_mul(3) / 5).max(30);
+    // Borders, padding row, the lines and the hint
+    let height = u16::try_from(lines.len() + 4).unwrap_or(u16::MAX);
+    let popup = centered_rect(width, height, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Unsaved changes ")
+        .border_style(theme.warning)
+        .title_style(theme.warning);
+    let inner = block.inner(popup).inner(Margin::new(1, 0));
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    let visible = usize::from(rows[0].height);
+    let mut text: Vec<Line> = lines
+        .iter()
+        .map(|line| {
+            let style = match line.kind {
+                DiffKind::Added => theme.success,
+                DiffKind::Removed => theme.error,
+                DiffKind::Changed => theme.warning,
+            };
+            Line::styled(line.to_string(), style)
+        })
+        .collect();
+    if text.len() > visible {
+        text.truncate(visible.saturating_sub(1));
+        let hidden = lines.len() - text.len();
+        text.push(Line::styled(format!("… {hidden} more"), theme.description));
     }
 
-    StatusBar::render(f, app, status_bar_area);
+    f.render_widget(Clear, popup);
+    f.render_widget(block, popup);
+    f.render_widget(Paragraph::new(text), rows[0]);
+    f.render_widget(
+        Paragraph::new("Press any key to dismiss")
+            .style(theme.description)
+            .alignment(Alignment::Center),
+        rows[1],
+    );
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::app::App;
+    use crate::components::test_render::buffer_rows;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
 
@@ -127,6 +481,37 @@ mod tests {
         insta::assert_debug_snapshot!(terminal.backend());
     }
 
+    /// Draws the home screen `width` columns wide and returns its rows.
+    fn render_home(width: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, 12)).unwrap();
+        let mut app = App::new();
+        terminal.draw(|f| ui(f, &mut app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer_rows(buffer, buffer.area)
+    }
+
+    #[test]
+    fn test_home_screen_40_cols() {
+        insta::assert_snapshot!(render_home(40));
+    }
+
+    #[test]
+    fn test_home_screen_80_cols() {
+        insta::assert_snapshot!(render_home(80));
+    }
+
+    #[test]
+    fn test_home_screen_120_cols() {
+        insta::assert_snapshot!(render_home(120));
+    }
+
+    #[test]
+    fn initials_abbreviate_sidebar_labels() {
+        assert_eq!(initials("Home"), "H");
+        assert_eq!(initials("Bitcoin Config"), "BC");
+        assert_eq!(initials("Shares Market"), "SM");
+    }
+
     #[test]
     fn test_bitcoin_config_screen_render() {
         let mut terminal = make_terminal();
@@ -244,4 +629,11 @@ mod tests {
         unsafe { std::env::remove_var("PDM_CONFIG_DIR") };
         insta::assert_debug_snapshot!(terminal.backend());
     }
+
+    #[test]
+    fn centered_rect_clamps_to_area() {
+        let area = Rect::new(0, 0, 80, 24);
+        assert_eq!(centered_rect(40, 10, area), Rect::new(20, 7, 40, 10));
+        assert_eq!(centered_rect(100, 30, area), area);
+    }
 }
@@ -0,0 +1,265 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! File helpers shared by the config writers.
+
+use anyhow::{Context, Result, bail};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Writes `contents` to a temporary file next to `path`, then renames it
+/// over `path`, so an interrupted write never leaves a truncated file. A
+/// symlinked `path` is resolved first, so the file it points to is replaced
+/// and the link survives.
+///
+/// An existing target must be writable: it is opened for writing up front,
+/// so a file the process may not change, such as a root-owned
+/// `/etc/bitcoin/bitcoin.conf`, is refused rather than silently replaced.
+/// The temporary file gets the original's permissions and, on Unix, its
+/// owner and group where the process is allowed to set them, before any
+/// contents are written to it.
+///
+/// # Errors
+/// Returns an error if the target is not writable, the directory does not
+/// allow creating the temporary file, or the write or rename fails. The
+/// target is left untouched in every case.
+pub fn atomic_write(path: &Path, contents: &str) -> Result<()> {
+    let path = &match std::fs::canonicalize(path) {
+        Ok(resolved) => resolved,
+        Err(e) if e.kind() == ErrorKind::NotFound => path.to_path_buf(),
+        Err(e) => return Err(e).with_context(|| format!("cannot access {}", path.display())),
+    };
+    let original = match std::fs::metadata(path) {
+        Ok(metadata) => Some(metadata),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(e).with_context(|| format!("cannot access {}", path.display())),
+    };
+    // Root may open anything, so a file nobody is meant to write is refused
+    // by its mode too
+    if let Some(metadata) = &original
+        && metadata.permissions().readonly()
+    {
+        bail!(
+            "{} is read-only; run pdm as a user that can write it",
+            path.display()
+        );
+    }
+    if original.is_some()
+        && let Err(e) = OpenOptions::new().write(true).open(path)
+    {
+        if e.kind() == ErrorKind::PermissionDenied {
+            bail!(
+                "{} is read-only; run pdm as a user that can write it",
+                path.display()
+            );
+        }
+        return Err(e).with_context(|| format!("cannot open {} for writing", path.display()));
+    }
+
+    let file_name = path
+        .file_name()
+        .map_or_else(|| "config".into(), |n| n.to_string_lossy().into_owned());
+    let tmp_path = path.with_file_name(format!(".{file_name}.tmp"));
+    // A leftover from an interrupted save would keep its own mode
+    let _ = std::fs::remove_file(&tmp_path);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if let Some(metadata) = &original {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(metadata.permissions().mode() & 0o777);
+    }
+    let mut file = options.open(&tmp_path).map_err(|e| {
+        let dir = tmp_path.parent().unwrap_or(Path::new("."));
+        if e.kind() == ErrorKind::PermissionDenied {
+            anyhow::anyhow!(
+                "cannot write {}: permission denied in {}",
+                path.display(),
+                dir.display()
+            )
+        } else {
+            anyhow::Error::new(e).context(format!("cannot create a file in {}", dir.display()))
+        }
+    })?;
+
+    let result = (|| -> Result<()> {
+        if let Some(metadata) = &original {
+            // The umask may have narrowed the mode given at creation
+            file.set_permissions(metadata.permissions())?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                // Only root may give a file away; keep our own ownership otherwise
+                let _ =
+                    std::os::unix::fs::fchown(&file, Some(metadata.uid()), Some(metadata.gid()));
+            }
+        }
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Whether the process can create files in `dir`, found by creating and
+/// removing an empty probe file there. Directory modes alone say nothing
+/// about the user pdm runs as.
+#[must_use]
+pub fn dir_is_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".pdm-probe-{}", std::process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => std::fs::remove_file(&probe).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Where [`backup_file`] copies `path`: `<path>.bak`, or once that exists
+/// the first free `<path>.bak.<n>` counting from 1.
+#[must_use]
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    let first = PathBuf::from(&name);
+    if !first.exists() {
+        return first;
+    }
+    (1..)
+        .map(|n| {
+            let mut numbered = name.clone();
+            numbered.push(format!(".{n}"));
+            PathBuf::from(numbered)
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(first)
+}
+
+/// Copies `path` to [`backup_path`] before it gets overwritten, never
+/// replacing an earlier backup.
+///
+/// # Errors
+/// Returns an error if `path` exists but cannot be copied; nothing is
+/// backed up, and `Ok(None)` returned, when it does not exist yet.
+pub fn backup_file(path: &Path) -> Result<Option<PathBuf>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let backup = backup_path(path);
+    std::fs::copy(path, &backup)
+        .with_context(|| format!("cannot back up {} to {}", path.display(), backup.display()))?;
+    Ok(Some(backup))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_write_replaces_contents_and_keeps_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        std::fs::write(&path, "server=0\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+        }
+
+        atomic_write(&path, "server=1\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "server=1\n");
+        assert!(!dir.path().join(".bitcoin.conf.tmp").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o640);
+        }
+    }
+
+    #[test]
+    fn dir_is_writable_leaves_no_probe_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(dir_is_writable(dir.path()));
+        assert!(std::fs::read_dir(dir.path()).unwrap().next().is_none());
+        assert!(!dir_is_writable(&dir.path().join("missing")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_write_replaces_the_target_of_a_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("bitcoin.conf");
+        let link = dir.path().join("link.conf");
+        std::fs::write(&target, "server=0\n").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        atomic_write(&link, "server=1\n").unwrap();
+
+        assert!(
+            std::fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "server=1\n");
+    }
+
+    #[test]
+    fn atomic_write_creates_a_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+
+        atomic_write(&path, "[stratum]\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[stratum]\n");
+    }
+
+    #[test]
+    fn backup_file_copies_the_original_without_clobbering_earlier_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        std::fs::write(&path, "server=0\n").unwrap();
+
+        let first = backup_file(&path).unwrap().unwrap();
+        assert_eq!(first, dir.path().join("bitcoin.conf.bak"));
+        std::fs::write(&path, "server=1\n").unwrap();
+        let second = backup_file(&path).unwrap().unwrap();
+        assert_eq!(second, dir.path().join("bitcoin.conf.bak.1"));
+
+        assert_eq!(std::fs::read_to_string(first).unwrap(), "server=0\n");
+        assert_eq!(std::fs::read_to_string(second).unwrap(), "server=1\n");
+    }
+
+    #[test]
+    fn backup_file_skips_a_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("p2pool.toml");
+
+        assert_eq!(backup_file(&path).unwrap(), None);
+        assert!(!dir.path().join("p2pool.toml.bak").exists());
+    }
+
+    #[test]
+    fn atomic_write_refuses_a_read_only_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bitcoin.conf");
+        std::fs::write(&path, "server=0\n").unwrap();
+        let mut permissions = std::fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&path, permissions).unwrap();
+
+        let err = atomic_write(&path, "server=1\n").unwrap_err();
+
+        assert!(err.to_string().contains("read-only"), "{err}");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "server=0\n");
+        assert!(!dir.path().join(".bitcoin.conf.tmp").exists());
+    }
+}
@@ -0,0 +1,22 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Field-level warnings shared by the bitcoin and p2pool checks.
+
+use std::fmt;
+
+/// A value that loads and saves but likely keeps the node or pool from
+/// working as intended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// The field the warning is about, as `section.key`.
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
@@ -0,0 +1,79 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, channel};
+
+/// Reports changes made to the loaded config files by other programs.
+///
+/// Each file's parent directory is watched rather than the file itself,
+/// since editors often save by writing a new file and renaming it over the
+/// old one, which would silently end a watch on the original inode.
+pub struct ConfigWatcher {
+    watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+    /// Absolute paths of the watched files.
+    files: Vec<PathBuf>,
+    dirs: Vec<PathBuf>,
+}
+
+impl ConfigWatcher {
+    /// # Errors
+    /// Returns an error if the platform file watcher cannot be started.
+    pub fn new() -> notify::Result<ConfigWatcher> {
+        let (tx, events) = channel();
+        Ok(ConfigWatcher {
+            watcher: notify::recommended_watcher(tx)?,
+            events,
+            files: Vec::new(),
+            dirs: Vec::new(),
+        })
+    }
+
+    /// Watches exactly `files`, dropping watches on files no longer listed.
+    /// Cheap to call every loop iteration; nothing changes if the set is the
+    /// same as last time.
+    pub fn watch(&mut self, files: &[&Path]) {
+        let files: Vec<PathBuf> = files
+            .iter()
+            .filter_map(|f| std::path::absolute(f).ok())
+            .collect();
+        if files == self.files {
+            return;
+        }
+        let mut dirs: Vec<PathBuf> = files
+            .iter()
+            .filter_map(|f| f.parent().map(Path::to_path_buf))
+            .collect();
+        dirs.dedup();
+
+        for dir in self.dirs.iter().filter(|d| !dirs.contains(d)) {
+            let _ = self.watcher.unwatch(dir);
+        }
+        for dir in dirs.iter().filter(|d| !self.dirs.contains(d)) {
+            // A directory that cannot be watched just means no reload prompts
+            let _ = self.watcher.watch(dir, RecursiveMode::NonRecursive);
+        }
+        self.files = files;
+        self.dirs = dirs;
+    }
+
+    /// Drains pending notifications and returns the watched files that were
+    /// created, modified or removed since the last call, without duplicates.
+    pub fn changed(&self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for event in self.events.try_iter().flatten() {
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            for path in event.paths {
+                if self.files.contains(&path) && !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+        }
+        changed
+    }
+}
@@ -0,0 +1,153 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+#![cfg(feature = "tui")]
+
+mod common;
+
+use common::P2POOL_TOML;
+use pdm::app::{App, CurrentScreen, SIDEBAR_ITEMS};
+use pdm::settings::SessionState;
+use std::path::Path;
+use std::process::Command;
+
+fn args(flag: &str, path: &Path) -> Vec<String> {
+    vec![flag.to_string(), path.display().to_string()]
+}
+
+fn selected_screen(app: &App) -> CurrentScreen {
+    SIDEBAR_ITEMS[app.sidebar_index].1
+}
+
+#[test]
+fn with_args_without_flags_matches_new() {
+    let app = App::with_args(&[]).unwrap();
+    let fresh = App::new();
+
+    assert_eq!(app.current_screen, fresh.current_screen);
+    assert_eq!(app.sidebar_index, fresh.sidebar_index);
+    assert!(app.bitcoin_conf_path.is_none());
+    assert!(app.p2pool_conf_path.is_none());
+}
+
+#[test]
+fn with_args_loads_bitcoin_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bitcoin.conf");
+    std::fs::write(&path, "server=1\nrpcuser=alice\n").unwrap();
+
+    let app = App::with_args(&args("--bitcoin", &path)).unwrap();
+
+    assert_eq!(app.current_screen, CurrentScreen::BitcoinConfig);
+    assert_eq!(selected_screen(&app), CurrentScreen::BitcoinConfig);
+    assert_eq!(app.bitcoin_conf_path, Some(path));
+    assert!(
+        app.bitcoin_data
+            .iter()
+            .any(|e| e.key == "rpcuser" && e.value == "alice" && e.enabled)
+    );
+}
+
+#[test]
+fn with_args_loads_p2pool_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("p2pool.toml");
+    std::fs::write(&path, P2POOL_TOML).unwrap();
+
+    let app = App::with_args(&args("--p2pool", &path)).unwrap();
+
+    assert_eq!(app.current_screen, CurrentScreen::P2PoolConfig);
+    assert_eq!(selected_screen(&app), CurrentScreen::P2PoolConfig);
+    assert_eq!(app.p2pool_conf_path, Some(path));
+    assert_eq!(
+        app.p2pool_config.as_ref().unwrap().stratum.hostname,
+        "pool.example.com"
+    );
+}
+
+#[test]
+fn with_args_rejects_missing_and_invalid_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.conf");
+    assert!(App::with_args(&args("--bitcoin", &missing)).is_err());
+
+    let bad = dir.path().join("bad.toml");
+    std::fs::write(&bad, "invalid === toml").unwrap();
+    assert!(App::with_args(&args("--p2pool", &bad)).is_err());
+
+    assert!(App::with_args(&["--bitcoin".to_string()]).is_err());
+}
+
+#[test]
+fn invalid_path_exits_non_zero_before_starting_the_tui() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pdm"))
+        .args(["--bitcoin", "/nonexistent/bitcoin.conf"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("does not exist"));
+}
+
+#[test]
+fn session_state_round_trips_through_restore() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("p2pool.toml");
+    std::fs::write(&path, P2POOL_TOML).unwrap();
+
+    let mut app = App::with_args(&args("--p2pool", &path)).unwrap();
+    app.theme = app.theme.next();
+    // Quitting is confirmed over the screen being left
+    app.previous_screen = app.current_screen;
+    app.current_screen = CurrentScreen::Exiting;
+    let state = app.session_state();
+    assert_eq!(state.screen.as_deref(), Some("P2Pool Config"));
+
+    let mut restored = App::new();
+    restored.restore_state(&state);
+    assert_eq!(restored.current_screen, CurrentScreen::P2PoolConfig);
+    assert_eq!(restored.sidebar_index, app.sidebar_index);
+    assert_eq!(restored.p2pool_conf_path, Some(path));
+    assert!(restored.p2pool_config.is_some());
+    assert_eq!(restored.theme.name, "light");
+}
+
+#[test]
+fn restore_skips_what_no_longer_applies() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = SessionState {
+        screen: Some("Retired Screen".to_string()),
+        sidebar_index: 99,
+        bitcoin_conf_path: Some(dir.path().join("gone.conf")),
+        p2pool_conf_path: None,
+        theme: Some("neon".to_string()),
+    };
+
+    let mut app = App::new();
+    app.restore_state(&state);
+
+    assert_eq!(app.current_screen, CurrentScreen::Home);
+    assert_eq!(app.sidebar_index, 0);
+    assert!(app.bitcoin_conf_path.is_none());
+    assert_eq!(app.theme.name, "dark");
+}
+
+#[test]
+fn command_line_configs_win_over_the_saved_session() {
+    let dir = tempfile::tempdir().unwrap();
+    let given = dir.path().join("given.conf");
+    std::fs::write(&given, "server=1\n").unwrap();
+    let saved = dir.path().join("saved.conf");
+    std::fs::write(&saved, "rpcuser=alice\n").unwrap();
+
+    let mut app = App::with_args(&args("--bitcoin", &given)).unwrap();
+    app.restore_state(&SessionState {
+        screen: Some("Settings".to_string()),
+        bitcoin_conf_path: Some(saved),
+        ..SessionState::default()
+    });
+
+    assert_eq!(app.bitcoin_conf_path, Some(given));
+    assert_eq!(app.current_screen, CurrentScreen::BitcoinConfig);
+}
@@ -0,0 +1,45 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+#![cfg(feature = "tui")]
+
+use std::process::Command;
+
+fn pdm_check(content: &str) -> std::process::Output {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bitcoin.conf");
+    std::fs::write(&path, content).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_pdm"))
+        .arg("--check")
+        .arg(&path)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn check_valid_config_exits_zero() {
+    let output = pdm_check("server=1\nrpcport=8332\n");
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("ok"));
+}
+
+#[test]
+fn check_invalid_config_exits_non_zero() {
+    let output = pdm_check("server=1\nrpcport=70000\n");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("rpcport: 70000 is not a valid port (1-65535)"));
+}
+
+#[test]
+fn check_missing_file_exits_non_zero() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pdm"))
+        .args(["--check", "/nonexistent/bitcoin.conf"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}
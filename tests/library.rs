@@ -0,0 +1,52 @@
+// SPDX-FileCopyrightText: 2024 PDM Authors
+//
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! The parsing API must not need the `tui` feature; run these with
+//! `cargo test --no-default-features --test library` to check that.
+
+mod common;
+
+use common::P2POOL_TOML;
+use pdm::p2poolv2_config::{ConfigSection, Origin};
+
+#[test]
+fn parse_bitcoin_reads_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bitcoin.conf");
+    std::fs::write(&path, "server=1\ntxindex=1\n").unwrap();
+
+    let entries = pdm::parse_bitcoin(&path).unwrap();
+
+    assert!(
+        entries
+            .iter()
+            .any(|e| e.key == "txindex" && e.value == "1" && e.enabled)
+    );
+    assert!(entries.iter().all(|e| e.validation_error.is_none()));
+}
+
+#[test]
+fn parse_p2pool_flattens_entries_with_origins() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("p2pool.toml");
+    std::fs::write(&path, P2POOL_TOML).unwrap();
+
+    let entries: Vec<pdm::P2PoolEntry> = pdm::parse_p2pool(&path).unwrap();
+
+    let hostname = entries
+        .iter()
+        .find(|e| e.section == ConfigSection::Stratum && e.key == "hostname")
+        .unwrap();
+    assert_eq!(hostname.value, "pool.example.com");
+    assert_eq!(hostname.origin, Origin::File);
+}
+
+#[test]
+fn parse_p2pool_rejects_invalid_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("p2pool.toml");
+    std::fs::write(&path, P2POOL_TOML.replace("port = 3333", "port = 0")).unwrap();
+
+    assert!(pdm::parse_p2pool(&path).is_err());
+}